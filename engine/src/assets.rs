@@ -0,0 +1,509 @@
+use crate::cache::DiskCache;
+use derivative::Derivative;
+use downcast_rs::{impl_downcast, DowncastSync};
+use fnv::FnvHashMap;
+use lazy_static::lazy_static;
+use relative_path::RelativePath;
+use std::{
+	any::{Any, TypeId},
+	clone::Clone,
+	fmt,
+	marker::PhantomData,
+	path::Path,
+	sync::{Arc, RwLock, Weak},
+};
+
+pub trait Asset: Send + Sync + 'static {}
+impl<T: Send + Sync + 'static> Asset for T {}
+
+pub trait ImportData: DowncastSync {}
+impl_downcast!(sync ImportData);
+impl<T: DowncastSync> ImportData for T {}
+
+/// A function that turns the asset at `path` into import data, loading other assets it needs.
+pub type Importer = fn(
+	path: &RelativePath,
+	asset_storage: &mut AssetStorage,
+) -> anyhow::Result<Box<dyn ImportData>>;
+
+pub struct AssetStorage {
+	importer: Importer,
+	source: Box<dyn DataSource>,
+	storages: FnvHashMap<TypeId, Box<dyn Any + Send + Sync>>,
+	handle_allocator: HandleAllocator,
+	cache: Option<Arc<DiskCache>>,
+}
+
+impl AssetStorage {
+	#[inline]
+	pub fn new(importer: Importer, source: impl DataSource) -> AssetStorage {
+		AssetStorage {
+			importer,
+			source: Box::new(source),
+			storages: FnvHashMap::default(),
+			handle_allocator: HandleAllocator::default(),
+			cache: None,
+		}
+	}
+
+	#[inline]
+	pub fn source(&self) -> &dyn DataSource {
+		&*self.source
+	}
+
+	/// Sets the cache that importers can keep assets in that take a while to make.
+	#[inline]
+	pub fn set_cache(&mut self, cache: DiskCache) {
+		self.cache = Some(Arc::new(cache));
+	}
+
+	#[inline]
+	pub fn cache(&self) -> Option<&Arc<DiskCache>> {
+		self.cache.as_ref()
+	}
+
+	#[inline]
+	pub fn add_storage<A: Asset>(&mut self, needs_processing: bool) {
+		let mut storage = AssetStorageTyped::<A>::default();
+
+		if needs_processing {
+			storage.unprocessed = Some(Vec::new());
+		}
+
+		self.storages.insert(TypeId::of::<A>(), Box::new(storage));
+	}
+
+	/// Sets a function that provides stand-in data for assets of this type that fail to load,
+	/// so that loading can carry on. Without one, a failed load is fatal.
+	#[inline]
+	pub fn set_placeholder<A: Asset>(&mut self, placeholder: fn() -> Box<dyn ImportData>) {
+		storage_mut::<A>(&mut self.storages).placeholder = Some(placeholder);
+	}
+
+	#[inline]
+	pub fn get<A: Asset>(&self, handle: &AssetHandle<A>) -> Option<&A> {
+		let storage = storage::<A>(&self.storages);
+		storage.assets.get(&handle.id())
+	}
+
+	#[inline]
+	pub fn iter<A: Asset>(&self) -> impl Iterator<Item = (&AssetHandle<A>, &A)> {
+		let storage = storage::<A>(&self.storages);
+		storage
+			.handles
+			.iter()
+			.map(move |handle| (handle, storage.assets.get(&handle.id()).unwrap()))
+	}
+
+	/// Returns how many assets of this type are stored, and how many of those are no longer
+	/// used by anything outside the storage.
+	pub fn usage<A: Asset>(&self) -> (usize, usize) {
+		let storage = storage::<A>(&self.storages);
+		let unused = storage
+			.handles
+			.iter()
+			.filter(|handle| handle.is_unique())
+			.count();
+		(storage.handles.len(), unused)
+	}
+
+	#[inline]
+	pub fn handle_for<A: Asset>(&self, name: &str) -> Option<AssetHandle<A>> {
+		// A name that was never interned can't have been loaded
+		AssetName::get(name).and_then(|name| self.handle_for_name(name))
+	}
+
+	#[inline]
+	pub fn handle_for_name<A: Asset>(&self, name: AssetName) -> Option<AssetHandle<A>> {
+		let storage = storage::<A>(&self.storages);
+		storage.names.get(&name).and_then(WeakHandle::upgrade)
+	}
+
+	/// Returns the name that an asset was loaded with, if it has one.
+	pub fn name_of<A: Asset>(&self, handle: &AssetHandle<A>) -> Option<&'static str> {
+		let storage = storage::<A>(&self.storages);
+		storage
+			.names
+			.iter()
+			.find(|(_, weak)| weak.upgrade().as_ref() == Some(handle))
+			.map(|(name, _)| name.as_str())
+	}
+
+	#[inline]
+	pub fn insert<A: Asset>(&mut self, asset: A) -> AssetHandle<A> {
+		let handle = self.handle_allocator.allocate();
+		let storage = storage_mut::<A>(&mut self.storages);
+		storage.assets.insert(handle.id(), asset);
+		storage.handles.push(handle.clone());
+		handle
+	}
+
+	#[inline]
+	pub fn insert_with_name<A: Asset>(&mut self, name: &str, asset: A) -> AssetHandle<A> {
+		let name = AssetName::new(name);
+		let storage = storage_mut::<A>(&mut self.storages);
+		match storage.names.get(&name).and_then(WeakHandle::upgrade) {
+			Some(handle) => {
+				storage.assets.insert(handle.id(), asset);
+				handle
+			}
+			None => {
+				let handle = {
+					let handle = self.handle_allocator.allocate();
+					storage.assets.insert(handle.id(), asset);
+					storage.handles.push(handle.clone());
+					handle
+				};
+				storage.names.insert(name, handle.downgrade());
+				handle
+			}
+		}
+	}
+
+	#[inline]
+	pub fn load<A: Asset>(&mut self, name: &str) -> AssetHandle<A> {
+		self.load_name(AssetName::new(name))
+	}
+
+	/// Like `load`, but with a name that was already interned, which saves looking it up.
+	#[inline]
+	pub fn load_name<A: Asset>(&mut self, name: AssetName) -> AssetHandle<A> {
+		match storage_mut::<A>(&mut self.storages)
+			.names
+			.get(&name)
+			.and_then(WeakHandle::upgrade)
+		{
+			Some(handle) => {
+				self.import_deferred(&handle);
+				handle
+			}
+			None => {
+				let handle = self.handle_allocator.allocate();
+				storage_mut::<A>(&mut self.storages)
+					.names
+					.insert(name, handle.downgrade());
+				self.import(handle.clone(), name);
+				handle
+			}
+		}
+	}
+
+	/// Like `load`, but the asset isn't imported until `import_deferred` is called for it,
+	/// or until it's loaded normally. Until then, `get` returns `None` for it.
+	/// This is for assets that many things refer to, but that are only needed once they're used.
+	pub fn load_deferred<A: Asset>(&mut self, name: &str) -> AssetHandle<A> {
+		let name = AssetName::new(name);
+		let storage = storage_mut::<A>(&mut self.storages);
+
+		match storage.names.get(&name).and_then(WeakHandle::upgrade) {
+			Some(handle) => handle,
+			None => {
+				let handle = self.handle_allocator.allocate();
+				storage.names.insert(name, handle.downgrade());
+				storage.deferred.insert(handle.id(), name);
+				handle
+			}
+		}
+	}
+
+	/// Returns whether the asset was loaded with `load_deferred` and hasn't been imported yet.
+	#[inline]
+	pub fn is_deferred<A: Asset>(&self, handle: &AssetHandle<A>) -> bool {
+		storage::<A>(&self.storages)
+			.deferred
+			.contains_key(&handle.id())
+	}
+
+	/// Imports an asset that was loaded with `load_deferred`, if it wasn't imported already.
+	pub fn import_deferred<A: Asset>(&mut self, handle: &AssetHandle<A>) {
+		if let Some(name) = storage_mut::<A>(&mut self.storages)
+			.deferred
+			.remove(&handle.id())
+		{
+			self.import(handle.clone(), name);
+		}
+	}
+
+	/// Imports all named assets of this type again from the source, replacing the old ones.
+	/// Assets that need processing are queued up again for the next call to `process`.
+	/// Deferred assets that weren't imported yet are left alone.
+	pub fn reimport<A: Asset>(&mut self) {
+		let storage = storage::<A>(&self.storages);
+		let handles: Vec<(AssetName, AssetHandle<A>)> = storage
+			.names
+			.iter()
+			.filter_map(|(name, handle)| handle.upgrade().map(|handle| (*name, handle)))
+			.filter(|(_, handle)| !storage.deferred.contains_key(&handle.id()))
+			.collect();
+
+		for (name, handle) in handles {
+			self.import(handle, name);
+		}
+	}
+
+	fn import<A: Asset>(&mut self, handle: AssetHandle<A>, name: AssetName) {
+		let import_result = (self.importer)(RelativePath::new(name.as_str()), self);
+		let storage = storage_mut::<A>(&mut self.storages);
+
+		if let Some(unprocessed) = &mut storage.unprocessed {
+			unprocessed.push((handle, import_result, name));
+		} else {
+			let data = match import_result {
+				Ok(data) => {
+					log::trace!("Loaded '{}'", name);
+					data
+				}
+				Err(e) => self.placeholder::<A>(name.as_str(), e),
+			};
+
+			let asset = *data.downcast().ok().unwrap();
+			let storage = storage_mut::<A>(&mut self.storages);
+			storage.assets.insert(handle.id(), asset);
+		}
+	}
+
+	#[inline]
+	pub fn process<
+		A: Asset,
+		F: FnMut(Box<dyn ImportData>, &mut AssetStorage) -> anyhow::Result<A>,
+	>(
+		&mut self,
+		mut process_func: F,
+	) {
+		let unprocessed =
+			if let Some(unprocessed) = &mut storage_mut::<A>(&mut self.storages).unprocessed {
+				std::mem::replace(unprocessed, Vec::new())
+			} else {
+				return;
+			};
+
+		for (handle, data, name) in unprocessed {
+			// Build the asset
+			let asset = match data.and_then(|d| process_func(d, self)) {
+				Ok(asset) => {
+					log::trace!("Loaded '{}'", name);
+					asset
+				}
+				Err(e) => {
+					let data = self.placeholder::<A>(name.as_str(), e);
+
+					match process_func(data, self) {
+						Ok(asset) => asset,
+						Err(e) => panic!("Placeholder for '{}' could not be loaded: {}", name, e),
+					}
+				}
+			};
+
+			// Insert it into the storage, replacing any old asset
+			{
+				let storage = storage_mut::<A>(&mut self.storages);
+
+				if storage.assets.insert(handle.id(), asset).is_none() {
+					storage.handles.push(handle);
+				}
+			}
+		}
+	}
+
+	fn placeholder<A: Asset>(&self, name: &str, error: anyhow::Error) -> Box<dyn ImportData> {
+		let placeholder = match storage::<A>(&self.storages).placeholder {
+			Some(placeholder) => placeholder,
+			None => panic!("'{}' could not be loaded: {}", name, error),
+		};
+
+		match self.source.location(RelativePath::new(name)) {
+			Some(location) => log::warn!(
+				"'{}' from {} could not be loaded, using a placeholder: {}",
+				name,
+				location.display(),
+				error
+			),
+			None => log::warn!(
+				"'{}' could not be loaded, using a placeholder: {}",
+				name,
+				error
+			),
+		}
+
+		placeholder()
+	}
+}
+
+#[inline]
+fn storage<A: Asset>(
+	storages: &FnvHashMap<TypeId, Box<dyn Any + Send + Sync>>,
+) -> &AssetStorageTyped<A> {
+	storages
+		.get(&TypeId::of::<A>())
+		.expect("unknown asset type")
+		.downcast_ref::<AssetStorageTyped<A>>()
+		.expect("failed to downcast")
+}
+
+#[inline]
+fn storage_mut<A: Asset>(
+	storages: &mut FnvHashMap<TypeId, Box<dyn Any + Send + Sync>>,
+) -> &mut AssetStorageTyped<A> {
+	storages
+		.get_mut(&TypeId::of::<A>())
+		.expect("unknown asset type")
+		.downcast_mut::<AssetStorageTyped<A>>()
+		.expect("failed to downcast")
+}
+
+#[derive(Derivative)]
+#[derivative(Default(bound = ""))]
+struct AssetStorageTyped<A: Asset> {
+	assets: FnvHashMap<u64, A>,
+	handles: Vec<AssetHandle<A>>,
+	names: FnvHashMap<AssetName, WeakHandle<A>>,
+	deferred: FnvHashMap<u64, AssetName>,
+	unprocessed: Option<
+		Vec<(
+			AssetHandle<A>,
+			anyhow::Result<Box<dyn ImportData>>,
+			AssetName,
+		)>,
+	>,
+	placeholder: Option<fn() -> Box<dyn ImportData>>,
+}
+
+/// An interned asset name. The same name always gives the same `AssetName`,
+/// so comparing and hashing one is as cheap as for an integer, and copying it doesn't allocate.
+/// Interned names are kept for as long as the program runs.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AssetName(u32);
+
+#[derive(Default)]
+struct AssetNames {
+	names: Vec<&'static str>,
+	indices: FnvHashMap<&'static str, u32>,
+}
+
+lazy_static! {
+	static ref ASSET_NAMES: RwLock<AssetNames> = RwLock::new(AssetNames::default());
+}
+
+impl AssetName {
+	/// Interns `name`, if it wasn't already.
+	pub fn new(name: &str) -> AssetName {
+		if let Some(name) = AssetName::get(name) {
+			return name;
+		}
+
+		let mut asset_names = ASSET_NAMES.write().unwrap();
+
+		// Another thread may have interned it in the meantime
+		if let Some(&index) = asset_names.indices.get(name) {
+			return AssetName(index);
+		}
+
+		let name: &'static str = Box::leak(name.to_owned().into_boxed_str());
+		let index = asset_names.names.len() as u32;
+		asset_names.names.push(name);
+		asset_names.indices.insert(name, index);
+		AssetName(index)
+	}
+
+	/// Returns the interned name for `name`, if it was ever interned.
+	pub fn get(name: &str) -> Option<AssetName> {
+		ASSET_NAMES
+			.read()
+			.unwrap()
+			.indices
+			.get(name)
+			.map(|&index| AssetName(index))
+	}
+
+	pub fn as_str(self) -> &'static str {
+		ASSET_NAMES.read().unwrap().names[self.0 as usize]
+	}
+}
+
+impl fmt::Debug for AssetName {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Debug::fmt(self.as_str(), f)
+	}
+}
+
+impl fmt::Display for AssetName {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+#[derive(Derivative)]
+#[derivative(
+	Clone(bound = ""),
+	Eq(bound = ""),
+	Hash(bound = ""),
+	PartialEq(bound = ""),
+	Debug(bound = "")
+)]
+pub struct AssetHandle<A: ?Sized> {
+	id: Arc<u64>,
+	marker: PhantomData<A>,
+}
+
+impl<A> AssetHandle<A> {
+	pub fn downgrade(&self) -> WeakHandle<A> {
+		let id = Arc::downgrade(&self.id);
+
+		WeakHandle {
+			id,
+			marker: PhantomData,
+		}
+	}
+
+	fn id(&self) -> u64 {
+		*self.id.as_ref()
+	}
+
+	fn is_unique(&self) -> bool {
+		Arc::strong_count(&self.id) == 1
+	}
+}
+
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""))]
+pub struct WeakHandle<A> {
+	id: Weak<u64>,
+	marker: PhantomData<A>,
+}
+
+impl<A> WeakHandle<A> {
+	pub fn upgrade(&self) -> Option<AssetHandle<A>> {
+		self.id.upgrade().map(|id| AssetHandle {
+			id,
+			marker: PhantomData,
+		})
+	}
+}
+
+#[derive(Clone, Debug, Default)]
+struct HandleAllocator {
+	highest_id: u64,
+	unused_ids: Vec<u64>,
+}
+
+impl HandleAllocator {
+	#[inline]
+	fn allocate<A: Asset>(&mut self) -> AssetHandle<A> {
+		let id = self.unused_ids.pop().unwrap_or_else(|| {
+			self.highest_id += 1;
+			self.highest_id
+		});
+
+		AssetHandle {
+			id: Arc::new(id),
+			marker: PhantomData,
+		}
+	}
+}
+
+pub trait DataSource: Send + Sync + 'static {
+	fn load(&self, path: &RelativePath) -> anyhow::Result<Vec<u8>>;
+	fn exists(&self, path: &RelativePath) -> bool;
+	fn location(&self, path: &RelativePath) -> Option<&Path>;
+	fn names<'a>(&'a self) -> Box<dyn Iterator<Item = &str> + 'a>;
+}
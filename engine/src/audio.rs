@@ -0,0 +1,729 @@
+use anyhow::Context;
+use crossbeam_channel::{select, Receiver, Sender};
+use rodio::{
+	cpal::{
+		self,
+		traits::{DeviceTrait, HostTrait},
+	},
+	OutputStream, OutputStreamHandle, Source,
+};
+use std::{
+	sync::{
+		atomic::{AtomicBool, AtomicU64, Ordering},
+		Arc, Mutex,
+	},
+	thread::Builder,
+	time::Duration,
+};
+
+/// How often the audio thread checks that its output device is still there.
+const DEVICE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+pub fn init() -> anyhow::Result<(Audio, AudioDeviceControl)> {
+	log::debug!("Spawning audio thread");
+	let (sender, receiver) = crossbeam_channel::unbounded();
+	let (device_sender, device_receiver) = crossbeam_channel::unbounded();
+	let mixer_state = Arc::new(Mutex::new(MixerState::new(receiver)));
+
+	Builder::new()
+		.name("audio".to_owned())
+		.spawn(move || audio_thread(mixer_state, device_receiver))
+		.context("Couldn't spawn audio thread")?;
+
+	Ok((
+		Audio {
+			sender,
+			next_id: Arc::new(AtomicU64::new(0)),
+		},
+		AudioDeviceControl {
+			sender: device_sender,
+			current: None,
+		},
+	))
+}
+
+/// Tells the audio thread which output device to play on.
+pub struct AudioDeviceControl {
+	sender: Sender<String>,
+	current: Option<String>,
+}
+
+impl AudioDeviceControl {
+	/// Switches to the output device with the given name, or to the system's default device
+	/// if the name is "default". Does nothing if that device was already asked for.
+	pub fn set_device(&mut self, name: &str) {
+		if self.current.as_deref() != Some(name) {
+			self.sender.send(name.to_owned()).ok();
+			self.current = Some(name.to_owned());
+		}
+	}
+}
+
+/// Returns the names of the available output devices.
+pub fn output_device_names() -> Vec<String> {
+	match cpal::default_host().output_devices() {
+		Ok(devices) => devices.filter_map(|device| device.name().ok()).collect(),
+		Err(err) => {
+			log::warn!("Couldn't list audio devices: {}", err);
+			Vec::new()
+		}
+	}
+}
+
+/// Keeps the output device open, and moves the mixer over to a new device when needed.
+/// The voices live in the shared mixer state, so they carry on playing after a switch.
+fn audio_thread(mixer_state: Arc<Mutex<MixerState>>, device_receiver: Receiver<String>) {
+	let mut device_name = String::from("default");
+	let mut output = open_output(&device_name, &mixer_state);
+
+	loop {
+		select! {
+			recv(device_receiver) -> name => match name {
+				Ok(name) => {
+					if name != device_name {
+						device_name = name;
+						drop(output.take());
+						output = open_output(&device_name, &mixer_state);
+					}
+				}
+				// The game has shut down
+				Err(_) => return,
+			},
+			default(DEVICE_CHECK_INTERVAL) => {
+				// Reconnect if the device was unplugged, or retry if there was none
+				let connected = match &output {
+					Some((current_name, _, _)) => output_device_names().contains(current_name),
+					None => false,
+				};
+
+				if !connected {
+					if output.is_some() {
+						log::warn!("Audio device disconnected");
+					}
+
+					drop(output.take());
+					output = open_output(&device_name, &mixer_state);
+				}
+
+				// Nothing is pulling commands out of the queue without a device
+				if output.is_none() {
+					mixer_state.lock().unwrap().process_commands();
+				}
+			},
+		}
+	}
+}
+
+/// Opens the output device with the given name, falling back to the default device,
+/// and starts the mixer on it. Returns the name of the device that was opened, and its stream.
+fn open_output(
+	device_name: &str,
+	mixer_state: &Arc<Mutex<MixerState>>,
+) -> Option<(String, OutputStream, OutputStreamHandle)> {
+	let host = cpal::default_host();
+	let mut device = None;
+
+	if device_name != "default" {
+		device = host.output_devices().ok().and_then(|mut devices| {
+			devices.find(|device| device.name().ok().as_deref() == Some(device_name))
+		});
+
+		if device.is_none() {
+			log::warn!(
+				"Audio device \"{}\" not found, using the default device",
+				device_name
+			);
+		}
+	}
+
+	let device = device.or_else(|| host.default_output_device())?;
+	let name = device.name().unwrap_or_default();
+
+	let (stream, stream_handle) = match OutputStream::try_from_device(&device) {
+		Ok(x) => x,
+		Err(err) => {
+			log::warn!("Couldn't open audio device \"{}\": {}", name, err);
+			return None;
+		}
+	};
+
+	if let Err(err) = stream_handle.play_raw(Mixer::new(mixer_state.clone())) {
+		log::warn!("Couldn't start the mixer on \"{}\": {}", name, err);
+		return None;
+	}
+
+	log::info!("Audio output: {}", name);
+	Some((name, stream, stream_handle))
+}
+
+/// Handle for game systems to play sounds, by sending commands to the mixer.
+#[derive(Clone)]
+pub struct Audio {
+	sender: Sender<AudioCommand>,
+	next_id: Arc<AtomicU64>,
+}
+
+impl Audio {
+	/// Starts playing a sound, with the given volumes for the left and right channel
+	/// and playback speed. The returned controller can change these while it plays.
+	pub fn play<S: Source<Item = f32> + Send + 'static>(
+		&self,
+		source: S,
+		volumes: [f32; 2],
+		pitch: f32,
+	) -> SoundController {
+		let controller = SoundController {
+			id: self.next_id.fetch_add(1, Ordering::Relaxed),
+			sender: self.sender.clone(),
+			done: Arc::new(AtomicBool::new(false)),
+		};
+
+		self.sender
+			.send(AudioCommand::Play {
+				id: controller.id,
+				source: Box::new(source),
+				volumes,
+				pitch,
+				done: controller.done.clone(),
+			})
+			.ok();
+
+		controller
+	}
+
+	/// Sets the volume of everything that plays, such as to turn the sound down
+	/// while the window isn't focused. 1.0 is full volume.
+	#[inline]
+	pub fn set_master_volume(&self, volume: f32) {
+		self.sender
+			.send(AudioCommand::SetMasterVolume { volume })
+			.ok();
+	}
+}
+
+enum AudioCommand {
+	Play {
+		id: u64,
+		source: Box<dyn Source<Item = f32> + Send>,
+		volumes: [f32; 2],
+		pitch: f32,
+		done: Arc<AtomicBool>,
+	},
+	SetVolumes {
+		id: u64,
+		volumes: [f32; 2],
+	},
+	SetPitch {
+		id: u64,
+		pitch: f32,
+	},
+	Stop {
+		id: u64,
+	},
+	SetMasterVolume {
+		volume: f32,
+	},
+}
+
+/// Controls a sound after it has started playing.
+#[derive(Clone, Debug)]
+pub struct SoundController {
+	id: u64,
+	sender: Sender<AudioCommand>,
+	done: Arc<AtomicBool>,
+}
+
+impl SoundController {
+	#[inline]
+	pub fn stop(&self) {
+		self.sender.send(AudioCommand::Stop { id: self.id }).ok();
+	}
+
+	/// Whether the sound has finished or was stopped.
+	#[inline]
+	pub fn is_done(&self) -> bool {
+		self.done.load(Ordering::Relaxed)
+	}
+
+	#[inline]
+	pub fn set_volumes(&self, volumes: [f32; 2]) {
+		self.sender
+			.send(AudioCommand::SetVolumes {
+				id: self.id,
+				volumes,
+			})
+			.ok();
+	}
+
+	/// Sets the playback speed, where 1.0 is normal speed and pitch.
+	#[inline]
+	pub fn set_pitch(&self, pitch: f32) {
+		self.sender
+			.send(AudioCommand::SetPitch { id: self.id, pitch })
+			.ok();
+	}
+}
+
+/// The most sounds that can play at once. The oldest sound is cut off beyond this.
+pub const MAX_VOICES: usize = 64;
+
+/// Sample rate that the mixer outputs at. Voices are resampled to this.
+const MIXER_SAMPLE_RATE: u32 = 44100;
+
+/// How far the playing volumes move towards the requested volumes on each sample.
+/// Smooths out the steps between game frames, which would otherwise be audible.
+const VOLUME_SMOOTHING: f32 = 0.002;
+
+/// A sound that is being mixed.
+struct Voice {
+	id: u64,
+	source: Box<dyn Source<Item = f32> + Send>,
+	/// How far to advance through the source for each output sample, at normal pitch.
+	step: f32,
+	pitch: f32,
+	position: f32,
+	current: Option<f32>,
+	next: Option<f32>,
+	volumes: [f32; 2],
+	target_volumes: [f32; 2],
+	done: Arc<AtomicBool>,
+}
+
+impl Voice {
+	/// Reads the next frame of the source, mixed down to mono.
+	fn read_frame(&mut self) -> Option<f32> {
+		let channels = self.source.channels().max(1);
+		let mut sum = self.source.next()?;
+
+		for _ in 1..channels {
+			sum += self.source.next().unwrap_or(0.0);
+		}
+
+		Some(sum / channels as f32)
+	}
+
+	/// Returns the next output frame, or `None` if the voice has finished.
+	#[inline]
+	fn next_frame(&mut self) -> Option<[f32; 2]> {
+		while self.position >= 1.0 {
+			self.position -= 1.0;
+			self.current = self.next.take();
+			self.next = self.read_frame();
+		}
+
+		let current = self.current?;
+		let next = self.next.unwrap_or(current);
+		let sample = current + (next - current) * self.position;
+		self.position += self.step * self.pitch;
+
+		for (volume, target) in self.volumes.iter_mut().zip(&self.target_volumes) {
+			*volume += (target - *volume) * VOLUME_SMOOTHING;
+		}
+
+		Some([sample * self.volumes[0], sample * self.volumes[1]])
+	}
+}
+
+/// The voices and command queue, shared between the mixer and the audio thread.
+struct MixerState {
+	commands: Receiver<AudioCommand>,
+	voices: Vec<Voice>,
+	master_volume: f32,
+	target_master_volume: f32,
+}
+
+impl MixerState {
+	fn new(commands: Receiver<AudioCommand>) -> MixerState {
+		MixerState {
+			commands,
+			voices: Vec::with_capacity(MAX_VOICES),
+			master_volume: 1.0,
+			target_master_volume: 1.0,
+		}
+	}
+
+	fn process_commands(&mut self) {
+		while let Ok(command) = self.commands.try_recv() {
+			match command {
+				AudioCommand::Play {
+					id,
+					source,
+					volumes,
+					pitch,
+					done,
+				} => {
+					if self.voices.len() >= MAX_VOICES {
+						let voice = self.voices.remove(0);
+						voice.done.store(true, Ordering::Relaxed);
+					}
+
+					let step = source.sample_rate() as f32 / MIXER_SAMPLE_RATE as f32;
+					let mut voice = Voice {
+						id,
+						source,
+						step,
+						pitch,
+						position: 0.0,
+						current: None,
+						next: None,
+						volumes,
+						target_volumes: volumes,
+						done,
+					};
+
+					// Prime the interpolation with the first two frames
+					voice.current = voice.read_frame();
+					voice.next = voice.read_frame();
+					self.voices.push(voice);
+				}
+				AudioCommand::SetVolumes { id, volumes } => {
+					if let Some(voice) = self.voices.iter_mut().find(|voice| voice.id == id) {
+						voice.target_volumes = volumes;
+					}
+				}
+				AudioCommand::SetPitch { id, pitch } => {
+					if let Some(voice) = self.voices.iter_mut().find(|voice| voice.id == id) {
+						voice.pitch = pitch;
+					}
+				}
+				AudioCommand::Stop { id } => {
+					if let Some(index) = self.voices.iter().position(|voice| voice.id == id) {
+						let voice = self.voices.remove(index);
+						voice.done.store(true, Ordering::Relaxed);
+					}
+				}
+				AudioCommand::SetMasterVolume { volume } => {
+					self.target_master_volume = volume;
+				}
+			}
+		}
+	}
+
+	/// Mixes the next output frame from all voices.
+	fn mix_frame(&mut self) -> [f32; 2] {
+		// Commands are applied between frames, so stops are sample-accurate
+		self.process_commands();
+
+		let mut output = [0.0; 2];
+		let mut i = 0;
+
+		while i < self.voices.len() {
+			match self.voices[i].next_frame() {
+				Some(frame) => {
+					output[0] += frame[0];
+					output[1] += frame[1];
+					i += 1;
+				}
+				None => {
+					// Keep the order, so that the oldest voice is always first
+					let voice = self.voices.remove(i);
+					voice.done.store(true, Ordering::Relaxed);
+				}
+			}
+		}
+
+		self.master_volume += (self.target_master_volume - self.master_volume) * VOLUME_SMOOTHING;
+		output[0] *= self.master_volume;
+		output[1] *= self.master_volume;
+
+		[output[0].max(-1.0).min(1.0), output[1].max(-1.0).min(1.0)]
+	}
+}
+
+/// How many frames the mixer renders at a time, while holding the lock on the mixer state.
+const MIXER_BLOCK_FRAMES: usize = 64;
+
+/// Mixes all playing voices into a stereo output.
+/// It never ends, and plays silence when there is nothing to play.
+struct Mixer {
+	state: Arc<Mutex<MixerState>>,
+	buffer: Vec<f32>,
+	position: usize,
+}
+
+impl Mixer {
+	fn new(state: Arc<Mutex<MixerState>>) -> Mixer {
+		Mixer {
+			state,
+			buffer: Vec::with_capacity(MIXER_BLOCK_FRAMES * 2),
+			position: 0,
+		}
+	}
+}
+
+impl Source for Mixer {
+	#[inline]
+	fn current_frame_len(&self) -> Option<usize> {
+		None
+	}
+
+	#[inline]
+	fn channels(&self) -> u16 {
+		2
+	}
+
+	#[inline]
+	fn sample_rate(&self) -> u32 {
+		MIXER_SAMPLE_RATE
+	}
+
+	#[inline]
+	fn total_duration(&self) -> Option<Duration> {
+		None
+	}
+}
+
+impl Iterator for Mixer {
+	type Item = f32;
+
+	#[inline]
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.position >= self.buffer.len() {
+			let mut state = self.state.lock().unwrap();
+			self.buffer.clear();
+			self.position = 0;
+
+			for _ in 0..MIXER_BLOCK_FRAMES {
+				self.buffer.extend_from_slice(&state.mix_frame());
+			}
+		}
+
+		let sample = self.buffer[self.position];
+		self.position += 1;
+		Some(sample)
+	}
+}
+
+pub struct Sound {
+	pub data: Arc<[i16]>,
+	pub sample_rate: u32,
+}
+
+#[derive(Clone)]
+pub struct SoundSource {
+	current: usize,
+	data: Arc<[i16]>,
+	duration: Duration,
+	sample_rate: u32,
+}
+
+impl SoundSource {
+	pub fn new(sound: &Sound) -> Self {
+		let duration_ns = 1_000_000_000u64
+			.checked_mul(sound.data.len() as u64)
+			.unwrap() / sound.sample_rate as u64;
+		let duration = Duration::new(
+			duration_ns / 1_000_000_000,
+			(duration_ns % 1_000_000_000) as u32,
+		);
+
+		let sample_rate = sound.sample_rate;
+
+		SoundSource {
+			current: 0,
+			data: sound.data.clone(),
+			duration,
+			sample_rate,
+		}
+	}
+}
+
+impl Source for SoundSource {
+	#[inline]
+	fn current_frame_len(&self) -> Option<usize> {
+		None
+	}
+
+	#[inline]
+	fn channels(&self) -> u16 {
+		1
+	}
+
+	#[inline]
+	fn sample_rate(&self) -> u32 {
+		self.sample_rate
+	}
+
+	#[inline]
+	fn total_duration(&self) -> Option<Duration> {
+		Some(self.duration)
+	}
+}
+
+impl Iterator for SoundSource {
+	type Item = i16;
+
+	#[inline]
+	fn next(&mut self) -> Option<Self::Item> {
+		let item = self.data.get(self.current);
+		self.current += 1;
+		item.copied()
+	}
+
+	#[inline]
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		let len = self.data.len();
+		(len, Some(len))
+	}
+}
+
+/// Adds echoes to a source, by feeding a delayed copy of the output back into it.
+/// Keeps playing after the source ends, until the echoes have died down.
+pub struct Reverb<S> {
+	inner: S,
+	buffer: Vec<f32>,
+	position: usize,
+	feedback: f32,
+	mix: f32,
+	tail_left: usize,
+}
+
+impl<S: Source<Item = f32>> Reverb<S> {
+	/// `feedback` is how much of each echo is kept for the next one,
+	/// `mix` is the volume of the echoes relative to the original sound.
+	pub fn new(inner: S, delay: Duration, feedback: f32, mix: f32) -> Self {
+		let feedback = feedback.max(0.0).min(0.95);
+		let frames = (delay.as_secs_f32() * inner.sample_rate() as f32) as usize;
+		let buffer_len = frames.max(1) * inner.channels() as usize;
+
+		// Stop once the echoes have faded to -60 dB
+		let echoes = if feedback > 0.0 {
+			(0.001f32.ln() / feedback.ln()).ceil() as usize
+		} else {
+			1
+		};
+
+		Reverb {
+			inner,
+			buffer: vec![0.0; buffer_len],
+			position: 0,
+			feedback,
+			mix,
+			tail_left: buffer_len * echoes,
+		}
+	}
+}
+
+impl<S: Source<Item = f32>> Source for Reverb<S> {
+	#[inline]
+	fn current_frame_len(&self) -> Option<usize> {
+		None
+	}
+
+	#[inline]
+	fn channels(&self) -> u16 {
+		self.inner.channels()
+	}
+
+	#[inline]
+	fn sample_rate(&self) -> u32 {
+		self.inner.sample_rate()
+	}
+
+	#[inline]
+	fn total_duration(&self) -> Option<Duration> {
+		None
+	}
+}
+
+impl<S: Source<Item = f32>> Iterator for Reverb<S> {
+	type Item = f32;
+
+	#[inline]
+	fn next(&mut self) -> Option<Self::Item> {
+		let input = match self.inner.next() {
+			Some(sample) => sample,
+			None if self.tail_left > 0 => {
+				self.tail_left -= 1;
+				0.0
+			}
+			None => return None,
+		};
+
+		let delayed = self.buffer[self.position];
+		self.buffer[self.position] = input + delayed * self.feedback;
+		self.position = (self.position + 1) % self.buffer.len();
+
+		Some(input + delayed * self.mix)
+	}
+}
+
+/// Muffles a source by cutting off high frequencies, with a second order (biquad) filter.
+pub struct LowPass<S> {
+	inner: S,
+	coefficients: [f32; 5],
+	/// The last two inputs and outputs, for each channel.
+	history: Vec<[f32; 4]>,
+	channel: usize,
+}
+
+impl<S: Source<Item = f32>> LowPass<S> {
+	/// Frequencies above `cutoff`, in Hz, are reduced.
+	pub fn new(inner: S, cutoff: f32) -> Self {
+		const Q: f32 = std::f32::consts::FRAC_1_SQRT_2;
+
+		let sample_rate = inner.sample_rate() as f32;
+		let cutoff = cutoff.max(1.0).min(sample_rate * 0.45);
+		let w0 = 2.0 * std::f32::consts::PI * cutoff / sample_rate;
+		let alpha = w0.sin() / (2.0 * Q);
+		let cos = w0.cos();
+
+		let a0 = 1.0 + alpha;
+		let coefficients = [
+			(1.0 - cos) * 0.5 / a0,
+			(1.0 - cos) / a0,
+			(1.0 - cos) * 0.5 / a0,
+			-2.0 * cos / a0,
+			(1.0 - alpha) / a0,
+		];
+
+		LowPass {
+			history: vec![[0.0; 4]; inner.channels() as usize],
+			inner,
+			coefficients,
+			channel: 0,
+		}
+	}
+}
+
+impl<S: Source<Item = f32>> Source for LowPass<S> {
+	#[inline]
+	fn current_frame_len(&self) -> Option<usize> {
+		self.inner.current_frame_len()
+	}
+
+	#[inline]
+	fn channels(&self) -> u16 {
+		self.inner.channels()
+	}
+
+	#[inline]
+	fn sample_rate(&self) -> u32 {
+		self.inner.sample_rate()
+	}
+
+	#[inline]
+	fn total_duration(&self) -> Option<Duration> {
+		self.inner.total_duration()
+	}
+}
+
+impl<S: Source<Item = f32>> Iterator for LowPass<S> {
+	type Item = f32;
+
+	#[inline]
+	fn next(&mut self) -> Option<Self::Item> {
+		let input = self.inner.next()?;
+		let [b0, b1, b2, a1, a2] = self.coefficients;
+		let [x1, x2, y1, y2] = &mut self.history[self.channel];
+
+		let output = b0 * input + b1 * *x1 + b2 * *x2 - a1 * *y1 - a2 * *y2;
+		*x2 = *x1;
+		*x1 = input;
+		*y2 = *y1;
+		*y1 = output;
+
+		self.channel = (self.channel + 1) % self.history.len();
+		Some(output)
+	}
+}
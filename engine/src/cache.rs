@@ -0,0 +1,96 @@
+use anyhow::Context;
+use fnv::FnvHasher;
+use std::{
+	fmt,
+	hash::Hasher,
+	path::{Path, PathBuf},
+};
+
+/// Increase this whenever the format of anything that is cached changes,
+/// so that old files are no longer used.
+const CACHE_VERSION: u32 = 1;
+
+/// A directory of assets that took a while to make, so that they can be read back
+/// the next time instead of made again.
+///
+/// Entries are found by a key made from the data they were made from, so a change to that data
+/// simply leads to a different entry. Nothing is ever removed; deleting the directory is safe.
+#[derive(Clone, Debug)]
+pub struct DiskCache {
+	dir: PathBuf,
+}
+
+/// Identifies an entry in a `DiskCache`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+	kind: &'static str,
+	hash: u64,
+}
+
+impl CacheKey {
+	/// Makes a key for an entry of the given kind, made from `sources`.
+	pub fn new(kind: &'static str, sources: &[&[u8]]) -> CacheKey {
+		let mut hasher = FnvHasher::default();
+
+		for source in sources {
+			// Include the lengths, so that the boundaries between the sources count
+			hasher.write_u64(source.len() as u64);
+			hasher.write(source);
+		}
+
+		CacheKey {
+			kind,
+			hash: hasher.finish(),
+		}
+	}
+}
+
+impl fmt::Display for CacheKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}-{:016x}", self.kind, self.hash)
+	}
+}
+
+impl DiskCache {
+	/// Uses the cache in `dir`, creating it if needed.
+	pub fn new(dir: &Path) -> anyhow::Result<DiskCache> {
+		let dir = dir.join(format!("v{}", CACHE_VERSION));
+		std::fs::create_dir_all(&dir)
+			.with_context(|| format!("Couldn't create cache directory {}", dir.display()))?;
+		Ok(DiskCache { dir })
+	}
+
+	/// Returns the data of an entry, if there is one.
+	pub fn load(&self, key: CacheKey) -> Option<Vec<u8>> {
+		let path = self.dir.join(key.to_string());
+
+		match std::fs::read(&path) {
+			Ok(data) => {
+				log::trace!("Loaded {} from the cache", key);
+				Some(data)
+			}
+			Err(err) => {
+				if err.kind() != std::io::ErrorKind::NotFound {
+					log::warn!("Couldn't read {}: {}", path.display(), err);
+				}
+
+				None
+			}
+		}
+	}
+
+	/// Writes an entry. A failure is only logged, since the cache isn't needed to carry on.
+	pub fn store(&self, key: CacheKey, data: &[u8]) {
+		// Write to another file first, so that a crash or another instance never sees half a file
+		let path = self.dir.join(key.to_string());
+		let temp_path = path.with_extension(format!("tmp{}", std::process::id()));
+
+		let result =
+			std::fs::write(&temp_path, data).and_then(|_| std::fs::rename(&temp_path, &path));
+
+		if let Err(err) = result {
+			log::warn!("Couldn't write {}: {}", path.display(), err);
+			std::fs::remove_file(&temp_path).ok();
+		}
+	}
+}
@@ -94,8 +94,8 @@ pub fn tokenize(mut text: &str) -> anyhow::Result<Vec<String>> {
 		// Whitespace, except newlines
 		static ref RE_SPACE    : Regex = Regex::new(r#"^[^\S\n]+"#).unwrap();
 
-		// C identifier or number literal
-		static ref RE_UNQUOTED : Regex = Regex::new(r#"^[+-]?[.0-9A-Za-z_]+"#).unwrap();
+		// C identifier, number literal or network address
+		static ref RE_UNQUOTED : Regex = Regex::new(r#"^[+-]?[.:0-9A-Za-z_]+"#).unwrap();
 
 		// Quoted string, with escapes
 		static ref RE_QUOTED   : Regex = Regex::new(r#"^"(?:[^"\\]*(?:\\.)?)*""#).unwrap();
@@ -0,0 +1,225 @@
+use crate::commands::tokenize;
+use anyhow::{anyhow, bail, Context};
+use downcast_rs::{impl_downcast, Downcast};
+use std::{collections::BTreeMap, fmt, fmt::Write, path::Path};
+
+pub trait ConfigValue: Clone + PartialEq + fmt::Display + Send + Sync + 'static {
+	fn parse(value: &str) -> anyhow::Result<Self>;
+}
+
+impl ConfigValue for bool {
+	fn parse(value: &str) -> anyhow::Result<Self> {
+		match value.to_ascii_lowercase().as_str() {
+			"1" | "true" | "on" | "yes" => Ok(true),
+			"0" | "false" | "off" | "no" => Ok(false),
+			_ => bail!("\"{}\" is not a boolean value", value),
+		}
+	}
+}
+
+impl ConfigValue for f32 {
+	fn parse(value: &str) -> anyhow::Result<Self> {
+		Ok(value.parse()?)
+	}
+}
+
+impl ConfigValue for i32 {
+	fn parse(value: &str) -> anyhow::Result<Self> {
+		Ok(value.parse()?)
+	}
+}
+
+impl ConfigValue for u32 {
+	fn parse(value: &str) -> anyhow::Result<Self> {
+		Ok(value.parse()?)
+	}
+}
+
+impl ConfigValue for String {
+	fn parse(value: &str) -> anyhow::Result<Self> {
+		Ok(value.to_owned())
+	}
+}
+
+pub struct ConfigVariable<T> {
+	name: &'static str,
+	value: T,
+	default: T,
+	validator: Option<Box<dyn Fn(&T) -> bool + Send + Sync>>,
+	archived: bool,
+}
+
+impl<T: ConfigValue> ConfigVariable<T> {
+	pub fn new(name: &'static str, default: T) -> ConfigVariable<T> {
+		ConfigVariable {
+			name,
+			value: default.clone(),
+			default,
+			validator: None,
+			archived: false,
+		}
+	}
+
+	/// Marks the variable as a setting, which is written to the config file on exit.
+	pub fn archived(mut self) -> ConfigVariable<T> {
+		self.archived = true;
+		self
+	}
+
+	pub fn with_validator(
+		mut self,
+		validator: impl Fn(&T) -> bool + Send + Sync + 'static,
+	) -> ConfigVariable<T> {
+		assert!(validator(&self.default));
+		self.validator = Some(Box::new(validator));
+		self
+	}
+
+	pub fn get(&self) -> &T {
+		&self.value
+	}
+
+	pub fn set(&mut self, new_value: T) -> anyhow::Result<()> {
+		if let Some(validator) = &self.validator {
+			if !validator(&new_value) {
+				bail!("\"{}\" is not a valid value for {}", new_value, self.name);
+			}
+		}
+
+		self.value = new_value;
+		Ok(())
+	}
+}
+
+impl<T: fmt::Display> fmt::Display for ConfigVariable<T> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.value.fmt(f)
+	}
+}
+
+pub trait ConfigVariableT: Downcast + fmt::Display + Send + Sync {
+	fn name(&self) -> &'static str;
+	fn is_archived(&self) -> bool;
+	fn set_string(&mut self, value: &str) -> anyhow::Result<()>;
+}
+impl_downcast!(ConfigVariableT);
+
+impl<T: ConfigValue> ConfigVariableT for ConfigVariable<T> {
+	fn name(&self) -> &'static str {
+		self.name
+	}
+
+	fn is_archived(&self) -> bool {
+		self.archived
+	}
+
+	fn set_string(&mut self, value: &str) -> anyhow::Result<()> {
+		self.set(T::parse(value)?)
+	}
+}
+
+/// All the config variables of the game, looked up by name.
+/// Registering the same name twice, or getting a variable with the wrong type, is a bug
+/// and will panic.
+#[derive(Default)]
+pub struct ConfigVariables {
+	variables: BTreeMap<&'static str, Box<dyn ConfigVariableT>>,
+}
+
+impl ConfigVariables {
+	pub fn new() -> ConfigVariables {
+		ConfigVariables::default()
+	}
+
+	pub fn add<T: ConfigValue>(&mut self, variable: ConfigVariable<T>) {
+		if let Some(old) = self.variables.insert(variable.name, Box::new(variable)) {
+			panic!("Duplicate config variable name: {}", old.name());
+		}
+	}
+
+	pub fn get<T: ConfigValue>(&self, name: &str) -> T {
+		self.variable::<T>(name).get().clone()
+	}
+
+	pub fn contains(&self, name: &str) -> bool {
+		self.variables.contains_key(name)
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = &dyn ConfigVariableT> {
+		self.variables.values().map(|v| v.as_ref())
+	}
+
+	/// Handles a console command naming a config variable, either printing or setting it.
+	pub fn command(&mut self, args: &[String]) -> anyhow::Result<()> {
+		let variable = self
+			.variables
+			.get_mut(args[0].as_str())
+			.ok_or_else(|| anyhow!("Unknown config variable: {}", args[0]))?;
+
+		match args.get(1) {
+			Some(value) => variable.set_string(value)?,
+			None => log::info!("\"{}\" is \"{}\"", variable.name(), variable),
+		}
+
+		Ok(())
+	}
+
+	/// Sets archived variables from a config file written by `write_config`.
+	/// A missing file is not an error, there's simply nothing to read yet.
+	/// Lines that can't be used are logged and skipped, so that one bad value
+	/// doesn't lose all the others.
+	pub fn read_config(&mut self, path: &Path) -> anyhow::Result<()> {
+		let text = match std::fs::read_to_string(path) {
+			Ok(text) => text,
+			Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+			Err(err) => {
+				return Err(err).with_context(|| format!("Couldn't read {}", path.display()))
+			}
+		};
+		let tokens =
+			tokenize(&text).with_context(|| format!("Couldn't parse {}", path.display()))?;
+
+		for args in tokens.split(|token| token == ";") {
+			let result = match args {
+				[] => continue,
+				[name, value] => match self.variables.get_mut(name.as_str()) {
+					Some(variable) if variable.is_archived() => variable.set_string(value),
+					Some(_) => Err(anyhow!("{} is not a saved setting", name)),
+					None => Err(anyhow!("Unknown config variable: {}", name)),
+				},
+				_ => Err(anyhow!("Expected a name and a value: {}", args.join(" "))),
+			};
+
+			if let Err(err) = result {
+				log::warn!("{}: {}", path.display(), err);
+			}
+		}
+
+		log::info!("Read config from {}", path.display());
+		Ok(())
+	}
+
+	/// Writes the archived variables to a config file, in the same form as console commands.
+	pub fn write_config(&self, path: &Path) -> anyhow::Result<()> {
+		let mut text = String::new();
+
+		for variable in self.iter().filter(|v| v.is_archived()) {
+			let value = variable
+				.to_string()
+				.replace('\\', "\\\\")
+				.replace('"', "\\\"");
+			writeln!(text, "{} \"{}\"", variable.name(), value)?;
+		}
+
+		std::fs::write(path, text).with_context(|| format!("Couldn't write {}", path.display()))?;
+		log::info!("Wrote config to {}", path.display());
+		Ok(())
+	}
+
+	fn variable<T: ConfigValue>(&self, name: &str) -> &ConfigVariable<T> {
+		self.variables
+			.get(name)
+			.and_then(|v| v.downcast_ref::<ConfigVariable<T>>())
+			.unwrap_or_else(|| panic!("No config variable {} of the requested type", name))
+	}
+}
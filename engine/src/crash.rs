@@ -0,0 +1,89 @@
+use crate::logger;
+use lazy_static::lazy_static;
+use std::{
+	backtrace::Backtrace,
+	fmt::Write as FmtWrite,
+	fs,
+	panic::{self, PanicHookInfo},
+	path::PathBuf,
+	sync::Mutex,
+};
+
+const CRASH_FILE: &str = "crash.txt";
+
+/// What the game was doing, to include in crash reports.
+#[derive(Default)]
+struct CrashInfo {
+	program: &'static str,
+	wads: Vec<PathBuf>,
+	map: Option<String>,
+	release_input: Option<Box<dyn Fn() + Send>>,
+}
+
+lazy_static! {
+	static ref CRASH_INFO: Mutex<CrashInfo> = Mutex::new(CrashInfo::default());
+}
+
+/// Installs a panic hook that writes a crash report before the default hook runs.
+/// `program` is the name and version of the program, to put at the top of the report.
+pub fn init(program: &'static str) {
+	CRASH_INFO.lock().unwrap().program = program;
+	let default_hook = panic::take_hook();
+
+	panic::set_hook(Box::new(move |info| {
+		// Locks are only tried, in case the panic happened while one was held
+		if let Ok(crash_info) = CRASH_INFO.try_lock() {
+			if let Some(release_input) = &crash_info.release_input {
+				release_input();
+			}
+
+			match fs::write(CRASH_FILE, report(info, &crash_info)) {
+				Ok(()) => eprintln!("A crash report was written to {}", CRASH_FILE),
+				Err(err) => eprintln!("Couldn't write crash report: {}", err),
+			}
+		}
+
+		default_hook(info);
+	}));
+}
+
+/// Sets the WAD files that are in use.
+pub fn set_wads(wads: Vec<PathBuf>) {
+	CRASH_INFO.lock().unwrap().wads = wads;
+}
+
+/// Sets the map that is currently loaded, if any.
+pub fn set_map(map: Option<String>) {
+	CRASH_INFO.lock().unwrap().map = map;
+}
+
+/// Sets a function that gives the mouse back to the user, to be called when crashing.
+pub fn set_release_input(release_input: impl Fn() + Send + 'static) {
+	CRASH_INFO.lock().unwrap().release_input = Some(Box::new(release_input));
+}
+
+fn report(info: &PanicHookInfo, crash_info: &CrashInfo) -> String {
+	let mut report = String::new();
+
+	writeln!(report, "{} crashed: {}", crash_info.program, info).ok();
+	writeln!(
+		report,
+		"\nMap: {}",
+		crash_info.map.as_deref().unwrap_or("none")
+	)
+	.ok();
+
+	writeln!(report, "\nWADs:").ok();
+	for wad in crash_info.wads.iter() {
+		writeln!(report, "    {}", wad.display()).ok();
+	}
+
+	writeln!(report, "\nRecent log:").ok();
+	for line in logger::recent_lines() {
+		writeln!(report, "    {}", line).ok();
+	}
+
+	writeln!(report, "\nBacktrace:\n{}", Backtrace::force_capture()).ok();
+
+	report
+}
@@ -0,0 +1,142 @@
+use crate::profiler;
+use fnv::FnvHashMap;
+use legion::{Resources, World};
+use shrev::{Event, EventChannel, EventIterator, ReaderId};
+
+/// How many frames a reader can go without reading before its unread events are thrown away.
+const STALE_FRAMES: u64 = 35;
+
+/// A channel of events of one type, along with a reader for each system that reads them.
+/// Readers are kept by name, so a system that is created again takes over its old reader
+/// instead of leaving it behind.
+pub struct Events<T: Event> {
+	channel: EventChannel<T>,
+	readers: FnvHashMap<&'static str, Reader<T>>,
+	frame: u64,
+}
+
+struct Reader<T: Event> {
+	id: ReaderId<T>,
+	last_read: u64,
+}
+
+impl<T: Event> Default for Events<T> {
+	fn default() -> Events<T> {
+		Events {
+			channel: EventChannel::new(),
+			readers: FnvHashMap::default(),
+			frame: 0,
+		}
+	}
+}
+
+impl<T: Event> Events<T> {
+	/// Adds a reader with the given name, which sees all events written from now on.
+	/// If there was already one with that name, it's replaced.
+	pub fn register(&mut self, name: &'static str) {
+		self.readers.insert(
+			name,
+			Reader {
+				id: self.channel.register_reader(),
+				last_read: self.frame,
+			},
+		);
+	}
+
+	pub fn unregister(&mut self, name: &'static str) {
+		self.readers.remove(name);
+	}
+
+	#[inline]
+	pub fn single_write(&mut self, event: T) {
+		self.channel.single_write(event);
+	}
+
+	#[inline]
+	pub fn iter_write<I>(&mut self, events: I)
+	where
+		I: IntoIterator<Item = T>,
+		I::IntoIter: ExactSizeIterator,
+	{
+		self.channel.iter_write(events);
+	}
+
+	/// Returns the events that the named reader hasn't seen yet.
+	/// A reader that wasn't registered is registered now, so it sees nothing the first time.
+	pub fn read(&mut self, name: &'static str) -> EventIterator<T> {
+		let Events {
+			channel,
+			readers,
+			frame,
+		} = self;
+		let reader = readers.entry(name).or_insert_with(|| Reader {
+			id: channel.register_reader(),
+			last_read: *frame,
+		});
+		reader.last_read = *frame;
+		channel.read(&mut reader.id)
+	}
+
+	/// Throws away the unread events of readers that haven't read in a while, so that a system
+	/// that stopped running doesn't make the channel keep its events forever.
+	fn maintain(&mut self) {
+		self.frame += 1;
+
+		for (name, reader) in self.readers.iter_mut() {
+			if self.frame - reader.last_read > STALE_FRAMES {
+				let count = self.channel.read(&mut reader.id).count();
+
+				if count > 0 {
+					log::trace!("Dropped {} stale events for \"{}\"", count, name);
+				}
+			}
+		}
+	}
+}
+
+/// The event types that have been added, so that they can all be maintained.
+#[derive(Default)]
+pub struct EventBus {
+	maintain: Vec<fn(&Resources)>,
+}
+
+/// Adds an `Events<T>` resource, if there isn't one already.
+pub fn add_events<T: Event>(resources: &mut Resources) {
+	if resources.contains::<Events<T>>() {
+		return;
+	}
+
+	resources.insert(Events::<T>::default());
+	resources
+		.get_mut_or_default::<EventBus>()
+		.maintain
+		.push(maintain_events::<T>);
+}
+
+/// Adds a named reader for events of type `T`, adding the events first if needed.
+pub fn register_reader<T: Event>(resources: &mut Resources, name: &'static str) {
+	add_events::<T>(resources);
+	resources.get_mut::<Events<T>>().unwrap().register(name);
+}
+
+fn maintain_events<T: Event>(resources: &Resources) {
+	if let Some(mut events) = resources.get_mut::<Events<T>>() {
+		events.maintain();
+	}
+}
+
+/// Runs once per game frame, after all systems that use events.
+pub fn event_maintenance_system() -> Box<dyn FnMut(&mut World, &mut Resources)> {
+	Box::new(|_world, resources| {
+		let _profile = profiler::scope("event_maintenance_system");
+
+		let maintain = match resources.get::<EventBus>() {
+			Some(event_bus) => event_bus.maintain.clone(),
+			None => return,
+		};
+
+		for maintain in maintain {
+			maintain(resources);
+		}
+	})
+}
@@ -1,4 +1,7 @@
-use crate::common::spawn::{ComponentAccessor, SpawnFrom};
+use crate::{
+	profiler,
+	spawn::{ComponentAccessor, SpawnFrom},
+};
 use legion::{
 	systems::{ResourceSet, Runnable},
 	IntoQuery, Read, Resources, SystemBuilder,
@@ -16,6 +19,14 @@ pub struct FrameState {
 	pub rng: Mutex<FrameRng>,
 }
 
+/// How far the current time is between the previous game frame and the next, from 0 to 1.
+/// Things that are drawn more often than the game updates are interpolated by this much
+/// between their previous and current state.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FrameInterpolation {
+	pub fraction: f32,
+}
+
 #[derive(Clone, Copy, Debug, Default)]
 pub struct FrameRngDef;
 
@@ -38,6 +49,8 @@ pub fn frame_state_system(frame_time: Duration) -> impl Runnable {
 		.write_resource::<FrameState>()
 		.with_query(<&mut FrameRng>::query())
 		.build(move |_, world, frame_state, query| {
+			let _profile = profiler::scope("frame_rng_system");
+
 			frame_state.delta_time = frame_time;
 			frame_state.time += frame_time;
 
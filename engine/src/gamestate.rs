@@ -0,0 +1,74 @@
+use std::fmt;
+
+/// The overall state that the game application is in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GameState {
+	Startup,
+	Title,
+	Loading { map: String },
+	Gameplay,
+	Intermission,
+	Finale,
+	Paused,
+}
+
+impl GameState {
+	/// Whether the game world is simulated in this state.
+	pub fn runs_world(&self) -> bool {
+		*self == GameState::Gameplay
+	}
+
+	/// Whether there is a level loaded in this state.
+	pub fn has_world(&self) -> bool {
+		*self == GameState::Gameplay || *self == GameState::Paused
+	}
+}
+
+impl fmt::Display for GameState {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			GameState::Startup => write!(f, "startup"),
+			GameState::Title => write!(f, "title"),
+			GameState::Loading { map } => write!(f, "loading {}", map),
+			GameState::Gameplay => write!(f, "gameplay"),
+			GameState::Intermission => write!(f, "intermission"),
+			GameState::Finale => write!(f, "finale"),
+			GameState::Paused => write!(f, "paused"),
+		}
+	}
+}
+
+/// Keeps track of the current game state.
+/// Changes are queued up, so that they always happen at the same point in the frame,
+/// with the exit hook of the old state running before the enter hook of the new one.
+#[derive(Debug)]
+pub struct GameStateMachine {
+	current: GameState,
+	queued: Option<GameState>,
+}
+
+impl GameStateMachine {
+	pub fn new() -> GameStateMachine {
+		GameStateMachine {
+			current: GameState::Startup,
+			queued: None,
+		}
+	}
+
+	pub fn current(&self) -> &GameState {
+		&self.current
+	}
+
+	/// Queues a change to a new state. If another change was already queued, it is replaced.
+	pub fn set(&mut self, state: GameState) {
+		self.queued = Some(state);
+	}
+
+	/// Switches to the queued state if there is one, returning the old and new states.
+	pub fn take_transition(&mut self) -> Option<(GameState, GameState)> {
+		let new_state = self.queued.take()?;
+		let old_state = std::mem::replace(&mut self.current, new_state.clone());
+		log::debug!("Game state: {} -> {}", old_state, new_state);
+		Some((old_state, new_state))
+	}
+}
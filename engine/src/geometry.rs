@@ -1,6 +1,6 @@
 use nalgebra::{
 	allocator::Allocator, storage::Owned, DefaultAllocator, DimName, Matrix4, Vector2, Vector3,
-	VectorN, U2, U3,
+	Vector4, VectorN, U2, U3,
 };
 use num_traits::identities::Zero;
 
@@ -183,10 +183,10 @@ impl Interval {
 		self.min >= self.max
 	}
 
-	/*#[inline]
+	#[inline]
 	pub fn is_inside(self, other: Interval) -> bool {
 		self.min >= other.min && self.max <= other.max
-	}*/
+	}
 
 	/*#[inline]
 	pub fn normalize(self) -> Interval {
@@ -208,10 +208,10 @@ impl Interval {
 		}
 	}
 
-	/*#[inline]
-	pub fn contains(&self, value: f32) -> bool {
+	#[inline]
+	pub fn contains(self, value: f32) -> bool {
 		self.min <= value && self.max >= value
-	}*/
+	}
 
 	#[inline]
 	pub fn intersection(self, other: Interval) -> Interval {
@@ -221,6 +221,15 @@ impl Interval {
 		}
 	}
 
+	/// Linear interpolation from `self` to `other`.
+	#[inline]
+	pub fn lerp(self, other: Interval, t: f32) -> Interval {
+		Interval {
+			min: self.min + (other.min - self.min) * t,
+			max: self.max + (other.max - self.max) * t,
+		}
+	}
+
 	#[inline]
 	pub fn middle(self) -> f32 {
 		0.5 * (self.min + self.max)
@@ -666,6 +675,26 @@ pub fn perspective_matrix(fovx: f32, aspect: f32, depth_range: Interval) -> Matr
 	)
 }
 
+/// Extracts the planes bounding the view frustum of a combined projection and view matrix.
+/// The normals point into the frustum.
+pub fn frustum_planes(matrix: &Matrix4<f32>) -> [Plane3; 6] {
+	let row = |i: usize| matrix.row(i).transpose();
+	let plane = |v: Vector4<f32>| {
+		let normal = Vector3::new(v[0], v[1], v[2]);
+		let norm = normal.norm();
+		Plane3::new(-v[3] / norm, normal / norm)
+	};
+
+	[
+		plane(row(3) + row(0)),
+		plane(row(3) - row(0)),
+		plane(row(3) + row(1)),
+		plane(row(3) - row(1)),
+		plane(row(2)),
+		plane(row(3) - row(2)),
+	]
+}
+
 #[rustfmt::skip]
 pub fn ortho_matrix(bbox: AABB3) -> Matrix4<f32> {
 	let rml = bbox[0].max - bbox[0].min;
@@ -1,7 +1,10 @@
 use fnv::FnvHashMap;
 use std::{fmt::Debug, hash::Hash};
-use winit::event::{
-	DeviceEvent, ElementState, Event, KeyboardInput, MouseButton, VirtualKeyCode, WindowEvent,
+use winit::{
+	event::{
+		DeviceEvent, ElementState, Event, KeyboardInput, MouseButton, VirtualKeyCode, WindowEvent,
+	},
+	window::Window,
 };
 
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
@@ -39,6 +42,9 @@ pub struct InputState {
 	mouse_delta_enabled: bool,
 	pressed_keys: Vec<VirtualKeyCode>,
 	pressed_mouse_buttons: Vec<MouseButton>,
+	/// Set when the window gets focus back. The first mouse movement after that can be
+	/// a jump from wherever the cursor was outside the window, so it's thrown away.
+	skip_mouse_delta: bool,
 }
 
 impl InputState {
@@ -48,6 +54,7 @@ impl InputState {
 			mouse_delta_enabled: false,
 			pressed_keys: Vec::new(),
 			pressed_mouse_buttons: Vec::new(),
+			skip_mouse_delta: false,
 		}
 	}
 
@@ -77,7 +84,10 @@ impl InputState {
 		}
 	}
 
-	pub fn process_event(&mut self, event: &Event<()>) {
+	/// Updates the state with an event from the window.
+	/// Returns the button that the event pressed, unless it was already down,
+	/// so that held keys that repeat are only reported once.
+	pub fn process_event(&mut self, event: &Event<()>) -> Option<Button> {
 		match event {
 			Event::WindowEvent { event, .. } => match *event {
 				WindowEvent::KeyboardInput {
@@ -91,6 +101,7 @@ impl InputState {
 				} => {
 					if self.pressed_keys.iter().all(|&k| k != key_code) {
 						self.pressed_keys.push(key_code);
+						return Some(Button::Key(key_code));
 					}
 				}
 				WindowEvent::KeyboardInput {
@@ -113,6 +124,7 @@ impl InputState {
 				} => {
 					if self.pressed_mouse_buttons.iter().all(|&b| b != button) {
 						self.pressed_mouse_buttons.push(button);
+						return Some(Button::Mouse(button));
 					}
 				}
 				WindowEvent::MouseInput {
@@ -128,11 +140,16 @@ impl InputState {
 					self.pressed_keys.clear();
 					self.pressed_mouse_buttons.clear();
 				}
+				WindowEvent::Focused(true) => {
+					self.skip_mouse_delta = true;
+				}
 				_ => {}
 			},
 			Event::DeviceEvent { event, .. } => match *event {
 				DeviceEvent::MouseMotion { delta } => {
-					if self.mouse_delta_enabled {
+					if self.skip_mouse_delta {
+						self.skip_mouse_delta = false;
+					} else if self.mouse_delta_enabled {
 						self.mouse_delta[0] += delta.0;
 						self.mouse_delta[1] += delta.1;
 					}
@@ -141,6 +158,105 @@ impl InputState {
 			},
 			_ => {}
 		}
+
+		None
+	}
+}
+
+/// Keeps track of whether the mouse should be grabbed by the window, so that it can be
+/// grabbed and released again as the game and the window's focus change.
+#[derive(Debug)]
+pub struct MouseGrab {
+	wanted: bool,
+	focused: bool,
+	grabbed: Option<bool>,
+}
+
+impl Default for MouseGrab {
+	fn default() -> MouseGrab {
+		MouseGrab {
+			wanted: false,
+			focused: true,
+			grabbed: None,
+		}
+	}
+}
+
+impl MouseGrab {
+	#[inline]
+	pub fn new() -> MouseGrab {
+		MouseGrab::default()
+	}
+
+	#[inline]
+	pub fn is_wanted(&self) -> bool {
+		self.wanted
+	}
+
+	#[inline]
+	pub fn set_wanted(&mut self, wanted: bool) {
+		self.wanted = wanted;
+	}
+
+	#[inline]
+	pub fn toggle(&mut self) {
+		self.wanted = !self.wanted;
+	}
+
+	/// The mouse is never grabbed while the window doesn't have focus.
+	#[inline]
+	pub fn set_focused(&mut self, focused: bool) {
+		self.focused = focused;
+	}
+
+	/// Makes the next `apply` grab or release the mouse even if it seems to be in the right
+	/// state already, for when something else may have changed it, like a change of video mode.
+	#[inline]
+	pub fn invalidate(&mut self) {
+		self.grabbed = None;
+	}
+
+	/// Grabs or releases the mouse, if it isn't in the wanted state already.
+	pub fn apply(&mut self, window: &Window, input_state: &mut InputState) {
+		let grab = self.wanted && self.focused;
+
+		if self.grabbed == Some(grab) {
+			return;
+		}
+
+		if let Err(err) = window.set_cursor_grab(grab) {
+			if grab {
+				log::warn!("Couldn't grab cursor: {}", err);
+			} else {
+				log::warn!("Couldn't release cursor: {}", err);
+			}
+		}
+
+		window.set_cursor_visible(!grab);
+		input_state.set_mouse_delta_enabled(grab);
+		self.grabbed = Some(grab);
+	}
+}
+
+/// Buttons that run a console command each time they are pressed.
+#[derive(Debug, Default, Clone)]
+pub struct CommandBindings {
+	bindings: FnvHashMap<Button, String>,
+}
+
+impl CommandBindings {
+	pub fn new() -> CommandBindings {
+		CommandBindings {
+			bindings: FnvHashMap::default(),
+		}
+	}
+
+	pub fn bind(&mut self, button: Button, command: &str) {
+		self.bindings.insert(button, command.to_owned());
+	}
+
+	pub fn command(&self, button: Button) -> Option<&str> {
+		self.bindings.get(&button).map(String::as_str)
 	}
 }
 
@@ -0,0 +1,47 @@
+//! The game-independent parts of Ferret, which can be used to build other games in the style
+//! of Doom. Everything that knows about Doom's data formats and gameplay lives in the `ferret`
+//! binary, which uses this crate.
+//!
+//! Games are built on the [legion](https://github.com/amethyst/legion) ECS: state is kept in a
+//! `World` and `Resources`, and the modules here provide resources, systems and helpers for them.
+
+/// Loading assets by name from a `DataSource`, and keeping them around in an `AssetStorage`.
+pub mod assets;
+/// Sound output on its own thread, with positional sound sources and effects.
+pub mod audio;
+/// Assets that took a while to make, kept on disk so that later runs can read them back.
+pub mod cache;
+/// Commands typed into the terminal, passed to the main loop.
+pub mod commands;
+/// Named settings that can be changed while running, like `r_maxfps`.
+pub mod configvars;
+/// Crash reports, written when the program panics.
+pub mod crash;
+/// Channels of events between systems, which keep track of the reader of each system.
+pub mod events;
+/// Fixed-rate game frames, and the interpolation between them for drawing.
+pub mod frame;
+/// The overall state of the program, such as which map is loading.
+pub mod gamestate;
+/// Lines, planes, intervals, bounding boxes and angles.
+pub mod geometry;
+/// Keyboard and mouse state, and bindings of buttons and axes to actions.
+pub mod input;
+/// Logging to the terminal, keeping recent lines around for crash reports.
+pub mod logger;
+/// The interface between the engine and a game, so that games can be chosen at startup.
+pub mod module;
+/// Sending and receiving packets over UDP.
+pub mod net;
+/// Measuring how long parts of each frame take.
+pub mod profiler;
+/// The packets that clients and servers send each other.
+pub mod protocol;
+/// A spatial index of entities, for finding the ones near a point quickly.
+pub mod quadtree;
+/// Spawning entities from templates, with components built from other components or resources.
+pub mod spawn;
+/// Timers that count game time, and sleeping until a deadline.
+pub mod time;
+/// Vulkan rendering: the device and window, render targets, and drawing steps.
+pub mod video;
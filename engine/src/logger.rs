@@ -1,24 +1,38 @@
-use clap::ArgMatches;
 use colored::Colorize;
+use lazy_static::lazy_static;
 use log::{self, Level, LevelFilter, Log, Metadata, Record};
+use std::{collections::VecDeque, sync::Mutex};
 
 pub static LOGGER: Logger = Logger;
 pub struct Logger;
 
+/// How many of the most recent log lines to keep, for crash reports.
+const RECENT_LINES: usize = 50;
+
+lazy_static! {
+	static ref RECENT: Mutex<VecDeque<String>> = Mutex::new(VecDeque::with_capacity(RECENT_LINES));
+}
+
+/// Returns the most recently logged lines, oldest first.
+/// Gives up instead of waiting if the log is in use, so that it's safe to call while panicking.
+pub fn recent_lines() -> Vec<String> {
+	match RECENT.try_lock() {
+		Ok(recent) => recent.iter().cloned().collect(),
+		Err(_) => Vec::new(),
+	}
+}
+
 #[cfg(debug_assertions)]
 const LOG_LEVEL: LevelFilter = LevelFilter::Debug;
 
 #[cfg(not(debug_assertions))]
 const LOG_LEVEL: LevelFilter = LevelFilter::Info;
 
-pub fn init(arg_matches: &ArgMatches) -> anyhow::Result<()> {
+/// Installs the logger. If `level` is not given, it defaults to `Debug` in debug builds
+/// and `Info` in release builds.
+pub fn init(level: Option<LevelFilter>) -> anyhow::Result<()> {
 	log::set_logger(&LOGGER)?;
-	log::set_max_level(
-		arg_matches
-			.value_of("log-level")
-			.map(|s| s.parse().unwrap())
-			.unwrap_or(LOG_LEVEL),
-	);
+	log::set_max_level(level.unwrap_or(LOG_LEVEL));
 	Ok(())
 }
 
@@ -29,6 +43,14 @@ impl Log for Logger {
 
 	fn log(&self, record: &Record<'_>) {
 		if self.enabled(record.metadata()) {
+			if let Ok(mut recent) = RECENT.lock() {
+				if recent.len() == RECENT_LINES {
+					recent.pop_front();
+				}
+
+				recent.push_back(format!("{}: {}", record.level(), record.args()));
+			}
+
 			match record.level() {
 				Level::Error => {
 					eprintln!("{}: {}", "ERROR".bright_red(), record.args());
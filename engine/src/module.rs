@@ -0,0 +1,57 @@
+use crate::{
+	assets::{AssetStorage, Importer},
+	spawn::SpawnMergerHandlerSet,
+};
+use legion::{systems::Builder, Resources, World};
+use std::time::Duration;
+
+/// A game that runs on the engine, like Doom or Heretic.
+///
+/// The main program picks one module at startup, and asks it for everything that depends on
+/// the game: the assets and components it uses, its resources and data, and the systems that
+/// run it. Games that are close to each other can share most of their implementation,
+/// and only differ in a few methods.
+pub trait GameModule {
+	/// The name that the module is selected by on the command line.
+	fn name(&self) -> &'static str;
+
+	/// The length of one game frame.
+	fn frame_time(&self) -> Duration;
+
+	/// The function that imports the game's assets from its data files.
+	fn importer(&self) -> Importer;
+
+	/// Adds storages and placeholders for the asset types that the game uses.
+	fn add_asset_types(&self, asset_storage: &mut AssetStorage);
+
+	/// Registers the components that entity templates can contain,
+	/// and how they are turned into components of spawned entities.
+	fn register_components(&self, handler_set: &mut SpawnMergerHandlerSet);
+
+	/// Inserts the resources that the game's systems need, with their initial values.
+	fn add_resources(&self, resources: &mut Resources);
+
+	/// Loads the game's entity templates and other definitions, before a map is loaded.
+	/// This is done for each map, so that changes to the data files are picked up.
+	fn load_data(&self, resources: &mut Resources) -> anyhow::Result<()>;
+
+	/// Adds the systems that run every game frame.
+	fn add_update_systems(&self, builder: &mut Builder, resources: &mut Resources);
+
+	/// Adds the systems that draw and play sound every time the screen is updated.
+	/// This is only called when there is video and audio output.
+	fn add_output_systems(
+		&self,
+		builder: &mut Builder,
+		resources: &mut Resources,
+	) -> anyhow::Result<()>;
+
+	/// Creates the status bar and other fixed parts of the screen, after a map is loaded.
+	fn spawn_hud(&self, world: &mut World, resources: &mut Resources);
+
+	/// The map that a new game starts on, when no other is given.
+	fn first_map(&self) -> &'static str;
+
+	/// Turns the numbers given to `-warp` into a map name.
+	fn warp_map_name(&self, warp: &[u32]) -> anyhow::Result<String>;
+}
@@ -0,0 +1,459 @@
+use crate::protocol::{ClientMessage, Packet, SequencedPacket, ServerInfo, ServerMessage, TryRead};
+use anyhow::{anyhow, bail};
+use crossbeam_channel::Sender;
+use net2::UdpBuilder;
+use std::{
+	convert::TryFrom,
+	fmt,
+	io::{self, Cursor, ErrorKind},
+	marker::PhantomData,
+	net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6, ToSocketAddrs, UdpSocket},
+	rc::Rc,
+};
+
+pub const DEFAULT_PORT: u16 = 10666;
+
+pub struct Socket {
+	v4: Option<UdpSocket>,
+	v6: Option<UdpSocket>,
+}
+
+impl Socket {
+	pub fn new(ipv4_addr: Ipv4Addr, ipv6_addr: Ipv6Addr, port: u16) -> anyhow::Result<Rc<Socket>> {
+		let ipv4_addr_port = SocketAddrV4::new(ipv4_addr, port);
+		let v4 = bind_v4(ipv4_addr_port);
+
+		if let Err(ref err) = v4 {
+			log::debug!("Could not bind IPv4 socket to {}: {}", ipv4_addr_port, err);
+		};
+
+		let ipv6_addr_port = SocketAddrV6::new(ipv6_addr, port, 0, 0);
+		let v6 = bind_v6(ipv6_addr_port);
+
+		if let Err(ref err) = v6 {
+			log::debug!("Could not bind IPv6 socket to {}: {}", ipv6_addr_port, err);
+		};
+
+		if v4.is_err() && v6.is_err() {
+			bail!("Both IPv4 and IPv6 bindings failed on port {}", port);
+		} else {
+			Ok(Rc::new(Socket {
+				v4: v4.ok(),
+				v6: v6.ok(),
+			}))
+		}
+	}
+
+	pub fn mode(&self) -> SocketMode {
+		let v4_addr = self.v4.as_ref().map(|s| s.local_addr().unwrap());
+		let v6_addr = self.v6.as_ref().map(|s| s.local_addr().unwrap());
+
+		match (v4_addr, v6_addr) {
+			(Some(SocketAddr::V4(v4)), Some(SocketAddr::V6(v6))) => SocketMode::DualStack(v4, v6),
+			(Some(SocketAddr::V4(v4)), None) => SocketMode::IPv4(v4),
+			(None, Some(SocketAddr::V6(v6))) => SocketMode::IPv6(v6),
+			_ => unreachable!(),
+		}
+	}
+
+	pub fn send_to(&self, packet: Vec<u8>, addr: SocketAddr) {
+		let socket = match addr {
+			SocketAddr::V4(_) => &self.v4,
+			SocketAddr::V6(_) => &self.v6,
+		};
+
+		if let Some(socket) = socket {
+			if let Err(err) = socket.send_to(packet.as_slice(), addr) {
+				log::error!("Could not send packet to {}: {}", addr, err);
+			}
+		} else {
+			log::error!(
+				"Socket does not support {} addresses",
+				match addr {
+					SocketAddr::V4(_) => "IPv4",
+					SocketAddr::V6(_) => "IPv6",
+				}
+			);
+		}
+	}
+
+	/// Sends a packet to all hosts on the local IPv4 network.
+	pub fn broadcast(&self, packet: Vec<u8>, port: u16) {
+		let addr = SocketAddrV4::new(Ipv4Addr::BROADCAST, port);
+
+		if let Some(socket) = &self.v4 {
+			if let Err(err) = socket.send_to(packet.as_slice(), addr) {
+				log::error!("Could not send broadcast packet to {}: {}", addr, err);
+			}
+		} else {
+			log::error!("Broadcasting requires an IPv4 socket");
+		}
+	}
+
+	pub fn next(&self) -> Option<(Vec<u8>, SocketAddr)> {
+		let mut buf = vec![0u8; 8192];
+
+		// Try reading from available sockets, first from the IPv6 socket,
+		// then from the IPv4 socket.
+		for socket in [self.v6.as_ref(), self.v4.as_ref()]
+			.iter()
+			.filter_map(|s| *s)
+		{
+			match socket.recv_from(&mut buf) {
+				Ok((bytes_read, addr)) => {
+					if bytes_read == buf.len() {
+						// Oversized packet, ignore it.
+						log::warn!("Received an oversized packet from {}", addr);
+					} else {
+						buf.truncate(bytes_read);
+						return Some((buf, addr));
+					}
+				}
+				Err(err) => {
+					if err.kind() != ErrorKind::WouldBlock {
+						log::error!(
+							"Could not receive {} packet: {}",
+							match socket.local_addr().unwrap() {
+								SocketAddr::V4(_) => "IPv4",
+								SocketAddr::V6(_) => "IPv6",
+							},
+							err,
+						);
+					}
+				}
+			}
+		}
+
+		None
+	}
+}
+
+pub enum SocketMode {
+	DualStack(SocketAddrV4, SocketAddrV6),
+	IPv4(SocketAddrV4),
+	IPv6(SocketAddrV6),
+}
+
+impl fmt::Display for SocketMode {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match *self {
+			SocketMode::DualStack(v4, v6) => write!(f, "dual-stack ({}, {})", v4, v6),
+			SocketMode::IPv4(v4) => write!(f, "IPv4-only ({})", v4),
+			SocketMode::IPv6(v6) => write!(f, "IPv6-only ({})", v6),
+		}
+	}
+}
+
+fn bind_v4(addr_port: SocketAddrV4) -> Result<UdpSocket, io::Error> {
+	let builder = UdpBuilder::new_v4()?;
+	let socket = builder.bind(addr_port)?;
+	socket.set_broadcast(true)?;
+	socket.set_nonblocking(true)?;
+
+	Ok(socket)
+}
+
+fn bind_v6(addr_port: SocketAddrV6) -> Result<UdpSocket, io::Error> {
+	// Keep the IPv6 socket from also claiming the port for IPv4, which the IPv4 socket uses
+	let builder = UdpBuilder::new_v6()?;
+	builder.only_v6(true)?;
+	let socket = builder.bind(addr_port)?;
+	socket.set_nonblocking(true)?;
+
+	Ok(socket)
+}
+
+/// Parses an address of the form `ip[:port]` or `host[:port]`, using the default port if
+/// none is given.
+pub fn parse_addr(text: &str) -> anyhow::Result<SocketAddr> {
+	let mut addrs = match text.to_socket_addrs() {
+		Ok(addrs) => addrs,
+		Err(_) => (text, DEFAULT_PORT).to_socket_addrs()?,
+	};
+
+	addrs
+		.next()
+		.ok_or_else(|| anyhow!("No address found for \"{}\"", text))
+}
+
+pub struct SequencedChannel<S, R> {
+	addr: SocketAddr,
+	socket: Rc<Socket>,
+	in_sequence: u32,
+	out_sequence: u32,
+	_phantom1: PhantomData<S>,
+	_phantom2: PhantomData<R>,
+}
+
+impl<S: Into<Vec<u8>>, R: TryRead<R>> SequencedChannel<S, R> {
+	pub fn new(socket: Rc<Socket>, addr: SocketAddr) -> SequencedChannel<S, R> {
+		SequencedChannel {
+			addr,
+			socket,
+			in_sequence: 0,
+			out_sequence: 1,
+			_phantom1: PhantomData,
+			_phantom2: PhantomData,
+		}
+	}
+
+	pub fn addr(&self) -> SocketAddr {
+		self.addr
+	}
+
+	pub fn send(&mut self, messages: Vec<S>) {
+		let data = messages
+			.into_iter()
+			.map(|x| x.into())
+			.collect::<Vec<Vec<u8>>>()
+			.concat();
+
+		let packet = SequencedPacket {
+			sequence: self.out_sequence,
+			data,
+		};
+
+		self.socket.send_to(packet.into(), self.addr);
+		self.out_sequence += 1;
+	}
+
+	pub fn process(&mut self, packet: SequencedPacket) -> Option<Vec<R>> {
+		if packet.sequence < self.in_sequence {
+			return None;
+		}
+
+		let mut reader = Cursor::new(packet.data);
+		let mut messages = Vec::new();
+
+		while reader.position() < reader.get_ref().len() as u64 {
+			let message = match R::try_read(&mut reader) {
+				Ok(message) => message,
+				Err(err) => {
+					log::warn!("Received a malformed packet from {}: {}", self.addr, err);
+					return None;
+				}
+			};
+
+			messages.push(message);
+		}
+
+		self.in_sequence = packet.sequence;
+		Some(messages)
+	}
+}
+
+/// Client and server endpoints of the game.
+/// The server socket listens on the default port, so that other games on the local network
+/// can find this one; the client socket uses a random port to talk to other servers.
+pub struct Network {
+	client_socket: Rc<Socket>,
+	server_socket: Option<Rc<Socket>>,
+	connecting: Option<SocketAddr>,
+	server: Option<SequencedChannel<ClientMessage, ServerMessage>>,
+	clients: Vec<SequencedChannel<ServerMessage, ClientMessage>>,
+	command_sender: Sender<String>,
+	rcon_password: Option<String>,
+	pub server_info: ServerInfo,
+}
+
+impl Network {
+	pub fn new(
+		command_sender: Sender<String>,
+		rcon_password: Option<String>,
+	) -> anyhow::Result<Network> {
+		let client_socket = Socket::new(Ipv4Addr::UNSPECIFIED, Ipv6Addr::UNSPECIFIED, 0)?;
+		log::debug!("Client socket is {}", client_socket.mode());
+
+		let server_socket =
+			match Socket::new(Ipv4Addr::UNSPECIFIED, Ipv6Addr::UNSPECIFIED, DEFAULT_PORT) {
+				Ok(socket) => {
+					log::debug!("Server socket is {}", socket.mode());
+					Some(socket)
+				}
+				Err(err) => {
+					log::warn!("{}, this game won't be visible on the network", err);
+					None
+				}
+			};
+
+		Ok(Network {
+			client_socket,
+			server_socket,
+			connecting: None,
+			server: None,
+			clients: Vec::new(),
+			command_sender,
+			rcon_password,
+			server_info: ServerInfo {
+				name: "Ferret".to_owned(),
+				..ServerInfo::default()
+			},
+		})
+	}
+
+	pub fn is_listening(&self) -> bool {
+		self.server_socket.is_some()
+	}
+
+	/// Whether we're connected to a server, or any clients are connected to us.
+	pub fn has_peers(&self) -> bool {
+		self.server.is_some() || !self.clients.is_empty()
+	}
+
+	pub fn list_servers(&self) {
+		log::info!("Searching for servers on the local network...");
+		let packet: Vec<u8> = Packet::Unsequenced(vec![ClientMessage::Discover]).into();
+		self.client_socket.broadcast(packet, DEFAULT_PORT);
+	}
+
+	pub fn connect(&mut self, addr: SocketAddr) {
+		log::info!("Connecting to {}...", addr);
+		let packet: Vec<u8> = Packet::Unsequenced(vec![ClientMessage::Connect]).into();
+		self.client_socket.send_to(packet, addr);
+		self.connecting = Some(addr);
+	}
+
+	pub fn disconnect(&mut self) {
+		self.connecting = None;
+
+		if let Some(mut server) = self.server.take() {
+			log::info!("Disconnecting from {}", server.addr());
+			server.send(vec![ClientMessage::Disconnect]);
+		}
+	}
+
+	/// Sends a console command to be executed by the server we're connected to.
+	pub fn rcon(&mut self, password: &str, command: &str) {
+		if let Some(server) = &mut self.server {
+			server.send(vec![ClientMessage::RCon {
+				password: password.to_owned(),
+				command: command.to_owned(),
+			}]);
+		} else {
+			log::error!("Not connected to a server");
+		}
+	}
+
+	pub fn process(&mut self) {
+		if let Some(server_socket) = self.server_socket.clone() {
+			while let Some((data, addr)) = server_socket.next() {
+				match Packet::<ClientMessage>::try_from(data) {
+					Ok(Packet::Unsequenced(messages)) => {
+						for message in messages {
+							self.handle_client_message(&server_socket, message, addr, false);
+						}
+					}
+					Ok(Packet::Sequenced(packet)) => {
+						if let Some(channel) = self.clients.iter_mut().find(|c| c.addr() == addr) {
+							if let Some(messages) = channel.process(packet) {
+								for message in messages {
+									self.handle_client_message(&server_socket, message, addr, true);
+								}
+							}
+						}
+					}
+					Err(err) => log::debug!("Received a malformed packet from {}: {}", addr, err),
+				}
+			}
+		}
+
+		while let Some((data, addr)) = self.client_socket.next() {
+			match Packet::<ServerMessage>::try_from(data) {
+				Ok(Packet::Unsequenced(messages)) => {
+					for message in messages {
+						self.handle_server_message(message, addr);
+					}
+				}
+				Ok(Packet::Sequenced(packet)) => {
+					if let Some(channel) = self.server.as_mut().filter(|c| c.addr() == addr) {
+						if let Some(messages) = channel.process(packet) {
+							for message in messages {
+								self.handle_server_message(message, addr);
+							}
+						}
+					}
+				}
+				Err(err) => log::debug!("Received a malformed packet from {}: {}", addr, err),
+			}
+		}
+	}
+
+	/// `sequenced` is whether the message came through the channel of a connected client.
+	fn handle_client_message(
+		&mut self,
+		socket: &Rc<Socket>,
+		message: ClientMessage,
+		addr: SocketAddr,
+		sequenced: bool,
+	) {
+		match message {
+			ClientMessage::Discover => {
+				let info = ServerInfo {
+					players: self.clients.len() as u8,
+					..self.server_info.clone()
+				};
+				let packet: Vec<u8> =
+					Packet::Unsequenced(vec![ServerMessage::DiscoverResponse(info)]).into();
+				socket.send_to(packet, addr);
+			}
+			ClientMessage::Connect => {
+				if !self.clients.iter().any(|c| c.addr() == addr) {
+					log::info!("Client {} connected", addr);
+					self.clients
+						.push(SequencedChannel::new(socket.clone(), addr));
+				}
+
+				let packet: Vec<u8> =
+					Packet::Unsequenced(vec![ServerMessage::ConnectResponse]).into();
+				socket.send_to(packet, addr);
+			}
+			ClientMessage::Disconnect => {
+				if let Some(index) = self.clients.iter().position(|c| c.addr() == addr) {
+					log::info!("Client {} disconnected", addr);
+					self.clients.remove(index);
+				}
+			}
+			ClientMessage::RCon { password, command } => {
+				// Only connected clients may send commands
+				if !sequenced {
+					log::warn!("Ignoring remote command from {}: not connected", addr);
+				} else if self.rcon_password.is_some()
+					&& self.rcon_password.as_ref() == Some(&password)
+				{
+					log::info!("Remote command from {}: {}", addr, command);
+					self.command_sender.send(command).ok();
+				} else {
+					log::warn!("Ignoring remote command from {}: wrong password", addr);
+				}
+			}
+		}
+	}
+
+	fn handle_server_message(&mut self, message: ServerMessage, addr: SocketAddr) {
+		match message {
+			ServerMessage::DiscoverResponse(info) => {
+				log::info!(
+					"{:<24} {:<8} {} players ({})",
+					info.name,
+					info.map,
+					info.players,
+					addr
+				);
+			}
+			ServerMessage::ConnectResponse => {
+				if self.connecting == Some(addr) {
+					log::info!("Connected to {}", addr);
+					self.connecting = None;
+					self.server = Some(SequencedChannel::new(self.client_socket.clone(), addr));
+				}
+			}
+			ServerMessage::Disconnect => {
+				if self.server.as_ref().map(|c| c.addr()) == Some(addr) {
+					log::info!("Disconnected from {}", addr);
+					self.server = None;
+				}
+			}
+			_ => {}
+		}
+	}
+}
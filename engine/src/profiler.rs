@@ -0,0 +1,414 @@
+use anyhow::Context;
+use lazy_static::lazy_static;
+use serde_json::{json, Map, Value};
+use std::{
+	collections::{BTreeSet, VecDeque},
+	fs::File,
+	io::{BufWriter, Write},
+	path::Path,
+	sync::{
+		atomic::{AtomicU32, Ordering},
+		Mutex,
+	},
+	thread,
+	time::{Duration, Instant},
+};
+
+/// How many frames of history to keep around.
+pub const MAX_FRAMES: usize = 256;
+
+lazy_static! {
+	static ref PROFILER: Mutex<Profiler> = Mutex::new(Profiler::new());
+}
+
+static NEXT_THREAD_ID: AtomicU32 = AtomicU32::new(0);
+
+thread_local! {
+	static THREAD_ID: u32 = {
+		let id = NEXT_THREAD_ID.fetch_add(1, Ordering::Relaxed);
+		let name = thread::current().name().unwrap_or("unnamed").to_owned();
+		PROFILER.lock().unwrap().thread_names.push((id, name));
+		id
+	};
+}
+
+#[derive(Clone, Debug)]
+struct Span {
+	name: &'static str,
+	thread: u32,
+	start: Duration,
+	duration: Duration,
+}
+
+#[derive(Clone, Debug)]
+struct FrameProfile {
+	start: Duration,
+	duration: Duration,
+	spans: Vec<Span>,
+	counters: Vec<(&'static str, u64)>,
+}
+
+struct Profiler {
+	epoch: Instant,
+	frames: VecDeque<FrameProfile>,
+	current: FrameProfile,
+	thread_names: Vec<(u32, String)>,
+	recording: Option<Vec<FrameProfile>>,
+}
+
+impl Profiler {
+	fn new() -> Profiler {
+		Profiler {
+			epoch: Instant::now(),
+			frames: VecDeque::with_capacity(MAX_FRAMES),
+			current: FrameProfile {
+				start: Duration::default(),
+				duration: Duration::default(),
+				spans: Vec::new(),
+				counters: Vec::new(),
+			},
+			thread_names: Vec::new(),
+			recording: None,
+		}
+	}
+}
+
+/// Records the time from its creation until it is dropped.
+#[must_use = "the scope is measured until the guard is dropped"]
+pub struct ProfileScope {
+	name: &'static str,
+	start: Instant,
+}
+
+impl Drop for ProfileScope {
+	fn drop(&mut self) {
+		let duration = self.start.elapsed();
+		let thread = THREAD_ID.with(|id| *id);
+		let mut profiler = PROFILER.lock().unwrap();
+		let start = self.start - profiler.epoch;
+
+		profiler.current.spans.push(Span {
+			name: self.name,
+			thread,
+			start,
+			duration,
+		});
+	}
+}
+
+/// Starts measuring a named part of the current frame.
+pub fn scope(name: &'static str) -> ProfileScope {
+	ProfileScope {
+		name,
+		start: Instant::now(),
+	}
+}
+
+/// Records a named value for the current frame, such as the number of things drawn.
+/// Recording the same name again in the same frame replaces the previous value.
+pub fn count(name: &'static str, value: u64) {
+	let mut profiler = PROFILER.lock().unwrap();
+
+	if let Some(counter) = profiler
+		.current
+		.counters
+		.iter_mut()
+		.find(|(counter_name, _)| *counter_name == name)
+	{
+		counter.1 = value;
+	} else {
+		profiler.current.counters.push((name, value));
+	}
+}
+
+/// Finishes the current frame and starts a new one.
+pub fn begin_frame() {
+	let mut profiler = PROFILER.lock().unwrap();
+	let now = profiler.epoch.elapsed();
+
+	if profiler.recording.is_some() {
+		if let Some(memory) = resident_memory() {
+			profiler
+				.current
+				.counters
+				.push(("resident_memory_kb", memory / 1024));
+		}
+	}
+
+	let spans = std::mem::replace(&mut profiler.current.spans, Vec::new());
+	let counters = std::mem::replace(&mut profiler.current.counters, Vec::new());
+	let frame = FrameProfile {
+		start: profiler.current.start,
+		duration: now - profiler.current.start,
+		spans,
+		counters,
+	};
+
+	if let Some(recording) = &mut profiler.recording {
+		recording.push(frame.clone());
+	}
+
+	if profiler.frames.len() == MAX_FRAMES {
+		profiler.frames.pop_front();
+	}
+
+	profiler.frames.push_back(frame);
+	profiler.current.start = now;
+}
+
+/// Returns the durations of the most recent frames, oldest first.
+pub fn frame_times() -> Vec<Duration> {
+	let profiler = PROFILER.lock().unwrap();
+	profiler.frames.iter().map(|frame| frame.duration).collect()
+}
+
+/// Returns the average duration of the named scope over the `frames` most recent frames,
+/// or `None` if it didn't run in any of them.
+pub fn average_scope_time(name: &str, frames: usize) -> Option<Duration> {
+	let profiler = PROFILER.lock().unwrap();
+	let (count, total) = profiler
+		.frames
+		.iter()
+		.rev()
+		.take(frames)
+		.flat_map(|frame| frame.spans.iter())
+		.filter(|span| span.name == name)
+		.fold((0, Duration::default()), |(count, total), span| {
+			(count + 1, total + span.duration)
+		});
+
+	if count > 0 {
+		Some(total / count)
+	} else {
+		None
+	}
+}
+
+/// Writes the recorded frames to a file in the Chrome tracing format,
+/// which can be viewed in `chrome://tracing` and other trace viewers.
+pub fn dump(path: &Path) -> anyhow::Result<()> {
+	let events = {
+		let profiler = PROFILER.lock().unwrap();
+		let mut events = Vec::new();
+
+		for (id, name) in profiler.thread_names.iter() {
+			events.push(json!({
+				"name": "thread_name",
+				"ph": "M",
+				"pid": 1,
+				"tid": id,
+				"args": { "name": name },
+			}));
+		}
+
+		for frame in profiler.frames.iter() {
+			events.push(json!({
+				"name": "frame",
+				"ph": "X",
+				"pid": 1,
+				"tid": -1,
+				"ts": frame.start.as_micros() as u64,
+				"dur": frame.duration.as_micros() as u64,
+			}));
+
+			for span in frame.spans.iter() {
+				events.push(json!({
+					"name": span.name,
+					"ph": "X",
+					"pid": 1,
+					"tid": span.thread,
+					"ts": span.start.as_micros() as u64,
+					"dur": span.duration.as_micros() as u64,
+				}));
+			}
+
+			for (name, value) in frame.counters.iter() {
+				events.push(json!({
+					"name": name,
+					"ph": "C",
+					"pid": 1,
+					"ts": frame.start.as_micros() as u64,
+					"args": { *name: value },
+				}));
+			}
+		}
+
+		events
+	};
+
+	let file = File::create(path).context("Couldn't create file")?;
+	serde_json::to_writer(
+		BufWriter::new(file),
+		&json!({
+			"traceEvents": events,
+			"displayTimeUnit": "ms",
+		}),
+	)
+	.context("Couldn't write profile")?;
+
+	Ok(())
+}
+
+/// Starts keeping every frame from the next one on, rather than only the most recent ones,
+/// along with the memory use of the process. Used for benchmark runs.
+pub fn start_recording() {
+	PROFILER.lock().unwrap().recording = Some(Vec::new());
+}
+
+/// Stops recording frames, and writes the recorded ones to `path` for other programs to read.
+/// The file is CSV if `path` ends in `.csv`, and JSON otherwise.
+/// Each frame has its duration, the total time of each named scope, and each counter.
+/// Returns the number of frames written.
+pub fn stop_recording(path: &Path) -> anyhow::Result<usize> {
+	let frames = PROFILER
+		.lock()
+		.unwrap()
+		.recording
+		.take()
+		.context("No frames are being recorded")?;
+
+	// Scopes that run several times per frame, like game frames, are added together
+	let frame_rows: Vec<(f64, f64, Vec<(&str, f64)>, &[(&str, u64)])> = frames
+		.iter()
+		.map(|frame| {
+			let mut times: Vec<(&str, f64)> = Vec::new();
+
+			for span in frame.spans.iter() {
+				let ms = span.duration.as_secs_f64() * 1000.0;
+
+				match times.iter_mut().find(|(name, _)| *name == span.name) {
+					Some(time) => time.1 += ms,
+					None => times.push((span.name, ms)),
+				}
+			}
+
+			(
+				frame.start.as_secs_f64() * 1000.0,
+				frame.duration.as_secs_f64() * 1000.0,
+				times,
+				frame.counters.as_slice(),
+			)
+		})
+		.collect();
+
+	let file = File::create(path).context("Couldn't create file")?;
+	let mut writer = BufWriter::new(file);
+
+	if path.extension().map_or(false, |ext| ext == "csv") {
+		let time_names: BTreeSet<&str> = frame_rows
+			.iter()
+			.flat_map(|(_, _, times, _)| times.iter().map(|(name, _)| *name))
+			.collect();
+		let counter_names: BTreeSet<&str> = frame_rows
+			.iter()
+			.flat_map(|(_, _, _, counters)| counters.iter().map(|(name, _)| *name))
+			.collect();
+
+		write!(writer, "frame,start_ms,frame_ms")?;
+
+		for name in time_names.iter() {
+			write!(writer, ",{}_ms", name)?;
+		}
+
+		for name in counter_names.iter() {
+			write!(writer, ",{}", name)?;
+		}
+
+		writeln!(writer)?;
+
+		// Missing values are left empty
+		for (index, (start, duration, times, counters)) in frame_rows.iter().enumerate() {
+			write!(writer, "{},{:.3},{:.3}", index, start, duration)?;
+
+			for name in time_names.iter() {
+				match times.iter().find(|(n, _)| n == name) {
+					Some((_, ms)) => write!(writer, ",{:.3}", ms)?,
+					None => write!(writer, ",")?,
+				}
+			}
+
+			for name in counter_names.iter() {
+				match counters.iter().find(|(n, _)| n == name) {
+					Some((_, value)) => write!(writer, ",{}", value)?,
+					None => write!(writer, ",")?,
+				}
+			}
+
+			writeln!(writer)?;
+		}
+	} else {
+		let mut durations: Vec<f64> = frame_rows
+			.iter()
+			.map(|(_, duration, _, _)| *duration)
+			.collect();
+		durations.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap());
+		let percentile = |p: f64| {
+			let index = ((durations.len() as f64 - 1.0) * p).round() as usize;
+			durations.get(index).copied().unwrap_or(0.0)
+		};
+		let total: f64 = durations.iter().sum();
+
+		let json_frames: Vec<Value> = frame_rows
+			.iter()
+			.enumerate()
+			.map(|(index, (start, duration, times, counters))| {
+				let times: Map<String, Value> = times
+					.iter()
+					.map(|(name, ms)| (name.to_string(), json!(ms)))
+					.collect();
+				let counters: Map<String, Value> = counters
+					.iter()
+					.map(|(name, value)| (name.to_string(), json!(value)))
+					.collect();
+
+				json!({
+					"frame": index,
+					"start_ms": start,
+					"frame_ms": duration,
+					"times_ms": times,
+					"counters": counters,
+				})
+			})
+			.collect();
+
+		serde_json::to_writer(
+			&mut writer,
+			&json!({
+				"summary": {
+					"frames": durations.len(),
+					"total_ms": total,
+					"average_ms": total / durations.len().max(1) as f64,
+					"min_ms": durations.first().copied().unwrap_or(0.0),
+					"median_ms": percentile(0.5),
+					"p95_ms": percentile(0.95),
+					"p99_ms": percentile(0.99),
+					"max_ms": durations.last().copied().unwrap_or(0.0),
+				},
+				"frames": json_frames,
+			}),
+		)
+		.context("Couldn't write telemetry")?;
+	}
+
+	writer.flush()?;
+	Ok(frames.len())
+}
+
+/// Returns how much memory the process has in RAM, in bytes.
+/// This is only known on Linux.
+pub fn resident_memory() -> Option<u64> {
+	if !cfg!(target_os = "linux") {
+		return None;
+	}
+
+	let status = std::fs::read_to_string("/proc/self/status").ok()?;
+	let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+	let kb: u64 = line
+		.trim_start_matches("VmRSS:")
+		.trim()
+		.trim_end_matches("kB")
+		.trim()
+		.parse()
+		.ok()?;
+	Some(kb * 1024)
+}
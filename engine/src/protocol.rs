@@ -1,13 +1,9 @@
-use anyhow::ensure;
+use anyhow::{bail, ensure};
 use byteorder::{NetworkEndian as NE, ReadBytesExt, WriteBytesExt};
 use std::{
 	convert::TryFrom,
-	error::Error,
 	io::{Cursor, Read, Write},
-	str,
 };
-use crate::commands;
-
 
 #[derive(Debug)]
 pub enum Packet<T> {
@@ -50,11 +46,11 @@ impl<T: Into<Vec<u8>>> From<Packet<T>> for Vec<u8> {
 				writer.write_u32::<NE>(0xFFFFFFFF).unwrap();
 
 				for message in messages {
-					writer.write(&message.into()).unwrap();
+					writer.write_all(&message.into()).unwrap();
 				}
 
 				writer.into_inner()
-			},
+			}
 			Packet::Sequenced(p) => p.into(),
 		}
 	}
@@ -86,7 +82,7 @@ impl From<SequencedPacket> for Vec<u8> {
 	fn from(packet: SequencedPacket) -> Vec<u8> {
 		let mut writer = Cursor::new(Vec::new());
 		writer.write_u32::<NE>(packet.sequence).unwrap();
-		writer.write(&packet.data).unwrap();
+		writer.write_all(&packet.data).unwrap();
 		writer.into_inner()
 	}
 }
@@ -95,6 +91,27 @@ pub trait TryRead<T> {
 	fn try_read(reader: &mut Cursor<Vec<u8>>) -> anyhow::Result<T>;
 }
 
+fn read_bytes(reader: &mut Cursor<Vec<u8>>) -> anyhow::Result<Vec<u8>> {
+	let length = reader.read_u32::<NE>()? as u64;
+	ensure!(
+		length <= reader.get_ref().len() as u64 - reader.position(),
+		"length {} exceeds packet size",
+		length
+	);
+
+	let mut data = vec![0u8; length as usize];
+	reader.read_exact(data.as_mut_slice())?;
+	Ok(data)
+}
+
+fn read_string(reader: &mut Cursor<Vec<u8>>) -> anyhow::Result<String> {
+	Ok(String::from_utf8(read_bytes(reader)?)?)
+}
+
+fn write_string(writer: &mut Cursor<Vec<u8>>, text: &str) {
+	writer.write_u32::<NE>(text.len() as u32).unwrap();
+	writer.write_all(text.as_bytes()).unwrap();
+}
 
 /*
 	Client-to-server protocol
@@ -103,7 +120,9 @@ pub trait TryRead<T> {
 #[derive(Debug)]
 pub enum ClientMessage {
 	Connect,
-	RCon(String),
+	RCon { password: String, command: String },
+	Discover,
+	Disconnect,
 }
 
 impl TryRead<ClientMessage> for ClientMessage {
@@ -111,16 +130,15 @@ impl TryRead<ClientMessage> for ClientMessage {
 		let message_type = reader.read_u8()?;
 
 		Ok(match message_type {
-			1 => {
-				ClientMessage::Connect
-			},
+			1 => ClientMessage::Connect,
 			2 => {
-				let length = reader.read_u32::<NE>()?;
-				let mut data = vec![0u8; length as usize];
-				reader.read_exact(data.as_mut_slice())?;
-				ClientMessage::RCon(String::from_utf8(data)?)
-			},
-			_ => unreachable!(),
+				let password = read_string(reader)?;
+				let command = read_string(reader)?;
+				ClientMessage::RCon { password, command }
+			}
+			3 => ClientMessage::Discover,
+			4 => ClientMessage::Disconnect,
+			_ => bail!("unknown client message type {}", message_type),
 		})
 	}
 }
@@ -133,10 +151,16 @@ impl From<ClientMessage> for Vec<u8> {
 			ClientMessage::Connect => {
 				writer.write_u8(1).unwrap();
 			}
-			ClientMessage::RCon(text) => {
+			ClientMessage::RCon { password, command } => {
 				writer.write_u8(2).unwrap();
-				writer.write_u32::<NE>(text.len() as u32).unwrap();
-				writer.write(text.as_bytes()).unwrap();
+				write_string(&mut writer, &password);
+				write_string(&mut writer, &command);
+			}
+			ClientMessage::Discover => {
+				writer.write_u8(3).unwrap();
+			}
+			ClientMessage::Disconnect => {
+				writer.write_u8(4).unwrap();
 			}
 		}
 
@@ -144,7 +168,6 @@ impl From<ClientMessage> for Vec<u8> {
 	}
 }
 
-
 /*
 	Server-to-client protocol
 */
@@ -159,6 +182,14 @@ pub enum ServerMessage {
 	Disconnect,
 	EntityDelete(u32),
 	EntityNew(u32),
+	DiscoverResponse(ServerInfo),
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ServerInfo {
+	pub name: String,
+	pub map: String,
+	pub players: u8,
 }
 
 impl TryRead<ServerMessage> for ServerMessage {
@@ -166,39 +197,39 @@ impl TryRead<ServerMessage> for ServerMessage {
 		let message_type = reader.read_u8()?;
 
 		Ok(match message_type {
-			1 => {
-				ServerMessage::ConnectResponse
-			},
+			1 => ServerMessage::ConnectResponse,
 			2 => {
 				let entity_id = reader.read_u32::<NE>()?;
 				let component_id = reader.read_u8()?;
 				ServerMessage::ComponentDelete(entity_id, component_id)
-			},
+			}
 			3 => {
 				let entity_id = reader.read_u32::<NE>()?;
 				let component_id = reader.read_u8()?;
-				let length = reader.read_u32::<NE>()?;
-				let mut data = vec![0u8; length as usize];
-				reader.read_exact(data.as_mut_slice())?;
+				let data = read_bytes(reader)?;
 				ServerMessage::ComponentDelta(entity_id, component_id, data)
-			},
+			}
 			4 => {
 				let entity_id = reader.read_u32::<NE>()?;
 				let component_id = reader.read_u8()?;
 				ServerMessage::ComponentNew(entity_id, component_id)
-			},
-			5 => {
-				ServerMessage::Disconnect
-			},
+			}
+			5 => ServerMessage::Disconnect,
 			6 => {
 				let entity_id = reader.read_u32::<NE>()?;
 				ServerMessage::EntityDelete(entity_id)
-			},
+			}
 			7 => {
 				let entity_id = reader.read_u32::<NE>()?;
 				ServerMessage::EntityNew(entity_id)
-			},
-			_ => unreachable!(),
+			}
+			8 => {
+				let name = read_string(reader)?;
+				let map = read_string(reader)?;
+				let players = reader.read_u8()?;
+				ServerMessage::DiscoverResponse(ServerInfo { name, map, players })
+			}
+			_ => bail!("unknown server message type {}", message_type),
 		})
 	}
 }
@@ -210,35 +241,41 @@ impl From<ServerMessage> for Vec<u8> {
 		match message {
 			ServerMessage::ConnectResponse => {
 				writer.write_u8(1).unwrap();
-			},
+			}
 			ServerMessage::ComponentDelete(entity_id, component_id) => {
 				writer.write_u8(2).unwrap();
 				writer.write_u32::<NE>(entity_id).unwrap();
 				writer.write_u8(component_id).unwrap();
-			},
+			}
 			ServerMessage::ComponentDelta(entity_id, component_id, data) => {
 				writer.write_u8(3).unwrap();
 				writer.write_u32::<NE>(entity_id).unwrap();
 				writer.write_u8(component_id).unwrap();
 				writer.write_u32::<NE>(data.len() as u32).unwrap();
-				writer.write(&data).unwrap();
-			},
+				writer.write_all(&data).unwrap();
+			}
 			ServerMessage::ComponentNew(entity_id, component_id) => {
 				writer.write_u8(4).unwrap();
 				writer.write_u32::<NE>(entity_id).unwrap();
 				writer.write_u8(component_id).unwrap();
-			},
+			}
 			ServerMessage::Disconnect => {
 				writer.write_u8(5).unwrap();
-			},
+			}
 			ServerMessage::EntityDelete(entity_id) => {
 				writer.write_u8(6).unwrap();
 				writer.write_u32::<NE>(entity_id).unwrap();
-			},
+			}
 			ServerMessage::EntityNew(entity_id) => {
 				writer.write_u8(7).unwrap();
 				writer.write_u32::<NE>(entity_id).unwrap();
-			},
+			}
+			ServerMessage::DiscoverResponse(info) => {
+				writer.write_u8(8).unwrap();
+				write_string(&mut writer, &info.name);
+				write_string(&mut writer, &info.map);
+				writer.write_u8(info.players).unwrap();
+			}
 		}
 
 		writer.into_inner()
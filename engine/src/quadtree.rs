@@ -1,4 +1,4 @@
-use crate::common::geometry::{Interval, AABB2};
+use crate::geometry::{Interval, Line2, AABB2};
 use fnv::FnvHashMap;
 use legion::Entity;
 use nalgebra::Vector2;
@@ -191,6 +191,87 @@ impl Quadtree {
 		}
 	}
 
+	/// Returns the bounding box that an entity was inserted with.
+	#[inline]
+	pub fn bbox(&self, entity: Entity) -> Option<&AABB2> {
+		self.bboxes.get(&entity)
+	}
+
+	/// Returns the entities whose bounding box overlaps `bbox`.
+	pub fn query_bbox(&self, bbox: &AABB2) -> Vec<Entity> {
+		let mut result = Vec::new();
+
+		if bbox.is_empty() {
+			return result;
+		}
+
+		self.traverse_nodes(bbox, &mut |entities: &[Entity]| {
+			result.extend(
+				entities
+					.iter()
+					.copied()
+					.filter(|entity| self.bboxes[entity].overlaps(bbox)),
+			);
+		});
+
+		result
+	}
+
+	/// Returns the entities whose bounding box is within `radius` of `center`.
+	pub fn query_radius(&self, center: Vector2<f32>, radius: f32) -> Vec<Entity> {
+		let offset = Vector2::new(radius, radius);
+		let mut result = self.query_bbox(&AABB2::from_minmax(center - offset, center + offset));
+
+		result.retain(|entity| {
+			let bbox = &self.bboxes[entity];
+			let closest = Vector2::new(
+				center[0].max(bbox[0].min).min(bbox[0].max),
+				center[1].max(bbox[1].min).min(bbox[1].max),
+			);
+			(closest - center).norm_squared() <= radius * radius
+		});
+
+		result
+	}
+
+	/// Returns the entities whose bounding box is crossed by `line`, between its start and end,
+	/// together with the fraction of the line where it enters the box.
+	/// The entities are sorted from the start of the line to its end.
+	pub fn query_line(&self, line: &Line2) -> Vec<(f32, Entity)> {
+		let mut bbox = AABB2::from_point(line.point);
+		bbox.add_point(line.point + line.dir);
+
+		let mut result: Vec<(f32, Entity)> = self
+			.query_bbox(&bbox)
+			.into_iter()
+			.filter_map(|entity| {
+				let bbox = &self.bboxes[&entity];
+				let mut range = Interval::new(0.0, 1.0);
+
+				for i in 0..2 {
+					if line.dir[i] == 0.0 {
+						if !bbox[i].contains(line.point[i]) {
+							return None;
+						}
+					} else {
+						let a = (bbox[i].min - line.point[i]) / line.dir[i];
+						let b = (bbox[i].max - line.point[i]) / line.dir[i];
+						range = range.intersection(Interval::new(a.min(b), a.max(b)));
+					}
+				}
+
+				if range.is_empty() {
+					None
+				} else {
+					Some((range.min, entity))
+				}
+			})
+			.collect();
+
+		result.sort_unstable_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+		result
+	}
+
 	#[inline]
 	pub fn traverse_nodes<F: FnMut(&[Entity])>(&self, bbox: &AABB2, func: &mut F) {
 		self.traverse_nodes_r(0, bbox, func);
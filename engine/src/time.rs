@@ -1,4 +1,7 @@
-use std::time::Duration;
+use std::{
+	thread,
+	time::{Duration, Instant},
+};
 
 /// A timer that elapses at the specified time
 #[derive(Clone, Copy, Debug)]
@@ -42,3 +45,26 @@ impl Timer {
 		self.target_time = target_time;
 	}
 }
+
+/// Sleeps the current thread until the given time.
+/// Sleeping is not very precise on some platforms, so the last stretch is spent yielding
+/// instead.
+pub fn sleep_until(deadline: Instant) {
+	const YIELD_TIME: Duration = Duration::from_micros(500);
+
+	loop {
+		let now = Instant::now();
+
+		if now >= deadline {
+			break;
+		}
+
+		let remaining = deadline - now;
+
+		if remaining > YIELD_TIME {
+			thread::sleep(remaining - YIELD_TIME);
+		} else {
+			thread::yield_now();
+		}
+	}
+}
@@ -0,0 +1,166 @@
+// The encoder is a fast one, meant for compressing at load time: each block's endpoints are
+// the corners of the bounding box of its colours, and each texel picks the nearest
+// of the interpolated colours. Doom's textures have few colours per block, so this does well.
+
+/// Compresses RGBA data to BC1. Alpha is ignored, so this is only for opaque images.
+/// `width` and `height` must be multiples of 4.
+pub fn compress_bc1(data: &[u8], width: usize, height: usize) -> Vec<u8> {
+	let mut output = Vec::with_capacity(width * height / 2);
+
+	for_each_block(data, width, height, |block| {
+		output.extend_from_slice(&encode_colour(block));
+	});
+
+	output
+}
+
+/// Compresses RGBA data to BC3, which keeps the alpha channel.
+/// `width` and `height` must be multiples of 4.
+pub fn compress_bc3(data: &[u8], width: usize, height: usize) -> Vec<u8> {
+	let mut output = Vec::with_capacity(width * height);
+
+	for_each_block(data, width, height, |block| {
+		output.extend_from_slice(&encode_alpha(block));
+		output.extend_from_slice(&encode_colour(block));
+	});
+
+	output
+}
+
+type Block = [[u8; 4]; 16];
+
+fn for_each_block(data: &[u8], width: usize, height: usize, mut func: impl FnMut(&Block)) {
+	assert!(width % 4 == 0 && height % 4 == 0);
+	assert_eq!(data.len(), width * height * 4);
+	let mut block = [[0u8; 4]; 16];
+
+	for block_y in (0..height).step_by(4) {
+		for block_x in (0..width).step_by(4) {
+			for y in 0..4 {
+				for x in 0..4 {
+					let i = ((block_y + y) * width + block_x + x) * 4;
+					block[y * 4 + x].copy_from_slice(&data[i..i + 4]);
+				}
+			}
+
+			func(&block);
+		}
+	}
+}
+
+fn encode_colour(block: &Block) -> [u8; 8] {
+	let mut min = [u8::MAX; 3];
+	let mut max = [u8::MIN; 3];
+
+	for texel in block.iter() {
+		for (c, &value) in texel[..3].iter().enumerate() {
+			min[c] = min[c].min(value);
+			max[c] = max[c].max(value);
+		}
+	}
+
+	// Pull the corners in a little, so that a single outlier doesn't spread out the others
+	for (min, max) in min.iter_mut().zip(max.iter_mut()) {
+		let inset = (*max - *min) / 16;
+		*min += inset;
+		*max -= inset;
+	}
+
+	// The first colour must be the larger one to get four colours instead of three
+	let mut colour0 = to_565(max);
+	let mut colour1 = to_565(min);
+
+	if colour0 < colour1 {
+		std::mem::swap(&mut colour0, &mut colour1);
+	}
+
+	let mut result = [0u8; 8];
+	result[0..2].copy_from_slice(&colour0.to_le_bytes());
+	result[2..4].copy_from_slice(&colour1.to_le_bytes());
+
+	if colour0 == colour1 {
+		return result;
+	}
+
+	let c0 = from_565(colour0);
+	let c1 = from_565(colour1);
+	let lerp = |weight0: u32, weight1: u32| {
+		let mut colour = [0u8; 3];
+
+		for (c, value) in colour.iter_mut().enumerate() {
+			*value = ((weight0 * c0[c] as u32 + weight1 * c1[c] as u32) / 3) as u8;
+		}
+
+		colour
+	};
+	let palette = [c0, c1, lerp(2, 1), lerp(1, 2)];
+
+	let mut indices = 0u32;
+
+	for (i, texel) in block.iter().enumerate() {
+		let best = (0..4)
+			.min_by_key(|&p| {
+				palette[p]
+					.iter()
+					.zip(texel.iter())
+					.map(|(&a, &b)| {
+						let diff = a as i32 - b as i32;
+						diff * diff
+					})
+					.sum::<i32>()
+			})
+			.unwrap();
+		indices |= (best as u32) << (i * 2);
+	}
+
+	result[4..8].copy_from_slice(&indices.to_le_bytes());
+	result
+}
+
+fn encode_alpha(block: &Block) -> [u8; 8] {
+	let min = block.iter().map(|texel| texel[3]).min().unwrap();
+	let max = block.iter().map(|texel| texel[3]).max().unwrap();
+
+	let mut result = [0u8; 8];
+	result[0] = max;
+	result[1] = min;
+
+	if max == min {
+		return result;
+	}
+
+	// With the first alpha larger, there are six values in between the two
+	let mut palette = [max, min, 0, 0, 0, 0, 0, 0];
+
+	for (i, value) in palette[2..].iter_mut().enumerate() {
+		let i = i as u32 + 1;
+		*value = (((7 - i) * max as u32 + i * min as u32) / 7) as u8;
+	}
+
+	let mut indices = 0u64;
+
+	for (i, texel) in block.iter().enumerate() {
+		let best = (0..8)
+			.min_by_key(|&p| (palette[p] as i32 - texel[3] as i32).abs())
+			.unwrap();
+		indices |= (best as u64) << (i * 3);
+	}
+
+	result[2..8].copy_from_slice(&indices.to_le_bytes()[0..6]);
+	result
+}
+
+fn to_565(colour: [u8; 3]) -> u16 {
+	((colour[0] as u16 >> 3) << 11) | ((colour[1] as u16 >> 2) << 5) | (colour[2] as u16 >> 3)
+}
+
+fn from_565(colour: u16) -> [u8; 3] {
+	let r = (colour >> 11) as u8 & 0x1F;
+	let g = (colour >> 5) as u8 & 0x3F;
+	let b = colour as u8 & 0x1F;
+	[
+		(r << 3) | (r >> 2),
+		(g << 2) | (g >> 4),
+		(b << 3) | (b >> 2),
+	]
+}
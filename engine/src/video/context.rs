@@ -9,7 +9,6 @@ use vulkano::{
 	},
 	swapchain::Surface,
 };
-use vulkano_win::VkSurfaceBuild;
 use winit::{
 	dpi::Size,
 	event_loop::EventLoop,
@@ -19,7 +18,7 @@ use winit::{
 pub struct RenderContext {
 	device: Arc<Device>,
 	queues: Queues,
-	surface: Arc<Surface<Window>>,
+	surface: Arc<Surface<Arc<Window>>>,
 }
 
 impl RenderContext {
@@ -35,12 +34,14 @@ impl RenderContext {
 		let instance = create_instance().context("Couldn't create Vulkan instance")?;
 
 		log::debug!("Creating Vulkan window and surface");
-		let surface = WindowBuilder::new()
+		let window = WindowBuilder::new()
 			.with_min_inner_size(Size::Physical([320, 240].into()))
 			.with_inner_size(Size::Physical([800, 600].into()))
 			.with_title("Ferret")
-			.build_vk_surface(event_loop, instance.clone())
-			.context("Couldn't create Vulkan rendering window")?;
+			.build(event_loop)
+			.context("Couldn't create rendering window")?;
+		let surface = vulkano_win::create_vk_surface(Arc::new(window), instance.clone())
+			.context("Couldn't create Vulkan surface")?;
 
 		// Setup debug callback for validation layers
 		#[cfg(debug_assertions)]
@@ -88,6 +89,34 @@ impl RenderContext {
 		))
 	}
 
+	/// Creates a new Vulkan instance, surface and device for the same window.
+	/// This is the only way to carry on after the device or the surface has been lost.
+	pub fn recreate(&mut self) -> anyhow::Result<()> {
+		log::debug!("Recreating Vulkan instance");
+		let instance = create_instance().context("Couldn't create Vulkan instance")?;
+
+		log::debug!("Recreating Vulkan surface");
+		let surface =
+			vulkano_win::create_vk_surface(self.surface.window().clone(), instance.clone())
+				.context("Couldn't create Vulkan surface")?;
+
+		log::debug!("Recreating Vulkan device");
+		let (device, queues) =
+			create_device(&instance, &surface).context("Couldn't create Vulkan device")?;
+		log::info!(
+			"Selected Vulkan device: {}",
+			device.physical_device().name()
+		);
+
+		*self = RenderContext {
+			device,
+			queues,
+			surface,
+		};
+
+		Ok(())
+	}
+
 	pub fn device(&self) -> &Arc<Device> {
 		&self.device
 	}
@@ -96,7 +125,7 @@ impl RenderContext {
 		&self.queues
 	}
 
-	pub fn surface(&self) -> &Arc<Surface<Window>> {
+	pub fn surface(&self) -> &Arc<Surface<Arc<Window>>> {
 		&self.surface
 	}
 }
@@ -145,7 +174,7 @@ fn create_instance() -> anyhow::Result<Arc<Instance>> {
 
 fn find_suitable_physical_device<'a>(
 	instance: &'a Arc<Instance>,
-	surface: &Surface<Window>,
+	surface: &Surface<Arc<Window>>,
 ) -> anyhow::Result<Option<(PhysicalDevice<'a>, QueueFamily<'a>)>> {
 	for physical_device in PhysicalDevice::enumerate(&instance) {
 		let family = {
@@ -191,12 +220,15 @@ pub struct Queues {
 
 fn create_device(
 	instance: &Arc<Instance>,
-	surface: &Arc<Surface<Window>>,
+	surface: &Arc<Surface<Arc<Window>>>,
 ) -> anyhow::Result<(Arc<Device>, Queues)> {
 	let (physical_device, family) = find_suitable_physical_device(&instance, &surface)?
 		.context("No suitable physical device found")?;
 
-	let features = Features::none();
+	let features = Features {
+		sampler_anisotropy: physical_device.supported_features().sampler_anisotropy,
+		..Features::none()
+	};
 	let extensions = DeviceExtensions {
 		khr_swapchain: true,
 		..DeviceExtensions::none()
@@ -1,23 +1,62 @@
+/// Compression of RGBA images to the BC1 and BC3 block formats, which take an eighth
+/// and a quarter of the memory.
+pub mod bcn;
 mod context;
 pub mod definition;
+pub mod mode;
 mod target;
+mod upload;
 
+use crate::profiler;
 use anyhow::Context;
 use legion::{systems::ResourceSet, Read, Resources, World};
 use std::sync::Arc;
 use vulkano::{
+	buffer::{BufferUsage, CpuBufferPool},
 	command_buffer::{AutoCommandBufferBuilder, CommandBuffer, DynamicState},
 	descriptor::descriptor_set::DescriptorSet,
 	device::Device,
-	format::Format,
+	format::{ClearValue, Format},
 	framebuffer::{Framebuffer, FramebufferAbstract, RenderPassAbstract},
 	image::{AttachmentImage, ImageAccess, ImageUsage},
+	ordered_passes_renderpass,
 	pipeline::viewport::Viewport,
-	single_pass_renderpass,
-	sync::GpuFuture,
+	swapchain::{AcquireError, SwapchainCreationError},
+	sync::{FlushError, GpuFuture},
 };
 
-pub use {context::RenderContext, target::RenderTarget};
+pub use {context::RenderContext, target::RenderTarget, upload::ImageUploader};
+
+/// Whether an error means that the Vulkan device or surface is gone for good,
+/// and the renderer needs to be restarted.
+pub fn is_device_lost(err: &anyhow::Error) -> bool {
+	err.chain().any(|err| {
+		matches!(
+			err.downcast_ref::<FlushError>(),
+			Some(FlushError::DeviceLost) | Some(FlushError::SurfaceLost)
+		) || matches!(
+			err.downcast_ref::<AcquireError>(),
+			Some(AcquireError::DeviceLost) | Some(AcquireError::SurfaceLost)
+		) || matches!(
+			err.downcast_ref::<SwapchainCreationError>(),
+			Some(SwapchainCreationError::DeviceLost) | Some(SwapchainCreationError::SurfaceLost)
+		)
+	})
+}
+
+/// Creates a pool for data that is uploaded to the GPU every frame.
+/// The pool is a persistently mapped ring buffer; reserving enough space up front means that
+/// it normally never has to allocate again, it just reuses chunks once the GPU is done with them.
+pub fn ring_buffer_pool<T>(
+	device: &Arc<Device>,
+	usage: BufferUsage,
+	capacity: usize,
+) -> anyhow::Result<CpuBufferPool<T>> {
+	let pool = CpuBufferPool::new(device.clone(), usage);
+	pool.reserve(capacity)
+		.context("Couldn't allocate buffer pool")?;
+	Ok(pool)
+}
 
 pub trait AsBytes {
 	fn as_bytes(&self) -> &[u8];
@@ -32,13 +71,19 @@ impl<T> AsBytes for Vec<T> {
 	}
 }
 
+/// Draws a frame in two subpasses. The first draws the scene, the second reads the scene
+/// as an input attachment and writes the final image, so that it can apply
+/// post-processing effects.
 pub struct DrawList {
 	steps: Vec<Box<dyn DrawStep>>,
+	post_steps: Vec<Box<dyn DrawStep>>,
 
-	colour_attachment: Arc<AttachmentImage>,
+	scene_attachment: Arc<AttachmentImage>,
 	depth_attachment: Arc<AttachmentImage>,
+	colour_attachment: Arc<AttachmentImage>,
 	framebuffer: Arc<dyn FramebufferAbstract + Send + Sync>,
 	render_pass: Arc<dyn RenderPassAbstract + Send + Sync>,
+	pixel_aspect: f32,
 }
 
 impl DrawList {
@@ -72,11 +117,11 @@ impl DrawList {
 
 		// Create render pass
 		let render_pass: Arc<dyn RenderPassAbstract + Send + Sync> = Arc::new(
-			single_pass_renderpass!(render_context.device().clone(),
+			ordered_passes_renderpass!(render_context.device().clone(),
 				attachments: {
-					color: {
+					scene: {
 						load: Clear,
-						store: Store,
+						store: DontCare,
 						format: colour_format,
 						samples: 1,
 					},
@@ -85,17 +130,31 @@ impl DrawList {
 						store: DontCare,
 						format: depth_format,
 						samples: 1,
+					},
+					color: {
+						load: DontCare,
+						store: Store,
+						format: colour_format,
+						samples: 1,
 					}
 				},
-				pass: {
-					color: [color],
-					depth_stencil: {depth}
-				}
+				passes: [
+					{
+						color: [scene],
+						depth_stencil: {depth},
+						input: []
+					},
+					{
+						color: [color],
+						depth_stencil: {},
+						input: [scene]
+					}
+				]
 			)
 			.context("Couldn't create render pass")?,
 		);
 
-		let (colour_attachment, depth_attachment) = Self::create_attachments(
+		let (scene_attachment, depth_attachment, colour_attachment) = Self::create_attachments(
 			&render_context.device(),
 			dimensions,
 			colour_format,
@@ -105,25 +164,36 @@ impl DrawList {
 		// Create framebuffer
 		let framebuffer = Arc::new(
 			Framebuffer::start(render_pass.clone())
-				.add(colour_attachment.clone())?
+				.add(scene_attachment.clone())?
 				.add(depth_attachment.clone())?
+				.add(colour_attachment.clone())?
 				.build()
 				.context("Couldn't create framebuffers")?,
 		);
 
 		Ok(DrawList {
 			steps: Vec::new(),
-			colour_attachment,
+			post_steps: Vec::new(),
+			scene_attachment,
 			depth_attachment,
+			colour_attachment,
 			framebuffer,
 			render_pass,
+			pixel_aspect: 1.0,
 		})
 	}
 
+	/// Adds a step that draws into the scene, in subpass 0.
 	pub fn add_step(&mut self, step: impl DrawStep + 'static) {
 		self.steps.push(Box::from(step));
 	}
 
+	/// Adds a step that draws into the final image, in subpass 1.
+	/// The scene is available to these steps as an input attachment.
+	pub fn add_post_step(&mut self, step: impl DrawStep + 'static) {
+		self.post_steps.push(Box::from(step));
+	}
+
 	pub fn resize(
 		&mut self,
 		render_context: &RenderContext,
@@ -132,20 +202,22 @@ impl DrawList {
 		log::debug!("Resizing DrawList");
 
 		// Create attachments
-		let (colour_attachment, depth_attachment) = Self::create_attachments(
+		let (scene_attachment, depth_attachment, colour_attachment) = Self::create_attachments(
 			&render_context.device(),
 			dimensions,
 			self.colour_attachment.format(),
 			self.depth_attachment.format(),
 		)?;
-		self.colour_attachment = colour_attachment;
+		self.scene_attachment = scene_attachment;
 		self.depth_attachment = depth_attachment;
+		self.colour_attachment = colour_attachment;
 
 		// Create framebuffer
 		self.framebuffer = Arc::new(
 			Framebuffer::start(self.render_pass.clone())
-				.add(self.colour_attachment.clone())?
+				.add(self.scene_attachment.clone())?
 				.add(self.depth_attachment.clone())?
+				.add(self.colour_attachment.clone())?
 				.build()
 				.context("Couldn't create framebuffers")?,
 		);
@@ -158,7 +230,25 @@ impl DrawList {
 		dimensions: [u32; 2],
 		colour_format: Format,
 		depth_format: Format,
-	) -> anyhow::Result<(Arc<AttachmentImage>, Arc<AttachmentImage>)> {
+	) -> anyhow::Result<(
+		Arc<AttachmentImage>,
+		Arc<AttachmentImage>,
+		Arc<AttachmentImage>,
+	)> {
+		// Create scene attachment, only used within the render pass
+		let scene_attachment = AttachmentImage::with_usage(
+			device.clone(),
+			dimensions,
+			colour_format,
+			ImageUsage {
+				color_attachment: true,
+				input_attachment: true,
+				transient_attachment: true,
+				..ImageUsage::none()
+			},
+		)
+		.context("Couldn't create scene attachment")?;
+
 		// Create colour attachment
 		let colour_attachment = AttachmentImage::with_usage(
 			device.clone(),
@@ -185,7 +275,7 @@ impl DrawList {
 		)
 		.context("Couldn't create depth attachment")?;
 
-		Ok((colour_attachment, depth_attachment))
+		Ok((scene_attachment, depth_attachment, colour_attachment))
 	}
 
 	pub fn dimensions(&self) -> [u32; 2] {
@@ -196,6 +286,11 @@ impl DrawList {
 		&self.render_pass
 	}
 
+	/// Sets the height of a pixel relative to its width, as it will appear on the screen.
+	pub fn set_pixel_aspect(&mut self, pixel_aspect: f32) {
+		self.pixel_aspect = pixel_aspect;
+	}
+
 	pub fn draw(
 		&mut self,
 		world: &World,
@@ -204,7 +299,7 @@ impl DrawList {
 		let render_context = <Read<RenderContext>>::fetch(resources);
 		let graphics_queue = &render_context.queues().graphics;
 
-		let clear_value = vec![[0.0, 0.0, 1.0, 1.0].into(), 1.0.into()];
+		let clear_value = vec![[0.0, 0.0, 1.0, 1.0].into(), 1.0.into(), ClearValue::None];
 		let dimensions = [
 			self.framebuffer.width() as f32,
 			self.framebuffer.height() as f32,
@@ -227,6 +322,9 @@ impl DrawList {
 				..DynamicState::none()
 			},
 			framebuffer: self.framebuffer.clone(),
+			scene_attachment: self.scene_attachment.clone(),
+			pixel_aspect: self.pixel_aspect,
+			draw_calls: 0,
 		};
 
 		draw_context
@@ -236,10 +334,19 @@ impl DrawList {
 		self.steps
 			.iter_mut()
 			.try_for_each(|step| step.draw(&mut draw_context, world, resources))?;
+		draw_context
+			.commands
+			.next_subpass(false)
+			.context("Couldn't begin post-processing subpass")?;
+		self.post_steps
+			.iter_mut()
+			.try_for_each(|step| step.draw(&mut draw_context, world, resources))?;
 		draw_context
 			.commands
 			.end_render_pass()
 			.context("Couldn't end render pass")?;
+		profiler::count("draw_calls", draw_context.draw_calls);
+
 		let future = draw_context
 			.commands
 			.build()?
@@ -264,4 +371,9 @@ pub struct DrawContext {
 	pub descriptor_sets: Vec<Arc<dyn DescriptorSet + Send + Sync>>,
 	pub dynamic_state: DynamicState,
 	pub framebuffer: Arc<dyn FramebufferAbstract + Send + Sync>,
+	pub scene_attachment: Arc<AttachmentImage>,
+	/// Height of a pixel relative to its width, as it will appear on the screen.
+	pub pixel_aspect: f32,
+	/// Number of draw commands recorded so far, which steps add to after each one.
+	pub draw_calls: u64,
 }
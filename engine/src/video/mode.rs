@@ -0,0 +1,206 @@
+use anyhow::bail;
+use std::{
+	str::FromStr,
+	time::{Duration, Instant},
+};
+use winit::{
+	dpi::PhysicalSize,
+	monitor::{MonitorHandle, VideoMode as MonitorVideoMode},
+	window::{Fullscreen, Window},
+};
+
+/// How the game window is shown on the screen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WindowMode {
+	/// A normal window with decorations.
+	Windowed,
+	/// A window without decorations that covers the whole monitor, at its current resolution.
+	Borderless,
+	/// Exclusive fullscreen, which changes the resolution of the monitor.
+	Fullscreen,
+}
+
+impl FromStr for WindowMode {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> anyhow::Result<WindowMode> {
+		Ok(match s {
+			"windowed" => WindowMode::Windowed,
+			"borderless" => WindowMode::Borderless,
+			"fullscreen" => WindowMode::Fullscreen,
+			_ => bail!("Invalid window mode: {}", s),
+		})
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VideoMode {
+	pub window_mode: WindowMode,
+	/// Size of the window, or the monitor resolution in exclusive fullscreen.
+	/// Not used for borderless windows.
+	pub dimensions: [u32; 2],
+	/// Which monitor to use when fullscreen, as a number from 1 as listed by `monitors`.
+	/// 0 means the monitor the window is on.
+	pub monitor: u32,
+}
+
+/// Switches the window to a new video mode.
+/// The swapchain is recreated once the window reports its new size.
+pub fn set_video_mode(window: &Window, video_mode: &VideoMode) {
+	log::info!(
+		"Setting video mode: {:?}, {}x{}",
+		video_mode.window_mode,
+		video_mode.dimensions[0],
+		video_mode.dimensions[1]
+	);
+
+	let monitor = select_monitor(window, video_mode.monitor);
+
+	match video_mode.window_mode {
+		WindowMode::Windowed => {
+			window.set_fullscreen(None);
+			window.set_decorations(true);
+			window.set_inner_size(PhysicalSize::new(
+				video_mode.dimensions[0],
+				video_mode.dimensions[1],
+			));
+		}
+		WindowMode::Borderless => {
+			window.set_fullscreen(Some(Fullscreen::Borderless(monitor)));
+		}
+		WindowMode::Fullscreen => match find_monitor_video_mode(&monitor, video_mode.dimensions) {
+			Some(monitor_video_mode) => {
+				window.set_fullscreen(Some(Fullscreen::Exclusive(monitor_video_mode)))
+			}
+			None => {
+				log::warn!(
+					"The monitor doesn't support {}x{}, using a borderless window instead",
+					video_mode.dimensions[0],
+					video_mode.dimensions[1]
+				);
+				window.set_fullscreen(Some(Fullscreen::Borderless(monitor)));
+			}
+		},
+	}
+}
+
+/// Returns the resolutions that the current monitor supports in exclusive fullscreen,
+/// from largest to smallest.
+pub fn available_resolutions(window: &Window) -> Vec<[u32; 2]> {
+	let mut resolutions: Vec<[u32; 2]> = window
+		.current_monitor()
+		.video_modes()
+		.map(|mode| mode.size().into())
+		.collect();
+	resolutions.sort_unstable_by(|a, b| b.cmp(a));
+	resolutions.dedup();
+	resolutions
+}
+
+/// Finds the monitor video mode with the given resolution,
+/// preferring the highest refresh rate and bit depth.
+fn find_monitor_video_mode(
+	monitor: &MonitorHandle,
+	dimensions: [u32; 2],
+) -> Option<MonitorVideoMode> {
+	monitor
+		.video_modes()
+		.filter(|mode| {
+			let size: [u32; 2] = mode.size().into();
+			size == dimensions
+		})
+		.max_by_key(|mode| (mode.refresh_rate(), mode.bit_depth()))
+}
+
+/// Returns the monitors that are connected, in the order that `VideoMode::monitor` counts them.
+pub fn monitors(window: &Window) -> Vec<MonitorHandle> {
+	window.available_monitors().collect()
+}
+
+/// Returns the monitor with the given number from `monitors`, counting from 1,
+/// or the monitor the window is on for 0 or a monitor that isn't there.
+pub fn select_monitor(window: &Window, number: u32) -> MonitorHandle {
+	if number == 0 {
+		return window.current_monitor();
+	}
+
+	match monitors(window).into_iter().nth(number as usize - 1) {
+		Some(monitor) => monitor,
+		None => {
+			log::warn!(
+				"There is no monitor {}, using the current monitor instead",
+				number
+			);
+			window.current_monitor()
+		}
+	}
+}
+
+/// Returns the refresh rate that the monitor is most likely running at.
+/// The current mode of a monitor can't be asked for directly, so this is the highest rate
+/// among its modes that have its current resolution.
+pub fn refresh_rate(monitor: &MonitorHandle) -> Option<u16> {
+	let size = monitor.size();
+
+	monitor
+		.video_modes()
+		.filter(|mode| mode.size() == size)
+		.map(|mode| mode.refresh_rate())
+		.filter(|&rate| rate > 0)
+		.max()
+}
+
+/// How often `MonitorWatcher` looks for monitors that were connected or disconnected.
+const MONITOR_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Notices when monitors are connected or disconnected, since the window system
+/// doesn't send events for it.
+#[derive(Debug, Default)]
+pub struct MonitorWatcher {
+	monitors: Vec<MonitorHandle>,
+	last_check: Option<Instant>,
+}
+
+impl MonitorWatcher {
+	#[inline]
+	pub fn new() -> MonitorWatcher {
+		MonitorWatcher::default()
+	}
+
+	/// Returns `None` if it isn't time to look again yet. Otherwise returns whether
+	/// the connected monitors changed since the last look, which they always have the first time.
+	pub fn check(&mut self, window: &Window) -> Option<bool> {
+		if let Some(last_check) = self.last_check {
+			if last_check.elapsed() < MONITOR_CHECK_INTERVAL {
+				return None;
+			}
+		}
+
+		self.last_check = Some(Instant::now());
+		let monitors = monitors(window);
+
+		if monitors == self.monitors {
+			return Some(false);
+		}
+
+		for (i, monitor) in monitors.iter().enumerate() {
+			let size = monitor.size();
+			log::debug!(
+				"Monitor {}: {}, {}x{}",
+				i + 1,
+				monitor.name().as_deref().unwrap_or("unnamed"),
+				size.width,
+				size.height
+			);
+		}
+
+		self.monitors = monitors;
+		Some(true)
+	}
+}
+
+/// The refresh rate of the monitor the game is shown on, for pacing frames.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MonitorInfo {
+	pub refresh_rate: Option<u16>,
+}
@@ -14,15 +14,27 @@ use vulkano::{
 use winit::window::Window;
 
 pub struct RenderTarget {
-	images: Vec<Arc<SwapchainImage<Window>>>,
-	swapchain: Arc<Swapchain<Window>>,
+	images: Vec<Arc<SwapchainImage<Arc<Window>>>>,
+	swapchain: Arc<Swapchain<Arc<Window>>>,
 	needs_recreate: bool,
+	present_modes: Vec<PresentMode>,
 }
 
+/// The present modes to use if nothing else was requested, in order of preference.
+const DEFAULT_PRESENT_MODES: [PresentMode; 2] = [PresentMode::Mailbox, PresentMode::Fifo];
+
 impl RenderTarget {
-	pub fn new(surface: Arc<Surface<Window>>, device: Arc<Device>) -> anyhow::Result<RenderTarget> {
-		let params =
-			choose_swapchain_params(&device, &surface, surface.window().inner_size().into())?;
+	pub fn new(
+		surface: Arc<Surface<Arc<Window>>>,
+		device: Arc<Device>,
+	) -> anyhow::Result<RenderTarget> {
+		let present_modes = DEFAULT_PRESENT_MODES.to_vec();
+		let params = choose_swapchain_params(
+			&device,
+			&surface,
+			surface.window().inner_size().into(),
+			&present_modes,
+		)?;
 		log::debug!("Creating swapchain: {:?}", params);
 
 		// Create swapchain and images
@@ -51,6 +63,7 @@ impl RenderTarget {
 			images,
 			swapchain,
 			needs_recreate: false,
+			present_modes,
 		})
 	}
 
@@ -59,6 +72,7 @@ impl RenderTarget {
 			&self.swapchain.device(),
 			self.swapchain.surface(),
 			self.swapchain.surface().window().inner_size().into(),
+			&self.present_modes,
 		)?;
 		log::debug!("Creating swapchain: {:?}", params);
 
@@ -90,11 +104,9 @@ impl RenderTarget {
 			Err(err) => Err(err).context("Couldn't recreate swapchain")?,
 		};
 
-		*self = RenderTarget {
-			images,
-			swapchain,
-			needs_recreate: false,
-		};
+		self.images = images;
+		self.swapchain = swapchain;
+		self.needs_recreate = false;
 
 		Ok(())
 	}
@@ -109,6 +121,17 @@ impl RenderTarget {
 		self.needs_recreate
 	}
 
+	/// Sets the present modes to use, in order of preference.
+	/// The first one that the surface supports is used; if there are none, FIFO is used,
+	/// which is always supported. The swapchain is recreated if the modes changed.
+	pub fn set_present_modes(&mut self, present_modes: &[PresentMode]) {
+		if present_modes != self.present_modes.as_slice() {
+			log::debug!("Present modes changed to {:?}", present_modes);
+			self.present_modes = present_modes.to_vec();
+			self.needs_recreate = true;
+		}
+	}
+
 	#[inline]
 	pub fn window_resized(&mut self, dimensions: [u32; 2]) {
 		log::debug!("Window resized to {:?}", dimensions);
@@ -118,10 +141,13 @@ impl RenderTarget {
 		}
 	}
 
+	/// Presents `image` on the screen. If `aspect_ratio` is given, the image is shown with that
+	/// aspect ratio, centred, and the rest of the screen is black.
 	pub fn present(
 		&mut self,
 		queue: &Arc<Queue>,
 		image: Arc<AttachmentImage>,
+		aspect_ratio: Option<f32>,
 		draw_future: impl GpuFuture,
 	) -> anyhow::Result<()> {
 		if self.needs_recreate() {
@@ -142,13 +168,51 @@ impl RenderTarget {
 
 		self.needs_recreate = suboptimal;
 
-		// Blit colour attachment onto swapchain
+		// Blit colour attachment onto swapchain, scaling it if it's a different size
 		let blit_command = {
 			let [width, height, depth] = image.dimensions().width_height_depth();
+			let [swapchain_width, swapchain_height] = self.dimensions();
 			let mut builder = AutoCommandBufferBuilder::primary_one_time_submit(
 				self.swapchain.device().clone(),
 				queue.family(),
 			)?;
+
+			let (destination_min, destination_max) = match aspect_ratio {
+				Some(aspect_ratio) => {
+					builder.clear_color_image(
+						self.images[image_num].clone(),
+						[0.0, 0.0, 0.0, 1.0].into(),
+					)?;
+
+					let fit_width = (swapchain_height as f32 * aspect_ratio) as u32;
+					let [fit_width, fit_height] = if fit_width <= swapchain_width {
+						[fit_width, swapchain_height]
+					} else {
+						[
+							swapchain_width,
+							(swapchain_width as f32 / aspect_ratio) as u32,
+						]
+					};
+					let offset = [
+						(swapchain_width - fit_width) / 2,
+						(swapchain_height - fit_height) / 2,
+					];
+
+					(
+						[offset[0] as i32, offset[1] as i32, 0],
+						[
+							(offset[0] + fit_width) as i32,
+							(offset[1] + fit_height) as i32,
+							1,
+						],
+					)
+				}
+				None => (
+					[0, 0, 0],
+					[swapchain_width as i32, swapchain_height as i32, 1],
+				),
+			};
+
 			builder.blit_image(
 				image,
 				[0, 0, 0],
@@ -156,8 +220,8 @@ impl RenderTarget {
 				0,
 				0,
 				self.images[image_num].clone(),
-				[0, 0, 0],
-				[width as i32, height as i32, depth as i32],
+				destination_min,
+				destination_max,
 				0,
 				0,
 				1,
@@ -196,8 +260,9 @@ struct SwapchainParams {
 
 fn choose_swapchain_params(
 	device: &Arc<Device>,
-	surface: &Arc<Surface<Window>>,
+	surface: &Arc<Surface<Arc<Window>>>,
 	dimensions: [u32; 2],
+	present_modes: &[PresentMode],
 ) -> anyhow::Result<SwapchainParams> {
 	let physical_device = device.physical_device();
 	let capabilities = surface.capabilities(device.physical_device())?;
@@ -224,9 +289,10 @@ fn choose_swapchain_params(
 		.context("No suitable format found")?,
 		dimensions: capabilities.current_extent.unwrap_or(dimensions),
 		transform: capabilities.current_transform,
-		present_mode: [PresentMode::Mailbox, PresentMode::Fifo]
+		present_mode: present_modes
 			.iter()
 			.copied()
+			.chain(std::iter::once(PresentMode::Fifo))
 			.find(|mode| capabilities.present_modes.supports(*mode))
 			.context("No suitable present mode found")?,
 	})
@@ -0,0 +1,118 @@
+use crate::video::RenderContext;
+use anyhow::Context;
+use std::{ops::Range, sync::Arc};
+use vulkano::{
+	buffer::{BufferAccess, BufferUsage, CpuAccessibleBuffer},
+	command_buffer::{AutoCommandBufferBuilder, CommandBuffer},
+	format::Format,
+	image::{
+		Dimensions, ImageLayout, ImageUsage, ImmutableImage, ImmutableImageInitialization,
+		MipmapsCount,
+	},
+	sync::GpuFuture,
+};
+
+/// Collects the data of newly created images, so that they can all be uploaded to the GPU
+/// at once, with one staging buffer and one command buffer, instead of one of each per image.
+#[derive(Default)]
+pub struct ImageUploader {
+	data: Vec<u8>,
+	images: Vec<(
+		ImmutableImageInitialization<Format>,
+		Range<usize>,
+		Dimensions,
+	)>,
+}
+
+impl ImageUploader {
+	#[inline]
+	pub fn new() -> ImageUploader {
+		ImageUploader::default()
+	}
+
+	/// Creates an image and queues up its data to be uploaded.
+	/// The image must not be used before `upload` is called.
+	pub fn add(
+		&mut self,
+		render_context: &RenderContext,
+		data: &[u8],
+		dimensions: Dimensions,
+		format: Format,
+	) -> anyhow::Result<Arc<ImmutableImage<Format>>> {
+		let (image, init) = ImmutableImage::uninitialized(
+			render_context.device().clone(),
+			dimensions,
+			format,
+			MipmapsCount::One,
+			ImageUsage {
+				transfer_destination: true,
+				sampled: true,
+				..ImageUsage::none()
+			},
+			ImageLayout::ShaderReadOnlyOptimal,
+			render_context.device().active_queue_families(),
+		)
+		.context("Couldn't create image")?;
+
+		// Copies from a buffer must start at a multiple of 4 bytes and of the texel block size,
+		// which is at most 16 bytes for the formats used
+		self.data.resize((self.data.len() + 15) & !15, 0);
+		let start = self.data.len();
+		self.data.extend_from_slice(data);
+		self.images.push((init, start..self.data.len(), dimensions));
+
+		Ok(image)
+	}
+
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.images.is_empty()
+	}
+
+	/// Uploads all queued images in a single submission, and waits until it's done.
+	pub fn upload(self, render_context: &RenderContext) -> anyhow::Result<()> {
+		if self.images.is_empty() {
+			return Ok(());
+		}
+
+		let queue = render_context.queues().graphics.clone();
+		let buffer = CpuAccessibleBuffer::from_iter(
+			render_context.device().clone(),
+			BufferUsage::transfer_source(),
+			false,
+			self.data.into_iter(),
+		)
+		.context("Couldn't create staging buffer")?;
+
+		let mut builder = AutoCommandBufferBuilder::primary_one_time_submit(
+			render_context.device().clone(),
+			queue.family(),
+		)?;
+		let count = self.images.len();
+
+		for (init, range, dimensions) in self.images {
+			let source = buffer.clone().into_buffer_slice().slice(range).unwrap();
+			builder
+				.copy_buffer_to_image_dimensions(
+					source,
+					init,
+					[0, 0, 0],
+					dimensions.width_height_depth(),
+					0,
+					dimensions.array_layers_with_cube(),
+					0,
+				)
+				.context("Couldn't record image upload")?;
+		}
+
+		builder
+			.build()?
+			.execute(queue)?
+			.then_signal_fence_and_flush()?
+			.wait(None)
+			.context("Couldn't upload images")?;
+
+		log::debug!("Uploaded {} images", count);
+		Ok(())
+	}
+}
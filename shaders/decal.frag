@@ -0,0 +1,12 @@
+#version 450
+
+layout(location = 0) in vec2 frag_texture_coord;
+layout(location = 1) in vec3 frag_colour;
+
+layout(location = 0) out vec4 out_color;
+
+void main() {
+	// Round mark with a soft edge, multiplied with the wall behind it
+	float strength = 1.0 - smoothstep(0.5, 1.0, length(frag_texture_coord));
+	out_color = vec4(mix(vec3(1.0), frag_colour, strength), 1.0);
+}
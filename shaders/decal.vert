@@ -0,0 +1,33 @@
+#version 450
+
+layout(set = 0, binding = 0) uniform Matrices {
+	mat4 proj;
+	mat4 view;
+	mat4 billboard;
+};
+
+// Per-instance
+layout(location = 0) in vec3 in_position;
+layout(location = 1) in vec3 in_right;
+layout(location = 2) in vec3 in_up;
+layout(location = 3) in vec3 in_colour;
+
+// Output
+layout(location = 0) out vec2 frag_texture_coord;
+layout(location = 1) out vec3 frag_colour;
+
+out gl_PerVertex {
+	vec4 gl_Position;
+};
+
+void main() {
+	vec2 corner;
+	corner.x = gl_VertexIndex >> 1;
+	corner.y = (gl_VertexIndex & 1) ^ (gl_VertexIndex >> 1);
+	frag_texture_coord = corner * 2.0 - 1.0;
+
+	vec3 vert = in_position + in_right * frag_texture_coord.x + in_up * frag_texture_coord.y;
+	gl_Position = proj * view * vec4(vert, 1.0);
+
+	frag_colour = in_colour;
+}
@@ -0,0 +1,43 @@
+#version 450
+
+#define MAX_DYNAMIC_LIGHTS 32
+
+layout(set = 1, binding = 0) uniform sampler2DArray texture_sampler;
+
+// Position and radius, colour. Unused lights have a radius of 0.
+layout(set = 1, binding = 1) uniform DynamicLights {
+	vec4 light_positions[MAX_DYNAMIC_LIGHTS];
+	vec4 light_colours[MAX_DYNAMIC_LIGHTS];
+};
+
+layout(location = 0) in vec2 frag_texture_coord;
+layout(location = 1) in float frag_light_level;
+layout(location = 2) in float frag_texture_layer;
+layout(location = 3) in vec3 frag_position;
+
+layout(location = 0) out vec4 out_color;
+
+void main() {
+	float light_factor = 60.0 - 64.0 * frag_light_level;
+	float distance_factor = 80.0 / ((0.0625 / gl_FragCoord.w) + 1.0);
+	float light_level = 1.0 - (light_factor - distance_factor) / 32.0;
+	light_level = clamp(light_level, 0.0, 1.0);
+
+	// Dynamic lights fall off linearly up to their radius
+	vec3 light = vec3(light_level);
+
+	for (int i = 0; i < MAX_DYNAMIC_LIGHTS; i++) {
+		float radius = light_positions[i].w;
+
+		if (radius > 0.0) {
+			float dist = distance(frag_position, light_positions[i].xyz);
+			light += light_colours[i].rgb * max(1.0 - dist / radius, 0.0);
+		}
+	}
+
+	vec4 texture_texel = texture(texture_sampler, vec3(frag_texture_coord, frag_texture_layer));
+	out_color = vec4(texture_texel.rgb * min(light, 1.0), texture_texel.a);
+
+	if (out_color.a < 0.5)
+		discard;
+}
@@ -8,10 +8,13 @@ layout(set = 0, binding = 0) uniform Matrices {
 
 layout(location = 0) in vec3 in_position;
 layout(location = 1) in vec2 in_texture_coord;
-layout(location = 2) in float in_light_level;
+layout(location = 2) in float in_texture_layer;
+layout(location = 3) in float in_light_level;
 
 layout(location = 0) out vec2 frag_texture_coord;
 layout(location = 1) out float frag_light_level;
+layout(location = 2) out float frag_texture_layer;
+layout(location = 3) out vec3 frag_position;
 
 out gl_PerVertex {
 	vec4 gl_Position;
@@ -20,5 +23,7 @@ out gl_PerVertex {
 void main() {
 	frag_texture_coord = in_texture_coord;
 	frag_light_level = in_light_level;
+	frag_texture_layer = in_texture_layer;
+	frag_position = in_position;
 	gl_Position = proj * view * vec4(in_position, 1);
 }
@@ -8,7 +8,7 @@ layout(set = 0, binding = 0) uniform Matrices {
 
 layout(location = 0) in vec3 in_position;
 
-layout(location = 0) out vec2 frag_texture_coord;
+layout(location = 0) out vec3 frag_direction;
 
 out gl_PerVertex {
 	vec4 gl_Position;
@@ -16,5 +16,7 @@ out gl_PerVertex {
 
 void main() {
 	gl_Position = proj * view * vec4(in_position, 1);
-	frag_texture_coord = (view * vec4(in_position, 1)).xy / gl_Position.z;
+
+	vec3 camera_position = inverse(view)[3].xyz;
+	frag_direction = in_position - camera_position;
 }
@@ -0,0 +1,13 @@
+#version 450
+
+layout(location = 0) in vec2 frag_texture_coord;
+layout(location = 1) in vec4 frag_colour;
+
+layout(location = 0) out vec4 out_color;
+
+void main() {
+	if (length(frag_texture_coord) > 1.0)
+		discard;
+
+	out_color = frag_colour;
+}
@@ -0,0 +1,34 @@
+#version 450
+
+layout(set = 0, binding = 0) uniform Matrices {
+	mat4 proj;
+	mat4 view;
+	mat4 billboard;
+};
+
+// Per-instance
+layout(location = 0) in vec3 in_position;
+layout(location = 1) in float in_size;
+layout(location = 2) in vec4 in_colour;
+
+// Output
+layout(location = 0) out vec2 frag_texture_coord;
+layout(location = 1) out vec4 frag_colour;
+
+out gl_PerVertex {
+	vec4 gl_Position;
+};
+
+void main() {
+	vec2 corner;
+	corner.x = gl_VertexIndex >> 1;
+	corner.y = (gl_VertexIndex & 1) ^ (gl_VertexIndex >> 1);
+	frag_texture_coord = corner * 2.0 - 1.0;
+
+	// Always face the camera
+	vec4 offset = billboard * vec4(0.0, -frag_texture_coord.x, -frag_texture_coord.y, 0.0);
+	vec4 vert = vec4(in_position, 1.0) + offset * in_size * 0.5;
+	gl_Position = proj * view * vert;
+
+	frag_colour = in_colour;
+}
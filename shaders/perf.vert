@@ -0,0 +1,29 @@
+#version 450
+
+layout(set = 0, binding = 0) uniform Matrices {
+	mat4 proj;
+};
+
+// Per-instance
+layout(location = 0) in vec2 in_position;
+layout(location = 1) in vec2 in_size;
+layout(location = 2) in vec4 in_color;
+
+// Output
+layout(location = 0) out vec4 frag_color;
+
+out gl_PerVertex {
+	vec4 gl_Position;
+};
+
+void main() {
+	vec2 corner;
+	corner.x = gl_VertexIndex >> 1;
+	corner.y = (gl_VertexIndex & 1) ^ (gl_VertexIndex >> 1);
+
+	vec4 vert = vec4(in_size * corner, 0.0, 1.0);
+	vert.xy += in_position;
+	gl_Position = proj * vert;
+
+	frag_color = in_color;
+}
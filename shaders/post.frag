@@ -0,0 +1,22 @@
+#version 450
+
+layout(input_attachment_index = 0, set = 0, binding = 0) uniform subpassInput scene;
+
+layout(push_constant) uniform PushConstants {
+	vec4 tint;
+	float invulnerability;
+};
+
+layout(location = 0) out vec4 out_color;
+
+void main() {
+	vec3 color = subpassLoad(scene).rgb;
+
+	// Inverse grayscale colormap
+	if (invulnerability != 0.0) {
+		float gray = dot(color, vec3(0.299, 0.587, 0.114));
+		color = vec3(1.0 - gray);
+	}
+
+	out_color = vec4(mix(color, tint.rgb, tint.a), 1.0);
+}
@@ -0,0 +1,11 @@
+#version 450
+
+out gl_PerVertex {
+	vec4 gl_Position;
+};
+
+void main() {
+	// Single triangle covering the whole screen
+	vec2 corner = vec2((gl_VertexIndex << 1) & 2, gl_VertexIndex & 2);
+	gl_Position = vec4(corner * 2.0 - 1.0, 0.0, 1.0);
+}
@@ -1,22 +1,35 @@
 #version 450
 
 layout(set = 1, binding = 0) uniform sampler2D texture_sampler;
-layout(set = 1, binding = 1) uniform FragParams {
-	float yaw;
-	float pitch;
-	vec2 screenSize;
-} fp;
 
-layout(location = 0) in vec2 frag_texture_coord;
+layout(location = 0) in vec3 frag_direction;
 
 layout(location = 0) out vec4 out_color;
 
+const float PI = 3.14159265358979;
+
+// The sky repeats every 1024 texels around the view, as in the original game.
+const float SKY_CIRCUMFERENCE = 1024.0;
+
+// Distance to the projection plane of the original 320x200 renderer.
+const float PROJECTION_DISTANCE = 160.0;
+
+// The bottom of the texture is this many texels below the horizon.
+const float SKY_BELOW_HORIZON = 28.0;
+
 void main() {
-	vec2 texCoords = gl_FragCoord.xy / fp.screenSize;
-	float ratio = fp.screenSize.x / fp.screenSize.y;
-	texCoords.x = (1.0 - texCoords.x) + (fp.yaw + 45.0) / 360.0 * 4;
-	texCoords.y = (texCoords.y + fp.pitch / 60.0) * ratio;
+	vec2 size = textureSize(texture_sampler, 0);
+
+	// Wrap the texture around a cylinder centred on the viewer
+	float angle = atan(frag_direction.y, frag_direction.x);
+	float column = angle / (2.0 * PI) * SKY_CIRCUMFERENCE;
+
+	// Tall skies reach further upwards, so they can be seen when looking up
+	float slope = frag_direction.z / length(frag_direction.xy);
+	float horizon = size.y - SKY_BELOW_HORIZON;
+	float row = clamp(horizon - slope * PROJECTION_DISTANCE, 0.5, size.y - 0.5);
 
-	vec4 texture_texel = texture(texture_sampler, texCoords);
+	// Mipmapping would show a seam where the angle wraps around
+	vec4 texture_texel = textureLod(texture_sampler, vec2(column, row) / size, 0.0);
 	out_color = vec4(texture_texel.rgb, 1.0);
 }
@@ -15,6 +15,7 @@ layout(set = 1, binding = 1) uniform ImageMatrix {
 layout(location = 0) in mat4 in_transform;
 layout(location = 4) in float in_flip;
 layout(location = 5) in float in_light_level;
+layout(location = 6) in float in_floor_height;
 
 // Output
 layout(location = 0) out vec2 frag_texture_coord;
@@ -28,6 +29,15 @@ void main() {
 	frag_texture_coord.x = gl_VertexIndex >> 1;
 	frag_texture_coord.y = (gl_VertexIndex & 1) ^ (gl_VertexIndex >> 1);
 
+	// Cut off the part of the image that sticks out below the floor
+	float top = in_transform[3].z - (image_matrix * vec4(0.0, 0.0, 0.0, 1.0)).y;
+	float bottom = in_transform[3].z - (image_matrix * vec4(0.0, 1.0, 0.0, 1.0)).y;
+
+	if (bottom < in_floor_height && top > bottom) {
+		float visible = clamp((top - in_floor_height) / (top - bottom), 0.0, 1.0);
+		frag_texture_coord.y = min(frag_texture_coord.y, visible);
+	}
+
 	vec4 vert = image_matrix * vec4(frag_texture_coord, 0.0, 1.0);
 	vert = vec4(0.0, -vert.x, -vert.y, 1.0);
 	gl_Position = proj * view * in_transform * billboard * vert;
@@ -1,12 +0,0 @@
-pub mod assets;
-pub mod audio;
-pub mod commands;
-pub mod configvars;
-pub mod frame;
-pub mod geometry;
-pub mod input;
-pub mod logger;
-pub mod quadtree;
-pub mod spawn;
-pub mod time;
-pub mod video;
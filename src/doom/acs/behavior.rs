@@ -0,0 +1,106 @@
+use crate::common::assets::{AssetStorage, ImportData};
+use anyhow::{bail, ensure, Context};
+use byteorder::{ReadBytesExt, LE};
+use relative_path::RelativePath;
+use std::{io::Cursor, sync::Arc};
+
+/// Script numbers from this one up are run when the map starts, rather than by a special.
+const OPEN_SCRIPT_BASE: u32 = 1000;
+
+/// The compiled ACS scripts of a map, from its BEHAVIOR lump.
+#[derive(Clone, Debug)]
+pub struct Behavior {
+	/// The whole lump, addresses in the scripts are offsets into it.
+	pub code: Arc<[u8]>,
+	pub scripts: Arc<[ScriptDef]>,
+	pub strings: Arc<[String]>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ScriptDef {
+	pub number: u16,
+	pub offset: usize,
+	pub arg_count: usize,
+	/// Whether the script runs by itself when the map starts.
+	pub open: bool,
+}
+
+impl Behavior {
+	pub fn script(&self, number: u16) -> Option<&ScriptDef> {
+		self.scripts
+			.iter()
+			.find(|script| script.number == number && !script.open)
+	}
+}
+
+pub fn import_behavior(
+	path: &RelativePath,
+	asset_storage: &mut AssetStorage,
+) -> anyhow::Result<Box<dyn ImportData>> {
+	let data = asset_storage.source().load(path)?;
+	Ok(Box::new(parse_behavior(data)?))
+}
+
+/// Parses a BEHAVIOR lump in the original Hexen format.
+/// The extended formats of ZDoom's compiler are not supported.
+pub fn parse_behavior(data: Vec<u8>) -> anyhow::Result<Behavior> {
+	ensure!(data.len() >= 8, "BEHAVIOR lump is too short");
+
+	match &data[0..4] {
+		b"ACS\0" => {}
+		b"ACSE" | b"ACSe" => bail!("Extended ACS formats are not supported"),
+		_ => bail!("No ACS signature found"),
+	}
+
+	let mut reader = Cursor::new(&data[..]);
+	reader.set_position(4);
+	let directory_offset = reader.read_u32::<LE>()? as u64;
+	ensure!(
+		directory_offset < data.len() as u64,
+		"ACS directory offset {} is past the end of the lump",
+		directory_offset
+	);
+	reader.set_position(directory_offset);
+
+	// Scripts
+	let script_count = reader.read_u32::<LE>()? as usize;
+	let mut scripts = Vec::with_capacity(script_count.min(data.len() / 12));
+
+	for _ in 0..script_count {
+		let number = reader.read_u32::<LE>()?;
+		let offset = reader.read_u32::<LE>()? as usize;
+		let arg_count = reader.read_u32::<LE>()? as usize;
+
+		ensure!(
+			offset < data.len(),
+			"Script {} starts past the end of the lump",
+			number
+		);
+
+		scripts.push(ScriptDef {
+			number: (number % OPEN_SCRIPT_BASE) as u16,
+			offset,
+			arg_count,
+			open: number >= OPEN_SCRIPT_BASE,
+		});
+	}
+
+	// Strings
+	let string_count = reader.read_u32::<LE>()? as usize;
+	let mut strings = Vec::with_capacity(string_count.min(data.len() / 4));
+
+	for i in 0..string_count {
+		let offset = reader.read_u32::<LE>()? as usize;
+		let bytes = data
+			.get(offset..)
+			.with_context(|| format!("String {} starts past the end of the lump", i))?;
+		let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+		strings.push(String::from_utf8_lossy(&bytes[..end]).into_owned());
+	}
+
+	Ok(Behavior {
+		code: data.into(),
+		scripts: scripts.into(),
+		strings: strings.into(),
+	})
+}
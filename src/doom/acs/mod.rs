@@ -0,0 +1,334 @@
+pub mod behavior;
+pub mod vm;
+
+use crate::{
+	common::{
+		assets::{AssetHandle, AssetStorage},
+		frame::FrameState,
+		profiler,
+	},
+	doom::{
+		acs::{
+			behavior::Behavior,
+			vm::{AcsHost, Script, ScriptState, MAP_VARS, WORLD_VARS},
+		},
+		client::{UseAction, User},
+		game::{GameMode, Skill},
+		health::Dead,
+		linespecial::execute_line_special,
+		map::MapDynamic,
+		message::HudMessage,
+		physics::TouchAction,
+		sectormove::{CeilingMove, FloorMove},
+		sound::Sound,
+		stats::LevelStats,
+	},
+};
+use legion::{
+	component, systems::ResourceSet, Entity, EntityStore, IntoQuery, Read, Resources, World, Write,
+};
+use rand::Rng;
+
+/// The scripts of the current map, and the variables they share.
+#[derive(Clone, Debug)]
+pub struct AcsState {
+	behavior: Option<Behavior>,
+	scripts: Vec<Script>,
+	commands: Vec<AcsCommand>,
+	map_vars: Vec<i32>,
+	world_vars: Vec<i32>,
+}
+
+impl Default for AcsState {
+	fn default() -> AcsState {
+		AcsState {
+			behavior: None,
+			scripts: Vec::new(),
+			commands: Vec::new(),
+			map_vars: vec![0; MAP_VARS],
+			world_vars: vec![0; WORLD_VARS],
+		}
+	}
+}
+
+/// Changes to the running scripts, made by specials.
+/// They take effect the next time the scripts are run.
+#[derive(Clone, Copy, Debug)]
+enum AcsCommand {
+	Execute {
+		number: u16,
+		args: [i32; 3],
+		line: Option<usize>,
+		always: bool,
+	},
+	Suspend(u16),
+	Terminate(u16),
+}
+
+impl AcsState {
+	/// Switches to the scripts of a newly loaded map, and starts its open scripts.
+	/// World variables are kept.
+	pub fn start_map(&mut self, behavior: Option<Behavior>) {
+		self.clear();
+
+		if let Some(behavior) = &behavior {
+			self.scripts.extend(
+				behavior
+					.scripts
+					.iter()
+					.filter(|def| def.open)
+					.map(|def| Script::new(def, &[], None)),
+			);
+		}
+
+		self.behavior = behavior;
+	}
+
+	pub fn clear(&mut self) {
+		self.behavior = None;
+		self.scripts.clear();
+		self.commands.clear();
+		self.map_vars.iter_mut().for_each(|var| *var = 0);
+	}
+
+	/// Starts a script, or resumes it if it was suspended.
+	/// If `always` is set, another copy is started even if the script is already running.
+	pub fn execute(&mut self, number: u16, args: [i32; 3], line: Option<usize>, always: bool) {
+		self.commands.push(AcsCommand::Execute {
+			number,
+			args,
+			line,
+			always,
+		});
+	}
+
+	pub fn suspend(&mut self, number: u16) {
+		self.commands.push(AcsCommand::Suspend(number));
+	}
+
+	pub fn terminate(&mut self, number: u16) {
+		self.commands.push(AcsCommand::Terminate(number));
+	}
+
+	fn apply_commands(&mut self) {
+		let behavior = match &self.behavior {
+			Some(behavior) => behavior,
+			None => {
+				self.commands.clear();
+				return;
+			}
+		};
+
+		for command in self.commands.drain(..) {
+			match command {
+				AcsCommand::Execute {
+					number,
+					args,
+					line,
+					always,
+				} => {
+					if !always {
+						if let Some(script) = self
+							.scripts
+							.iter_mut()
+							.find(|script| script.number == number)
+						{
+							if script.state == ScriptState::Suspended {
+								script.state = ScriptState::Running;
+							}
+
+							continue;
+						}
+					}
+
+					match behavior.script(number) {
+						Some(def) => self.scripts.push(Script::new(def, &args, line)),
+						None => log::warn!("Script {} doesn't exist", number),
+					}
+				}
+				AcsCommand::Suspend(number) => {
+					for script in self.scripts.iter_mut().filter(|s| s.number == number) {
+						script.state = ScriptState::Suspended;
+					}
+				}
+				AcsCommand::Terminate(number) => {
+					for script in self.scripts.iter_mut().filter(|s| s.number == number) {
+						script.state = ScriptState::Terminated;
+					}
+				}
+			}
+		}
+
+		self.scripts
+			.retain(|script| script.state != ScriptState::Terminated);
+	}
+}
+
+/// Runs the scripts of the current map, one step each frame.
+pub fn acs_system() -> Box<dyn FnMut(&mut World, &mut Resources)> {
+	Box::new(move |world, resources| {
+		let _profile = profiler::scope("acs_system");
+
+		let (behavior, mut scripts, mut map_vars, mut world_vars) = {
+			let mut acs_state = <Write<AcsState>>::fetch_mut(resources);
+			acs_state.apply_commands();
+
+			let behavior = match &acs_state.behavior {
+				Some(behavior) => behavior.clone(),
+				None => return,
+			};
+
+			(
+				behavior,
+				std::mem::take(&mut acs_state.scripts),
+				std::mem::take(&mut acs_state.map_vars),
+				std::mem::take(&mut acs_state.world_vars),
+			)
+		};
+
+		let running: Vec<u16> = scripts.iter().map(|script| script.number).collect();
+
+		for script in scripts.iter_mut() {
+			let mut host = LevelHost {
+				world: &mut *world,
+				resources: &mut *resources,
+				line: script.line,
+			};
+
+			script.state = match script.state {
+				ScriptState::Delayed(1) => ScriptState::Running,
+				ScriptState::Delayed(tics) => ScriptState::Delayed(tics - 1),
+				ScriptState::TagWait(tag) if !host.tag_busy(tag) => ScriptState::Running,
+				ScriptState::ScriptWait(number) if !running.contains(&(number as u16)) => {
+					ScriptState::Running
+				}
+				state => state,
+			};
+
+			if let Err(e) = script.run(&behavior, &mut map_vars, &mut world_vars, &mut host) {
+				log::error!("{}", e);
+			}
+		}
+
+		let mut acs_state = <Write<AcsState>>::fetch_mut(resources);
+		scripts.retain(|script| script.state != ScriptState::Terminated);
+		acs_state.scripts = scripts;
+		acs_state.map_vars = map_vars;
+		acs_state.world_vars = world_vars;
+	})
+}
+
+/// Gives scripts access to the current level.
+struct LevelHost<'a> {
+	world: &'a mut World,
+	resources: &'a mut Resources,
+	line: Option<usize>,
+}
+
+impl<'a> AcsHost for LevelHost<'a> {
+	fn line_special(&mut self, special: u8, args: [i32; 5]) -> bool {
+		execute_line_special(special, args, self.line, self.world, self.resources)
+	}
+
+	fn print(&mut self, text: &str) {
+		log::info!("{}", text);
+		<Write<HudMessage>>::fetch_mut(self.resources).show(text);
+	}
+
+	fn random(&mut self, min: i32, max: i32) -> i32 {
+		if max <= min {
+			return min;
+		}
+
+		let frame_state = <Read<FrameState>>::fetch(self.resources);
+		let mut rng = frame_state.rng.lock().unwrap();
+		rng.gen_range(min, max + 1)
+	}
+
+	fn thing_count(&mut self, _type: i32, _tid: i32) -> i32 {
+		// Things don't have thing IDs or spawn numbers yet, so there are none to count
+		0
+	}
+
+	fn player_count(&mut self) -> i32 {
+		<&User>::query()
+			.filter(!component::<Dead>())
+			.iter(self.world)
+			.count() as i32
+	}
+
+	fn game_type(&mut self) -> i32 {
+		match *<Read<GameMode>>::fetch(self.resources) {
+			GameMode::SinglePlayer => 0,
+			GameMode::Cooperative => 1,
+			GameMode::Deathmatch => 2,
+		}
+	}
+
+	fn game_skill(&mut self) -> i32 {
+		// Counted from 0 rather than 1
+		*<Read<Skill>>::fetch(self.resources) as i32 - 1
+	}
+
+	fn timer(&mut self) -> i32 {
+		<Read<LevelStats>>::fetch(self.resources).time as i32
+	}
+
+	fn tag_busy(&mut self, tag: i32) -> bool {
+		let world = &*self.world;
+		let asset_storage = <Read<AssetStorage>>::fetch(self.resources);
+		let map_dynamic = match <&MapDynamic>::query().iter(world).next() {
+			Some(map_dynamic) => map_dynamic,
+			None => return false,
+		};
+		let map = asset_storage.get(&map_dynamic.map).unwrap();
+
+		map.sectors_with_tag(tag as u16)
+			.iter()
+			.any(|&sector_index| {
+				let entry = world
+					.entry_ref(map_dynamic.sectors[sector_index].entity)
+					.unwrap();
+				entry.get_component::<FloorMove>().is_ok()
+					|| entry.get_component::<CeilingMove>().is_ok()
+			})
+	}
+
+	fn sector_sound(&mut self, name: &str, _volume: i32) {
+		let entity = match self.line_front_sector() {
+			Some(entity) => entity,
+			None => return,
+		};
+
+		let (mut asset_storage, mut sound_queue) = <(
+			Write<AssetStorage>,
+			Write<Vec<(AssetHandle<Sound>, Entity)>>,
+		)>::fetch_mut(self.resources);
+		let handle = asset_storage.load::<Sound>(&format!("{}.sound", name.to_ascii_lowercase()));
+		sound_queue.push((handle, entity));
+	}
+
+	fn clear_line_special(&mut self) {
+		let entity = match (self.line, <&MapDynamic>::query().iter(self.world).next()) {
+			(Some(line), Some(map_dynamic)) => map_dynamic.linedefs[line].entity,
+			_ => return,
+		};
+
+		if let Some(mut entry) = self.world.entry(entity) {
+			entry.remove_component::<TouchAction>();
+			entry.remove_component::<UseAction>();
+		}
+	}
+}
+
+impl<'a> LevelHost<'a> {
+	/// Returns the entity of the sector in front of the line that started the script.
+	fn line_front_sector(&self) -> Option<Entity> {
+		let line = self.line?;
+		let asset_storage = <Read<AssetStorage>>::fetch(self.resources);
+		let map_dynamic = <&MapDynamic>::query().iter(&*self.world).next()?;
+		let map = asset_storage.get(&map_dynamic.map).unwrap();
+		let sidedef = map.linedefs[line].sidedefs[0].as_ref()?;
+		Some(map_dynamic.sectors[sidedef.sector_index].entity)
+	}
+}
@@ -0,0 +1,463 @@
+use crate::doom::acs::behavior::{Behavior, ScriptDef};
+use anyhow::{bail, ensure, Context};
+use byteorder::{ByteOrder, LE};
+use std::convert::TryFrom;
+
+/// How many local variables a script has. The arguments it was started with are the first ones.
+pub const SCRIPT_VARS: usize = 10;
+
+/// How many variables are shared by the scripts of a map.
+pub const MAP_VARS: usize = 32;
+
+/// How many variables are shared by the scripts of every map.
+pub const WORLD_VARS: usize = 64;
+
+const STACK_SIZE: usize = 200;
+
+/// How many instructions a script can run in one frame before it's assumed to be stuck.
+const MAX_INSTRUCTIONS: u32 = 500_000;
+
+/// What scripts can do to and find out about the game.
+pub trait AcsHost {
+	/// Runs a line special, returns whether it did anything.
+	fn line_special(&mut self, special: u8, args: [i32; 5]) -> bool;
+	fn print(&mut self, text: &str);
+	/// Returns a random number from `min` to `max` inclusive.
+	fn random(&mut self, min: i32, max: i32) -> i32;
+	fn thing_count(&mut self, r#type: i32, tid: i32) -> i32;
+	fn player_count(&mut self) -> i32;
+	fn game_type(&mut self) -> i32;
+	fn game_skill(&mut self) -> i32;
+	/// The time since the level started, in frames.
+	fn timer(&mut self) -> i32;
+	/// Whether any sector with the tag still has a floor or ceiling moving.
+	fn tag_busy(&mut self, tag: i32) -> bool;
+	/// Plays a sound from the sector in front of the line that started the script.
+	fn sector_sound(&mut self, name: &str, volume: i32);
+	/// Stops the line that started the script from being activated again.
+	fn clear_line_special(&mut self);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScriptState {
+	Running,
+	Suspended,
+	/// Waiting for this many more frames.
+	Delayed(u32),
+	/// Waiting for the sectors with a tag to stop moving.
+	TagWait(i32),
+	/// Waiting for another script to finish.
+	ScriptWait(i32),
+	Terminated,
+}
+
+/// A running instance of a script.
+#[derive(Clone, Debug)]
+pub struct Script {
+	pub number: u16,
+	pub state: ScriptState,
+	/// The index of the linedef whose special started the script.
+	pub line: Option<usize>,
+	start: usize,
+	pc: usize,
+	stack: Vec<i32>,
+	vars: [i32; SCRIPT_VARS],
+	print_buffer: String,
+}
+
+impl Script {
+	pub fn new(def: &ScriptDef, args: &[i32], line: Option<usize>) -> Script {
+		let mut vars = [0; SCRIPT_VARS];
+
+		for (var, arg) in vars.iter_mut().zip(args.iter().take(def.arg_count)) {
+			*var = *arg;
+		}
+
+		Script {
+			number: def.number,
+			state: ScriptState::Running,
+			line,
+			start: def.offset,
+			pc: def.offset,
+			stack: Vec::new(),
+			vars,
+			print_buffer: String::new(),
+		}
+	}
+
+	/// Runs the script until it has to wait or finishes.
+	/// A script that goes wrong is terminated.
+	pub fn run(
+		&mut self,
+		behavior: &Behavior,
+		map_vars: &mut [i32],
+		world_vars: &mut [i32],
+		host: &mut impl AcsHost,
+	) -> anyhow::Result<()> {
+		let result = self.run_instructions(behavior, map_vars, world_vars, host);
+
+		if result.is_err() {
+			self.state = ScriptState::Terminated;
+		}
+
+		result
+	}
+
+	fn run_instructions(
+		&mut self,
+		behavior: &Behavior,
+		map_vars: &mut [i32],
+		world_vars: &mut [i32],
+		host: &mut impl AcsHost,
+	) -> anyhow::Result<()> {
+		for _ in 0..MAX_INSTRUCTIONS {
+			if self.state != ScriptState::Running {
+				return Ok(());
+			}
+
+			let pcode = self.read(behavior)?;
+
+			match pcode {
+				// Nop
+				0 => {}
+				// Terminate
+				1 => self.state = ScriptState::Terminated,
+				// Suspend
+				2 => self.state = ScriptState::Suspended,
+				// PushNumber
+				3 => {
+					let value = self.read(behavior)?;
+					self.push(value)?;
+				}
+				// LSpec1 to LSpec5, arguments on the stack
+				4..=8 => {
+					let special = self.read(behavior)?;
+					let mut args = [0; 5];
+
+					for arg in args[..(pcode - 3) as usize].iter_mut().rev() {
+						*arg = self.pop()?;
+					}
+
+					host.line_special(special as u8, args);
+				}
+				// LSpec1Direct to LSpec5Direct, arguments in the code
+				9..=13 => {
+					let special = self.read(behavior)?;
+					let mut args = [0; 5];
+
+					for arg in args[..(pcode - 8) as usize].iter_mut() {
+						*arg = self.read(behavior)?;
+					}
+
+					host.line_special(special as u8, args);
+				}
+				// Arithmetic and comparisons
+				14..=24 | 70..=74 | 76 | 77 => {
+					let b = self.pop()?;
+					let a = self.pop()?;
+					self.push(binary_op(pcode, a, b)?)?;
+				}
+				// Assign, push, add, subtract, multiply, divide, modulus, increment and
+				// decrement, each for script, map and world variables
+				25..=51 => {
+					let op = (pcode - 25) / 3;
+					let index = self.read(behavior)? as usize;
+					let value = match op {
+						1 | 7 | 8 => 0,
+						_ => self.pop()?,
+					};
+					let var = match (pcode - 25) % 3 {
+						0 => self.vars.get_mut(index),
+						1 => map_vars.get_mut(index),
+						_ => world_vars.get_mut(index),
+					}
+					.with_context(|| format!("Variable {} doesn't exist", index))?;
+
+					match op {
+						0 => *var = value,
+						1 => {
+							let value = *var;
+							self.push(value)?;
+						}
+						2 => *var = var.wrapping_add(value),
+						3 => *var = var.wrapping_sub(value),
+						4 => *var = var.wrapping_mul(value),
+						5 => *var = binary_op(17, *var, value)?,
+						6 => *var = binary_op(18, *var, value)?,
+						7 => *var = var.wrapping_add(1),
+						_ => *var = var.wrapping_sub(1),
+					}
+				}
+				// Goto
+				52 => self.pc = self.read_address(behavior)?,
+				// IfGoto
+				53 => {
+					let address = self.read_address(behavior)?;
+
+					if self.pop()? != 0 {
+						self.pc = address;
+					}
+				}
+				// Drop
+				54 => {
+					self.pop()?;
+				}
+				// Delay
+				55 => {
+					let tics = self.pop()?;
+					self.delay(tics);
+				}
+				// DelayDirect
+				56 => {
+					let tics = self.read(behavior)?;
+					self.delay(tics);
+				}
+				// Random
+				57 => {
+					let max = self.pop()?;
+					let min = self.pop()?;
+					self.push(host.random(min, max))?;
+				}
+				// RandomDirect
+				58 => {
+					let min = self.read(behavior)?;
+					let max = self.read(behavior)?;
+					self.push(host.random(min, max))?;
+				}
+				// ThingCount
+				59 => {
+					let tid = self.pop()?;
+					let r#type = self.pop()?;
+					self.push(host.thing_count(r#type, tid))?;
+				}
+				// ThingCountDirect
+				60 => {
+					let r#type = self.read(behavior)?;
+					let tid = self.read(behavior)?;
+					self.push(host.thing_count(r#type, tid))?;
+				}
+				// TagWait
+				61 => {
+					let tag = self.pop()?;
+					self.state = ScriptState::TagWait(tag);
+				}
+				// TagWaitDirect
+				62 => {
+					let tag = self.read(behavior)?;
+					self.state = ScriptState::TagWait(tag);
+				}
+				// PolyWait, there are no polyobjects so there's nothing to wait for
+				63 => {
+					self.pop()?;
+				}
+				// PolyWaitDirect
+				64 => {
+					self.read(behavior)?;
+				}
+				// ChangeFloor and ChangeCeiling, sector flats can't be changed yet
+				65 | 67 => {
+					self.pop()?;
+					self.pop()?;
+					log::debug!("Script {}: changing flats is not supported", self.number);
+				}
+				// ChangeFloorDirect and ChangeCeilingDirect
+				66 | 68 => {
+					self.read(behavior)?;
+					self.read(behavior)?;
+					log::debug!("Script {}: changing flats is not supported", self.number);
+				}
+				// Restart
+				69 => self.pc = self.start,
+				// NegateLogical
+				75 => {
+					let value = self.pop()?;
+					self.push((value == 0) as i32)?;
+				}
+				// UnaryMinus
+				78 => {
+					let value = self.pop()?;
+					self.push(value.wrapping_neg())?;
+				}
+				// IfNotGoto
+				79 => {
+					let address = self.read_address(behavior)?;
+
+					if self.pop()? == 0 {
+						self.pc = address;
+					}
+				}
+				// LineSide, scripts always count as started from the front
+				80 => self.push(0)?,
+				// ScriptWait
+				81 => {
+					let number = self.pop()?;
+					self.state = ScriptState::ScriptWait(number);
+				}
+				// ScriptWaitDirect
+				82 => {
+					let number = self.read(behavior)?;
+					self.state = ScriptState::ScriptWait(number);
+				}
+				// ClearLineSpecial
+				83 => host.clear_line_special(),
+				// CaseGoto
+				84 => {
+					let value = self.read(behavior)?;
+					let address = self.read_address(behavior)?;
+
+					if self.stack.last() == Some(&value) {
+						self.pop()?;
+						self.pc = address;
+					}
+				}
+				// BeginPrint
+				85 => self.print_buffer.clear(),
+				// EndPrint and EndPrintBold, there's only one player to print to
+				86 | 101 => host.print(&self.print_buffer),
+				// PrintString
+				87 => {
+					let index = self.pop()?;
+					let string = string(behavior, index)?;
+					self.print_buffer.push_str(string);
+				}
+				// PrintNumber
+				88 => {
+					let value = self.pop()?;
+					self.print_buffer.push_str(&value.to_string());
+				}
+				// PrintCharacter
+				89 => {
+					let value = self.pop()?;
+					self.print_buffer
+						.extend(std::char::from_u32(value as u32).into_iter());
+				}
+				// PlayerCount
+				90 => self.push(host.player_count())?,
+				// GameType
+				91 => self.push(host.game_type())?,
+				// GameSkill
+				92 => self.push(host.game_skill())?,
+				// Timer
+				93 => self.push(host.timer())?,
+				// SectorSound
+				94 => {
+					let volume = self.pop()?;
+					let index = self.pop()?;
+					host.sector_sound(string(behavior, index)?, volume);
+				}
+				// AmbientSound, SoundSequence, SetLineTexture, SetLineBlocking, SetLineSpecial
+				// and ThingSound need things that aren't there yet, so they do nothing
+				95 | 96 | 97 | 98 | 99 | 100 => {
+					let arg_count = match pcode {
+						95 => 2,
+						96 => 1,
+						97 => 4,
+						98 => 2,
+						99 => 7,
+						_ => 3,
+					};
+
+					for _ in 0..arg_count {
+						self.pop()?;
+					}
+
+					log::debug!(
+						"Script {}: instruction {} is not supported",
+						self.number,
+						pcode
+					);
+				}
+				_ => bail!("Unknown instruction {} at offset {}", pcode, self.pc - 4),
+			}
+		}
+
+		bail!(
+			"Script {} ran for more than {} instructions without waiting",
+			self.number,
+			MAX_INSTRUCTIONS
+		)
+	}
+
+	fn read(&mut self, behavior: &Behavior) -> anyhow::Result<i32> {
+		let end = self.pc.checked_add(4);
+		let bytes = end
+			.and_then(|end| behavior.code.get(self.pc..end))
+			.with_context(|| format!("Script {} ran past the end of the lump", self.number))?;
+		self.pc += 4;
+		Ok(LE::read_i32(bytes))
+	}
+
+	/// Reads the target of a jump, which must lie within the lump.
+	fn read_address(&mut self, behavior: &Behavior) -> anyhow::Result<usize> {
+		let address = self.read(behavior)?;
+
+		match usize::try_from(address) {
+			Ok(address) if address < behavior.code.len() => Ok(address),
+			_ => bail!(
+				"Script {} jumped to invalid address {}",
+				self.number,
+				address
+			),
+		}
+	}
+
+	fn push(&mut self, value: i32) -> anyhow::Result<()> {
+		ensure!(
+			self.stack.len() < STACK_SIZE,
+			"Script {} overflowed its stack",
+			self.number
+		);
+		self.stack.push(value);
+		Ok(())
+	}
+
+	fn pop(&mut self) -> anyhow::Result<i32> {
+		self.stack
+			.pop()
+			.with_context(|| format!("Script {} underflowed its stack", self.number))
+	}
+
+	fn delay(&mut self, tics: i32) {
+		if tics > 0 {
+			self.state = ScriptState::Delayed(tics as u32);
+		}
+	}
+}
+
+fn binary_op(pcode: i32, a: i32, b: i32) -> anyhow::Result<i32> {
+	Ok(match pcode {
+		14 => a.wrapping_add(b),
+		15 => a.wrapping_sub(b),
+		16 => a.wrapping_mul(b),
+		17 | 18 => {
+			ensure!(b != 0, "Division by zero");
+
+			if pcode == 17 {
+				a.wrapping_div(b)
+			} else {
+				a.wrapping_rem(b)
+			}
+		}
+		19 => (a == b) as i32,
+		20 => (a != b) as i32,
+		21 => (a < b) as i32,
+		22 => (a > b) as i32,
+		23 => (a <= b) as i32,
+		24 => (a >= b) as i32,
+		70 => (a != 0 && b != 0) as i32,
+		71 => (a != 0 || b != 0) as i32,
+		72 => a & b,
+		73 => a | b,
+		74 => a ^ b,
+		76 => a.wrapping_shl(b as u32),
+		_ => a.wrapping_shr(b as u32),
+	})
+}
+
+fn string(behavior: &Behavior, index: i32) -> anyhow::Result<&str> {
+	behavior
+		.strings
+		.get(index as usize)
+		.map(String::as_str)
+		.with_context(|| format!("String {} doesn't exist", index))
+}
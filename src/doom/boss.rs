@@ -0,0 +1,227 @@
+use crate::{
+	common::{
+		assets::{AssetHandle, AssetStorage},
+		frame::FrameState,
+		gamestate::GameStateMachine,
+		profiler,
+	},
+	doom::{
+		client::{UseAction, User},
+		door::{self, DoorSwitchUse, DoorTouch},
+		entitytemplate::{EntityTemplate, EntityTemplateRef, EntityTypeId, TemplateIndex},
+		exit::{self, ExitSwitchUse, ExitTouch},
+		floor::{self, FloorSwitchUse, FloorTouch},
+		health::Dead,
+		intermission::IntermissionInfo,
+		map::MapDynamic,
+		mapinfo::{map_info, BossAction},
+		physics::TouchAction,
+		plat::{self, PlatSwitchUse, PlatTouch},
+		profile::GameProfile,
+		progression::default_boss_actions,
+	},
+};
+use legion::{
+	component,
+	systems::{CommandBuffer, ResourceSet},
+	IntoQuery, Read, Resources, World, Write,
+};
+
+/// The boss actions of the current level that haven't happened yet,
+/// together with the template of the monster that each one is waiting for.
+#[derive(Clone, Debug, Default)]
+pub struct BossActions {
+	pending: Vec<(AssetHandle<EntityTemplate>, BossAction)>,
+}
+
+impl BossActions {
+	/// Finds the boss actions for `map`, from the map information if it gives any,
+	/// otherwise the built-in ones. In the Doom games, killing all the Commander Keens opens
+	/// the doors tagged 666 on every map.
+	/// Actions are left out if none of their monsters were spawned, so they can never happen.
+	pub fn new(
+		map: &str,
+		world: &World,
+		asset_storage: &AssetStorage,
+		game_profile: GameProfile,
+	) -> BossActions {
+		let mut actions = map_info(asset_storage, map)
+			.and_then(|entry| entry.boss_actions.clone())
+			.unwrap_or_else(|| default_boss_actions(map, game_profile));
+
+		if game_profile != GameProfile::Heretic {
+			actions.push(BossAction {
+				template: "keen".to_owned(),
+				special: 2,
+				tag: 666,
+			});
+		}
+
+		let pending = actions
+			.into_iter()
+			.filter_map(|action| {
+				let handle = match asset_storage.handle_for::<EntityTemplate>(&action.template) {
+					Some(handle) => handle,
+					None => {
+						log::warn!("Boss action has unknown monster \"{}\"", action.template);
+						return None;
+					}
+				};
+
+				if <&EntityTemplateRef>::query()
+					.iter(world)
+					.any(|template_ref| template_ref.0 == handle)
+				{
+					Some((handle, action))
+				} else {
+					None
+				}
+			})
+			.collect();
+
+		BossActions { pending }
+	}
+}
+
+/// Triggers boss actions once all of their monsters are dead, like `A_BossDeath` and
+/// `A_KeenDie` in the original game. Bosses only count as dead while a player is alive.
+pub fn boss_system() -> Box<dyn FnMut(&mut World, &mut Resources)> {
+	Box::new(move |world, resources| {
+		let _profile = profiler::scope("boss_system");
+
+		let triggered: Vec<BossAction> = {
+			let mut boss_actions = <Write<BossActions>>::fetch_mut(resources);
+
+			if boss_actions.pending.is_empty() {
+				return;
+			}
+
+			if <&User>::query()
+				.filter(!component::<Dead>())
+				.iter(world)
+				.next()
+				.is_none()
+			{
+				return;
+			}
+
+			let mut living = <&EntityTemplateRef>::query().filter(!component::<Dead>());
+			let (dead, alive) =
+				boss_actions
+					.pending
+					.drain(..)
+					.partition::<Vec<_>, _>(|(handle, _)| {
+						!living
+							.iter(world)
+							.any(|template_ref| template_ref.0 == *handle)
+					});
+			boss_actions.pending = alive;
+			dead.into_iter().map(|(_, action)| action).collect()
+		};
+
+		for action in triggered {
+			log::debug!(
+				"Boss action for \"{}\": special {}, tag {}",
+				action.template,
+				action.special,
+				action.tag
+			);
+			activate(&action, world, resources);
+		}
+	})
+}
+
+/// Does what the linedef special of `action` would do, on sectors with its tag.
+fn activate(action: &BossAction, world: &mut World, resources: &mut Resources) {
+	let (
+		asset_storage,
+		frame_state,
+		game_profile,
+		template_index,
+		mut game_state,
+		mut intermission_info,
+	) = <(
+		Read<AssetStorage>,
+		Read<FrameState>,
+		Read<GameProfile>,
+		Read<TemplateIndex>,
+		Write<GameStateMachine>,
+		Write<IntermissionInfo>,
+	)>::fetch_mut(resources);
+
+	let template = match template_index
+		.get(EntityTypeId::Linedef(action.special))
+		.and_then(|handle| asset_storage.get(handle))
+	{
+		Some(template) => template,
+		None => {
+			log::warn!("Boss action has unknown special {}", action.special);
+			return;
+		}
+	};
+
+	let mut command_buffer = CommandBuffer::new(world);
+
+	{
+		let map_dynamic = match <&MapDynamic>::query().iter(world).next() {
+			Some(map_dynamic) => map_dynamic,
+			None => return,
+		};
+		let map = asset_storage.get(&map_dynamic.map).unwrap();
+		let touch_action = <&TouchAction>::query().iter(&template.world).next();
+		let use_action = <&UseAction>::query().iter(&template.world).next();
+
+		match (touch_action, use_action) {
+			(Some(TouchAction::DoorTouch(DoorTouch { params, .. })), _)
+			| (_, Some(UseAction::DoorSwitchUse(DoorSwitchUse { params, .. }))) => {
+				door::activate_with_tag(
+					params,
+					&mut command_buffer,
+					&frame_state,
+					action.tag,
+					world,
+					map,
+					map_dynamic,
+				);
+			}
+			(Some(TouchAction::FloorTouch(FloorTouch { params, .. })), _)
+			| (_, Some(UseAction::FloorSwitchUse(FloorSwitchUse { params, .. }))) => {
+				floor::activate_with_tag(
+					params,
+					&mut command_buffer,
+					&frame_state,
+					action.tag,
+					world,
+					map,
+					map_dynamic,
+				);
+			}
+			(Some(TouchAction::PlatTouch(PlatTouch { params, .. })), _)
+			| (_, Some(UseAction::PlatSwitchUse(PlatSwitchUse { params, .. }))) => {
+				plat::activate_with_tag(
+					params,
+					&mut command_buffer,
+					&frame_state,
+					action.tag,
+					world,
+					map,
+					map_dynamic,
+				);
+			}
+			(Some(TouchAction::ExitTouch(ExitTouch { exit_type })), _)
+			| (_, Some(UseAction::ExitSwitchUse(ExitSwitchUse { exit_type, .. }))) => {
+				exit::exit_level(
+					*exit_type,
+					&asset_storage,
+					*game_profile,
+					map_dynamic,
+					&mut game_state,
+					&mut intermission_info,
+				);
+			}
+			_ => log::warn!("Special {} can't be used as a boss action", action.special),
+		}
+	}
+
+	command_buffer.flush(world);
+}
@@ -1,15 +1,20 @@
 use crate::{
-	common::{assets::AssetHandle, audio::Sound, frame::FrameState, geometry::Angle},
+	common::{
+		assets::AssetHandle,
+		audio::Sound,
+		events::{register_reader, Events},
+		frame::FrameState,
+		geometry::Angle,
+		profiler,
+	},
 	doom::{
 		components::Velocity,
 		data::FRAME_RATE,
 		physics::{StepEvent, TouchEvent},
-		psprite::PlayerSpriteRender,
 	},
 };
 use legion::{systems::Runnable, Entity, IntoQuery, Resources, SystemBuilder};
 use nalgebra::{Vector2, Vector3};
-use shrev::EventChannel;
 use std::time::Duration;
 
 #[derive(Clone, Debug)]
@@ -21,31 +26,41 @@ pub struct Camera {
 	pub weapon_bob_period: Duration,
 	pub deviation_position: f32,
 	pub deviation_velocity: f32,
+	/// Extra view rotation after taking damage, as pitch and yaw in degrees.
+	/// It returns to zero by itself.
+	pub punch: Vector2<f32>,
 	pub impact_sound: AssetHandle<Sound>,
 }
 
+/// How quickly the view recovers from a punch, as a fraction per second.
+const PUNCH_RECOVERY: f32 = 8.0;
+
+impl Camera {
+	/// Returns how far the view and weapon bob when moving at the given velocity.
+	pub fn bob_amplitude(&self, velocity: &Velocity) -> f32 {
+		let velocity2 = Vector2::new(velocity.velocity[0], velocity.velocity[1]) / FRAME_RATE;
+		(velocity2.norm_squared() * 0.25).min(self.bob_max)
+	}
+}
+
 pub fn camera_system(resources: &mut Resources) -> impl Runnable {
-	let mut step_event_reader = resources
-		.get_mut::<EventChannel<StepEvent>>()
-		.unwrap()
-		.register_reader();
-	let mut touch_event_reader = resources
-		.get_mut::<EventChannel<TouchEvent>>()
-		.unwrap()
-		.register_reader();
+	register_reader::<StepEvent>(resources, "camera_system");
+	register_reader::<TouchEvent>(resources, "camera_system");
 
 	SystemBuilder::new("camera_system")
 		.read_resource::<FrameState>()
-		.read_resource::<EventChannel<StepEvent>>()
-		.read_resource::<EventChannel<TouchEvent>>()
+		.write_resource::<Events<StepEvent>>()
+		.write_resource::<Events<TouchEvent>>()
 		.write_resource::<Vec<(AssetHandle<Sound>, Entity)>>()
 		.with_query(<&mut Camera>::query())
-		.with_query(<(&Velocity, &mut Camera, &mut PlayerSpriteRender)>::query())
+		.with_query(<(&Velocity, &mut Camera)>::query())
 		.build(move |_, world, resources, queries| {
+			let _profile = profiler::scope("camera_system");
+
 			let (frame_state, step_event_channel, touch_event_channel, sound_queue) = resources;
 
 			// Entity hitting the ground
-			for touch_event in touch_event_channel.read(&mut touch_event_reader) {
+			for touch_event in touch_event_channel.read("camera_system") {
 				if let (Ok(mut camera), Some(collision)) = (
 					queries.0.get_mut(world, touch_event.toucher),
 					touch_event.collision,
@@ -60,14 +75,14 @@ pub fn camera_system(resources: &mut Resources) -> impl Runnable {
 			}
 
 			// Entity stepping up
-			for step_event in step_event_channel.read(&mut step_event_reader) {
+			for step_event in step_event_channel.read("camera_system") {
 				if let Ok(mut camera) = queries.0.get_mut(world, step_event.entity) {
 					camera.deviation_position -= step_event.height;
 					camera.deviation_velocity = -camera.deviation_position / 8.0 * FRAME_RATE;
 				}
 			}
 
-			for (velocity, mut camera, player_sprite_render) in queries.1.iter_mut(world) {
+			for (velocity, mut camera) in queries.1.iter_mut(world) {
 				// Calculate deviation
 				if camera.deviation_position != 0.0 || camera.deviation_velocity != 0.0 {
 					const DEVIATION_ACCEL: f32 = 0.25 * FRAME_RATE * FRAME_RATE;
@@ -91,25 +106,16 @@ pub fn camera_system(resources: &mut Resources) -> impl Runnable {
 				}
 
 				// Calculate movement bobbing
-				let velocity2 =
-					Vector2::new(velocity.velocity[0], velocity.velocity[1]) / FRAME_RATE;
-				let bob_amplitude = (velocity2.norm_squared() * 0.25).min(camera.bob_max);
-
-				// Set camera position
+				let bob_amplitude = camera.bob_amplitude(velocity);
 				let angle = Angle::from_units(
 					frame_state.time.as_secs_f64() / camera.view_bob_period.as_secs_f64(),
 				); // TODO replace with div_duration_f64 once it's stable
 				let bob = bob_amplitude * 0.5 * angle.sin() as f32;
 				camera.offset[2] = camera.deviation_position + bob;
 
-				// Set weapon position
-				let mut angle = Angle::from_units(
-					frame_state.time.as_secs_f64() / camera.weapon_bob_period.as_secs_f64(),
-				);
-				player_sprite_render.position[0] = 1.0 + bob_amplitude * angle.cos() as f32;
-
-				angle.0 &= 0x7FFF_FFFF;
-				player_sprite_render.position[1] = bob_amplitude * angle.sin() as f32;
+				// Recover from view punch
+				camera.punch *=
+					1.0 - (PUNCH_RECOVERY * frame_state.delta_time.as_secs_f32()).min(1.0);
 			}
 		})
 }
@@ -0,0 +1,186 @@
+use crate::{
+	common::{assets::AssetStorage, configvars::ConfigVariables, geometry::Angle, profiler},
+	doom::{
+		components::Transform,
+		profile::GameProfile,
+		ui::{HudFont, UiAlignment, UiTransform},
+	},
+};
+use legion::{systems::ResourceSet, Entity, Read, Resources, World, Write};
+use nalgebra::Vector2;
+use std::{
+	collections::VecDeque,
+	time::{Duration, Instant},
+};
+
+/// How long a caption stays on screen.
+pub const CAPTION_TIME: Duration = Duration::from_secs(3);
+
+/// The most captions shown at once. Older ones are pushed out by newer ones.
+pub const MAX_CAPTIONS: usize = 4;
+
+/// Sounds closer than this are described as being nearby.
+const NEARBY_DISTANCE: f32 = 320.0;
+
+const LINE_HEIGHT: f32 = 9.0;
+
+/// Where a sound came from, relative to the way the listener is facing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CaptionDirection {
+	Ahead,
+	Behind,
+	Left,
+	Right,
+}
+
+impl CaptionDirection {
+	/// Returns the direction of `entity_transform` as seen from `client_transform`.
+	/// The angle is measured the same way as for stereo panning.
+	pub fn new(client_transform: &Transform, entity_transform: &Transform) -> CaptionDirection {
+		let to_entity_vec = entity_transform.position - client_transform.position;
+		let angle = client_transform.rotation[2]
+			- Angle::from_radians(f64::atan2(to_entity_vec[1] as f64, to_entity_vec[0] as f64));
+
+		// Within 30 degrees of straight ahead or behind
+		if angle.sin().abs() < 0.5 {
+			if angle.cos() > 0.0 {
+				CaptionDirection::Ahead
+			} else {
+				CaptionDirection::Behind
+			}
+		} else if angle.sin() > 0.0 {
+			CaptionDirection::Right
+		} else {
+			CaptionDirection::Left
+		}
+	}
+
+	pub fn name(self) -> &'static str {
+		match self {
+			CaptionDirection::Ahead => "ahead",
+			CaptionDirection::Behind => "behind",
+			CaptionDirection::Left => "left",
+			CaptionDirection::Right => "right",
+		}
+	}
+}
+
+#[derive(Clone, Debug)]
+struct Caption {
+	text: String,
+	expires: Instant,
+}
+
+/// Captions for sounds that were heard recently, shown when `snd_captions` is enabled.
+#[derive(Clone, Debug, Default)]
+pub struct Captions {
+	captions: VecDeque<Caption>,
+	changed: bool,
+}
+
+impl Captions {
+	/// Adds a caption for a sound. If the sound came from somewhere other than the listener,
+	/// `position` should be given as the direction and distance to its source.
+	pub fn push(&mut self, text: &str, position: Option<(CaptionDirection, f32)>) {
+		let text = match position {
+			Some((direction, distance)) if distance < NEARBY_DISTANCE => {
+				format!("{} nearby, {}", text, direction.name())
+			}
+			Some((direction, _)) => format!("{}, {}", text, direction.name()),
+			None => text.to_owned(),
+		};
+
+		// Don't repeat a caption that is already showing, just keep it around for longer
+		self.captions.retain(|caption| caption.text != text);
+
+		if self.captions.len() >= MAX_CAPTIONS {
+			self.captions.pop_front();
+		}
+
+		self.captions.push_back(Caption {
+			text,
+			expires: Instant::now() + CAPTION_TIME,
+		});
+		self.changed = true;
+	}
+
+	pub fn clear(&mut self) {
+		self.captions.clear();
+		self.changed = true;
+	}
+}
+
+/// Keeps the caption text in the HUD up to date.
+pub fn caption_system(resources: &mut Resources) -> Box<dyn FnMut(&mut World, &mut Resources)> {
+	resources.insert(Captions::default());
+
+	let font = {
+		let (mut asset_storage, game_profile) =
+			<(Write<AssetStorage>, Read<GameProfile>)>::fetch_mut(resources);
+		HudFont::load(&mut asset_storage, *game_profile)
+	};
+	let mut entities: Vec<Entity> = Vec::new();
+
+	Box::new(move |world, resources| {
+		let _profile = profiler::scope("caption_system");
+
+		let (asset_storage, config_variables, mut captions) =
+			<(Read<AssetStorage>, Read<ConfigVariables>, Write<Captions>)>::fetch_mut(resources);
+
+		if !config_variables.get::<bool>("snd_captions") && !captions.captions.is_empty() {
+			captions.clear();
+		}
+
+		let now = Instant::now();
+
+		while captions
+			.captions
+			.front()
+			.map_or(false, |caption| caption.expires <= now)
+		{
+			captions.captions.pop_front();
+			captions.changed = true;
+		}
+
+		// The text entities are gone if the world was cleared
+		if entities.first().map_or(false, |e| !world.contains(*e)) {
+			entities.clear();
+			captions.changed = true;
+		}
+
+		if !captions.changed {
+			return;
+		}
+
+		if !font.is_ready(&asset_storage) {
+			return;
+		}
+
+		captions.changed = false;
+
+		for entity in entities.drain(..) {
+			world.remove(entity);
+		}
+
+		// Newest caption at the bottom, just above the status bar
+		for (line, caption) in captions.captions.iter().rev().enumerate() {
+			let width = font.width(&caption.text, &asset_storage);
+
+			entities.extend(font.spawn_text(
+				world,
+				&asset_storage,
+				&caption.text,
+				UiTransform {
+					position: Vector2::new(
+						160.0 - (width * 0.5).floor(),
+						158.0 - line as f32 * LINE_HEIGHT,
+					),
+					depth: 20.0,
+					alignment: [UiAlignment::Middle, UiAlignment::Far],
+					size: Vector2::new(0.0, 0.0),
+					stretch: [false; 2],
+				},
+			));
+		}
+	})
+}
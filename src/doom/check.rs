@@ -0,0 +1,312 @@
+use crate::{
+	common::{
+		assets::{AssetHandle, AssetStorage},
+		module::GameModule,
+	},
+	doom::{
+		entitytemplate::{EntityTypeId, TemplateIndex},
+		image::Image,
+		map::{
+			load::{build_things, import_map, is_hexen_format},
+			textures::TextureType,
+			Map, SectorSlot, SidedefSlot, Thing,
+		},
+		profile::GameProfile,
+		wad::WadLoader,
+	},
+};
+use anyhow::ensure;
+use clap::ArgMatches;
+use fnv::{FnvHashMap, FnvHashSet};
+use legion::{systems::ResourceSet, Read, Resources, Write};
+use nalgebra::Vector2;
+use relative_path::RelativePath;
+use std::path::Path;
+
+/// Sector tags that the original game uses for boss deaths, without any linedef referring to them.
+const BOSS_TAGS: [u16; 2] = [666, 667];
+
+const SIDE_NAMES: [&str; 2] = ["front", "back"];
+
+/// Loads maps without video or sound, and prints the problems in them that would otherwise only
+/// show up while playing. The target is either a WAD file, of which every map is checked,
+/// or the name of a map in the loaded WADs. Returns the number of problems found.
+pub fn run(arg_matches: &ArgMatches) -> anyhow::Result<usize> {
+	let target = arg_matches.value_of("TARGET").unwrap();
+	let mut loader = WadLoader::new();
+	crate::load_wads(&mut loader, arg_matches)?;
+
+	let target_path = Path::new(target);
+	let maps: Vec<String> = if target_path.is_file() {
+		crate::add_wad(&mut loader, target_path)?;
+
+		// Maps are marked by a lump with their name, followed by their things
+		let lumps = loader.lumps();
+		lumps
+			.iter()
+			.zip(lumps.iter().skip(1))
+			.filter(|(lump, next)| lump.path == target_path && next.name == "things")
+			.map(|(lump, _)| lump.name.clone())
+			.collect()
+	} else {
+		let name = target.to_ascii_lowercase();
+		ensure!(
+			loader.exists(&RelativePath::new(&name).with_extension("things")),
+			"Map {} does not exist",
+			target
+		);
+		vec![name]
+	};
+	ensure!(!maps.is_empty(), "{} contains no maps", target);
+
+	let game_profile = GameProfile::detect(&loader);
+	let module = crate::game_module(arg_matches.value_of("game"), game_profile);
+
+	let mut resources = Resources::default();
+	resources.insert(game_profile);
+	resources.insert(crate::create_asset_storage(&*module, loader));
+	module.load_data(&mut resources)?;
+
+	let (mut asset_storage, template_index) =
+		<(Write<AssetStorage>, Read<TemplateIndex>)>::fetch_mut(&mut resources);
+	let mut problem_count = 0;
+
+	for name in &maps {
+		let problems = check_map(name, &mut asset_storage, &template_index)
+			.unwrap_or_else(|err| vec![format!("Couldn't load map: {:#}", err)]);
+
+		for problem in &problems {
+			println!("{}: {}", name.to_ascii_uppercase(), problem);
+		}
+
+		problem_count += problems.len();
+	}
+
+	println!(
+		"Checked {} maps, found {} problems",
+		maps.len(),
+		problem_count
+	);
+
+	Ok(problem_count)
+}
+
+fn check_map(
+	name: &str,
+	asset_storage: &mut AssetStorage,
+	template_index: &TemplateIndex,
+) -> anyhow::Result<Vec<String>> {
+	let path = RelativePath::new(name);
+	let map: Map = *import_map(&path.with_extension("map"), asset_storage)?
+		.downcast()
+		.ok()
+		.unwrap();
+	let hexen_format = is_hexen_format(asset_storage.source(), path);
+	let things = build_things(
+		&asset_storage
+			.source()
+			.load(&path.with_extension("things"))?,
+		hexen_format,
+	)?;
+
+	let mut problems = Vec::new();
+	check_textures(&map, asset_storage, &mut problems);
+	check_things(&things, template_index, &mut problems);
+	check_sectors_closed(&map, &mut problems);
+	check_tags(&map, hexen_format, &mut problems);
+
+	Ok(problems)
+}
+
+/// Finds textures that don't exist, and walls that need a texture but don't have one.
+fn check_textures(map: &Map, asset_storage: &mut AssetStorage, problems: &mut Vec<String>) {
+	let mut missing = FnvHashMap::default();
+
+	for (linedef_index, linedef) in map.linedefs.iter().enumerate() {
+		for (side, sidedef) in linedef.sidedefs.iter().enumerate() {
+			let sidedef = match sidedef {
+				Some(sidedef) => sidedef,
+				None => continue,
+			};
+
+			for texture in sidedef.textures.iter() {
+				if let TextureType::Normal(handle) = texture {
+					if let Some(name) = missing_texture(handle, asset_storage, &mut missing) {
+						problems.push(format!(
+							"Linedef {} uses unknown texture {} on its {} side",
+							linedef_index, name, SIDE_NAMES[side]
+						));
+					}
+				}
+			}
+
+			let front_sector = &map.sectors[sidedef.sector_index];
+			let back_sector = match &linedef.sidedefs[1 - side] {
+				Some(back_sidedef) => &map.sectors[back_sidedef.sector_index],
+				None => {
+					if let TextureType::None = sidedef.textures[SidedefSlot::Middle as usize] {
+						problems.push(format!(
+							"Linedef {} has no middle texture on its {} side",
+							linedef_index, SIDE_NAMES[side]
+						));
+					}
+
+					continue;
+				}
+			};
+
+			// Sky ceilings on both sides hide the missing upper texture
+			let back_sky = match back_sector.textures[SectorSlot::Ceiling as usize] {
+				TextureType::Sky => true,
+				_ => false,
+			};
+
+			if let TextureType::None = sidedef.textures[SidedefSlot::Top as usize] {
+				if front_sector.interval.max > back_sector.interval.max && !back_sky {
+					problems.push(format!(
+						"Linedef {} has no upper texture on its {} side",
+						linedef_index, SIDE_NAMES[side]
+					));
+				}
+			}
+
+			if let TextureType::None = sidedef.textures[SidedefSlot::Bottom as usize] {
+				if front_sector.interval.min < back_sector.interval.min {
+					problems.push(format!(
+						"Linedef {} has no lower texture on its {} side",
+						linedef_index, SIDE_NAMES[side]
+					));
+				}
+			}
+		}
+	}
+
+	for (sector_index, sector) in map.sectors.iter().enumerate() {
+		for texture in sector.textures.iter() {
+			if let TextureType::Normal(handle) = texture {
+				if let Some(name) = missing_texture(handle, asset_storage, &mut missing) {
+					problems.push(format!(
+						"Sector {} uses unknown flat {}",
+						sector_index, name
+					));
+				}
+			}
+		}
+	}
+}
+
+/// Returns the name of the texture if it can't be imported.
+/// The asset storage replaces those with a placeholder, so they are imported again to find out.
+fn missing_texture(
+	handle: &AssetHandle<Image>,
+	asset_storage: &mut AssetStorage,
+	missing: &mut FnvHashMap<String, bool>,
+) -> Option<String> {
+	let name = asset_storage.name_of(handle)?.to_owned();
+	let is_missing = *missing
+		.entry(name.clone())
+		.or_insert_with(|| crate::doom::import(RelativePath::new(&name), asset_storage).is_err());
+
+	if is_missing {
+		let stem = RelativePath::new(&name).file_stem().unwrap_or_default();
+		Some(stem.to_ascii_uppercase())
+	} else {
+		None
+	}
+}
+
+/// Finds things that no entity template exists for, which are left out when the map is spawned.
+fn check_things(things: &[Thing], template_index: &TemplateIndex, problems: &mut Vec<String>) {
+	for (thing_index, thing) in things.iter().enumerate() {
+		if template_index
+			.get(EntityTypeId::Thing(thing.r#type))
+			.is_none()
+		{
+			problems.push(format!(
+				"Thing {} has unknown type {}",
+				thing_index, thing.r#type
+			));
+		}
+	}
+}
+
+/// Finds sectors whose sides don't form closed loops. Going around a sector, each vertex
+/// must be left as many times as it's arrived at.
+fn check_sectors_closed(map: &Map, problems: &mut Vec<String>) {
+	// Round the vertices a little, so that rounding errors don't count as gaps
+	let vertex_key = |point: Vector2<f32>| {
+		[
+			(point[0] * 16.0).round() as i32,
+			(point[1] * 16.0).round() as i32,
+		]
+	};
+	let mut balance: FnvHashMap<(usize, [i32; 2]), i32> = FnvHashMap::default();
+
+	for linedef in map.linedefs.iter() {
+		let start = vertex_key(linedef.line.point);
+		let end = vertex_key(linedef.line.point + linedef.line.dir);
+
+		// The front side goes from start to end, the back side the other way around
+		for (sidedef, from, to) in [
+			(&linedef.sidedefs[0], start, end),
+			(&linedef.sidedefs[1], end, start),
+		]
+		.iter()
+		{
+			if let Some(sidedef) = sidedef {
+				*balance.entry((sidedef.sector_index, *from)).or_default() += 1;
+				*balance.entry((sidedef.sector_index, *to)).or_default() -= 1;
+			}
+		}
+	}
+
+	let mut unclosed: Vec<usize> = balance
+		.into_iter()
+		.filter(|(_, count)| *count != 0)
+		.map(|((sector_index, _), _)| sector_index)
+		.collect();
+	unclosed.sort_unstable();
+	unclosed.dedup();
+
+	for sector_index in unclosed {
+		problems.push(format!("Sector {} is not closed", sector_index));
+	}
+}
+
+/// Finds linedef specials with a tag that no sector has, and sector tags that nothing uses.
+fn check_tags(map: &Map, hexen_format: bool, problems: &mut Vec<String>) {
+	let mut used_tags = FnvHashSet::default();
+
+	for (linedef_index, linedef) in map.linedefs.iter().enumerate() {
+		if linedef.special_type.is_none() || linedef.sector_tag == 0 {
+			continue;
+		}
+
+		used_tags.insert(linedef.sector_tag);
+
+		if map.sectors_with_tag(linedef.sector_tag).is_empty() {
+			problems.push(format!(
+				"Linedef {} has tag {}, but no sector has that tag",
+				linedef_index, linedef.sector_tag
+			));
+		}
+	}
+
+	// Hexen format maps refer to tags from special arguments and scripts, which can't be
+	// followed here
+	if hexen_format {
+		return;
+	}
+
+	for (sector_index, sector) in map.sectors.iter().enumerate() {
+		if sector.sector_tag != 0
+			&& !used_tags.contains(&sector.sector_tag)
+			&& !BOSS_TAGS.contains(&sector.sector_tag)
+		{
+			problems.push(format!(
+				"Sector {} has tag {}, but no linedef uses it",
+				sector_index, sector.sector_tag
+			));
+		}
+	}
+}
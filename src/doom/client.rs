@@ -2,32 +2,140 @@ use crate::{
 	common::{
 		assets::{AssetHandle, AssetStorage},
 		audio::Sound,
+		configvars::ConfigVariables,
+		events::{add_events, Events},
 		frame::FrameState,
-		geometry::{Line2, AABB3},
+		geometry::{Angle, Line2, AABB2, AABB3},
 		input::{Bindings, InputState},
+		profiler,
 		quadtree::Quadtree,
 	},
 	doom::{
 		camera::Camera,
 		components::{Transform, Velocity},
 		data::{FORWARD_ACCEL, STRAFE_ACCEL},
+		decal::{Decal, DecalKind, Decals},
 		door::{DoorSwitchUse, DoorUse},
+		entitytemplate::{EntityTemplateRef, ImpactEffect},
+		exit::ExitSwitchUse,
 		floor::FloorSwitchUse,
+		health::{is_dead, Dead},
 		input::{BoolInput, FloatInput, UserCommand},
-		map::MapDynamic,
+		item::drop_item_request,
+		linespecial::LineSpecialUse,
+		map::{spawn::SpawnRequest, MapDynamic, NodeChild},
+		monster::{noise_alert, Ambush, MonsterRespawnQueue},
+		particle::{ParticleKind, Particles},
 		physics::{BoxCollider, EntityTracer, SolidMask},
 		plat::PlatSwitchUse,
+		stats::{CountKill, LevelStats},
 	},
 };
 use legion::{systems::Runnable, Entity, EntityStore, IntoQuery, Resources, SystemBuilder};
 use nalgebra::{Vector2, Vector3};
-use shrev::EventChannel;
 
 #[derive(Default)]
 pub struct Client {
 	pub entity: Option<Entity>,
 	pub command: UserCommand,
 	pub previous_command: UserCommand,
+	/// A command to use for the next game frame instead of the player's input,
+	/// such as one read from a demo.
+	pub forced_command: Option<UserCommand>,
+	pub view: ClientView,
+	/// The view transform at the start of the current game frame.
+	pub previous_view_transform: Option<Transform>,
+}
+
+impl Client {
+	/// Whether the client is watching something other than its own entity, in which case
+	/// the player's inputs should not affect that entity.
+	pub fn is_spectating(&self) -> bool {
+		match self.view {
+			ClientView::Player => false,
+			ClientView::Free(_) => true,
+			ClientView::Chase(entity) => Some(entity) != self.entity,
+		}
+	}
+
+	/// Returns the position and rotation that the world is viewed from.
+	pub fn view_transform(&self, world: &impl EntityStore) -> Option<Transform> {
+		match self.view {
+			ClientView::Player => {
+				let entry = world.entry_ref(self.entity?).ok()?;
+				let mut transform = *entry.get_component::<Transform>().ok()?;
+
+				if let Ok(camera) = entry.get_component::<Camera>() {
+					transform.position += camera.base + camera.offset;
+					transform.rotation[1] += Angle::from_degrees(camera.punch[0] as f64);
+					transform.rotation[2] += Angle::from_degrees(camera.punch[1] as f64);
+				}
+
+				Some(transform)
+			}
+			ClientView::Free(transform) => Some(transform),
+			ClientView::Chase(entity) => {
+				let entry = world.entry_ref(entity).ok()?;
+				let mut transform = *entry.get_component::<Transform>().ok()?;
+				let axes = crate::common::geometry::angles_to_axes(Vector3::new(
+					0.into(),
+					0.into(),
+					transform.rotation[2],
+				));
+
+				transform.position +=
+					Vector3::new(0.0, 0.0, CHASE_HEIGHT) - axes[0] * CHASE_DISTANCE;
+				transform.rotation[1] = Angle::from_degrees(CHASE_PITCH);
+
+				Some(transform)
+			}
+		}
+	}
+
+	/// Returns the view transform to draw the world from, `fraction` of the way between
+	/// the previous and current game frame. Only the position is interpolated; the rotation
+	/// is always the current one so that looking around stays responsive.
+	pub fn interpolated_view_transform(
+		&self,
+		world: &impl EntityStore,
+		fraction: f32,
+	) -> Option<Transform> {
+		let mut transform = self.view_transform(world)?;
+
+		if let Some(previous) = self.previous_view_transform {
+			let delta = transform.position - previous.position;
+
+			// Don't smear teleports and view changes
+			if delta.norm_squared() < MAX_INTERPOLATION_DISTANCE * MAX_INTERPOLATION_DISTANCE {
+				transform.position = previous.position + delta * fraction;
+			}
+		}
+
+		Some(transform)
+	}
+}
+
+/// Views that moved further than this in one frame jump to their new position immediately.
+const MAX_INTERPOLATION_DISTANCE: f32 = 64.0;
+
+const CHASE_DISTANCE: f32 = 96.0;
+const CHASE_HEIGHT: f32 = 64.0;
+const CHASE_PITCH: f64 = 20.0;
+
+#[derive(Clone, Copy, Debug)]
+pub enum ClientView {
+	/// Looking through the eyes of the client's own entity
+	Player,
+	/// Flying around freely, detached from any entity
+	Free(Transform),
+	/// Following behind an entity
+	Chase(Entity),
+}
+
+impl Default for ClientView {
+	fn default() -> ClientView {
+		ClientView::Player
+	}
 }
 
 pub fn player_command_system() -> impl Runnable {
@@ -35,22 +143,33 @@ pub fn player_command_system() -> impl Runnable {
 		.read_resource::<Bindings<BoolInput, FloatInput>>()
 		.read_resource::<InputState>()
 		.write_resource::<Client>()
-		.build(move |_, _, resources, _| {
+		.read_component::<Camera>() // used by view_transform
+		.read_component::<Transform>() // used by view_transform
+		.build(move |_, world, resources, _| {
+			let _profile = profiler::scope("player_command_system");
+
 			let (bindings, input_state, client) = resources;
 
-			let mut command = UserCommand {
-				attack: bindings.bool_value(&BoolInput::Attack, &input_state),
-				r#use: bindings.bool_value(&BoolInput::Use, &input_state),
-				forward: bindings.float_value(&FloatInput::Forward, &input_state) as f32,
-				pitch: bindings.float_value(&FloatInput::Pitch, &input_state) as f32,
-				strafe: bindings.float_value(&FloatInput::Strafe, &input_state) as f32,
-				yaw: bindings.float_value(&FloatInput::Yaw, &input_state) as f32,
-			};
+			// This is the first system of the frame, so nothing has moved yet
+			client.previous_view_transform = client.view_transform(world);
+
+			let command = client.forced_command.take().unwrap_or_else(|| {
+				let mut command = UserCommand {
+					attack: bindings.bool_value(&BoolInput::Attack, &input_state),
+					r#use: bindings.bool_value(&BoolInput::Use, &input_state),
+					forward: bindings.float_value(&FloatInput::Forward, &input_state) as f32,
+					pitch: bindings.float_value(&FloatInput::Pitch, &input_state) as f32,
+					strafe: bindings.float_value(&FloatInput::Strafe, &input_state) as f32,
+					yaw: bindings.float_value(&FloatInput::Yaw, &input_state) as f32,
+				};
 
-			if bindings.bool_value(&BoolInput::Walk, &input_state) {
-				command.forward *= 0.5;
-				command.strafe *= 0.6;
-			}
+				if bindings.bool_value(&BoolInput::Walk, &input_state) {
+					command.forward *= 0.5;
+					command.strafe *= 0.6;
+				}
+
+				command
+			});
 
 			client.previous_command = client.command;
 			client.command = command;
@@ -68,8 +187,11 @@ pub fn player_move_system() -> impl Runnable {
 		.with_query(<(&Transform, &BoxCollider)>::query())
 		.with_query(<(&Transform, &mut Velocity)>::query())
 		.read_component::<BoxCollider>() // used by EntityTracer
+		.read_component::<Dead>()
 		.read_component::<Transform>() // used by EntityTracer
 		.build(move |_, world, resources, queries| {
+			let _profile = profiler::scope("player_move_system");
+
 			let (asset_storage, client, frame_state, quadtree) = resources;
 
 			let client_entity = match client.entity {
@@ -77,6 +199,10 @@ pub fn player_move_system() -> impl Runnable {
 				None => return,
 			};
 
+			if client.is_spectating() || is_dead(world, client_entity) {
+				return;
+			}
+
 			// Apply rotation
 			{
 				let transform = queries.0.get_mut(world, client_entity).unwrap();
@@ -139,21 +265,28 @@ pub fn player_move_system() -> impl Runnable {
 }
 
 pub fn player_use_system(resources: &mut Resources) -> impl Runnable {
-	resources.insert(EventChannel::<UseEvent>::new());
+	add_events::<UseEvent>(resources);
 
 	SystemBuilder::new("player_use_system")
 		.read_resource::<AssetStorage>()
 		.read_resource::<Client>()
-		.write_resource::<EventChannel<UseEvent>>()
+		.write_resource::<Events<UseEvent>>()
 		.write_resource::<Vec<(AssetHandle<Sound>, Entity)>>()
 		.with_query(<(&Transform, &User)>::query())
 		.with_query(<&MapDynamic>::query())
+		.read_component::<Dead>()
 		.read_component::<UseAction>()
 		.build(move |_, world, resources, queries| {
+			let _profile = profiler::scope("player_use_system");
+
 			let (asset_storage, client, use_event_channel, sound_queue) = resources;
 
 			if let Some(entity) = client.entity {
-				if client.command.r#use && !client.previous_command.r#use {
+				if client.command.r#use
+					&& !client.previous_command.r#use
+					&& !client.is_spectating()
+					&& !is_dead(world, entity)
+				{
 					let (transform, user) = queries.0.get(world, entity).unwrap();
 					let map_dynamic = queries.1.iter(world).next().unwrap();
 					let map = asset_storage.get(&map_dynamic.map).unwrap();
@@ -165,11 +298,35 @@ pub fn player_use_system(resources: &mut Resources) -> impl Runnable {
 						Vector2::new(yaw.cos(), yaw.sin()) * USERANGE,
 					);
 
+					// Only the linedefs near the use line can be hit
+					let mut bbox = AABB2::from_point(use_line.point);
+					bbox.add_point(use_line.point + use_line.dir);
+					let mut nearby_linedefs = Vec::new();
+
+					map.traverse_nodes(NodeChild::Node(0), &bbox, &mut |node: NodeChild| {
+						let linedefs = match node {
+							NodeChild::Subsector(index) => &map.subsectors[index].linedefs,
+							NodeChild::Node(index) => &map.nodes[index].linedefs,
+						};
+
+						nearby_linedefs.extend(
+							linedefs
+								.iter()
+								.copied()
+								.filter(|&index| bbox.overlaps(&map.linedefs[index].bbox)),
+						);
+					});
+
+					nearby_linedefs.sort_unstable();
+					nearby_linedefs.dedup();
+
 					// Find the closest linedef hit
 					let mut pmax = 1.0;
 					let mut closest_linedef = None;
 
-					for (i, linedef) in map.linedefs.iter().enumerate() {
+					for i in nearby_linedefs {
+						let linedef = &map.linedefs[i];
+
 						if let Some((linedef_p, use_p)) = linedef.line.intersect(&use_line) {
 							if linedef_p >= 0.0 && linedef_p <= 1.0 && use_p >= 0.0 && use_p < pmax
 							{
@@ -225,25 +382,59 @@ pub fn player_attack_system(_resources: &mut Resources) -> impl Runnable {
 	SystemBuilder::new("player_attack_system")
 		.read_resource::<AssetStorage>()
 		.read_resource::<Client>()
+		.read_resource::<ConfigVariables>()
+		.read_resource::<FrameState>()
+		.write_resource::<Decals>()
+		.write_resource::<LevelStats>()
+		.write_resource::<MonsterRespawnQueue>()
+		.write_resource::<Particles>()
 		.write_resource::<Quadtree>()
+		.write_resource::<Vec<SpawnRequest>>()
 		.with_query(<(&Transform, Option<&Camera>)>::query())
-		.with_query(<&MapDynamic>::query())
+		.with_query(<&mut MapDynamic>::query())
+		.read_component::<Ambush>()
 		.read_component::<BoxCollider>() // used by EntityTracer
+		.read_component::<CountKill>()
+		.read_component::<Dead>()
+		.read_component::<EntityTemplateRef>()
 		.read_component::<Transform>() // used by EntityTracer
 		.build(move |command_buffer, world, resources, queries| {
-			let (asset_storage, client, quadtree) = resources;
+			let _profile = profiler::scope("player_attack_system");
+
+			let (
+				asset_storage,
+				client,
+				config_variables,
+				frame_state,
+				decals,
+				level_stats,
+				monster_respawn_queue,
+				particles,
+				quadtree,
+				spawn_queue,
+			) = resources;
+			let (mut world1, world) = world.split_for_query(&queries.1);
 
 			if let Some(client_entity) = client.entity {
-				if client.command.attack && !client.previous_command.attack {
-					let (transform, camera) = queries.0.get(world, client_entity).unwrap();
-					let map_dynamic = queries.1.iter(world).next().unwrap();
+				if client.command.attack
+					&& !client.previous_command.attack
+					&& !client.is_spectating()
+					&& !is_dead(&world, client_entity)
+				{
+					let (transform, camera) = queries.0.get(&world, client_entity).unwrap();
+					let map_dynamic = queries.1.iter_mut(&mut world1).next().unwrap();
 					let map = asset_storage.get(&map_dynamic.map).unwrap();
 
+					// Wake up the monsters that can hear the shot
+					let ssect = map
+						.find_subsector(Vector2::new(transform.position[0], transform.position[1]));
+					noise_alert(map, map_dynamic, ssect.sector_index, client_entity);
+
 					let tracer = EntityTracer {
 						map,
 						map_dynamic,
 						quadtree: &quadtree,
-						world,
+						world: &world,
 					};
 
 					const ATTACKRANGE: f32 = 2000.0;
@@ -261,14 +452,85 @@ pub fn player_attack_system(_resources: &mut Resources) -> impl Runnable {
 					);
 
 					if let Some(collision) = trace.collision {
-						if world
-							.entry_ref(collision.entity)
-							.unwrap()
-							.get_component::<BoxCollider>()
-							.is_ok()
-						{
+						let hit_position = position + trace.move_step;
+						let show_particles = config_variables.get::<bool>("r_particles");
+						let mut rng = frame_state.rng.lock().unwrap();
+
+						let entry = world.entry_ref(collision.entity).unwrap();
+
+						if entry.get_component::<BoxCollider>().is_ok() {
 							command_buffer.remove(collision.entity);
 							quadtree.remove(collision.entity);
+
+							if entry.get_component::<CountKill>().is_ok() {
+								level_stats.kills += 1;
+
+								// Nothing is left behind, so there is no corpse to remove
+								if let (Ok(template_ref), Ok(transform)) = (
+									entry.get_component::<EntityTemplateRef>(),
+									entry.get_component::<Transform>(),
+								) {
+									monster_respawn_queue.push(
+										template_ref.0.clone(),
+										*transform,
+										entry.get_component::<Ambush>().is_ok(),
+										None,
+										frame_state,
+									);
+								}
+							}
+
+							let template = entry
+								.get_component::<EntityTemplateRef>()
+								.ok()
+								.and_then(|template_ref| asset_storage.get(&template_ref.0));
+							let impact_effect = template
+								.map_or(ImpactEffect::default(), |template| template.impact_effect);
+
+							if let (Some(template), Ok(transform)) =
+								(template, entry.get_component::<Transform>())
+							{
+								spawn_queue.extend(drop_item_request(template, transform));
+							}
+
+							if show_particles {
+								match impact_effect {
+									ImpactEffect::Blood => particles.spawn(
+										ParticleKind::Blood,
+										hit_position,
+										collision.normal * 64.0,
+										8,
+										&mut *rng,
+									),
+									ImpactEffect::Puff => particles.spawn(
+										ParticleKind::Spark,
+										hit_position,
+										collision.normal * 96.0,
+										6,
+										&mut *rng,
+									),
+									ImpactEffect::Nothing => {}
+								}
+							}
+						} else {
+							if collision.normal[2] == 0.0 {
+								// Hit a wall
+								decals.add(Decal {
+									position: hit_position,
+									normal: collision.normal,
+									kind: DecalKind::BulletHole,
+								});
+							}
+
+							if show_particles {
+								particles.spawn(
+									ParticleKind::Spark,
+									hit_position,
+									collision.normal * 96.0,
+									6,
+									&mut *rng,
+								);
+							}
 						}
 					}
 				}
@@ -285,7 +547,9 @@ pub struct User {
 pub enum UseAction {
 	DoorUse(DoorUse),
 	DoorSwitchUse(DoorSwitchUse),
+	ExitSwitchUse(ExitSwitchUse),
 	FloorSwitchUse(FloorSwitchUse),
+	LineSpecialUse(LineSpecialUse),
 	PlatSwitchUse(PlatSwitchUse),
 }
 
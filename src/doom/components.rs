@@ -10,12 +10,17 @@ use crate::{
 };
 use legion::{systems::ResourceSet, Read, Resources};
 use nalgebra::Vector3;
+use serde::Deserialize;
 
 #[derive(Clone, Copy, Debug)]
 pub struct SpawnPoint {
 	pub player_num: usize,
 }
 
+/// A place where players can appear in deathmatch games.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DeathmatchSpawnPoint;
+
 #[derive(Clone, Copy, Debug, Default)]
 pub struct Transform {
 	pub position: Vector3<f32>,
@@ -24,7 +29,16 @@ pub struct Transform {
 
 #[derive(Clone, Copy, Debug, Default)]
 pub struct TransformDef {
-	pub spawn_on_ceiling: bool,
+	pub spawn_height: SpawnHeight,
+}
+
+/// Where an entity is placed vertically, when it's spawned without a given height.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum SpawnHeight {
+	/// Standing on the floor.
+	Floor,
+	/// Hanging from the ceiling, with the top of the entity touching it.
+	Ceiling,
 }
 
 impl SpawnFrom<TransformDef> for Transform {
@@ -33,21 +47,23 @@ impl SpawnFrom<TransformDef> for Transform {
 		let mut transform = spawn_context.transform;
 
 		if transform.position[2].is_nan() {
-			if component.spawn_on_ceiling {
-				transform.position[2] = spawn_context.sector_interval.max - DISTANCE_EPSILON;
-
-				if let Some(box_collider) = accessor.get::<BoxCollider>() {
-					transform.position[2] -= box_collider.height;
+			transform.position[2] = match component.spawn_height {
+				SpawnHeight::Floor => spawn_context.sector_interval.min + DISTANCE_EPSILON,
+				SpawnHeight::Ceiling => {
+					let height = accessor.get::<BoxCollider>().map_or(0.0, |b| b.height);
+					spawn_context.sector_interval.max - DISTANCE_EPSILON - height
 				}
-			} else {
-				transform.position[2] = spawn_context.sector_interval.min + DISTANCE_EPSILON;
-			}
+			};
 		}
 
 		transform
 	}
 }
 
+/// Entities with this component float in the air, instead of falling down.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoGravity;
+
 #[derive(Clone, Copy, Debug, Default)]
 pub struct Velocity {
 	pub velocity: Vector3<f32>,
@@ -1,5 +1,5 @@
 use crate::{
-	common::input::{Axis, Bindings, Button, ButtonBinding, MouseAxis},
+	common::input::{Axis, Bindings, Button, ButtonBinding, CommandBindings, MouseAxis},
 	doom::input::{BoolInput, FloatInput},
 };
 use winit::event::{MouseButton, VirtualKeyCode};
@@ -47,3 +47,10 @@ pub fn get_bindings() -> Bindings<BoolInput, FloatInput> {
 
 	bindings
 }
+
+pub fn get_command_bindings() -> CommandBindings {
+	let mut command_bindings = CommandBindings::new();
+	command_bindings.bind(Button::Key(VirtualKeyCode::Escape), "togglegrab");
+
+	command_bindings
+}
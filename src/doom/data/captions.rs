@@ -0,0 +1,106 @@
+use crate::doom::profile::GameProfile;
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+
+/// Returns the caption for a sound, by the name of the sound asset.
+/// The caption names a string in the `StringTable`, prefixed with `$`.
+pub fn caption_for(profile: GameProfile, name: &str) -> Option<&'static str> {
+	if profile == GameProfile::ChexQuest {
+		if let Some(caption) = CHEX_CAPTIONS.get(name) {
+			return Some(caption);
+		}
+	}
+
+	CAPTIONS.get(name).copied()
+}
+
+lazy_static! {
+	/// Captions for sounds that are worth mentioning, by the name of the sound asset.
+	pub static ref CAPTIONS: HashMap<&'static str, &'static str> = {
+		let mut captions = HashMap::new();
+
+		// World
+		captions.insert("dsdoropn.sound", "$CAP_DOOROPEN");
+		captions.insert("dsdorcls.sound", "$CAP_DOORCLOSE");
+		captions.insert("dsbdopn.sound", "$CAP_DOOROPEN");
+		captions.insert("dsbdcls.sound", "$CAP_DOORCLOSE");
+		captions.insert("dspstart.sound", "$CAP_LIFTSTART");
+		captions.insert("dspstop.sound", "$CAP_LIFTSTOP");
+		captions.insert("dsstnmov.sound", "$CAP_STONEMOVE");
+		captions.insert("dsswtchn.sound", "$CAP_SWITCH");
+		captions.insert("dsswtchx.sound", "$CAP_SWITCH");
+		captions.insert("dsitmbk.sound", "$CAP_ITEMRESPAWN");
+		captions.insert("dstelept.sound", "$CAP_TELEPORT");
+		captions.insert("dsbarexp.sound", "$CAP_BARRELEXPLODE");
+
+		// Monsters waking up
+		captions.insert("dsposit1.sound", "$CAP_ZOMBIESIGHT");
+		captions.insert("dsposit2.sound", "$CAP_ZOMBIESIGHT");
+		captions.insert("dsposit3.sound", "$CAP_ZOMBIESIGHT");
+		captions.insert("dsbgsit1.sound", "$CAP_IMPSIGHT");
+		captions.insert("dsbgsit2.sound", "$CAP_IMPSIGHT");
+		captions.insert("dssgtsit.sound", "$CAP_DEMONSIGHT");
+		captions.insert("dscacsit.sound", "$CAP_CACOSIGHT");
+		captions.insert("dsbrssit.sound", "$CAP_BARONSIGHT");
+		captions.insert("dskntsit.sound", "$CAP_KNIGHTSIGHT");
+		captions.insert("dsskesit.sound", "$CAP_REVENANTSIGHT");
+		captions.insert("dsmansit.sound", "$CAP_FATSOSIGHT");
+		captions.insert("dsvilsit.sound", "$CAP_VILESIGHT");
+		captions.insert("dsspisit.sound", "$CAP_SPIDERSIGHT");
+		captions.insert("dsbspsit.sound", "$CAP_ARACHSIGHT");
+		captions.insert("dscybsit.sound", "$CAP_CYBERSIGHT");
+		captions.insert("dspesit.sound", "$CAP_PAINSIGHT");
+		captions.insert("dssssit.sound", "$CAP_SSSIGHT");
+
+		// Monsters dying
+		captions.insert("dspodth1.sound", "$CAP_ZOMBIEDEATH");
+		captions.insert("dspodth2.sound", "$CAP_ZOMBIEDEATH");
+		captions.insert("dspodth3.sound", "$CAP_ZOMBIEDEATH");
+		captions.insert("dsbgdth1.sound", "$CAP_IMPDEATH");
+		captions.insert("dsbgdth2.sound", "$CAP_IMPDEATH");
+		captions.insert("dssgtdth.sound", "$CAP_DEMONDEATH");
+		captions.insert("dscacdth.sound", "$CAP_CACODEATH");
+		captions.insert("dsbrsdth.sound", "$CAP_BARONDEATH");
+		captions.insert("dskntdth.sound", "$CAP_KNIGHTDEATH");
+		captions.insert("dsskedth.sound", "$CAP_REVENANTDEATH");
+		captions.insert("dsmandth.sound", "$CAP_FATSODEATH");
+		captions.insert("dsvildth.sound", "$CAP_VILEDEATH");
+		captions.insert("dsspidth.sound", "$CAP_SPIDERDEATH");
+		captions.insert("dsbspdth.sound", "$CAP_ARACHDEATH");
+		captions.insert("dscybdth.sound", "$CAP_CYBERDEATH");
+		captions.insert("dspedth.sound", "$CAP_PAINDEATH");
+		captions.insert("dsssdth.sound", "$CAP_SSDEATH");
+		captions.insert("dskeendt.sound", "$CAP_KEENDEATH");
+		captions.insert("dsfirxpl.sound", "$CAP_FIREBALLEXPLODE");
+
+		captions
+	};
+
+	/// Chex Quest uses the same sounds for its Flemoids, which replace the monsters of Doom.
+	pub static ref CHEX_CAPTIONS: HashMap<&'static str, &'static str> = {
+		let mut captions = HashMap::new();
+
+		captions.insert("dsbarexp.sound", "$CAP_CHEX_BARRELEXPLODE");
+		captions.insert("dsfirxpl.sound", "$CAP_CHEX_FIREBALLEXPLODE");
+
+		// Flemoids waking up
+		captions.insert("dsposit1.sound", "$CAP_CHEX_FLEMOIDSIGHT");
+		captions.insert("dsposit2.sound", "$CAP_CHEX_FLEMOIDSIGHT");
+		captions.insert("dsposit3.sound", "$CAP_CHEX_FLEMOIDSIGHT");
+		captions.insert("dsbgsit1.sound", "$CAP_CHEX_ARMOREDSIGHT");
+		captions.insert("dsbgsit2.sound", "$CAP_CHEX_ARMOREDSIGHT");
+		captions.insert("dssgtsit.sound", "$CAP_CHEX_CYCLOPTISSIGHT");
+		captions.insert("dsbrssit.sound", "$CAP_CHEX_FLEMBRANESIGHT");
+
+		// Flemoids being sent back
+		captions.insert("dspodth1.sound", "$CAP_CHEX_FLEMOIDDEATH");
+		captions.insert("dspodth2.sound", "$CAP_CHEX_FLEMOIDDEATH");
+		captions.insert("dspodth3.sound", "$CAP_CHEX_FLEMOIDDEATH");
+		captions.insert("dsbgdth1.sound", "$CAP_CHEX_ARMOREDDEATH");
+		captions.insert("dsbgdth2.sound", "$CAP_CHEX_ARMOREDDEATH");
+		captions.insert("dssgtdth.sound", "$CAP_CHEX_CYCLOPTISDEATH");
+		captions.insert("dsbrsdth.sound", "$CAP_CHEX_FLEMBRANEDEATH");
+
+		captions
+	};
+}
@@ -5,9 +5,11 @@ use crate::{
 		data::{FRAME_RATE, FRAME_TIME},
 		door::{DoorParams, DoorState, DoorSwitchUse, DoorTouch, DoorUse},
 		entitytemplate::{EntityTemplate, EntityTypeId},
+		exit::{ExitSwitchUse, ExitTouch},
 		floor::{FloorParams, FloorSwitchUse, FloorTargetHeight, FloorTouch},
 		physics::TouchAction,
 		plat::{PlatParams, PlatSwitchUse, PlatTargetHeight, PlatTouch},
+		progression::ExitType,
 		switch::SwitchParams,
 		texture::TextureScroll,
 	},
@@ -2445,6 +2447,19 @@ pub fn load(resources: &mut Resources) {
 
 	let template = EntityTemplate {
 		type_id: Some(EntityTypeId::Linedef(11)),
+		world: {
+			let mut world = World::default();
+			world.push((
+				UseAction::ExitSwitchUse(ExitSwitchUse {
+					exit_type: ExitType::Normal,
+					switch_params: SwitchParams {
+						sound: Some(asset_storage.load("dsswtchx.sound")),
+						retrigger_time: None,
+					},
+				}),
+			));
+			world
+		},
 		.. EntityTemplate::default()
 	};
 	asset_storage.insert(template);
@@ -2580,12 +2595,34 @@ pub fn load(resources: &mut Resources) {
 
 	let template = EntityTemplate {
 		type_id: Some(EntityTypeId::Linedef(51)),
+		world: {
+			let mut world = World::default();
+			world.push((
+				UseAction::ExitSwitchUse(ExitSwitchUse {
+					exit_type: ExitType::Secret,
+					switch_params: SwitchParams {
+						sound: Some(asset_storage.load("dsswtchx.sound")),
+						retrigger_time: None,
+					},
+				}),
+			));
+			world
+		},
 		.. EntityTemplate::default()
 	};
 	asset_storage.insert(template);
 
 	let template = EntityTemplate {
 		type_id: Some(EntityTypeId::Linedef(52)),
+		world: {
+			let mut world = World::default();
+			world.push((
+				TouchAction::ExitTouch(ExitTouch {
+					exit_type: ExitType::Normal,
+				}),
+			));
+			world
+		},
 		.. EntityTemplate::default()
 	};
 	asset_storage.insert(template);
@@ -2712,6 +2749,15 @@ pub fn load(resources: &mut Resources) {
 
 	let template = EntityTemplate {
 		type_id: Some(EntityTypeId::Linedef(124)),
+		world: {
+			let mut world = World::default();
+			world.push((
+				TouchAction::ExitTouch(ExitTouch {
+					exit_type: ExitType::Secret,
+				}),
+			));
+			world
+		},
 		.. EntityTemplate::default()
 	};
 	asset_storage.insert(template);
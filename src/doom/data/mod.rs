@@ -1,10 +1,12 @@
 pub mod anims;
 mod bindings;
+pub mod captions;
 pub mod linedefs;
-pub mod mobjs;
+pub mod partimes;
 pub mod sectors;
+pub mod strings;
 
-pub use bindings::get_bindings;
+pub use bindings::{get_bindings, get_command_bindings};
 
 use lazy_static::lazy_static;
 use std::time::Duration;
@@ -12,6 +14,9 @@ use std::time::Duration;
 pub const FRAME_RATE: f32 = 35.0;
 pub const FRAME_TIME: Duration = Duration::from_nanos(28_571_429); // 1/35 sec
 
+/// Doom's built-in entity templates, as a JSON list of `EntityTemplateDef`.
+pub const MOBJS: &str = include_str!("mobjs.json");
+
 pub const FORWARD_ACCEL: f32 = (50.0 * 2048.0 / 65536.0) * FRAME_RATE * FRAME_RATE;
 pub const STRAFE_ACCEL: f32 = (40.0 * 2048.0 / 65536.0) * FRAME_RATE * FRAME_RATE;
 
@@ -0,0 +1,36 @@
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+
+lazy_static! {
+	/// Par times of the original levels in seconds, by map name.
+	pub static ref PAR_TIMES: HashMap<String, u32> = {
+		let mut par_times = HashMap::new();
+
+		// Doom 1, the fourth episode has no par times
+		let episodes: [[u32; 9]; 3] = [
+			[30, 75, 120, 90, 165, 180, 180, 30, 165],
+			[90, 90, 90, 120, 90, 360, 240, 30, 170],
+			[90, 45, 90, 150, 90, 90, 165, 30, 135],
+		];
+
+		for (episode, times) in episodes.iter().enumerate() {
+			for (map, &time) in times.iter().enumerate() {
+				par_times.insert(format!("e{}m{}", episode + 1, map + 1), time);
+			}
+		}
+
+		// Doom 2
+		let maps: [u32; 32] = [
+			30, 90, 120, 120, 90, 150, 120, 120, 270, 90,
+			210, 150, 150, 150, 210, 150, 420, 150, 210, 150,
+			240, 150, 180, 150, 150, 300, 330, 420, 300, 180,
+			120, 30,
+		];
+
+		for (map, &time) in maps.iter().enumerate() {
+			par_times.insert(format!("map{:02}", map + 1), time);
+		}
+
+		par_times
+	};
+}
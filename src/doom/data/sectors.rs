@@ -4,6 +4,7 @@ use crate::{
 		data::{FRAME_RATE, FRAME_TIME},
 		entitytemplate::{EntityTemplate, EntityTypeId},
 		light::{LightFlashDef, LightFlashType, LightGlow},
+		stats::SecretSector,
 	},
 };
 use legion::{systems::ResourceSet, Resources, World, Write};
@@ -122,6 +123,13 @@ pub fn load(resources: &mut Resources) {
 	// Secret
 	let template = EntityTemplate {
 		type_id: Some(EntityTypeId::Sector(9)),
+		world: {
+			let mut world = World::default();
+			world.push((
+				SecretSector,
+			));
+			world
+		},
 		.. EntityTemplate::default()
 	};
 	asset_storage.insert(template);
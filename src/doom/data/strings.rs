@@ -0,0 +1,170 @@
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+
+lazy_static! {
+	/// The built-in English text, by string name. A LANGUAGE lump or a locale file can
+	/// replace any of these. `{}` is filled in with values when the text is shown.
+	pub static ref STRINGS: HashMap<&'static str, &'static str> = {
+		let mut strings = HashMap::new();
+
+		// HUD
+		strings.insert("HUD_KILLS", "K {}/{}");
+		strings.insert("HUD_ITEMS", "I {}/{}");
+		strings.insert("HUD_SECRETS", "S {}/{}");
+		strings.insert("HUD_TIME", "T {}");
+		strings.insert("SECRETMESSAGE", "A secret is revealed!");
+
+		// Intermission
+		strings.insert("WI_FINISHED", "{} finished");
+		strings.insert("WI_KILLS", "Kills {}%");
+		strings.insert("WI_ITEMS", "Items {}%");
+		strings.insert("WI_SECRETS", "Secret {}%");
+		strings.insert("WI_TIME", "Time {}");
+		strings.insert("WI_PAR", "Par {}");
+		strings.insert("WI_UNDERPAR", "{} under par");
+		strings.insert("WI_OVERPAR", "{} over par");
+
+		// Doom armor, health and powerups
+		strings.insert("GOTARMOR", "Picked up the armor.");
+		strings.insert("GOTMEGA", "Picked up the MegaArmor!");
+		strings.insert("GOTHTHBONUS", "Picked up a health bonus.");
+		strings.insert("GOTARMBONUS", "Picked up an armor bonus.");
+		strings.insert("GOTSTIM", "Picked up a stimpack.");
+		strings.insert("GOTMEDIKIT", "Picked up a medikit.");
+		strings.insert("GOTSUPER", "Supercharge!");
+		strings.insert("GOTINVUL", "Invulnerability!");
+		strings.insert("GOTBERSERK", "Berserk!");
+		strings.insert("GOTINVIS", "Partial Invisibility");
+		strings.insert("GOTSUIT", "Radiation Shielding Suit");
+		strings.insert("GOTMAP", "Computer Area Map");
+		strings.insert("GOTVISOR", "Light Amplification Visor");
+		strings.insert("GOTMSPHERE", "MegaSphere!");
+
+		// Doom keys
+		strings.insert("GOTBLUECARD", "Picked up a blue keycard.");
+		strings.insert("GOTYELWCARD", "Picked up a yellow keycard.");
+		strings.insert("GOTREDCARD", "Picked up a red keycard.");
+		strings.insert("GOTBLUESKUL", "Picked up a blue skull key.");
+		strings.insert("GOTYELWSKUL", "Picked up a yellow skull key.");
+		strings.insert("GOTREDSKULL", "Picked up a red skull key.");
+
+		// Doom ammo
+		strings.insert("GOTCLIP", "Picked up a clip.");
+		strings.insert("GOTCLIPBOX", "Picked up a box of bullets.");
+		strings.insert("GOTROCKET", "Picked up a rocket.");
+		strings.insert("GOTROCKBOX", "Picked up a box of rockets.");
+		strings.insert("GOTCELL", "Picked up an energy cell.");
+		strings.insert("GOTCELLBOX", "Picked up an energy cell pack.");
+		strings.insert("GOTSHELLS", "Picked up 4 shotgun shells.");
+		strings.insert("GOTSHELLBOX", "Picked up a box of shotgun shells.");
+		strings.insert("GOTBACKPACK", "Picked up a backpack full of ammo!");
+
+		// Doom weapons
+		strings.insert("GOTBFG9000", "You got the BFG9000!  Oh, yes.");
+		strings.insert("GOTCHAINGUN", "You got the chaingun!");
+		strings.insert("GOTCHAINSAW", "A chainsaw!  Find some meat!");
+		strings.insert("GOTLAUNCHER", "You got the rocket launcher!");
+		strings.insert("GOTPLASMA", "You got the plasma gun!");
+		strings.insert("GOTSHOTGUN", "You got the shotgun!");
+		strings.insert("GOTSHOTGUN2", "You got the super shotgun!");
+
+		// Heretic weapons
+		strings.insert("TXT_WPNCROSSBOW", "ETHEREAL CROSSBOW");
+		strings.insert("TXT_WPNMACE", "FIREMACE");
+		strings.insert("TXT_WPNPHOENIXROD", "PHOENIX ROD");
+		strings.insert("TXT_WPNSKULLROD", "HELLSTAFF");
+		strings.insert("TXT_WPNGAUNTLETS", "GAUNTLETS OF THE NECROMANCER");
+		strings.insert("TXT_WPNBLASTER", "DRAGON CLAW");
+
+		// Heretic ammo
+		strings.insert("TXT_AMMOGOLDWAND1", "WAND CRYSTAL");
+		strings.insert("TXT_AMMOGOLDWAND2", "CRYSTAL GEODE");
+		strings.insert("TXT_AMMOCROSSBOW1", "ETHEREAL ARROWS");
+		strings.insert("TXT_AMMOCROSSBOW2", "QUIVER OF ETHEREAL ARROWS");
+		strings.insert("TXT_AMMOBLASTER1", "CLAW ORB");
+		strings.insert("TXT_AMMOBLASTER2", "ENERGY ORB");
+		strings.insert("TXT_AMMOSKULLROD1", "LESSER RUNES");
+		strings.insert("TXT_AMMOSKULLROD2", "GREATER RUNES");
+		strings.insert("TXT_AMMOPHOENIXROD1", "FLAME ORB");
+		strings.insert("TXT_AMMOPHOENIXROD2", "INFERNO ORB");
+		strings.insert("TXT_AMMOMACE1", "MACE SPHERES");
+		strings.insert("TXT_AMMOMACE2", "PILE OF MACE SPHERES");
+
+		// Heretic items and keys
+		strings.insert("TXT_ITEMHEALTH", "CRYSTAL VIAL");
+		strings.insert("TXT_ITEMSHIELD1", "SILVER SHIELD");
+		strings.insert("TXT_ITEMSHIELD2", "ENCHANTED SHIELD");
+		strings.insert("TXT_ITEMBAGOFHOLDING", "BAG OF HOLDING");
+		strings.insert("TXT_ITEMSUPERMAP", "MAP SCROLL");
+		strings.insert("TXT_GOTGREENKEY", "GREEN KEY");
+		strings.insert("TXT_GOTBLUEKEY", "BLUE KEY");
+		strings.insert("TXT_GOTYELLOWKEY", "YELLOW KEY");
+
+		// Heretic artifacts
+		strings.insert("TXT_ARTITOMEOFPOWER", "TOME OF POWER");
+		strings.insert("TXT_ARTIEGG", "MORPH OVUM");
+		strings.insert("TXT_ARTISUPERHEALTH", "MYSTIC URN");
+		strings.insert("TXT_ARTITORCH", "TORCH");
+		strings.insert("TXT_ARTIFIREBOMB", "TIME BOMB OF THE ANCIENTS");
+		strings.insert("TXT_ARTITELEPORT", "CHAOS DEVICE");
+		strings.insert("TXT_ARTIINVISIBILITY", "SHADOWSPHERE");
+		strings.insert("TXT_ARTIFLY", "WINGS OF WRATH");
+		strings.insert("TXT_ARTIINVULNERABILITY", "RING OF INVINCIBILITY");
+		strings.insert("TXT_ARTIHEALTH", "QUARTZ FLASK");
+
+		// Sound captions
+		strings.insert("CAP_DOOROPEN", "Door opens");
+		strings.insert("CAP_DOORCLOSE", "Door closes");
+		strings.insert("CAP_LIFTSTART", "Lift starts");
+		strings.insert("CAP_LIFTSTOP", "Lift stops");
+		strings.insert("CAP_STONEMOVE", "Stone grinds");
+		strings.insert("CAP_SWITCH", "Switch clicks");
+		strings.insert("CAP_ITEMRESPAWN", "Item reappears");
+		strings.insert("CAP_TELEPORT", "Teleporter hums");
+		strings.insert("CAP_BARRELEXPLODE", "Barrel explodes");
+		strings.insert("CAP_ZOMBIESIGHT", "Zombie grunts");
+		strings.insert("CAP_IMPSIGHT", "Imp growls");
+		strings.insert("CAP_DEMONSIGHT", "Demon snarls");
+		strings.insert("CAP_CACOSIGHT", "Cacodemon growls");
+		strings.insert("CAP_BARONSIGHT", "Baron roars");
+		strings.insert("CAP_KNIGHTSIGHT", "Hell knight roars");
+		strings.insert("CAP_REVENANTSIGHT", "Revenant shrieks");
+		strings.insert("CAP_FATSOSIGHT", "Mancubus bellows");
+		strings.insert("CAP_VILESIGHT", "Arch-vile hisses");
+		strings.insert("CAP_SPIDERSIGHT", "Spider mastermind roars");
+		strings.insert("CAP_ARACHSIGHT", "Arachnotron chitters");
+		strings.insert("CAP_CYBERSIGHT", "Cyberdemon roars");
+		strings.insert("CAP_PAINSIGHT", "Pain elemental wails");
+		strings.insert("CAP_SSSIGHT", "Soldier shouts");
+		strings.insert("CAP_ZOMBIEDEATH", "Zombie dies");
+		strings.insert("CAP_IMPDEATH", "Imp dies");
+		strings.insert("CAP_DEMONDEATH", "Demon dies");
+		strings.insert("CAP_CACODEATH", "Cacodemon dies");
+		strings.insert("CAP_BARONDEATH", "Baron dies");
+		strings.insert("CAP_KNIGHTDEATH", "Hell knight dies");
+		strings.insert("CAP_REVENANTDEATH", "Revenant dies");
+		strings.insert("CAP_FATSODEATH", "Mancubus dies");
+		strings.insert("CAP_VILEDEATH", "Arch-vile dies");
+		strings.insert("CAP_SPIDERDEATH", "Spider mastermind dies");
+		strings.insert("CAP_ARACHDEATH", "Arachnotron dies");
+		strings.insert("CAP_CYBERDEATH", "Cyberdemon dies");
+		strings.insert("CAP_PAINDEATH", "Pain elemental dies");
+		strings.insert("CAP_SSDEATH", "Soldier dies");
+		strings.insert("CAP_KEENDEATH", "Keen dies");
+		strings.insert("CAP_FIREBALLEXPLODE", "Fireball explodes");
+
+		// Chex Quest sound captions
+		strings.insert("CAP_CHEX_BARRELEXPLODE", "Vile barrel bursts");
+		strings.insert("CAP_CHEX_FIREBALLEXPLODE", "Slime splats");
+		strings.insert("CAP_CHEX_FLEMOIDSIGHT", "Flemoid gurgles");
+		strings.insert("CAP_CHEX_ARMOREDSIGHT", "Armored Flemoid gurgles");
+		strings.insert("CAP_CHEX_CYCLOPTISSIGHT", "Cycloptis snarls");
+		strings.insert("CAP_CHEX_FLEMBRANESIGHT", "Flembrane roars");
+		strings.insert("CAP_CHEX_FLEMOIDDEATH", "Flemoid is zorched");
+		strings.insert("CAP_CHEX_ARMOREDDEATH", "Armored Flemoid is zorched");
+		strings.insert("CAP_CHEX_CYCLOPTISDEATH", "Cycloptis is zorched");
+		strings.insert("CAP_CHEX_FLEMBRANEDEATH", "Flembrane is zorched");
+
+		strings
+	};
+}
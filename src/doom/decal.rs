@@ -0,0 +1,63 @@
+use nalgebra::Vector3;
+use std::collections::VecDeque;
+
+/// The most decals that can exist at once. When more are added, the oldest ones disappear.
+pub const MAX_DECALS: usize = 256;
+
+/// A mark left on a wall, like a bullet hole or a scorch mark.
+#[derive(Clone, Copy, Debug)]
+pub struct Decal {
+	/// Centre of the decal, on the surface of the wall.
+	pub position: Vector3<f32>,
+	/// Direction that the wall faces.
+	pub normal: Vector3<f32>,
+	pub kind: DecalKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecalKind {
+	BulletHole,
+	Scorch,
+}
+
+impl DecalKind {
+	/// Width and height of the decal.
+	pub fn size(self) -> f32 {
+		match self {
+			DecalKind::BulletHole => 4.0,
+			DecalKind::Scorch => 48.0,
+		}
+	}
+
+	/// Colour that the wall is multiplied with at the centre of the decal.
+	pub fn colour(self) -> [f32; 3] {
+		match self {
+			DecalKind::BulletHole => [0.15, 0.15, 0.15],
+			DecalKind::Scorch => [0.1, 0.08, 0.06],
+		}
+	}
+}
+
+/// All decals in the current map, oldest first.
+#[derive(Clone, Debug, Default)]
+pub struct Decals {
+	decals: VecDeque<Decal>,
+}
+
+impl Decals {
+	pub fn add(&mut self, decal: Decal) {
+		if self.decals.len() >= MAX_DECALS {
+			self.decals.pop_front();
+		}
+
+		self.decals.push_back(decal);
+	}
+
+	pub fn clear(&mut self) {
+		self.decals.clear();
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = &Decal> {
+		self.decals.iter()
+	}
+}
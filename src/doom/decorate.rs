@@ -0,0 +1,715 @@
+use crate::{
+	common::assets::AssetStorage,
+	doom::{
+		components::SpawnHeight,
+		entitytemplate::{
+			BoxColliderDef, ComponentsDef, EntityTemplate, EntityTemplateDef, EntityTypeId,
+			ImpactEffect, SoundDef, SpriteRenderDef, StateActionDef, StateInfoDef,
+		},
+	},
+};
+use anyhow::{bail, ensure, Context};
+use legion::{systems::ResourceSet, Resources, Write};
+use relative_path::RelativePath;
+use std::{collections::HashMap, iter::Peekable, str::Chars};
+
+/// Name of the lump with custom actor definitions.
+pub const DECORATE_LUMP: &str = "decorate.txt";
+
+/// Loads the actors from the DECORATE lump, if there is one, on top of the existing templates.
+/// Only a subset of the language is understood: properties, flags and states that map onto
+/// what the engine supports. Anything else is skipped with a warning.
+pub fn load_decorate(resources: &mut Resources) -> anyhow::Result<()> {
+	let mut asset_storage = <Write<AssetStorage>>::fetch_mut(resources);
+	let path = RelativePath::new(DECORATE_LUMP);
+
+	if !asset_storage.source().exists(path) {
+		return Ok(());
+	}
+
+	let data = asset_storage.source().load(path)?;
+	let text = String::from_utf8_lossy(&data);
+	let actors = parse_decorate(&text).context("Couldn't parse DECORATE")?;
+
+	for actor in &actors {
+		let mut template = actor
+			.def
+			.build(&mut asset_storage)
+			.with_context(|| format!("Couldn't build DECORATE actor \"{}\"", actor.name))?;
+
+		// The replacement gets spawned in place of the original, this one only by name
+		if actor.replaces.is_some() {
+			template.type_id = None;
+		}
+
+		asset_storage.insert_with_name(&actor.name, template);
+
+		// Replacing an actor means taking over its name, so that anything referring to it
+		// uses the new one instead
+		if let Some(replaces) = &actor.replaces {
+			let mut template = actor.def.build(&mut asset_storage)?;
+
+			if template.type_id.is_none() {
+				template.type_id = asset_storage
+					.handle_for::<EntityTemplate>(replaces)
+					.and_then(|handle| asset_storage.get(&handle).and_then(|t| t.type_id));
+			}
+
+			asset_storage.insert_with_name(replaces, template);
+		}
+	}
+
+	log::info!("Loaded {} actors from DECORATE", actors.len());
+	Ok(())
+}
+
+#[derive(Clone, Debug)]
+pub struct DecorateActor {
+	pub name: String,
+	pub def: EntityTemplateDef,
+	/// Name of the template that this actor replaces, if any.
+	pub replaces: Option<String>,
+}
+
+pub fn parse_decorate(text: &str) -> anyhow::Result<Vec<DecorateActor>> {
+	let mut tokens = Tokenizer::new(text).collect::<anyhow::Result<Vec<Token>>>()?;
+	tokens.reverse();
+	let mut parser = Parser { tokens };
+	let mut actors: Vec<DecorateActor> = Vec::new();
+
+	while let Some(token) = parser.next() {
+		match token {
+			Token::Word(word) if word.eq_ignore_ascii_case("actor") => {
+				let actor = parser
+					.parse_actor(&actors)
+					.context("Invalid actor definition")?;
+				actors.push(actor);
+			}
+			token => bail!("Expected \"actor\", found {:?}", token),
+		}
+	}
+
+	Ok(actors)
+}
+
+/// A token of DECORATE-like text, which is also shared by the MAPINFO parser.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Token {
+	Word(String),
+	String(String),
+	Number(f32),
+	Symbol(char),
+	Newline,
+}
+
+pub struct Tokenizer<'a> {
+	chars: Peekable<Chars<'a>>,
+}
+
+impl<'a> Tokenizer<'a> {
+	pub fn new(text: &'a str) -> Tokenizer<'a> {
+		Tokenizer {
+			chars: text.chars().peekable(),
+		}
+	}
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+	type Item = anyhow::Result<Token>;
+
+	fn next(&mut self) -> Option<anyhow::Result<Token>> {
+		loop {
+			let c = *self.chars.peek()?;
+
+			if c == '\n' {
+				self.chars.next();
+				return Some(Ok(Token::Newline));
+			} else if c.is_whitespace() {
+				self.chars.next();
+			} else if c == '/' {
+				self.chars.next();
+
+				match self.chars.peek() {
+					Some('/') => {
+						while self.chars.peek().map_or(false, |&c| c != '\n') {
+							self.chars.next();
+						}
+					}
+					Some('*') => {
+						self.chars.next();
+						let mut previous = ' ';
+
+						loop {
+							match self.chars.next() {
+								Some('/') if previous == '*' => break,
+								Some(c) => previous = c,
+								None => return Some(Err(anyhow::anyhow!("Unterminated comment"))),
+							}
+						}
+					}
+					_ => return Some(Ok(Token::Symbol('/'))),
+				}
+			} else if c == '"' {
+				self.chars.next();
+				let mut string = String::new();
+
+				loop {
+					match self.chars.next() {
+						Some('"') => return Some(Ok(Token::String(string))),
+						Some(c) => string.push(c),
+						None => return Some(Err(anyhow::anyhow!("Unterminated string"))),
+					}
+				}
+			} else if c.is_ascii_digit() || c == '.' {
+				let mut string = String::new();
+
+				while let Some(&c) = self.chars.peek() {
+					if c.is_ascii_digit() || c == '.' {
+						string.push(c);
+						self.chars.next();
+					} else {
+						break;
+					}
+				}
+
+				return Some(
+					string
+						.parse()
+						.map(Token::Number)
+						.with_context(|| format!("Invalid number: {}", string)),
+				);
+			} else if c.is_alphanumeric() || c == '_' || c == '[' || c == ']' || c == '\\' {
+				let mut string = String::new();
+
+				while let Some(&c) = self.chars.peek() {
+					if c.is_alphanumeric() || c == '_' || c == '[' || c == ']' || c == '\\' {
+						string.push(c);
+						self.chars.next();
+					} else {
+						break;
+					}
+				}
+
+				return Some(Ok(Token::Word(string)));
+			} else {
+				self.chars.next();
+				return Some(Ok(Token::Symbol(c)));
+			}
+		}
+	}
+}
+
+struct Parser {
+	/// Remaining tokens, in reverse order.
+	tokens: Vec<Token>,
+}
+
+impl Parser {
+	/// Returns the next token, skipping newlines.
+	fn next(&mut self) -> Option<Token> {
+		self.skip_newlines();
+		self.tokens.pop()
+	}
+
+	fn peek(&mut self) -> Option<&Token> {
+		self.skip_newlines();
+		self.tokens.last()
+	}
+
+	fn skip_newlines(&mut self) {
+		while self.tokens.last() == Some(&Token::Newline) {
+			self.tokens.pop();
+		}
+	}
+
+	/// Returns whether the next token is on the same line.
+	fn same_line(&self) -> bool {
+		match self.tokens.last() {
+			Some(Token::Newline) | None => false,
+			_ => true,
+		}
+	}
+
+	fn expect_symbol(&mut self, symbol: char) -> anyhow::Result<()> {
+		match self.next() {
+			Some(Token::Symbol(c)) if c == symbol => Ok(()),
+			token => bail!("Expected '{}', found {:?}", symbol, token),
+		}
+	}
+
+	fn expect_word(&mut self) -> anyhow::Result<String> {
+		match self.next() {
+			Some(Token::Word(word)) => Ok(word),
+			token => bail!("Expected a name, found {:?}", token),
+		}
+	}
+
+	fn expect_string(&mut self) -> anyhow::Result<String> {
+		match self.next() {
+			Some(Token::String(string)) => Ok(string),
+			token => bail!("Expected a string, found {:?}", token),
+		}
+	}
+
+	fn expect_number(&mut self) -> anyhow::Result<f32> {
+		let negative = if self.peek() == Some(&Token::Symbol('-')) {
+			self.next();
+			true
+		} else {
+			false
+		};
+
+		match self.next() {
+			Some(Token::Number(number)) => Ok(if negative { -number } else { number }),
+			token => bail!("Expected a number, found {:?}", token),
+		}
+	}
+
+	/// Skips the rest of the current line.
+	fn skip_line(&mut self) {
+		while self.same_line() {
+			self.tokens.pop();
+		}
+	}
+
+	fn parse_actor(&mut self, actors: &[DecorateActor]) -> anyhow::Result<DecorateActor> {
+		let name = self.expect_word()?.to_ascii_lowercase();
+
+		let mut def = EntityTemplateDef {
+			name: Some(name.clone()),
+			type_id: None,
+			not_in_deathmatch: false,
+			impact_effect: ImpactEffect::Blood,
+			drop_item: None,
+			states: HashMap::new(),
+			sounds: HashMap::new(),
+			random_spawn: Vec::new(),
+			components: ComponentsDef {
+				box_collider: Some(BoxColliderDef {
+					height: 16.0,
+					radius: 20.0,
+					solid: false,
+				}),
+				transform: Some(SpawnHeight::Floor),
+				..ComponentsDef::default()
+			},
+		};
+		let mut replaces = None;
+		let mut is_random_spawner = false;
+
+		// Header
+		loop {
+			match self.next() {
+				Some(Token::Symbol(':')) => {
+					let parent_name = self.expect_word()?.to_ascii_lowercase();
+
+					match actors.iter().find(|a| a.name == parent_name) {
+						Some(parent) => {
+							def = EntityTemplateDef {
+								name: Some(name.clone()),
+								type_id: None,
+								..parent.def.clone()
+							};
+							is_random_spawner = !def.random_spawn.is_empty();
+						}
+						None if parent_name == "randomspawner" => is_random_spawner = true,
+						None => log::warn!(
+							"Actor \"{}\" inherits from unknown actor \"{}\", ignoring",
+							name,
+							parent_name
+						),
+					}
+				}
+				Some(Token::Word(word)) if word.eq_ignore_ascii_case("replaces") => {
+					replaces = Some(self.expect_word()?.to_ascii_lowercase());
+				}
+				Some(Token::Number(number)) => {
+					def.type_id = Some(EntityTypeId::Thing(number as u16));
+				}
+				Some(Token::Symbol('{')) => break,
+				token => bail!("Unexpected {:?} in actor header", token),
+			}
+		}
+
+		let mut drop_items = Vec::new();
+		let mut solid = def
+			.components
+			.box_collider
+			.map_or(false, |box_collider| box_collider.solid);
+
+		// Body
+		loop {
+			match self.next() {
+				Some(Token::Symbol('}')) => break,
+				Some(Token::Symbol(sign)) if sign == '+' || sign == '-' => {
+					let flag = self.expect_word()?.to_ascii_uppercase();
+					let value = sign == '+';
+
+					match flag.as_str() {
+						"SOLID" => solid = value,
+						"NOGRAVITY" => def.components.no_gravity = value,
+						"NOTDMATCH" => def.not_in_deathmatch = value,
+						"NOBLOOD" => {
+							def.impact_effect = if value {
+								ImpactEffect::Puff
+							} else {
+								ImpactEffect::Blood
+							}
+						}
+						"SPAWNCEILING" => {
+							def.components.transform = Some(if value {
+								SpawnHeight::Ceiling
+							} else {
+								SpawnHeight::Floor
+							})
+						}
+						_ => log::warn!("Actor \"{}\": unsupported flag {}", name, flag),
+					}
+				}
+				Some(Token::Word(word)) => match word.to_ascii_lowercase().as_str() {
+					"states" => self.parse_states(&mut def)?,
+					"monster" => {
+						solid = true;
+						def.components.velocity = true;
+					}
+					"projectile" => {
+						def.components.no_gravity = true;
+						def.components.velocity = true;
+					}
+					"radius" => {
+						let radius = self.expect_number()?;
+						def.components.box_collider.as_mut().unwrap().radius = radius;
+					}
+					"height" => {
+						let height = self.expect_number()?;
+						def.components.box_collider.as_mut().unwrap().height = height;
+					}
+					"conversationid" => {
+						def.components.talker = Some(self.expect_number()? as u32);
+						self.skip_line();
+					}
+					"seesound" | "attacksound" | "painsound" | "deathsound" | "activesound" => {
+						let sound = self.expect_string()?.to_ascii_lowercase();
+						let key = word[..word.len() - "sound".len()].to_ascii_lowercase();
+						def.sounds.insert(key, SoundDef::Single(sound));
+					}
+					"dropitem" => {
+						let item = self.expect_string()?.to_ascii_lowercase();
+						let mut numbers = Vec::new();
+
+						while self.same_line() {
+							match self.tokens.pop() {
+								Some(Token::Number(number)) => numbers.push(number as u32),
+								Some(Token::Symbol(',')) => {}
+								token => bail!("Unexpected {:?} in DropItem", token),
+							}
+						}
+
+						// Probability out of 255, and amount
+						let probability = numbers.get(0).copied().unwrap_or(255);
+						let amount = numbers.get(1).copied().unwrap_or(1);
+						drop_items.push((item, probability, amount));
+					}
+					_ => {
+						log::warn!("Actor \"{}\": unsupported property {}", name, word);
+						self.skip_line();
+					}
+				},
+				token => bail!("Unexpected {:?} in actor \"{}\"", token, name),
+			}
+		}
+
+		def.components.box_collider.as_mut().unwrap().solid = solid;
+
+		if is_random_spawner {
+			// Random spawners use the amount as the weight
+			if !drop_items.is_empty() {
+				def.random_spawn = drop_items
+					.into_iter()
+					.map(|(item, _, amount)| (item, amount))
+					.collect();
+			}
+
+			// They are never spawned themselves, so they need nothing else
+			return Ok(DecorateActor {
+				name,
+				def,
+				replaces,
+			});
+		} else if let Some((item, _, _)) = drop_items.first() {
+			// Monsters always drop their item, like in vanilla
+			if drop_items.len() > 1 {
+				log::warn!(
+					"Actor \"{}\": only the first DropItem is dropped on death",
+					name
+				);
+			}
+
+			def.drop_item = Some(item.clone());
+		}
+
+		// Entities are drawn with the first frame of their spawn state
+		let spawn_frame = def.states.get("spawn").and_then(|infos| infos.first());
+		ensure!(
+			spawn_frame.is_some(),
+			"Actor \"{}\" has no spawn state",
+			name
+		);
+		let spawn_frame = spawn_frame.unwrap();
+		def.components.sprite = Some(SpriteRenderDef {
+			sprite: spawn_frame.sprite.clone(),
+			frame: spawn_frame.frame,
+			full_bright: spawn_frame.full_bright,
+		});
+
+		Ok(DecorateActor {
+			name,
+			def,
+			replaces,
+		})
+	}
+
+	/// Parses the arguments of an action function, after the opening parenthesis.
+	fn parse_arguments(&mut self) -> anyhow::Result<Vec<Argument>> {
+		let mut arguments = Vec::new();
+
+		loop {
+			let mut token = match self.next() {
+				Some(Token::Symbol(')')) => break,
+				Some(Token::Symbol('-')) => match self.next() {
+					Some(Token::Number(number)) => Token::Number(-number),
+					token => bail!("Expected a number, found {:?}", token),
+				},
+				Some(token) => token,
+				None => bail!("Unterminated argument list"),
+			};
+			let mut name = None;
+
+			if let Token::Word(word) = &token {
+				if self.peek() == Some(&Token::Symbol('=')) {
+					self.next();
+					name = Some(word.to_ascii_lowercase());
+					token = match self.next() {
+						Some(Token::Symbol('-')) => Token::Number(-self.expect_number()?),
+						Some(token) => token,
+						None => bail!("Unterminated argument list"),
+					};
+				}
+			}
+
+			arguments.push(Argument { name, value: token });
+
+			match self.next() {
+				Some(Token::Symbol(',')) => continue,
+				Some(Token::Symbol(')')) => break,
+				token => bail!("Expected ',' or ')', found {:?}", token),
+			}
+		}
+
+		Ok(arguments)
+	}
+
+	fn parse_states(&mut self, def: &mut EntityTemplateDef) -> anyhow::Result<()> {
+		self.expect_symbol('{')?;
+
+		// Labels that are waiting for their first frame
+		let mut pending_labels: Vec<String> = Vec::new();
+		// Labels that point to the same frame as another label
+		let mut aliases: Vec<(String, String)> = Vec::new();
+		// The label that frames are currently being added to
+		let mut current: Option<(String, Vec<StateInfoDef>)> = None;
+
+		loop {
+			let word = match self.next() {
+				Some(Token::Symbol('}')) => break,
+				Some(Token::Word(word)) => word,
+				token => bail!("Unexpected {:?} in states", token),
+			};
+
+			// Label
+			if self.tokens.last() == Some(&Token::Symbol(':')) {
+				self.tokens.pop();
+				pending_labels.push(word.to_ascii_lowercase());
+				continue;
+			}
+
+			// Flow control
+			let next = match word.to_ascii_lowercase().as_str() {
+				"loop" => match &current {
+					Some((label, _)) => Some(Some((label.clone(), 0))),
+					None => bail!("\"loop\" without a label"),
+				},
+				"goto" => {
+					let label = self.expect_word()?.to_ascii_lowercase();
+					let offset = if self.same_line() && self.peek() == Some(&Token::Symbol('+')) {
+						self.next();
+						self.expect_number()? as usize
+					} else {
+						0
+					};
+					Some(Some((label, offset)))
+				}
+				"stop" | "wait" | "fail" => Some(None),
+				_ => None,
+			};
+
+			if let Some(next) = next {
+				// Labels that jump straight somewhere else are the same as the target
+				if let Some((target, _)) = &next {
+					aliases.extend(
+						pending_labels
+							.drain(..)
+							.map(|label| (label, target.clone())),
+					);
+				}
+
+				if let Some((label, mut infos)) = current.take() {
+					if let Some(last) = infos.last_mut() {
+						match next {
+							Some(next) => last.next = Some(next),
+							// The entity stays on the last frame
+							None => last.time = None,
+						}
+					}
+
+					def.states.insert(label, infos);
+				}
+
+				continue;
+			}
+
+			// Frames
+			let frames = match self.next() {
+				Some(Token::Word(frames)) => frames,
+				Some(Token::String(frames)) => frames,
+				token => bail!("Expected frame letters, found {:?}", token),
+			};
+			let time = self.expect_number()?;
+			let mut full_bright = false;
+			let mut action = None;
+
+			while self.same_line() {
+				match self.tokens.pop() {
+					Some(Token::Word(keyword)) if keyword.eq_ignore_ascii_case("bright") => {
+						full_bright = true;
+					}
+					Some(Token::Word(name)) => {
+						let arguments = if self.tokens.last() == Some(&Token::Symbol('(')) {
+							self.tokens.pop();
+							self.parse_arguments()?
+						} else {
+							Vec::new()
+						};
+
+						action = parse_action(&name, &arguments)
+							.with_context(|| format!("Invalid call to {}", name))?;
+					}
+					_ => {}
+				}
+			}
+
+			// A new label starts a new state sequence, the previous one falls through into it
+			if !pending_labels.is_empty() {
+				let label = pending_labels.remove(0);
+
+				if let Some((previous, mut infos)) = current.take() {
+					if let Some(last) = infos.last_mut() {
+						last.next = Some((label.clone(), 0));
+					}
+
+					def.states.insert(previous, infos);
+				}
+
+				aliases.extend(pending_labels.drain(..).map(|alias| (alias, label.clone())));
+				current = Some((label, Vec::new()));
+			}
+
+			let (_, infos) = match &mut current {
+				Some(x) => x,
+				None => bail!("Frame without a label"),
+			};
+
+			for c in frames.chars() {
+				ensure!(c.is_ascii_alphabetic(), "Invalid frame letter: {}", c);
+
+				infos.push(StateInfoDef {
+					sprite: word.to_ascii_lowercase(),
+					frame: (c.to_ascii_uppercase() as u8 - b'A') as usize,
+					full_bright,
+					time: if time < 0.0 { None } else { Some(time as u32) },
+					next: None,
+					action: action.clone(),
+				});
+			}
+		}
+
+		if let Some((label, infos)) = current.take() {
+			def.states.insert(label, infos);
+		}
+
+		for (alias, target) in aliases {
+			match def.states.get(&target) {
+				Some(infos) => {
+					let infos = infos.clone();
+					def.states.insert(alias, infos);
+				}
+				None => log::warn!("State label \"{}\" not found", target),
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// An argument to an action function, either given by position or by name.
+#[derive(Clone, Debug)]
+struct Argument {
+	name: Option<String>,
+	value: Token,
+}
+
+/// Returns the argument with the given name, or else the one at the given position.
+fn find_argument<'a>(arguments: &'a [Argument], name: &str, index: usize) -> Option<&'a Token> {
+	arguments
+		.iter()
+		.find(|arg| arg.name.as_deref() == Some(name))
+		.or_else(|| arguments.get(index).filter(|arg| arg.name.is_none()))
+		.map(|arg| &arg.value)
+}
+
+/// Speed of projectiles when it isn't given, in map units per frame.
+const DEFAULT_PROJECTILE_SPEED: f32 = 10.0;
+
+fn parse_action(name: &str, arguments: &[Argument]) -> anyhow::Result<Option<StateActionDef>> {
+	Ok(match name.to_ascii_lowercase().as_str() {
+		"a_fall" | "a_noblocking" => Some(StateActionDef::Fall),
+		"a_scream" => Some(StateActionDef::Sound("death".into())),
+		"a_pain" => Some(StateActionDef::Sound("pain".into())),
+		"a_activesound" => Some(StateActionDef::Sound("active".into())),
+		"a_playsound" => match find_argument(arguments, "sound", 0) {
+			Some(Token::String(sound)) => {
+				Some(StateActionDef::PlaySound(sound.to_ascii_lowercase()))
+			}
+			_ => bail!("Expected a sound name"),
+		},
+		"a_spawnprojectile" | "a_custommissile" => {
+			let template = match find_argument(arguments, "missiletype", 0) {
+				Some(Token::String(template)) => template.to_ascii_lowercase(),
+				_ => bail!("Expected a projectile name"),
+			};
+			let speed = match find_argument(arguments, "speed", usize::MAX) {
+				Some(Token::Number(speed)) => *speed,
+				Some(token) => bail!("Expected a number for the speed, found {:?}", token),
+				None => DEFAULT_PROJECTILE_SPEED,
+			};
+
+			Some(StateActionDef::SpawnProjectile { template, speed })
+		}
+		// Handled by the monster systems already
+		"a_look" | "a_chase" | "a_facetarget" => None,
+		_ => {
+			log::warn!("Unsupported action function {}", name);
+			None
+		}
+	})
+}
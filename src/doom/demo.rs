@@ -0,0 +1,399 @@
+use crate::{
+	common::{
+		assets::AssetHandle,
+		audio::Sound,
+		events::{register_reader, Events},
+		frame::{FrameRng, FrameState},
+		gamestate::{GameState, GameStateMachine},
+		module::GameModule,
+	},
+	doom::{
+		client::Client,
+		components::Transform,
+		data::FRAME_RATE,
+		health::{is_dead, DamageEvent},
+		input::UserCommand,
+		profile::GameProfile,
+		stats::LevelStats,
+		wad::WadLoader,
+	},
+};
+use anyhow::{bail, ensure, Context};
+use byteorder::ReadBytesExt;
+use clap::ArgMatches;
+use legion::{systems::ResourceSet, Entity, IntoQuery, Read, Resources, World, Write};
+use nalgebra::Vector2;
+use rand::SeedableRng;
+use std::{io::Cursor, path::Path};
+
+/// Runs one of the `demo` subcommands, which look at recorded demos without starting the game.
+pub fn run(arg_matches: &ArgMatches) -> anyhow::Result<()> {
+	match arg_matches.subcommand() {
+		("stats", Some(matches)) => {
+			let path = Path::new(matches.value_of("FILE").unwrap());
+			let data =
+				std::fs::read(path).with_context(|| format!("Couldn't read {}", path.display()))?;
+			let demo = parse_demo(&data)?;
+			let stats = play_demo(&demo, matches)?;
+			print_stats(&demo, &stats);
+			Ok(())
+		}
+		_ => bail!("No demo command given, try \"demo --help\""),
+	}
+}
+
+/// A recording of a player's commands, in the `.lmp` format of the original game.
+#[derive(Clone, Debug)]
+pub struct Demo {
+	pub version: u8,
+	/// Skill level, numbered from 1 like the `skill` setting.
+	pub skill: u8,
+	pub episode: u8,
+	pub map: u8,
+	pub deathmatch: bool,
+	pub respawn: bool,
+	pub fast: bool,
+	pub nomonsters: bool,
+	/// Which of the players was recording.
+	pub console_player: usize,
+	pub player_count: usize,
+	/// The commands of the recording player, one for each game frame.
+	pub tics: Vec<DemoTic>,
+}
+
+/// The command of one player for one game frame, as the original game stores it.
+#[derive(Clone, Copy, Debug)]
+pub struct DemoTic {
+	pub forward: i8,
+	/// Sideways movement, positive to the right.
+	pub side: i8,
+	/// Turning, in 256ths of a full turn, positive to the left.
+	pub turn: i8,
+	pub buttons: u8,
+}
+
+const BT_ATTACK: u8 = 1;
+const BT_USE: u8 = 2;
+const BT_SPECIAL: u8 = 128;
+
+/// Marks the end of the commands.
+const DEMO_END: u8 = 0x80;
+
+impl DemoTic {
+	/// Turns the command into one for the player movement systems,
+	/// where running at full speed moves at 1.0.
+	pub fn to_command(self) -> UserCommand {
+		// Special commands, like pausing, use the other buttons for something else
+		let buttons = if self.buttons & BT_SPECIAL != 0 {
+			0
+		} else {
+			self.buttons
+		};
+
+		UserCommand {
+			attack: buttons & BT_ATTACK != 0,
+			r#use: buttons & BT_USE != 0,
+			forward: self.forward as f32 / 50.0,
+			pitch: 0.0,
+			strafe: -self.side as f32 / 40.0,
+			// The player movement system multiplies this by a million to get an angle,
+			// and the turn is the top 8 bits of a 32-bit angle
+			yaw: -(self.turn as f32) * (1 << 24) as f32 / 1e6,
+		}
+	}
+}
+
+pub fn parse_demo(data: &[u8]) -> anyhow::Result<Demo> {
+	let mut reader = Cursor::new(data);
+	let first = reader.read_u8().context("Demo is empty")?;
+
+	// Versions before 1.4 had a shorter header, that started with the skill
+	let mut demo = match first {
+		0..=4 => Demo {
+			version: 0,
+			skill: first + 1,
+			episode: reader.read_u8()?,
+			map: reader.read_u8()?,
+			deathmatch: false,
+			respawn: false,
+			fast: false,
+			nomonsters: false,
+			console_player: 0,
+			player_count: 0,
+			tics: Vec::new(),
+		},
+		104..=111 => Demo {
+			version: first,
+			skill: reader.read_u8()? + 1,
+			episode: reader.read_u8()?,
+			map: reader.read_u8()?,
+			deathmatch: reader.read_u8()? != 0,
+			respawn: reader.read_u8()? != 0,
+			fast: reader.read_u8()? != 0,
+			nomonsters: reader.read_u8()? != 0,
+			console_player: reader.read_u8()? as usize,
+			player_count: 0,
+			tics: Vec::new(),
+		},
+		_ => bail!("Unsupported demo version {}", first),
+	};
+
+	let mut players = [false; 4];
+
+	for player in players.iter_mut() {
+		*player = reader.read_u8()? != 0;
+	}
+
+	ensure!(
+		(1..=5).contains(&demo.skill),
+		"Invalid skill {} in demo",
+		demo.skill
+	);
+	ensure!(
+		players.get(demo.console_player) == Some(&true),
+		"The recording player {} is not in the game",
+		demo.console_player + 1
+	);
+
+	// Each tic has the commands of all players in the game, in order
+	let console_index = players[..demo.console_player]
+		.iter()
+		.filter(|in_game| **in_game)
+		.count();
+	demo.player_count = players.iter().filter(|in_game| **in_game).count();
+
+	'tics: loop {
+		let mut console_tic = None;
+
+		for index in 0..demo.player_count {
+			let forward = reader
+				.read_u8()
+				.context("Demo ends without an end marker")?;
+
+			if forward == DEMO_END {
+				break 'tics;
+			}
+
+			let tic = DemoTic {
+				forward: forward as i8,
+				side: reader.read_i8()?,
+				turn: reader.read_i8()?,
+				buttons: reader.read_u8()?,
+			};
+
+			if index == console_index {
+				console_tic = Some(tic);
+			}
+		}
+
+		demo.tics.extend(console_tic);
+	}
+
+	Ok(demo)
+}
+
+/// What happened while a demo was played back.
+#[derive(Clone, Debug, Default)]
+pub struct DemoStats {
+	pub map: String,
+	/// Number of game frames that were played.
+	pub tics: usize,
+	pub outcome: DemoOutcome,
+	pub kills: u32,
+	pub total_kills: u32,
+	pub items: u32,
+	pub total_items: u32,
+	pub secrets: u32,
+	pub total_secrets: u32,
+	pub damage_dealt: i64,
+	pub damage_taken: i64,
+	pub shots: u32,
+	/// Distance the player travelled across the map, not counting teleports.
+	pub route_length: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DemoOutcome {
+	/// The demo ran out of commands before anything else happened.
+	Ended,
+	/// The player left the map through an exit.
+	Exited,
+	/// The player died.
+	Died,
+}
+
+impl Default for DemoOutcome {
+	fn default() -> DemoOutcome {
+		DemoOutcome::Ended
+	}
+}
+
+/// Moves of more than this in one frame are teleports, which don't add to the route length.
+const MAX_STEP: f32 = 64.0;
+
+/// Plays the first map of a demo without video or sound, and records what the player does.
+/// The engine doesn't simulate the original game exactly, so longer demos drift away from
+/// what was recorded, and the results are only an approximation.
+pub fn play_demo(demo: &Demo, arg_matches: &ArgMatches) -> anyhow::Result<DemoStats> {
+	let mut loader = WadLoader::new();
+	crate::load_wads(&mut loader, arg_matches)?;
+	let game_profile = GameProfile::detect(&loader);
+	let module = crate::game_module(arg_matches.value_of("game"), game_profile);
+
+	let map = if game_profile.has_episodes() {
+		module.warp_map_name(&[demo.episode as u32, demo.map as u32])?
+	} else {
+		module.warp_map_name(&[demo.map as u32])?
+	};
+
+	// Set up a game like the one the demo was recorded in
+	let mut config_variables = crate::create_config_variables();
+	let mut set = |name: &str, value: String| config_variables.command(&[name.to_owned(), value]);
+	set("skill", demo.skill.to_string())?;
+	set("nomonsters", (demo.nomonsters as u8).to_string())?;
+	set("fast", (demo.fast as u8).to_string())?;
+
+	if demo.deathmatch {
+		set("gamemode", "deathmatch".to_owned())?;
+	}
+
+	let mut resources = Resources::default();
+	resources.insert(config_variables);
+	resources.insert(game_profile);
+	crate::add_game_resources(&*module, &mut resources);
+	resources.insert(crate::create_asset_storage(&*module, loader));
+	let mut update_dispatcher = crate::create_update_dispatcher(&*module, &mut resources);
+
+	// The same demo should always give the same results
+	*<Write<FrameState>>::fetch_mut(&mut resources)
+		.rng
+		.get_mut()
+		.unwrap() = FrameRng::seed_from_u64(0);
+
+	let mut game_state = GameStateMachine::new();
+	game_state.set(GameState::Gameplay);
+	game_state.take_transition();
+	resources.insert(game_state);
+
+	register_reader::<DamageEvent>(&mut resources, "demo_stats");
+
+	let mut world = World::default();
+	super::game::new_game(&mut resources);
+	crate::load_map(&map, &mut world, &mut resources, &*module, false)?;
+
+	let player = <Read<Client>>::fetch(&resources)
+		.entity
+		.context("No player was spawned")?;
+	let mut stats = DemoStats {
+		map,
+		..DemoStats::default()
+	};
+	let mut previous_position = player_position(&world, player);
+
+	for tic in demo.tics.iter() {
+		<Write<Client>>::fetch_mut(&mut resources).forced_command = Some(tic.to_command());
+		update_dispatcher.execute(&mut world, &mut resources);
+		stats.tics += 1;
+
+		// Nothing plays the sounds
+		<Write<Vec<(AssetHandle<Sound>, Entity)>>>::fetch_mut(&mut resources).clear();
+
+		{
+			let (client, mut damage_event_channel) =
+				<(Read<Client>, Write<Events<DamageEvent>>)>::fetch_mut(&mut resources);
+
+			// Only the press of the button fires, like in the player attack system
+			if client.command.attack && !client.previous_command.attack {
+				stats.shots += 1;
+			}
+
+			for event in damage_event_channel.read("demo_stats") {
+				if event.entity == player {
+					stats.damage_taken += event.amount as i64;
+				} else if event.source == Some(player) {
+					stats.damage_dealt += event.amount as i64;
+				}
+			}
+		}
+
+		let position = player_position(&world, player);
+
+		if let (Some(previous), Some(current)) = (previous_position, position) {
+			let step = (current - previous).norm();
+
+			if step <= MAX_STEP {
+				stats.route_length += step;
+			}
+		}
+
+		previous_position = position;
+
+		if is_dead(&world, player) {
+			stats.outcome = DemoOutcome::Died;
+			break;
+		}
+
+		let transition = <Write<GameStateMachine>>::fetch_mut(&mut resources).take_transition();
+
+		if let Some((_, GameState::Intermission)) = transition {
+			stats.outcome = DemoOutcome::Exited;
+			break;
+		}
+	}
+
+	let level_stats = <Read<LevelStats>>::fetch(&resources);
+	stats.kills = level_stats.kills;
+	stats.total_kills = level_stats.total_kills;
+	stats.items = level_stats.items;
+	stats.total_items = level_stats.total_items;
+	stats.secrets = level_stats.secrets;
+	stats.total_secrets = level_stats.total_secrets;
+
+	Ok(stats)
+}
+
+/// Returns the position of the player in the horizontal plane.
+fn player_position(world: &World, player: Entity) -> Option<Vector2<f32>> {
+	let mut query = <&Transform>::query();
+	let position = query.get(world, player).ok()?.position;
+	Some(Vector2::new(position[0], position[1]))
+}
+
+fn print_stats(demo: &Demo, stats: &DemoStats) {
+	let seconds = stats.tics as f32 / FRAME_RATE;
+	let hundredths = (seconds * 100.0).round() as u32;
+
+	println!(
+		"Map: {}, skill {}",
+		stats.map.to_ascii_uppercase(),
+		demo.skill
+	);
+	println!(
+		"Players: {}, recorded by player {}",
+		demo.player_count,
+		demo.console_player + 1
+	);
+	println!(
+		"Duration: {}:{:02}.{:02} ({} of {} tics)",
+		hundredths / 6000,
+		hundredths / 100 % 60,
+		hundredths % 100,
+		stats.tics,
+		demo.tics.len()
+	);
+	println!(
+		"Outcome: {}",
+		match stats.outcome {
+			DemoOutcome::Ended => "demo ended",
+			DemoOutcome::Exited => "exited the map",
+			DemoOutcome::Died => "player died",
+		}
+	);
+	println!("Kills: {} / {}", stats.kills, stats.total_kills);
+	println!("Items: {} / {}", stats.items, stats.total_items);
+	println!("Secrets: {} / {}", stats.secrets, stats.total_secrets);
+	println!("Damage dealt: {}", stats.damage_dealt);
+	println!("Damage taken: {}", stats.damage_taken);
+	println!("Shots fired: {}", stats.shots);
+	println!("Route length: {:.0} units", stats.route_length);
+}
@@ -0,0 +1,822 @@
+use crate::{
+	common::{
+		assets::{AssetHandle, AssetStorage, DataSource, ImportData},
+		geometry::Line2,
+		profiler,
+		quadtree::Quadtree,
+	},
+	doom::{
+		client::Client,
+		components::Transform,
+		decorate::{Token, Tokenizer},
+		health::is_dead,
+		image::Image,
+		input::UserCommand,
+		linespecial::execute_line_special,
+		message::HudMessage,
+		profile::GameProfile,
+		sound::Sound,
+		ui::{HudFont, UiAlignment, UiImage, UiTransform},
+	},
+};
+use anyhow::{bail, ensure, Context};
+use byteorder::{ReadBytesExt, LE};
+use fnv::FnvHashMap;
+use legion::{systems::ResourceSet, Entity, EntityStore, IntoQuery, Read, Resources, World, Write};
+use nalgebra::Vector2;
+use relative_path::RelativePath;
+use std::io::{Cursor, Read as IoRead};
+
+/// How far away a player can be from someone to talk to them.
+const TALK_RANGE: f32 = 64.0;
+
+/// The conversations that can be had on a map.
+#[derive(Clone, Debug, Default)]
+pub struct Dialogs {
+	pub pages: Vec<DialogPage>,
+	/// The page that each conversation starts on, by conversation ID.
+	pub starts: FnvHashMap<u32, usize>,
+}
+
+impl Dialogs {
+	/// Adds the conversations of `other`, replacing any existing ones with the same ID.
+	fn extend(&mut self, other: Dialogs) {
+		let base = self.pages.len();
+
+		self.pages.extend(other.pages.into_iter().map(|mut page| {
+			for choice in &mut page.choices {
+				if let Some(next) = &mut choice.next_page {
+					*next += base;
+				}
+			}
+
+			page
+		}));
+		self.starts.extend(
+			other
+				.starts
+				.into_iter()
+				.map(|(id, start)| (id, start + base)),
+		);
+	}
+}
+
+/// One screen of a conversation: what the speaker says, and what the player can answer.
+#[derive(Clone, Debug, Default)]
+pub struct DialogPage {
+	pub name: String,
+	pub text: String,
+	/// Image shown behind the text.
+	pub panel: Option<String>,
+	/// Sound that is played when the page is shown.
+	pub voice: Option<String>,
+	/// Text of the choice that ends the conversation, which every page has.
+	pub goodbye: Option<String>,
+	pub choices: Vec<DialogChoice>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct DialogChoice {
+	pub text: String,
+	/// Message shown when the choice is picked.
+	pub message: Option<String>,
+	/// Page to go to, or `None` to end the conversation.
+	pub next_page: Option<usize>,
+	/// Whether the speaker starts on `next_page` the next time they're talked to.
+	pub remember: bool,
+	/// Linedef special run when the choice is picked, with its arguments.
+	pub special: Option<(u8, [i32; 5])>,
+}
+
+/// Marks an entity that players can talk to, with the ID of its conversation.
+#[derive(Clone, Copy, Debug)]
+pub struct Talker {
+	pub conversation: u32,
+	/// Page to start on instead of the first, if an earlier conversation changed it.
+	pub start_page: Option<usize>,
+}
+
+/// The conversation that is going on, if any.
+#[derive(Clone, Debug, Default)]
+pub struct DialogState {
+	pub dialogs: Option<AssetHandle<Dialogs>>,
+	pub open: Option<OpenDialog>,
+	pub changed: bool,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct OpenDialog {
+	pub speaker: Entity,
+	pub page: usize,
+	/// Index of the highlighted choice. One past the last choice is the goodbye.
+	pub selected: usize,
+}
+
+impl DialogState {
+	pub fn start_map(&mut self, dialogs: AssetHandle<Dialogs>) {
+		self.clear();
+		self.dialogs = Some(dialogs);
+	}
+
+	pub fn clear(&mut self) {
+		self.dialogs = None;
+		self.close();
+	}
+
+	pub fn is_open(&self) -> bool {
+		self.open.is_some()
+	}
+
+	fn close(&mut self) {
+		self.open = None;
+		self.changed = true;
+	}
+}
+
+/// Loads the conversations for the map that `path` is named after.
+/// The global SCRIPT00 and DIALOGUE lumps are read first, then the ones for the map,
+/// SCRIPTxx in Strife's binary format or DIALOGxx in the USDF text format.
+pub fn import_dialogs(
+	path: &RelativePath,
+	asset_storage: &mut AssetStorage,
+) -> anyhow::Result<Box<dyn ImportData>> {
+	let source = asset_storage.source();
+	let mut lumps = vec![String::from("script00"), String::from("dialogue")];
+
+	// Only maps named MAPxx have their own conversations
+	if let Some(number) = path
+		.file_stem()
+		.and_then(|stem| stem.strip_prefix("map"))
+		.and_then(|number| number.parse::<u32>().ok())
+	{
+		lumps.push(format!("script{:02}", number));
+		lumps.push(format!("dialog{:02}", number));
+	}
+
+	let mut dialogs = Dialogs::default();
+
+	for lump in lumps {
+		let lump_path = RelativePath::new(&lump);
+
+		if !source.exists(lump_path) {
+			continue;
+		}
+
+		dialogs.extend(
+			load_dialog_lump(source, lump_path)
+				.with_context(|| format!("Couldn't parse {}", lump.to_ascii_uppercase()))?,
+		);
+	}
+
+	Ok(Box::new(dialogs))
+}
+
+fn load_dialog_lump(source: &dyn DataSource, path: &RelativePath) -> anyhow::Result<Dialogs> {
+	let data = source.load(path)?;
+
+	if path.as_str().starts_with("script") {
+		parse_strife_script(&data)
+	} else {
+		parse_usdf(&String::from_utf8_lossy(&data))
+	}
+}
+
+/// Size of a page in a SCRIPTxx lump.
+const STRIFE_PAGE_SIZE: usize = 1516;
+
+/// Parses a SCRIPTxx lump in Strife's binary format.
+/// Pages for the same speaker belong to one conversation, which starts at the first of them.
+pub fn parse_strife_script(data: &[u8]) -> anyhow::Result<Dialogs> {
+	ensure!(
+		data.len() % STRIFE_PAGE_SIZE == 0,
+		"Lump size {} is not a multiple of {}",
+		data.len(),
+		STRIFE_PAGE_SIZE
+	);
+
+	let mut reader = Cursor::new(data);
+	let mut dialogs = Dialogs::default();
+
+	for index in 0..data.len() / STRIFE_PAGE_SIZE {
+		let speaker = reader.read_i32::<LE>()?;
+
+		// Dropped item, item checks and link. Checking items needs an inventory,
+		// so pages are shown as if the player has nothing.
+		for _ in 0..5 {
+			reader.read_i32::<LE>()?;
+		}
+
+		let name = read_fixed_string(&mut reader, 16)?;
+		let voice = read_fixed_string(&mut reader, 8)?;
+		let panel = read_fixed_string(&mut reader, 8)?;
+		let text = read_fixed_string(&mut reader, 320)?;
+		let mut choices = Vec::new();
+
+		for _ in 0..5 {
+			// Item to give, and items that the choice costs, which are all ignored
+			for _ in 0..7 {
+				reader.read_i32::<LE>()?;
+			}
+
+			let choice_text = read_fixed_string(&mut reader, 32)?;
+			let message = read_fixed_string(&mut reader, 80)?;
+			let next = reader.read_i32::<LE>()?;
+			let _log = reader.read_i32::<LE>()?;
+			let _message_no = read_fixed_string(&mut reader, 80)?;
+
+			if choice_text.is_empty() {
+				continue;
+			}
+
+			choices.push(DialogChoice {
+				text: choice_text,
+				message: non_empty(message),
+				// Page numbers count from 1, negative ones are remembered by the speaker
+				next_page: match next {
+					0 => None,
+					_ => Some(next.abs() as usize - 1),
+				},
+				remember: next < 0,
+				special: None,
+			});
+		}
+
+		dialogs.starts.entry(speaker as u32).or_insert(index);
+		dialogs.pages.push(DialogPage {
+			name,
+			text,
+			panel: non_empty(panel),
+			voice: non_empty(voice),
+			goodbye: None,
+			choices,
+		});
+	}
+
+	check_next_pages(&dialogs)?;
+	Ok(dialogs)
+}
+
+/// Checks that every page that a choice leads to exists.
+fn check_next_pages(dialogs: &Dialogs) -> anyhow::Result<()> {
+	for page in &dialogs.pages {
+		for choice in &page.choices {
+			if let Some(next) = choice.next_page {
+				ensure!(
+					next < dialogs.pages.len(),
+					"Page {} doesn't exist",
+					next + 1
+				);
+			}
+		}
+	}
+
+	Ok(())
+}
+
+fn read_fixed_string(reader: &mut Cursor<&[u8]>, len: usize) -> anyhow::Result<String> {
+	let mut buf = vec![0; len];
+	reader.read_exact(&mut buf)?;
+	let end = buf.iter().position(|&b| b == 0).unwrap_or(len);
+	Ok(String::from_utf8_lossy(&buf[..end]).trim().to_owned())
+}
+
+fn non_empty(string: String) -> Option<String> {
+	if string.is_empty() {
+		None
+	} else {
+		Some(string)
+	}
+}
+
+/// Parses conversations in the USDF text format.
+/// Pages are numbered from 1 within each conversation.
+pub fn parse_usdf(text: &str) -> anyhow::Result<Dialogs> {
+	let mut tokens = Tokenizer::new(text)
+		.filter(|token| match token {
+			Ok(Token::Newline) => false,
+			_ => true,
+		})
+		.collect::<anyhow::Result<Vec<Token>>>()?;
+	tokens.reverse();
+	let root = parse_block(&mut tokens, false)?;
+	let mut dialogs = Dialogs::default();
+
+	for (name, block) in &root.blocks {
+		if !name.eq_ignore_ascii_case("conversation") {
+			continue;
+		}
+
+		let actor = match block.get("actor") {
+			Some(Token::Number(number)) => *number as u32,
+			token => bail!("Expected an actor number, found {:?}", token),
+		};
+		let base = dialogs.pages.len();
+		dialogs.starts.insert(actor, base);
+
+		for (name, page_block) in &block.blocks {
+			if !name.eq_ignore_ascii_case("page") {
+				continue;
+			}
+
+			let mut page = DialogPage {
+				name: page_block.string("name").unwrap_or_default(),
+				text: page_block.string("dialog").unwrap_or_default(),
+				panel: page_block.string("panel"),
+				voice: page_block.string("voice"),
+				goodbye: page_block.string("goodbye"),
+				choices: Vec::new(),
+			};
+
+			for (name, choice_block) in &page_block.blocks {
+				if !name.eq_ignore_ascii_case("choice") {
+					continue;
+				}
+
+				let next_page = match choice_block.get("nextpage") {
+					Some(Token::Number(number)) if *number >= 1.0 => {
+						Some(base + *number as usize - 1)
+					}
+					_ => None,
+				};
+				let special = match choice_block.get("special") {
+					Some(Token::Number(special)) => {
+						let mut args = [0; 5];
+
+						for (i, arg) in args.iter_mut().enumerate() {
+							if let Some(Token::Number(number)) =
+								choice_block.get(&format!("arg{}", i))
+							{
+								*arg = *number as i32;
+							}
+						}
+
+						Some((*special as u8, args))
+					}
+					_ => None,
+				};
+
+				page.choices.push(DialogChoice {
+					text: choice_block.string("text").unwrap_or_default(),
+					message: choice_block.string("yesmessage"),
+					next_page: if choice_block.flag("closedialog") {
+						None
+					} else {
+						next_page
+					},
+					remember: false,
+					special,
+				});
+			}
+
+			dialogs.pages.push(page);
+		}
+	}
+
+	check_next_pages(&dialogs)?;
+	Ok(dialogs)
+}
+
+/// A block of USDF, with its properties and the blocks inside it.
+#[derive(Debug, Default)]
+struct Block {
+	properties: Vec<(String, Token)>,
+	blocks: Vec<(String, Block)>,
+}
+
+impl Block {
+	fn get(&self, key: &str) -> Option<&Token> {
+		self.properties
+			.iter()
+			.find(|(name, _)| name.eq_ignore_ascii_case(key))
+			.map(|(_, value)| value)
+	}
+
+	fn string(&self, key: &str) -> Option<String> {
+		match self.get(key) {
+			Some(Token::String(string)) | Some(Token::Word(string)) => Some(string.clone()),
+			_ => None,
+		}
+	}
+
+	fn flag(&self, key: &str) -> bool {
+		match self.get(key) {
+			Some(Token::Word(word)) => word.eq_ignore_ascii_case("true"),
+			_ => false,
+		}
+	}
+}
+
+/// Parses `key = value;` properties and `name { ... }` blocks, up to the closing brace
+/// if `nested` is set, otherwise to the end.
+fn parse_block(tokens: &mut Vec<Token>, nested: bool) -> anyhow::Result<Block> {
+	let mut block = Block::default();
+
+	loop {
+		let name = match tokens.pop() {
+			Some(Token::Word(name)) => name,
+			Some(Token::Symbol('}')) if nested => break,
+			None if !nested => break,
+			token => bail!("Expected a property or block name, found {:?}", token),
+		};
+
+		match tokens.pop() {
+			Some(Token::Symbol('=')) => {
+				let value = match tokens.pop() {
+					Some(Token::Symbol('-')) => match tokens.pop() {
+						Some(Token::Number(number)) => Token::Number(-number),
+						token => bail!("Expected a number, found {:?}", token),
+					},
+					Some(token) => token,
+					None => bail!("Expected a value for \"{}\"", name),
+				};
+
+				match tokens.pop() {
+					Some(Token::Symbol(';')) => {}
+					token => bail!("Expected ';' after \"{}\", found {:?}", name, token),
+				}
+
+				block.properties.push((name, value));
+			}
+			Some(Token::Symbol('{')) => {
+				let inner = parse_block(tokens, true)?;
+				block.blocks.push((name, inner));
+			}
+			token => bail!("Expected '=' or '{{' after \"{}\", found {:?}", name, token),
+		}
+	}
+
+	Ok(block)
+}
+
+/// Starts conversations when a player uses someone who can talk, and picks choices.
+/// While a conversation is going on, the player's movement inputs are used to pick a choice
+/// instead: forward and back to move, use to pick, attack to leave.
+pub fn dialog_system() -> Box<dyn FnMut(&mut World, &mut Resources)> {
+	let mut previous = UserCommand::default();
+	let mut hold_use = false;
+
+	Box::new(move |world, resources| {
+		let _profile = profiler::scope("dialog_system");
+
+		let (command, player) = {
+			let client = <Read<Client>>::fetch(resources);
+
+			match client.entity {
+				Some(entity) if !client.is_spectating() => (client.command, entity),
+				_ => return,
+			}
+		};
+
+		let pressed = |now: bool, before: bool| now && !before;
+		let use_pressed = pressed(command.r#use, previous.r#use);
+		let attack_pressed = pressed(command.attack, previous.attack);
+		let up_pressed = pressed(command.forward > 0.0, previous.forward > 0.0);
+		let down_pressed = pressed(command.forward < 0.0, previous.forward < 0.0);
+		previous = command;
+
+		let mut picked = None;
+
+		{
+			let (asset_storage, mut dialog_state, quadtree) =
+				<(Read<AssetStorage>, Write<DialogState>, Read<Quadtree>)>::fetch_mut(resources);
+			let dialogs = match dialog_state
+				.dialogs
+				.as_ref()
+				.and_then(|handle| asset_storage.get(handle))
+			{
+				Some(dialogs) => dialogs,
+				None => return,
+			};
+
+			let open = dialog_state.open;
+
+			match open {
+				Some(mut open) => {
+					// Conversations end if either side can't talk any more
+					if is_dead(world, player)
+						|| is_dead(world, open.speaker)
+						|| world.entry_ref(open.speaker).is_err()
+					{
+						dialog_state.close();
+						return;
+					}
+
+					let page = &dialogs.pages[open.page];
+
+					if attack_pressed {
+						dialog_state.close();
+					} else if use_pressed {
+						hold_use = true;
+						picked = Some((open, page.choices.get(open.selected).cloned()));
+					} else if up_pressed {
+						open.selected = open.selected.checked_sub(1).unwrap_or(page.choices.len());
+					} else if down_pressed {
+						open.selected = (open.selected + 1) % (page.choices.len() + 1);
+					}
+
+					if up_pressed || down_pressed {
+						dialog_state.open = Some(open);
+						dialog_state.changed = true;
+					}
+				}
+				None if use_pressed && !is_dead(world, player) => {
+					if let Some((speaker, page)) = find_talker(world, &quadtree, player, dialogs) {
+						hold_use = true;
+						dialog_state.open = Some(OpenDialog {
+							speaker,
+							page,
+							selected: 0,
+						});
+						dialog_state.changed = true;
+						drop(asset_storage);
+						drop(dialog_state);
+						drop(quadtree);
+						play_voice(world, resources);
+					}
+				}
+				None => {}
+			}
+		}
+
+		if let Some((open, choice)) = picked {
+			pick_choice(open, choice, world, resources);
+		}
+
+		// Don't let the player move or fire while talking, or use what's behind the speaker
+		// with the same button press
+		let is_open = <Read<DialogState>>::fetch(resources).is_open();
+		let mut client = <Write<Client>>::fetch_mut(resources);
+		hold_use = hold_use && command.r#use;
+
+		if is_open {
+			client.command = UserCommand::default();
+		} else if hold_use {
+			client.command.r#use = false;
+		}
+	})
+}
+
+/// Returns the closest entity in front of `player` that can talk, with the page to start on.
+fn find_talker(
+	world: &World,
+	quadtree: &Quadtree,
+	player: Entity,
+	dialogs: &Dialogs,
+) -> Option<(Entity, usize)> {
+	let transform = *<&Transform>::query().get(world, player).ok()?;
+	let yaw = transform.rotation[2].to_radians() as f32;
+	let direction = Vector2::new(yaw.cos(), yaw.sin());
+	let position = Vector2::new(transform.position[0], transform.position[1]);
+	let talk_line = Line2::new(position, direction * TALK_RANGE);
+
+	for (_, entity) in quadtree.query_line(&talk_line) {
+		if entity == player || is_dead(world, entity) {
+			continue;
+		}
+
+		let talker = match <&Talker>::query().get(world, entity) {
+			Ok(talker) => talker,
+			Err(_) => continue,
+		};
+
+		match talker.start_page {
+			Some(page) => return Some((entity, page)),
+			None => match dialogs.starts.get(&talker.conversation) {
+				Some(&start) => return Some((entity, start)),
+				None => log::warn!("Conversation {} doesn't exist", talker.conversation),
+			},
+		}
+	}
+
+	None
+}
+
+/// Does what a choice says, and goes on to the next page. `None` is the goodbye choice.
+fn pick_choice(
+	mut open: OpenDialog,
+	choice: Option<DialogChoice>,
+	world: &mut World,
+	resources: &mut Resources,
+) {
+	let choice = match choice {
+		Some(choice) => choice,
+		None => {
+			<Write<DialogState>>::fetch_mut(resources).close();
+			return;
+		}
+	};
+
+	if let Some(message) = &choice.message {
+		<Write<HudMessage>>::fetch_mut(resources).show(message);
+	}
+
+	if let Some((special, args)) = choice.special {
+		execute_line_special(special, args, None, world, resources);
+	}
+
+	match choice.next_page {
+		Some(page) => {
+			if choice.remember {
+				if let Some(mut entry) = world.entry(open.speaker) {
+					if let Ok(talker) = entry.get_component_mut::<Talker>() {
+						talker.start_page = Some(page);
+					}
+				}
+			}
+
+			open.page = page;
+			open.selected = 0;
+
+			{
+				let mut dialog_state = <Write<DialogState>>::fetch_mut(resources);
+				dialog_state.open = Some(open);
+				dialog_state.changed = true;
+			}
+
+			play_voice(world, resources);
+		}
+		None => <Write<DialogState>>::fetch_mut(resources).close(),
+	}
+}
+
+/// Plays the voice of the current page, if it has one.
+fn play_voice(_world: &World, resources: &mut Resources) {
+	let (mut asset_storage, dialog_state, mut sound_queue) = <(
+		Write<AssetStorage>,
+		Read<DialogState>,
+		Write<Vec<(AssetHandle<Sound>, Entity)>>,
+	)>::fetch_mut(resources);
+
+	let (open, handle) = match (&dialog_state.open, &dialog_state.dialogs) {
+		(Some(open), Some(handle)) => (open, handle),
+		_ => return,
+	};
+
+	let voice = match asset_storage
+		.get(handle)
+		.and_then(|dialogs| dialogs.pages[open.page].voice.clone())
+	{
+		Some(voice) => voice,
+		None => return,
+	};
+
+	let sound = asset_storage.load(&format!("{}.sound", voice.to_ascii_lowercase()));
+	sound_queue.push((sound, open.speaker));
+}
+
+const LINE_HEIGHT: f32 = 9.0;
+const TEXT_WIDTH: f32 = 280.0;
+
+/// Shows the current page of the conversation.
+pub fn dialog_ui_system(resources: &mut Resources) -> Box<dyn FnMut(&mut World, &mut Resources)> {
+	let font = {
+		let (mut asset_storage, game_profile) =
+			<(Write<AssetStorage>, Read<GameProfile>)>::fetch_mut(resources);
+		HudFont::load(&mut asset_storage, *game_profile)
+	};
+	let mut entities: Vec<Entity> = Vec::new();
+
+	Box::new(move |world, resources| {
+		let _profile = profiler::scope("dialog_ui_system");
+
+		let (mut asset_storage, mut dialog_state) =
+			<(Write<AssetStorage>, Write<DialogState>)>::fetch_mut(resources);
+
+		// The UI entities are gone if the world was cleared
+		if entities.first().map_or(false, |e| !world.contains(*e)) {
+			entities.clear();
+			dialog_state.changed = true;
+		}
+
+		if !dialog_state.changed || !font.is_ready(&asset_storage) {
+			return;
+		}
+
+		let (open, page) = match (&dialog_state.open, &dialog_state.dialogs) {
+			(Some(open), Some(handle)) => match asset_storage.get(handle) {
+				Some(dialogs) => (*open, dialogs.pages[open.page].clone()),
+				None => return,
+			},
+			_ => {
+				dialog_state.changed = false;
+
+				for entity in entities.drain(..) {
+					world.remove(entity);
+				}
+
+				return;
+			}
+		};
+
+		let panel: Option<AssetHandle<Image>> = match &page.panel {
+			Some(panel)
+				if asset_storage
+					.source()
+					.exists(RelativePath::new(&panel.to_ascii_lowercase())) =>
+			{
+				Some(asset_storage.load(&format!("{}.patch", panel.to_ascii_lowercase())))
+			}
+			_ => None,
+		};
+
+		// The panel isn't available until the renderer has processed it
+		if let Some(panel) = &panel {
+			if asset_storage.get(panel).is_none() {
+				return;
+			}
+		}
+
+		dialog_state.changed = false;
+
+		for entity in entities.drain(..) {
+			world.remove(entity);
+		}
+
+		if let Some(panel) = panel {
+			entities.push(world.push((
+				UiTransform {
+					position: Vector2::new(0.0, 0.0),
+					depth: 10.0,
+					alignment: [UiAlignment::Middle, UiAlignment::Middle],
+					size: Vector2::new(320.0, 200.0),
+					stretch: [false; 2],
+				},
+				UiImage { image: panel },
+			)));
+		}
+
+		let mut lines = Vec::new();
+
+		if !page.name.is_empty() {
+			lines.push((20.0, page.name.to_ascii_uppercase()));
+			lines.push((20.0, String::new()));
+		}
+
+		for line in wrap_text(&page.text.to_ascii_uppercase(), &font, &asset_storage) {
+			lines.push((20.0, line));
+		}
+
+		lines.push((20.0, String::new()));
+
+		let goodbye = page
+			.goodbye
+			.clone()
+			.unwrap_or_else(|| String::from("Goodbye"));
+
+		for (i, text) in page
+			.choices
+			.iter()
+			.map(|choice| &choice.text)
+			.chain(std::iter::once(&goodbye))
+			.enumerate()
+		{
+			let marker = if i == open.selected { '>' } else { ' ' };
+			lines.push((
+				12.0,
+				format!("{} {}. {}", marker, i + 1, text.to_ascii_uppercase()),
+			));
+		}
+
+		for (i, (x, line)) in lines.iter().enumerate() {
+			entities.extend(font.spawn_text(
+				world,
+				&asset_storage,
+				line,
+				UiTransform {
+					position: Vector2::new(*x, 16.0 + i as f32 * LINE_HEIGHT),
+					depth: 11.0,
+					alignment: [UiAlignment::Middle, UiAlignment::Middle],
+					size: Vector2::new(0.0, 0.0),
+					stretch: [false; 2],
+				},
+			));
+		}
+	})
+}
+
+/// Splits `text` into lines that fit in the dialog panel, breaking between words.
+fn wrap_text(text: &str, font: &HudFont, asset_storage: &AssetStorage) -> Vec<String> {
+	let mut lines = Vec::new();
+
+	for paragraph in text.lines() {
+		let mut line = String::new();
+
+		for word in paragraph.split_whitespace() {
+			let candidate = if line.is_empty() {
+				word.to_owned()
+			} else {
+				format!("{} {}", line, word)
+			};
+
+			if !line.is_empty() && font.width(&candidate, asset_storage) > TEXT_WIDTH {
+				lines.push(std::mem::replace(&mut line, word.to_owned()));
+			} else {
+				line = candidate;
+			}
+		}
+
+		lines.push(line);
+	}
+
+	lines
+}
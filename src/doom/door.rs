@@ -2,8 +2,10 @@ use crate::{
 	common::{
 		assets::{AssetHandle, AssetStorage},
 		audio::Sound,
+		events::{register_reader, Events},
 		frame::FrameState,
 		geometry::Side,
+		profiler,
 		time::Timer,
 	},
 	doom::{
@@ -19,7 +21,6 @@ use legion::{
 	systems::{CommandBuffer, Runnable},
 	Entity, EntityStore, IntoQuery, Resources, SystemBuilder,
 };
-use shrev::EventChannel;
 use std::time::Duration;
 
 #[derive(Clone, Debug)]
@@ -58,17 +59,16 @@ pub struct DoorParams {
 }
 
 pub fn door_active_system(resources: &mut Resources) -> impl Runnable {
-	let mut sector_move_event_reader = resources
-		.get_mut::<EventChannel<SectorMoveEvent>>()
-		.unwrap()
-		.register_reader();
+	register_reader::<SectorMoveEvent>(resources, "door_active_system");
 
 	SystemBuilder::new("door_active_system")
 		.read_resource::<FrameState>()
-		.read_resource::<EventChannel<SectorMoveEvent>>()
+		.write_resource::<Events<SectorMoveEvent>>()
 		.write_resource::<Vec<(AssetHandle<Sound>, Entity)>>()
 		.with_query(<(Entity, &mut CeilingMove, &mut DoorActive)>::query())
 		.build(move |command_buffer, world, resources, query| {
+			let _profile = profiler::scope("door_active_system");
+
 			let (frame_state, sector_move_event_channel, sound_queue) = resources;
 
 			for (entity, ceiling_move, mut door_active) in query.iter_mut(world) {
@@ -98,7 +98,7 @@ pub fn door_active_system(resources: &mut Resources) -> impl Runnable {
 			}
 
 			for event in sector_move_event_channel
-				.read(&mut sector_move_event_reader)
+				.read("door_active_system")
 				.filter(|e| e.normal == -1.0)
 			{
 				let (ceiling_move, door_active) = match query.get_mut(world, event.entity) {
@@ -160,23 +160,22 @@ pub struct DoorUse {
 }
 
 pub fn door_use_system(resources: &mut Resources) -> impl Runnable {
-	let mut use_event_reader = resources
-		.get_mut::<EventChannel<UseEvent>>()
-		.unwrap()
-		.register_reader();
+	register_reader::<UseEvent>(resources, "door_use_system");
 
 	SystemBuilder::new("door_use_system")
 		.read_resource::<AssetStorage>()
-		.read_resource::<EventChannel<UseEvent>>()
+		.write_resource::<Events<UseEvent>>()
 		.read_resource::<FrameState>()
 		.with_query(<(&LinedefRef, &UseAction)>::query())
 		.with_query(<&MapDynamic>::query())
 		.with_query(<(&mut CeilingMove, &mut DoorActive)>::query())
 		.build(move |command_buffer, world, resources, queries| {
+			let _profile = profiler::scope("door_use_system");
+
 			let (asset_storage, use_event_channel, frame_state) = resources;
 			let (mut world2, world) = world.split_for_query(&queries.2);
 
-			for use_event in use_event_channel.read(&mut use_event_reader) {
+			for use_event in use_event_channel.read("door_use_system") {
 				let (linedef_ref, door_use) = match queries.0.get(&world, use_event.linedef_entity)
 				{
 					Ok((linedef_ref, UseAction::DoorUse(door_use))) => (linedef_ref, door_use),
@@ -243,24 +242,23 @@ pub struct DoorSwitchUse {
 }
 
 pub fn door_switch_system(resources: &mut Resources) -> impl Runnable {
-	let mut use_event_reader = resources
-		.get_mut::<EventChannel<UseEvent>>()
-		.unwrap()
-		.register_reader();
+	register_reader::<UseEvent>(resources, "door_switch_system");
 
 	SystemBuilder::new("door_switch_system")
 		.read_resource::<AssetStorage>()
-		.read_resource::<EventChannel<UseEvent>>()
+		.write_resource::<Events<UseEvent>>()
 		.read_resource::<FrameState>()
 		.write_resource::<Vec<(AssetHandle<Sound>, Entity)>>()
 		.with_query(<(&LinedefRef, &UseAction)>::query().filter(!component::<SwitchActive>()))
 		.with_query(<&mut MapDynamic>::query())
 		.read_component::<DoorActive>() // used by activate_with_tag
 		.build(move |command_buffer, world, resources, queries| {
+			let _profile = profiler::scope("door_switch_system");
+
 			let (asset_storage, use_event_channel, frame_state, sound_queue) = resources;
 			let (mut world1, world) = world.split_for_query(&queries.1);
 
-			for use_event in use_event_channel.read(&mut use_event_reader) {
+			for use_event in use_event_channel.read("door_switch_system") {
 				let (linedef_ref, door_switch_use) =
 					match queries.0.get(&world, use_event.linedef_entity) {
 						Ok((linedef_ref, UseAction::DoorSwitchUse(door_switch_use))) => {
@@ -312,25 +310,24 @@ pub struct DoorTouch {
 }
 
 pub fn door_touch_system(resources: &mut Resources) -> impl Runnable {
-	let mut touch_event_reader = resources
-		.get_mut::<EventChannel<TouchEvent>>()
-		.unwrap()
-		.register_reader();
+	register_reader::<TouchEvent>(resources, "door_touch_system");
 
 	SystemBuilder::new("door_touch_system")
 		.read_resource::<AssetStorage>()
-		.read_resource::<EventChannel<TouchEvent>>()
+		.write_resource::<Events<TouchEvent>>()
 		.read_resource::<FrameState>()
 		.with_query(<(&LinedefRef, &TouchAction)>::query())
 		.with_query(<&mut MapDynamic>::query())
 		.read_component::<DoorActive>() // used by activate_with_tag
 		.build(move |command_buffer, world, resources, queries| {
+			let _profile = profiler::scope("door_touch_system");
+
 			let (asset_storage, touch_event_channel, frame_state) = resources;
 
 			let (mut world0, mut world) = world.split_for_query(&queries.0);
 			let (mut world1, world) = world.split_for_query(&queries.1);
 
-			for touch_event in touch_event_channel.read(&mut touch_event_reader) {
+			for touch_event in touch_event_channel.read("door_touch_system") {
 				if touch_event.collision.is_some() {
 					continue;
 				}
@@ -367,7 +364,8 @@ pub fn door_touch_system(resources: &mut Resources) -> impl Runnable {
 		})
 }
 
-fn activate(
+/// Starts a door moving in sector `sector_index`.
+pub fn activate(
 	params: &DoorParams,
 	command_buffer: &mut CommandBuffer,
 	frame_state: &FrameState,
@@ -413,7 +411,9 @@ fn activate(
 	);
 }
 
-fn activate_with_tag<W: EntityStore>(
+/// Starts a door moving in every sector with the tag `sector_tag` that isn't busy already.
+/// Returns whether any were started.
+pub fn activate_with_tag<W: EntityStore>(
 	params: &DoorParams,
 	command_buffer: &mut CommandBuffer,
 	frame_state: &FrameState,
@@ -425,12 +425,7 @@ fn activate_with_tag<W: EntityStore>(
 	let mut activated = false;
 
 	// Activate all the doors with the same tag
-	for (sector_index, _) in map
-		.sectors
-		.iter()
-		.enumerate()
-		.filter(|(_, s)| s.sector_tag == sector_tag)
-	{
+	for &sector_index in map.sectors_with_tag(sector_tag) {
 		let sector_entity = map_dynamic.sectors[sector_index].entity;
 
 		if world
@@ -1,31 +1,81 @@
 use crate::{
 	common::{
-		assets::AssetHandle,
+		assets::{AssetHandle, AssetName, AssetStorage},
 		spawn::{ComponentAccessor, SpawnFrom},
 	},
 	doom::{
+		camera::Camera,
+		client::User,
+		components::{
+			DeathmatchSpawnPoint, NoGravity, SpawnHeight, SpawnPoint, TransformDef, VelocityDef,
+		},
+		data::FRAME_TIME,
+		decorate::load_decorate,
+		dialog::Talker,
+		health::Health,
+		item::{Pickup, PowerPickup},
+		light::DynamicLight,
 		map::spawn::SpawnContext,
-		state::{StateInfo, StateName},
+		palette::PaletteEffects,
+		particle::ParticleEmitter,
+		physics::{BoxCollider, SolidMask},
+		psprite::PlayerSpriteRender,
+		sound::{load_sound_group, AmbientSound, ReverbPreset, SectorReverb, Sound},
+		sprite::{sprite_has_frame, Sprite, SpriteRender},
+		state::{StateAction, StateDef, StateInfo, StateName},
+		stats::{CountItem, CountKill},
 	},
 };
-use legion::{systems::ResourceSet, Read, Resources, World};
+use anyhow::{bail, ensure, Context};
+use fnv::FnvHashMap;
+use legion::{systems::ResourceSet, IntoQuery, Read, Resources, World, Write};
+use nalgebra::{Vector2, Vector3};
+use relative_path::RelativePath;
+use serde::Deserialize;
 use std::collections::HashMap;
 
 #[derive(Default)]
 pub struct EntityTemplate {
 	pub name: Option<&'static str>,
 	pub type_id: Option<EntityTypeId>,
+	/// Things of this type are not spawned in deathmatch games.
+	pub not_in_deathmatch: bool,
+	/// What appears where the entity is hit by a hitscan attack.
+	pub impact_effect: ImpactEffect,
+	/// Template of the item that is dropped when the entity dies.
+	pub drop_item: Option<AssetName>,
 	pub states: HashMap<StateName, Vec<StateInfo>>,
+	/// Sounds the entity makes, like "see", "pain" or "death".
+	/// When there are several, one of them is picked at random each time.
+	pub sounds: HashMap<String, Vec<AssetHandle<Sound>>>,
+	/// If not empty, this template is a random spawner. When a map is spawned, one of these
+	/// templates is spawned in its place instead, picked at random by weight.
+	pub random_spawn: Vec<(AssetName, u32)>,
 	pub world: World,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
 pub enum EntityTypeId {
 	Linedef(u16),
 	Sector(u16),
 	Thing(u16),
 }
 
+/// The effect that is spawned when an entity is hit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum ImpactEffect {
+	Blood,
+	/// Sparks, like when a wall is hit. For things that don't bleed.
+	Puff,
+	Nothing,
+}
+
+impl Default for ImpactEffect {
+	fn default() -> ImpactEffect {
+		ImpactEffect::Blood
+	}
+}
+
 #[derive(Clone, Debug)]
 pub struct EntityTemplateRef(pub AssetHandle<EntityTemplate>);
 
@@ -42,3 +92,792 @@ impl SpawnFrom<EntityTemplateRefDef> for EntityTemplateRef {
 		EntityTemplateRef(spawn_context.template_handle.clone())
 	}
 }
+
+/// Maps type ids to the templates that have them, so that map things and specials
+/// can be looked up without going through every template.
+#[derive(Clone, Debug, Default)]
+pub struct TemplateIndex {
+	by_type_id: FnvHashMap<EntityTypeId, AssetHandle<EntityTemplate>>,
+}
+
+impl TemplateIndex {
+	pub fn new(asset_storage: &AssetStorage) -> TemplateIndex {
+		let mut by_type_id = FnvHashMap::default();
+
+		for (handle, template) in asset_storage.iter::<EntityTemplate>() {
+			if let Some(type_id) = template.type_id {
+				// Duplicates are reported by validate_templates, keep the first one
+				by_type_id.entry(type_id).or_insert_with(|| handle.clone());
+			}
+		}
+
+		TemplateIndex { by_type_id }
+	}
+
+	#[inline]
+	pub fn get(&self, type_id: EntityTypeId) -> Option<&AssetHandle<EntityTemplate>> {
+		self.by_type_id.get(&type_id)
+	}
+}
+
+/// Builds the template index from the currently loaded templates.
+/// Must be called again whenever templates are added or replaced.
+pub fn index_templates(resources: &mut Resources) {
+	let index = TemplateIndex::new(&<Read<AssetStorage>>::fetch(resources));
+	resources.insert(index);
+}
+
+/// Imports the sprites of a template that haven't been imported yet.
+/// Templates load their sprites deferred, so that a map only imports the sprites
+/// of the things that are actually spawned in it.
+pub fn load_template_sprites(
+	template_handle: &AssetHandle<EntityTemplate>,
+	asset_storage: &mut AssetStorage,
+) {
+	let sprites: Vec<AssetHandle<Sprite>> = {
+		let template = asset_storage.get(template_handle).unwrap();
+		let mut sprites = Vec::new();
+		let mut add = |sprite_render: &SpriteRender| {
+			if asset_storage.is_deferred(&sprite_render.sprite) {
+				sprites.push(sprite_render.sprite.clone());
+			}
+		};
+
+		template
+			.states
+			.values()
+			.flatten()
+			.for_each(|info| add(&info.sprite));
+		<&SpriteRender>::query()
+			.iter(&template.world)
+			.for_each(|sprite_render| add(sprite_render));
+		<&PlayerSpriteRender>::query()
+			.iter(&template.world)
+			.flat_map(|player_sprite_render| player_sprite_render.slots.iter().flatten())
+			.for_each(|sprite_render| add(sprite_render));
+
+		sprites
+	};
+
+	for sprite in &sprites {
+		asset_storage.import_deferred(sprite);
+	}
+}
+
+/// Name of the lump that replaces the game's built-in entity templates,
+/// in the same form as `TEMPLATES_LUMP`.
+pub const DEFAULT_TEMPLATES_LUMP: &str = "mobjs.json";
+
+/// Name of the lump that holds extra entity templates, as a JSON list of `EntityTemplateDef`.
+pub const TEMPLATES_LUMP: &str = "template.json";
+
+/// An entity template as it is written in a data file.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EntityTemplateDef {
+	/// Name of the template. Templates that are only spawned through their type id
+	/// don't need one.
+	#[serde(default)]
+	pub name: Option<String>,
+	#[serde(default)]
+	pub type_id: Option<EntityTypeId>,
+	#[serde(default)]
+	pub not_in_deathmatch: bool,
+	#[serde(default)]
+	pub impact_effect: ImpactEffect,
+	#[serde(default)]
+	pub drop_item: Option<String>,
+	#[serde(default)]
+	pub states: HashMap<String, Vec<StateInfoDef>>,
+	#[serde(default)]
+	pub sounds: HashMap<String, SoundDef>,
+	/// Names and weights of the templates to pick from, for random spawners.
+	#[serde(default)]
+	pub random_spawn: Vec<(String, u32)>,
+	#[serde(default)]
+	pub components: ComponentsDef,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StateInfoDef {
+	/// Name of the sprite, without extension.
+	pub sprite: String,
+	pub frame: usize,
+	#[serde(default)]
+	pub full_bright: bool,
+	/// How long the state lasts, in frames. If absent, the state lasts forever.
+	#[serde(default)]
+	pub time: Option<u32>,
+	/// State to go to next. If absent, goes to the next state in the list.
+	#[serde(default)]
+	pub next: Option<(String, usize)>,
+	#[serde(default)]
+	pub action: Option<StateActionDef>,
+}
+
+/// Names of sound lumps, without extension.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum SoundDef {
+	/// A single sound, or a random group defined in SNDINFO.
+	Single(String),
+	/// A group of sounds to pick from at random.
+	Random(Vec<String>),
+}
+
+impl SoundDef {
+	pub fn build(&self, asset_storage: &mut AssetStorage) -> Vec<AssetHandle<Sound>> {
+		match self {
+			SoundDef::Single(name) => load_sound_group(asset_storage, name),
+			SoundDef::Random(names) => names
+				.iter()
+				.flat_map(|name| load_sound_group(asset_storage, name))
+				.collect(),
+		}
+	}
+}
+
+/// A `StateAction` as it is written in a data file, with names instead of handles.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub enum StateActionDef {
+	Fall,
+	Sound(String),
+	/// Name of the sound lump, without extension.
+	PlaySound(String),
+	SpawnProjectile {
+		template: String,
+		speed: f32,
+	},
+}
+
+impl StateActionDef {
+	pub fn build(&self, asset_storage: &mut AssetStorage) -> StateAction {
+		match self {
+			StateActionDef::Fall => StateAction::Fall,
+			StateActionDef::Sound(name) => StateAction::Sound(name.clone()),
+			StateActionDef::PlaySound(sound) => {
+				StateAction::PlaySound(load_sound_group(asset_storage, sound))
+			}
+			StateActionDef::SpawnProjectile { template, speed } => StateAction::SpawnProjectile {
+				template: AssetName::new(template),
+				speed: *speed,
+			},
+		}
+	}
+}
+
+/// The components of an entity template. Absent components are not added.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ComponentsDef {
+	/// Index of the `$ambient` sound in SNDINFO.
+	pub ambient_sound: Option<u16>,
+	pub box_collider: Option<BoxColliderDef>,
+	pub camera: Option<CameraDef>,
+	pub count_item: bool,
+	pub count_kill: bool,
+	pub deathmatch_spawn_point: bool,
+	pub dynamic_light: Option<DynamicLight>,
+	/// Starting and maximum health.
+	pub health: Option<i32>,
+	pub no_gravity: bool,
+	pub palette_effects: bool,
+	pub particle_emitter: Option<ParticleEmitter>,
+	pub pickup: Option<PickupDef>,
+	/// Sprite of the weapon the player holds.
+	pub player_sprite: Option<SpriteRenderDef>,
+	pub power_pickup: Option<PowerPickup>,
+	/// Echo for sounds heard inside the sector, for sector templates.
+	pub reverb: Option<ReverbPreset>,
+	pub spawn_point: Option<usize>,
+	pub sprite: Option<SpriteRenderDef>,
+	/// Conversation that players have when they talk to the entity.
+	pub talker: Option<u32>,
+	pub transform: Option<SpawnHeight>,
+	pub user: Option<UserDef>,
+	pub velocity: bool,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BoxColliderDef {
+	pub height: f32,
+	pub radius: f32,
+	/// Whether other entities collide with this one.
+	#[serde(default)]
+	pub solid: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CameraDef {
+	/// Height of the camera above the entity's position.
+	pub view_height: f32,
+	pub bob_max: f32,
+	/// Periods of the view and weapon bobbing, in frames.
+	pub view_bob_period: u32,
+	pub weapon_bob_period: u32,
+	/// Name of the sound played when landing hard, without extension.
+	pub impact_sound: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PickupDef {
+	/// Name of the sound lump, without extension.
+	pub sound: String,
+	#[serde(default)]
+	pub message: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpriteRenderDef {
+	pub sprite: String,
+	pub frame: usize,
+	#[serde(default)]
+	pub full_bright: bool,
+}
+
+impl SpriteRenderDef {
+	pub fn build(&self, asset_storage: &mut AssetStorage) -> SpriteRender {
+		SpriteRender {
+			sprite: asset_storage.load_deferred(&format!("{}.sprite", self.sprite)),
+			frame: self.frame,
+			full_bright: self.full_bright,
+		}
+	}
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UserDef {
+	/// Name of the sound played when a use fails, without extension.
+	pub error_sound: String,
+}
+
+impl EntityTemplateDef {
+	/// Turns the definition into a template, loading the sprites and sounds it refers to.
+	pub fn build(&self, asset_storage: &mut AssetStorage) -> anyhow::Result<EntityTemplate> {
+		let mut states = HashMap::with_capacity(self.states.len());
+
+		for (name, infos) in &self.states {
+			let state_name = match StateName::from(name) {
+				Ok(x) => x,
+				Err(_) => bail!("State name \"{}\" is too long", name),
+			};
+
+			let infos = infos
+				.iter()
+				.map(|info| {
+					let next = match &info.next {
+						Some((name, index)) => match StateName::from(name) {
+							Ok(x) => Some((x, *index)),
+							Err(_) => bail!("State name \"{}\" is too long", name),
+						},
+						None => None,
+					};
+
+					Ok(StateInfo {
+						sprite: SpriteRender {
+							sprite: asset_storage.load_deferred(&format!("{}.sprite", info.sprite)),
+							frame: info.frame,
+							full_bright: info.full_bright,
+						},
+						next: info.time.map(|time| (time * FRAME_TIME, next)),
+						action: info
+							.action
+							.as_ref()
+							.map(|action| action.build(asset_storage)),
+					})
+				})
+				.collect::<anyhow::Result<Vec<_>>>()?;
+
+			states.insert(state_name, infos);
+		}
+
+		let sounds = self
+			.sounds
+			.iter()
+			.map(|(name, sound)| {
+				let handles = sound.build(asset_storage);
+				ensure!(
+					!handles.is_empty(),
+					"Sound \"{}\" has no sounds in it",
+					name
+				);
+				Ok((name.clone(), handles))
+			})
+			.collect::<anyhow::Result<_>>()?;
+
+		// Build the template world, one component at a time
+		let mut world = World::default();
+		let entity = world.push((EntityTemplateRefDef,));
+		let mut entry = world.entry(entity).unwrap();
+		let components = &self.components;
+
+		if let Some(index) = components.ambient_sound {
+			entry.add_component(AmbientSound { index });
+		}
+
+		if let Some(box_collider) = components.box_collider {
+			entry.add_component(BoxCollider {
+				height: box_collider.height,
+				radius: box_collider.radius,
+				solid_mask: if box_collider.solid {
+					SolidMask::all()
+				} else {
+					SolidMask::empty()
+				},
+			});
+		}
+
+		if let Some(camera) = &components.camera {
+			entry.add_component(Camera {
+				base: Vector3::new(0.0, 0.0, camera.view_height),
+				offset: Vector3::zeros(),
+				bob_max: camera.bob_max,
+				view_bob_period: camera.view_bob_period * FRAME_TIME,
+				weapon_bob_period: camera.weapon_bob_period * FRAME_TIME,
+				deviation_position: 0.0,
+				deviation_velocity: 0.0,
+				punch: Vector2::zeros(),
+				impact_sound: asset_storage.load(&format!("{}.sound", camera.impact_sound)),
+			});
+		}
+
+		if components.count_item {
+			entry.add_component(CountItem);
+		}
+
+		if components.count_kill {
+			entry.add_component(CountKill);
+		}
+
+		if components.deathmatch_spawn_point {
+			entry.add_component(DeathmatchSpawnPoint);
+		}
+
+		if let Some(dynamic_light) = components.dynamic_light {
+			entry.add_component(dynamic_light);
+		}
+
+		if let Some(health) = components.health {
+			entry.add_component(Health {
+				current: health,
+				max: health,
+			});
+		}
+
+		if components.no_gravity {
+			entry.add_component(NoGravity);
+		}
+
+		if components.palette_effects {
+			entry.add_component(PaletteEffects::default());
+		}
+
+		if let Some(particle_emitter) = components.particle_emitter {
+			entry.add_component(particle_emitter);
+		}
+
+		if let Some(pickup) = &components.pickup {
+			entry.add_component(Pickup {
+				sound: asset_storage.load(&format!("{}.sound", pickup.sound)),
+				message: pickup.message.clone(),
+			});
+		}
+
+		if let Some(sprite) = &components.player_sprite {
+			entry.add_component(PlayerSpriteRender {
+				position: Vector2::zeros(),
+				sway: Vector2::zeros(),
+				slots: [Some(sprite.build(asset_storage)), None],
+			});
+		}
+
+		if let Some(power_pickup) = components.power_pickup {
+			entry.add_component(power_pickup);
+		}
+
+		if let Some(preset) = components.reverb {
+			entry.add_component(SectorReverb { preset });
+		}
+
+		if let Some(player_num) = components.spawn_point {
+			entry.add_component(SpawnPoint { player_num });
+		}
+
+		if let Some(sprite) = &components.sprite {
+			entry.add_component(sprite.build(asset_storage));
+		}
+
+		if !states.is_empty() {
+			entry.add_component(StateDef);
+		}
+
+		if let Some(conversation) = components.talker {
+			entry.add_component(Talker {
+				conversation,
+				start_page: None,
+			});
+		}
+
+		if let Some(spawn_height) = components.transform {
+			entry.add_component(TransformDef { spawn_height });
+		}
+
+		if let Some(user) = &components.user {
+			entry.add_component(User {
+				error_sound: asset_storage.load(&format!("{}.sound", user.error_sound)),
+			});
+		}
+
+		if components.velocity {
+			entry.add_component(VelocityDef);
+		}
+
+		Ok(EntityTemplate {
+			// The name is kept by the asset storage instead
+			name: None,
+			type_id: self.type_id,
+			not_in_deathmatch: self.not_in_deathmatch,
+			impact_effect: self.impact_effect,
+			drop_item: self.drop_item.as_deref().map(AssetName::new),
+			states,
+			sounds,
+			random_spawn: self
+				.random_spawn
+				.iter()
+				.map(|(name, weight)| (AssetName::new(name), *weight))
+				.collect(),
+			world,
+		})
+	}
+}
+
+/// Loads the game's built-in entity templates. If the data files have a `DEFAULT_TEMPLATES_LUMP`,
+/// it is loaded instead of `builtin`.
+pub fn load_default_templates(resources: &mut Resources, builtin: &str) -> anyhow::Result<()> {
+	let mut asset_storage = <Write<AssetStorage>>::fetch_mut(resources);
+	let path = RelativePath::new(DEFAULT_TEMPLATES_LUMP);
+
+	if asset_storage.source().exists(path) {
+		let data = asset_storage.source().load(path)?;
+		let count = insert_templates(&mut asset_storage, &data)?;
+		log::info!("Loaded {} default entity templates from data files", count);
+	} else {
+		insert_templates(&mut asset_storage, builtin.as_bytes())
+			.context("Couldn't load the built-in entity templates")?;
+	}
+
+	Ok(())
+}
+
+/// Loads the entity templates from the data files, if there are any, on top of the built-in ones.
+/// Templates with the same name as an existing one replace it.
+pub fn load_templates(resources: &mut Resources) -> anyhow::Result<()> {
+	let mut asset_storage = <Write<AssetStorage>>::fetch_mut(resources);
+	let path = RelativePath::new(TEMPLATES_LUMP);
+
+	if !asset_storage.source().exists(path) {
+		return Ok(());
+	}
+
+	let data = asset_storage.source().load(path)?;
+	let count = insert_templates(&mut asset_storage, &data)?;
+
+	log::info!("Loaded {} entity templates from data files", count);
+	Ok(())
+}
+
+/// Parses a JSON list of `EntityTemplateDef`, builds the templates and adds them to the
+/// asset storage. Returns how many templates there were.
+fn insert_templates(asset_storage: &mut AssetStorage, data: &[u8]) -> anyhow::Result<usize> {
+	let defs: Vec<EntityTemplateDef> =
+		serde_json::from_slice(data).context("Couldn't parse entity templates")?;
+
+	for def in &defs {
+		let template = def.build(asset_storage).with_context(|| {
+			format!(
+				"Couldn't build entity template \"{}\"",
+				def.name.as_deref().unwrap_or("<unnamed>")
+			)
+		})?;
+
+		match &def.name {
+			Some(name) => asset_storage.insert_with_name(name, template),
+			None => asset_storage.insert(template),
+		};
+	}
+
+	Ok(defs.len())
+}
+
+/// Loads the external template data again, replacing the templates from the previous load.
+/// Entities that already exist keep using their template, so they pick up the changes.
+pub fn reload_templates(resources: &mut Resources) {
+	let result = load_templates(resources).and_then(|_| load_decorate(resources));
+
+	match result {
+		Ok(()) => {
+			log::info!("Reloaded entity templates");
+			check_templates(resources);
+			index_templates(resources);
+		}
+		Err(err) => log::error!("Couldn't reload entity templates: {:?}", err),
+	}
+}
+
+/// Validates the entity templates and logs every problem that was found.
+pub fn check_templates(resources: &Resources) {
+	let asset_storage = <Read<AssetStorage>>::fetch(resources);
+	let problems = validate_templates(&asset_storage);
+
+	for problem in &problems {
+		log::warn!("{}", problem);
+	}
+
+	if !problems.is_empty() {
+		log::warn!("Found {} problems in the entity templates", problems.len());
+	}
+}
+
+/// Checks all entity templates for mistakes that would otherwise only show up once the template
+/// is used, and returns a description of each one.
+pub fn validate_templates(asset_storage: &AssetStorage) -> Vec<String> {
+	let mut problems = Vec::new();
+	let mut type_ids: HashMap<EntityTypeId, Vec<&str>> = HashMap::new();
+	let spawn_state_name = StateName::from("spawn").unwrap();
+
+	let check_sprite = |sprite_render: &SpriteRender| -> Option<String> {
+		let sprite_name = asset_storage.name_of(&sprite_render.sprite);
+		let has_frame = match asset_storage.get(&sprite_render.sprite) {
+			// Sprites that failed to load have no frames, and were already reported
+			Some(sprite) if sprite.frames().is_empty() => return None,
+			Some(sprite) => matches!(
+				sprite.frames().get(sprite_render.frame),
+				Some(rotations) if !rotations.is_empty()
+			),
+			// Deferred sprites aren't imported until they're used, so look at their lumps instead
+			None if asset_storage.is_deferred(&sprite_render.sprite) => sprite_has_frame(
+				asset_storage,
+				RelativePath::new(sprite_name?),
+				sprite_render.frame,
+			),
+			None => return None,
+		};
+
+		if has_frame {
+			None
+		} else {
+			Some(format!(
+				"sprite {} has no frame {}",
+				sprite_name.unwrap_or("<unnamed>"),
+				sprite_render.frame,
+			))
+		}
+	};
+
+	for (handle, template) in asset_storage.iter::<EntityTemplate>() {
+		let name = asset_storage
+			.name_of(handle)
+			.or(template.name)
+			.unwrap_or("<unnamed>");
+
+		if let Some(type_id) = template.type_id {
+			type_ids.entry(type_id).or_default().push(name);
+		}
+
+		if let Some(drop_item) = &template.drop_item {
+			if asset_storage
+				.handle_for_name::<EntityTemplate>(*drop_item)
+				.is_none()
+			{
+				problems.push(format!(
+					"Template \"{}\": drop item template \"{}\" does not exist",
+					name, drop_item
+				));
+			}
+		}
+
+		for (spawn_name, _) in &template.random_spawn {
+			if asset_storage
+				.handle_for_name::<EntityTemplate>(*spawn_name)
+				.is_none()
+			{
+				problems.push(format!(
+					"Template \"{}\": random spawn template \"{}\" does not exist",
+					name, spawn_name
+				));
+			}
+		}
+
+		if !template.random_spawn.is_empty()
+			&& template.random_spawn.iter().all(|(_, weight)| *weight == 0)
+		{
+			problems.push(format!(
+				"Template \"{}\": random spawn weights are all zero",
+				name
+			));
+		}
+
+		for sprite_render in <&SpriteRender>::query().iter(&template.world) {
+			if let Some(problem) = check_sprite(sprite_render) {
+				problems.push(format!("Template \"{}\": {}", name, problem));
+			}
+		}
+
+		if <&StateDef>::query().iter(&template.world).next().is_some()
+			&& !template.states.contains_key(&spawn_state_name)
+		{
+			problems.push(format!("Template \"{}\" has no spawn state", name));
+		}
+
+		let mut state_names: Vec<_> = template.states.keys().collect();
+		state_names.sort_unstable();
+
+		for state_name in state_names {
+			for (i, info) in template.states[state_name].iter().enumerate() {
+				let location = format!("Template \"{}\", state {} {}", name, state_name, i);
+
+				if let Some(problem) = check_sprite(&info.sprite) {
+					problems.push(format!("{}: {}", location, problem));
+				}
+
+				if let Some((_, Some((next_name, next_index)))) = &info.next {
+					if template
+						.states
+						.get(next_name)
+						.map_or(true, |infos| *next_index >= infos.len())
+					{
+						problems.push(format!(
+							"{}: next state {} {} does not exist",
+							location, next_name, next_index
+						));
+					}
+				}
+
+				if let Some(StateAction::SpawnProjectile {
+					template: projectile,
+					..
+				}) = &info.action
+				{
+					if asset_storage
+						.handle_for_name::<EntityTemplate>(*projectile)
+						.is_none()
+					{
+						problems.push(format!(
+							"{}: projectile template \"{}\" does not exist",
+							location, projectile
+						));
+					}
+				}
+			}
+		}
+	}
+
+	let mut duplicates: Vec<_> = type_ids
+		.into_iter()
+		.filter(|(_, names)| names.len() > 1)
+		.collect();
+	duplicates.sort_unstable_by_key(|(_, names)| names[0]);
+
+	for (type_id, names) in duplicates {
+		problems.push(format!(
+			"{:?} is used by more than one template: {}",
+			type_id,
+			names.join(", ")
+		));
+	}
+
+	problems
+}
+
+/// Logs the names and type IDs of all entity templates, sorted by name.
+pub fn list_templates(resources: &Resources) {
+	let asset_storage = <Read<AssetStorage>>::fetch(resources);
+	let mut templates: Vec<(&str, Option<EntityTypeId>)> = asset_storage
+		.iter::<EntityTemplate>()
+		.map(|(handle, template)| {
+			let name = asset_storage
+				.name_of(handle)
+				.or(template.name)
+				.unwrap_or("<unnamed>");
+			(name, template.type_id)
+		})
+		.collect();
+	templates.sort_unstable_by_key(|(name, _)| *name);
+
+	for (name, type_id) in &templates {
+		match type_id {
+			Some(type_id) => log::info!("{} {:?}", name, type_id),
+			None => log::info!("{}", name),
+		}
+	}
+
+	log::info!("{} templates", templates.len());
+}
+
+/// Logs the states of the entity template with the given name or thing number,
+/// as they were resolved.
+pub fn dump_template(name: &str, resources: &Resources) {
+	let (asset_storage, template_index) =
+		<(Read<AssetStorage>, Read<TemplateIndex>)>::fetch(resources);
+
+	// Thing numbers can be given instead of a name
+	let handle = match name.parse::<u16>() {
+		Ok(number) => template_index.get(EntityTypeId::Thing(number)).cloned(),
+		Err(_) => asset_storage.handle_for::<EntityTemplate>(name),
+	};
+	let (name, template) = match handle.as_ref().and_then(|handle| {
+		let template = asset_storage.get(handle)?;
+		Some((asset_storage.name_of(handle).unwrap_or(name), template))
+	}) {
+		Some(x) => x,
+		None => {
+			log::error!("Entity template not found: {}", name);
+			return;
+		}
+	};
+
+	log::info!("{} {:?}", name, template.type_id);
+
+	let mut states: Vec<_> = template.states.iter().collect();
+	states.sort_unstable_by_key(|(state_name, _)| *state_name);
+
+	for (state_name, infos) in states {
+		log::info!("{}:", state_name);
+
+		for (i, info) in infos.iter().enumerate() {
+			let sprite = asset_storage
+				.name_of(&info.sprite.sprite)
+				.unwrap_or("<unnamed>");
+			let next = match &info.next {
+				Some((time, Some((next_name, next_index)))) => {
+					format!("{} ms, then {} {}", time.as_millis(), next_name, next_index)
+				}
+				Some((time, None)) => format!("{} ms", time.as_millis()),
+				None => String::from("forever"),
+			};
+
+			log::info!(
+				"  {} {} frame {}{}, {}{}",
+				i,
+				sprite,
+				info.sprite.frame,
+				if info.sprite.full_bright {
+					" bright"
+				} else {
+					""
+				},
+				next,
+				match &info.action {
+					Some(action) => format!(", {:?}", action),
+					None => String::new(),
+				}
+			);
+		}
+	}
+}
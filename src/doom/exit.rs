@@ -0,0 +1,176 @@
+use crate::{
+	common::{
+		assets::{AssetHandle, AssetStorage},
+		audio::Sound,
+		events::{register_reader, Events},
+		frame::FrameState,
+		gamestate::{GameState, GameStateMachine},
+		profiler,
+	},
+	doom::{
+		client::{UseAction, UseEvent, User},
+		health::Dead,
+		intermission::IntermissionInfo,
+		map::{LinedefRef, MapDynamic},
+		mapinfo::map_info,
+		physics::{TouchAction, TouchEvent},
+		profile::GameProfile,
+		progression::{ends_episode, next_map, ExitType},
+		switch::{SwitchActive, SwitchParams},
+	},
+};
+use legion::{component, systems::Runnable, Entity, IntoQuery, Resources, SystemBuilder};
+use relative_path::RelativePath;
+
+#[derive(Clone, Debug)]
+pub struct ExitSwitchUse {
+	pub exit_type: ExitType,
+	pub switch_params: SwitchParams,
+}
+
+pub fn exit_switch_system(resources: &mut Resources) -> impl Runnable {
+	register_reader::<UseEvent>(resources, "exit_switch_system");
+
+	SystemBuilder::new("exit_switch_system")
+		.read_resource::<AssetStorage>()
+		.write_resource::<Events<UseEvent>>()
+		.read_resource::<FrameState>()
+		.read_resource::<GameProfile>()
+		.write_resource::<GameStateMachine>()
+		.write_resource::<IntermissionInfo>()
+		.write_resource::<Vec<(AssetHandle<Sound>, Entity)>>()
+		.with_query(<(&LinedefRef, &UseAction)>::query().filter(!component::<SwitchActive>()))
+		.with_query(<&mut MapDynamic>::query())
+		.build(move |command_buffer, world, resources, queries| {
+			let _profile = profiler::scope("exit_switch_system");
+
+			let (
+				asset_storage,
+				use_event_channel,
+				frame_state,
+				game_profile,
+				game_state,
+				intermission_info,
+				sound_queue,
+			) = resources;
+			let (mut world1, world) = world.split_for_query(&queries.1);
+
+			for use_event in use_event_channel.read("exit_switch_system") {
+				let (linedef_ref, exit_switch_use) =
+					match queries.0.get(&world, use_event.linedef_entity) {
+						Ok((linedef_ref, UseAction::ExitSwitchUse(exit_switch_use))) => {
+							(linedef_ref, exit_switch_use)
+						}
+						_ => continue,
+					};
+
+				let map_dynamic = queries
+					.1
+					.get_mut(&mut world1, linedef_ref.map_entity)
+					.unwrap();
+				let map = asset_storage.get(&map_dynamic.map).unwrap();
+
+				crate::doom::switch::activate(
+					&exit_switch_use.switch_params,
+					command_buffer,
+					sound_queue.as_mut(),
+					frame_state,
+					linedef_ref.index,
+					map,
+					map_dynamic,
+				);
+				command_buffer.remove_component::<UseAction>(use_event.linedef_entity);
+
+				exit_level(
+					exit_switch_use.exit_type,
+					asset_storage,
+					**game_profile,
+					map_dynamic,
+					game_state,
+					intermission_info,
+				);
+			}
+		})
+}
+
+#[derive(Clone, Debug)]
+pub struct ExitTouch {
+	pub exit_type: ExitType,
+}
+
+pub fn exit_touch_system(resources: &mut Resources) -> impl Runnable {
+	register_reader::<TouchEvent>(resources, "exit_touch_system");
+
+	SystemBuilder::new("exit_touch_system")
+		.read_resource::<AssetStorage>()
+		.write_resource::<Events<TouchEvent>>()
+		.read_resource::<GameProfile>()
+		.write_resource::<GameStateMachine>()
+		.write_resource::<IntermissionInfo>()
+		.with_query(<(&LinedefRef, &TouchAction)>::query())
+		.with_query(<&MapDynamic>::query())
+		.with_query(<&User>::query().filter(!component::<Dead>()))
+		.build(move |_, world, resources, queries| {
+			let _profile = profiler::scope("exit_touch_system");
+
+			let (asset_storage, touch_event_channel, game_profile, game_state, intermission_info) =
+				resources;
+
+			for touch_event in touch_event_channel.read("exit_touch_system") {
+				if touch_event.collision.is_some() {
+					continue;
+				}
+
+				// Only players can leave the level
+				if queries.2.get(world, touch_event.toucher).is_err() {
+					continue;
+				}
+
+				let (linedef_ref, exit_touch) = match queries.0.get(world, touch_event.touched) {
+					Ok((linedef_ref, TouchAction::ExitTouch(exit_touch))) => {
+						(linedef_ref, exit_touch)
+					}
+					_ => continue,
+				};
+
+				let map_dynamic = queries.1.get(world, linedef_ref.map_entity).unwrap();
+
+				exit_level(
+					exit_touch.exit_type,
+					asset_storage,
+					**game_profile,
+					map_dynamic,
+					game_state,
+					intermission_info,
+				);
+			}
+		})
+}
+
+/// Ends the current level, going to the intermission screen and then on to the next map.
+pub fn exit_level(
+	exit_type: ExitType,
+	asset_storage: &AssetStorage,
+	game_profile: GameProfile,
+	map_dynamic: &MapDynamic,
+	game_state: &mut GameStateMachine,
+	intermission_info: &mut IntermissionInfo,
+) {
+	let map = match asset_storage.name_of(&map_dynamic.map) {
+		Some(name) => name.trim_end_matches(".map").to_owned(),
+		None => return,
+	};
+
+	let map_info = map_info(asset_storage, &map);
+
+	if ends_episode(&map, exit_type, map_info, game_profile) {
+		log::info!("Episode finished");
+	}
+
+	let next_map = next_map(&map, exit_type, map_info, game_profile, |name| {
+		asset_storage.source().exists(RelativePath::new(name))
+	});
+
+	*intermission_info = IntermissionInfo { map, next_map };
+	game_state.set(GameState::Intermission);
+}
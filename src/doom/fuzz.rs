@@ -0,0 +1,84 @@
+use crate::{
+	common::assets::{AssetStorage, DataSource, ImportData},
+	doom::{
+		image::{parse_patch, Image},
+		map::load::import_map,
+		mapinfo::{placeholder_mapinfo, MapInfo},
+		sound::parse_sound,
+		wad::WadLoader,
+	},
+};
+use anyhow::{bail, Context};
+use relative_path::RelativePath;
+use std::{io::Read, path::Path};
+
+/// Runs one of the lump parsers on data from standard input, for use with a fuzzer
+/// like AFL or honggfuzz. Errors in the data are expected and are only logged,
+/// anything that goes wrong otherwise is a bug that the fuzzer will catch as a crash.
+///
+/// `kind` is one of:
+/// * `map`: a WAD file, of which the directory and every map are loaded.
+/// * `patch`: a single lump in the Doom patch format.
+/// * `sound`: a single lump in the Doom sound format.
+pub fn fuzz_stdin(kind: &str) -> anyhow::Result<()> {
+	let mut data = Vec::new();
+	std::io::stdin()
+		.read_to_end(&mut data)
+		.context("Couldn't read standard input")?;
+
+	let result = match kind {
+		"map" => fuzz_maps(&data),
+		"patch" => parse_patch(&data).map(|_| ()),
+		"sound" => parse_sound(&data).map(|_| ()),
+		_ => bail!("Unknown kind of data to fuzz: {}", kind),
+	};
+
+	if let Err(e) = result {
+		log::info!("Rejected: {:#}", e);
+	}
+
+	Ok(())
+}
+
+fn fuzz_maps(data: &[u8]) -> anyhow::Result<()> {
+	// The WAD loader reads from files, so give it one
+	let path = std::env::temp_dir().join(format!("ferret-fuzz-{}.wad", std::process::id()));
+	std::fs::write(&path, data)?;
+
+	// The loader reopens the file for every lump, so it can only be removed at the end
+	let result = fuzz_wad(&path);
+	std::fs::remove_file(&path)?;
+	result
+}
+
+fn fuzz_wad(path: &Path) -> anyhow::Result<()> {
+	let mut loader = WadLoader::new();
+	loader.add(path)?;
+
+	let maps: Vec<String> = loader
+		.names()
+		.filter(|name| loader.exists(&RelativePath::new(name).with_extension("things")))
+		.map(|name| format!("{}.map", name))
+		.collect();
+
+	// Textures and flats are not needed to check the map data, so they are left unloaded
+	let mut asset_storage = AssetStorage::new(import, loader);
+	asset_storage.add_storage::<Image>(true);
+	asset_storage.add_storage::<MapInfo>(false);
+	asset_storage.set_placeholder::<MapInfo>(placeholder_mapinfo);
+
+	for map in maps {
+		if let Err(e) = import_map(RelativePath::new(&map), &mut asset_storage) {
+			log::info!("Rejected {}: {:#}", map, e);
+		}
+	}
+
+	Ok(())
+}
+
+fn import(
+	path: &RelativePath,
+	_asset_storage: &mut AssetStorage,
+) -> anyhow::Result<Box<dyn ImportData>> {
+	bail!("{} is not loaded while fuzzing", path)
+}
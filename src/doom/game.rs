@@ -0,0 +1,129 @@
+use crate::{common::configvars::ConfigVariables, doom::map::ThingFlags};
+use anyhow::bail;
+use legion::{systems::ResourceSet, Read, Resources};
+use std::str::FromStr;
+
+/// Sets up the resources for a new game, from the current settings.
+pub fn new_game(resources: &mut Resources) {
+	let (skill, game_mode, fast_monsters) = {
+		let config_variables = <Read<ConfigVariables>>::fetch(resources);
+		let skill = Skill::from_number(config_variables.get("skill")).unwrap_or_default();
+		(
+			skill,
+			config_variables
+				.get::<String>("gamemode")
+				.parse::<GameMode>()
+				.unwrap_or_default(),
+			FastMonsters(skill.fast_monsters() || config_variables.get::<bool>("fast")),
+		)
+	};
+
+	log::info!(
+		"New game: {:?}, {:?}, {:?}",
+		game_mode,
+		skill,
+		fast_monsters
+	);
+	resources.insert(skill);
+	resources.insert(game_mode);
+	resources.insert(fast_monsters);
+}
+
+/// Difficulty level of the current game.
+/// This is taken from the `skill` setting when a new game is started,
+/// so changing the setting only takes effect with the next new game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Skill {
+	Baby = 1,
+	Easy = 2,
+	Medium = 3,
+	Hard = 4,
+	Nightmare = 5,
+}
+
+impl Skill {
+	/// Converts the skill number used by the `skill` setting, from 1 to 5.
+	pub fn from_number(number: u32) -> Option<Skill> {
+		Some(match number {
+			1 => Skill::Baby,
+			2 => Skill::Easy,
+			3 => Skill::Medium,
+			4 => Skill::Hard,
+			5 => Skill::Nightmare,
+			_ => return None,
+		})
+	}
+
+	/// The flag that things must have to appear on this skill.
+	pub fn thing_flag(self) -> ThingFlags {
+		match self {
+			Skill::Baby | Skill::Easy => ThingFlags::EASY,
+			Skill::Medium => ThingFlags::NORMAL,
+			Skill::Hard | Skill::Nightmare => ThingFlags::HARD,
+		}
+	}
+
+	/// Scales damage dealt to a player. Players take half damage on the easiest skill.
+	pub fn player_damage(self, amount: i32) -> i32 {
+		match self {
+			Skill::Baby => amount >> 1,
+			_ => amount,
+		}
+	}
+
+	/// Whether killed monsters come back to life after a while.
+	pub fn respawn_monsters(self) -> bool {
+		self == Skill::Nightmare
+	}
+
+	/// Whether monsters are always fast on this skill, even without the `fast` setting.
+	pub fn fast_monsters(self) -> bool {
+		self == Skill::Nightmare
+	}
+}
+
+impl Default for Skill {
+	fn default() -> Skill {
+		Skill::Medium
+	}
+}
+
+/// Whether monsters chase and attack faster, and fire faster projectiles.
+/// This is taken from the skill and the `fast` setting when a new game is started.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FastMonsters(pub bool);
+
+/// The rules that the current game is played by.
+/// Like the skill, this is taken from the `gamemode` setting when a new game is started.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameMode {
+	SinglePlayer,
+	Cooperative,
+	Deathmatch,
+}
+
+impl GameMode {
+	/// Whether more than one player can be in the game.
+	pub fn is_multiplayer(self) -> bool {
+		self != GameMode::SinglePlayer
+	}
+}
+
+impl FromStr for GameMode {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> anyhow::Result<GameMode> {
+		Ok(match s {
+			"single" => GameMode::SinglePlayer,
+			"coop" => GameMode::Cooperative,
+			"deathmatch" => GameMode::Deathmatch,
+			_ => bail!("Invalid game mode: {}", s),
+		})
+	}
+}
+
+impl Default for GameMode {
+	fn default() -> GameMode {
+		GameMode::SinglePlayer
+	}
+}
@@ -0,0 +1,341 @@
+use crate::{
+	common::{
+		assets::{AssetHandle, AssetStorage},
+		events::{add_events, register_reader, Events},
+		frame::FrameState,
+		gamestate::{GameState, GameStateMachine},
+		geometry::Angle,
+		profiler,
+	},
+	doom::{
+		camera::Camera,
+		client::{Client, User},
+		components::Transform,
+		data::FRAME_RATE,
+		entitytemplate::EntityTemplateRef,
+		game::{FastMonsters, GameMode, Skill},
+		item::drop_item_request,
+		map::{
+			spawn::{spawn_player, SpawnRequest},
+			MapDynamic,
+		},
+		monster::{Ambush, MonsterRespawnQueue},
+		palette::PaletteEffects,
+		psprite::PlayerSpriteRender,
+		sound::Sound,
+		sprite::SpriteRender,
+		state::{State, StateName},
+		stats::{CountKill, LevelStats},
+	},
+};
+use legion::{
+	component,
+	systems::{ResourceSet, Runnable},
+	Entity, EntityStore, IntoQuery, Read, Resources, SystemBuilder, World, Write,
+};
+use nalgebra::Vector2;
+use rand::seq::SliceRandom;
+
+/// How much damage an entity can take before it dies.
+#[derive(Clone, Copy, Debug)]
+pub struct Health {
+	pub current: i32,
+	pub max: i32,
+}
+
+/// Damage dealt to an entity, by `source` if it came from another entity.
+#[derive(Clone, Copy, Debug)]
+pub struct DamageEvent {
+	pub entity: Entity,
+	pub amount: i32,
+	pub source: Option<Entity>,
+}
+
+/// Sent when an entity's health runs out.
+#[derive(Clone, Copy, Debug)]
+pub struct DeathEvent {
+	pub entity: Entity,
+}
+
+/// Marks an entity whose health has run out.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Dead;
+
+/// The most damage that the red screen flash counts, in frames of flashing.
+const MAX_DAMAGE_COUNT: u32 = 100;
+
+/// How far the view is punched for each point of damage, in degrees.
+const PUNCH_PER_DAMAGE: f32 = 0.25;
+
+/// The furthest that the view can be punched, in degrees.
+const MAX_PUNCH: f32 = 8.0;
+
+/// View height of a dead player, lying on the floor.
+const DEAD_VIEW_HEIGHT: f32 = 6.0;
+
+/// How fast the view of a dead player drops to the floor, in units per second.
+const DEAD_VIEW_SPEED: f32 = 1.0 * FRAME_RATE;
+
+pub fn damage_system(resources: &mut Resources) -> impl Runnable {
+	add_events::<DamageEvent>(resources);
+	add_events::<DeathEvent>(resources);
+	register_reader::<DamageEvent>(resources, "damage_system");
+
+	SystemBuilder::new("damage_system")
+		.read_resource::<AssetStorage>()
+		.write_resource::<Events<DamageEvent>>()
+		.read_resource::<FastMonsters>()
+		.read_resource::<FrameState>()
+		.read_resource::<Skill>()
+		.write_resource::<Events<DeathEvent>>()
+		.write_resource::<LevelStats>()
+		.write_resource::<MonsterRespawnQueue>()
+		.write_resource::<Vec<(AssetHandle<Sound>, Entity)>>()
+		.write_resource::<Vec<SpawnRequest>>()
+		.with_query(
+			<(
+				&EntityTemplateRef,
+				&mut Health,
+				Option<&mut Camera>,
+				Option<&mut PaletteEffects>,
+				Option<&mut PlayerSpriteRender>,
+				&mut SpriteRender,
+				&mut State,
+				Option<&Transform>,
+			)>::query()
+			.filter(!component::<Dead>()),
+		)
+		.read_component::<Ambush>()
+		.read_component::<CountKill>()
+		.read_component::<User>()
+		.build(move |command_buffer, world, resources, query| {
+			let _profile = profiler::scope("damage_system");
+
+			let (
+				asset_storage,
+				damage_event_channel,
+				fast_monsters,
+				frame_state,
+				skill,
+				death_event_channel,
+				level_stats,
+				monster_respawn_queue,
+				sound_queue,
+				spawn_queue,
+			) = resources;
+
+			for event in damage_event_channel.read("damage_system") {
+				let source_position = event.source.and_then(|source| {
+					<&Transform>::query()
+						.get(world, source)
+						.ok()
+						.map(|transform| transform.position)
+				});
+				let (ambush, count_kill, is_player) = match world.entry_ref(event.entity) {
+					Ok(entry) => (
+						entry.get_component::<Ambush>().is_ok(),
+						entry.get_component::<CountKill>().is_ok(),
+						entry.get_component::<User>().is_ok(),
+					),
+					Err(_) => continue,
+				};
+				let amount = if is_player {
+					skill.player_damage(event.amount)
+				} else {
+					event.amount
+				};
+
+				let (
+					template_ref,
+					health,
+					camera,
+					palette_effects,
+					player_sprite_render,
+					sprite_render,
+					state,
+					transform,
+				) = match query.get_mut(world, event.entity) {
+					Ok(x) => x,
+					Err(_) => continue,
+				};
+
+				health.current = health.current.saturating_sub(amount);
+
+				// Flash the screen red, more strongly for more damage
+				if let Some(palette_effects) = palette_effects {
+					palette_effects.damage_count =
+						(palette_effects.damage_count + amount.max(0) as u32).min(MAX_DAMAGE_COUNT);
+				}
+
+				// Kick the view upwards, and towards the attacker
+				if let Some(camera) = camera {
+					let punch = (amount as f32 * PUNCH_PER_DAMAGE).min(MAX_PUNCH);
+					let yaw_punch = match (source_position, transform) {
+						(Some(source_position), Some(transform)) => {
+							let to_source = source_position - transform.position;
+							let angle = Angle::from_radians(f64::atan2(
+								to_source[1] as f64,
+								to_source[0] as f64,
+							)) - transform.rotation[2];
+							punch * angle.sin().signum() as f32
+						}
+						_ => 0.0,
+					};
+
+					camera.punch = Vector2::new(-punch, yaw_punch);
+				}
+
+				if health.current > 0 {
+					continue;
+				}
+
+				command_buffer.add_component(event.entity, Dead);
+				death_event_channel.single_write(DeathEvent {
+					entity: event.entity,
+				});
+
+				if count_kill {
+					level_stats.kills += 1;
+
+					if let Some(transform) = transform {
+						monster_respawn_queue.push(
+							template_ref.0.clone(),
+							*transform,
+							ambush,
+							Some(event.entity),
+							frame_state,
+						);
+					}
+				}
+
+				let template = asset_storage.get(&template_ref.0).unwrap();
+				let death_state = StateName::from("death").unwrap();
+
+				if template.states.contains_key(&death_state) {
+					state.set(
+						(death_state, 0),
+						&template.states,
+						sprite_render,
+						frame_state.time,
+						fast_monsters.0,
+					);
+				}
+
+				// Drop an item where the entity died, on the floor
+				if let Some(transform) = transform {
+					spawn_queue.extend(drop_item_request(template, transform));
+				}
+
+				if let Some(sounds) = template.sounds.get("death") {
+					let mut rng = frame_state.rng.lock().unwrap();
+
+					if let Some(sound) = sounds.choose(&mut *rng) {
+						sound_queue.push((sound.clone(), event.entity));
+					}
+				}
+
+				// Dead players drop their weapon
+				if let Some(player_sprite_render) = player_sprite_render {
+					player_sprite_render.slots = [None, None];
+				}
+			}
+		})
+}
+
+/// Lowers the view of dead players towards the floor.
+pub fn player_death_system() -> impl Runnable {
+	SystemBuilder::new("player_death_system")
+		.read_resource::<FrameState>()
+		.with_query(<&mut Camera>::query().filter(component::<Dead>() & component::<User>()))
+		.build(move |_, world, resources, query| {
+			let _profile = profiler::scope("player_death_system");
+
+			let frame_state = resources;
+			let delta_time = frame_state.delta_time.as_secs_f32();
+
+			for camera in query.iter_mut(world) {
+				camera.base[2] =
+					(camera.base[2] - DEAD_VIEW_SPEED * delta_time).max(DEAD_VIEW_HEIGHT);
+			}
+		})
+}
+
+/// Lets a dead player start again by pressing use or fire.
+/// In single player the level restarts, otherwise a new player appears at a player start,
+/// leaving the old body behind.
+/// There are no saved games yet, so there's nothing else to go back to.
+pub fn player_respawn_system() -> Box<dyn FnMut(&mut World, &mut Resources)> {
+	Box::new(move |world, resources| {
+		let _profile = profiler::scope("player_respawn_system");
+
+		let entity = {
+			let (asset_storage, client, game_mode, mut game_state) = <(
+				Read<AssetStorage>,
+				Read<Client>,
+				Read<GameMode>,
+				Write<GameStateMachine>,
+			)>::fetch_mut(resources);
+
+			let entity = match client.entity {
+				Some(entity) if is_dead(world, entity) => entity,
+				_ => return,
+			};
+
+			let pressed = (client.command.r#use && !client.previous_command.r#use)
+				|| (client.command.attack && !client.previous_command.attack);
+
+			if !pressed || client.is_spectating() {
+				return;
+			}
+
+			if *game_mode == GameMode::SinglePlayer {
+				let map_name = <&MapDynamic>::query()
+					.iter(world)
+					.next()
+					.and_then(|map_dynamic| asset_storage.name_of(&map_dynamic.map))
+					.map(|name| name.trim_end_matches(".map").to_owned());
+
+				if let Some(map) = map_name {
+					game_state.set(GameState::Loading { map });
+				}
+
+				return;
+			}
+
+			entity
+		};
+
+		// The old body stays where it is, but is no longer controlled
+		if let Some(mut entry) = world.entry(entity) {
+			entry.remove_component::<User>();
+			entry.remove_component::<Camera>();
+			entry.remove_component::<PlayerSpriteRender>();
+		}
+
+		match spawn_player(world, resources, 1) {
+			Ok(entity) => <Write<Client>>::fetch_mut(resources).entity = Some(entity),
+			Err(e) => log::error!("Couldn't respawn player: {}", e),
+		}
+	})
+}
+
+/// Kills the player's entity, like the `kill` console command.
+pub fn kill_player(resources: &mut Resources) {
+	let (client, mut damage_event_channel) =
+		<(Read<Client>, Write<Events<DamageEvent>>)>::fetch_mut(resources);
+
+	if let Some(entity) = client.entity {
+		damage_event_channel.single_write(DamageEvent {
+			entity,
+			amount: i32::MAX,
+			source: None,
+		});
+	}
+}
+
+/// Returns whether the entity has died.
+pub fn is_dead(world: &impl EntityStore, entity: Entity) -> bool {
+	world
+		.entry_ref(entity)
+		.map_or(false, |entry| entry.get_component::<Dead>().is_ok())
+}
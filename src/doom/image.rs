@@ -1,5 +1,9 @@
-use crate::common::assets::{AssetStorage, ImportData};
-use byteorder::{ReadBytesExt, LE};
+use crate::common::{
+	assets::{AssetHandle, AssetStorage, ImportData},
+	video::{AsBytes, ImageUploader, RenderContext},
+};
+use anyhow::{ensure, Context};
+use byteorder::{ReadBytesExt, WriteBytesExt, LE};
 use nalgebra::Vector2;
 use relative_path::RelativePath;
 use std::{
@@ -7,7 +11,10 @@ use std::{
 	ops::Deref,
 	sync::Arc,
 };
-use vulkano::image::ImageViewAccess;
+use vulkano::{
+	format::Format,
+	image::{Dimensions, ImageViewAccess},
+};
 
 #[derive(Copy, Clone, Debug, Default)]
 #[repr(C)]
@@ -64,6 +71,92 @@ pub struct ImageData {
 	pub offset: Vector2<isize>,
 }
 
+impl ImageData {
+	/// Writes the image in the format used for the disk cache.
+	pub fn to_cache(&self) -> Vec<u8> {
+		let mut bytes = Vec::with_capacity(16 + self.data.len() * 2);
+		bytes.write_u32::<LE>(self.size[0] as u32).unwrap();
+		bytes.write_u32::<LE>(self.size[1] as u32).unwrap();
+		bytes.write_i32::<LE>(self.offset[0] as i32).unwrap();
+		bytes.write_i32::<LE>(self.offset[1] as i32).unwrap();
+
+		for pixel in &self.data {
+			bytes.extend_from_slice(&[pixel.i, pixel.a]);
+		}
+
+		bytes
+	}
+
+	/// Reads an image written by `to_cache`.
+	pub fn from_cache(bytes: &[u8]) -> anyhow::Result<ImageData> {
+		let mut reader = Cursor::new(bytes);
+		let size = [
+			reader.read_u32::<LE>()? as usize,
+			reader.read_u32::<LE>()? as usize,
+		];
+		let offset = Vector2::new(
+			reader.read_i32::<LE>()? as isize,
+			reader.read_i32::<LE>()? as isize,
+		);
+		let pixels = &bytes[16..];
+
+		ensure!(
+			pixels.len() == size[0] * size[1] * 2,
+			"Cached image has {} bytes of pixels, but should have {}",
+			pixels.len(),
+			size[0] * size[1] * 2
+		);
+
+		Ok(ImageData {
+			data: pixels
+				.chunks_exact(2)
+				.map(|pixel| IAColor {
+					i: pixel[0],
+					a: pixel[1],
+				})
+				.collect(),
+			size,
+			offset,
+		})
+	}
+
+	/// Converts the image to RGBA colors using the given palette.
+	/// Transparent pixels take on the color of an opaque neighbour if there is one,
+	/// so that texture filtering doesn't blend dark fringes into the edges of sprites.
+	pub fn to_rgba(&self, palette: &Palette) -> Vec<RGBAColor> {
+		let [width, height] = self.size;
+
+		self.data
+			.iter()
+			.enumerate()
+			.map(|(i, pixel)| {
+				if pixel.a == 0xFF {
+					return palette[pixel.i as usize];
+				}
+
+				let (x, y) = (i % width, i / width);
+				let neighbours = [
+					(x.wrapping_sub(1), y),
+					(x + 1, y),
+					(x, y.wrapping_sub(1)),
+					(x, y + 1),
+				];
+
+				neighbours
+					.iter()
+					.filter(|(x, y)| *x < width && *y < height)
+					.map(|(x, y)| self.data[y * width + x])
+					.find(|neighbour| neighbour.a == 0xFF)
+					.map(|neighbour| RGBAColor {
+						a: 0,
+						..palette[neighbour.i as usize]
+					})
+					.unwrap_or_default()
+			})
+			.collect()
+	}
+}
+
 pub struct Image {
 	pub image: Arc<dyn ImageViewAccess + Send + Sync>,
 	pub offset: Vector2<f32>,
@@ -76,11 +169,71 @@ impl Image {
 	}
 }
 
+/// Turns all newly imported images into GPU images.
+/// Their data is added to `uploader`, they can't be used until it has uploaded them.
+pub fn process_images(
+	render_context: &RenderContext,
+	asset_storage: &mut AssetStorage,
+	uploader: &mut ImageUploader,
+) {
+	let palette_handle: AssetHandle<Palette> = asset_storage.load("playpal.palette");
+
+	asset_storage.process::<Image, _>(|data, asset_storage| {
+		let image_data: ImageData = *data.downcast().ok().unwrap();
+		let palette = asset_storage.get(&palette_handle).unwrap();
+		let data = image_data.to_rgba(palette);
+
+		// Create the image
+		let image = uploader.add(
+			render_context,
+			data.as_bytes(),
+			Dimensions::Dim2d {
+				width: image_data.size[0] as u32,
+				height: image_data.size[1] as u32,
+			},
+			Format::R8G8B8A8Unorm,
+		)?;
+
+		Ok(Image {
+			image,
+			offset: Vector2::new(image_data.offset[0] as f32, image_data.offset[1] as f32),
+		})
+	});
+}
+
+/// A black and white checkerboard, to stand in for images that couldn't be loaded.
+pub fn placeholder_image() -> Box<dyn ImportData> {
+	const SIZE: usize = 64;
+	const SQUARE_SIZE: usize = 8;
+
+	let data = (0..SIZE * SIZE)
+		.map(|i| {
+			let (x, y) = (i % SIZE / SQUARE_SIZE, i / SIZE / SQUARE_SIZE);
+			IAColor {
+				i: if (x + y) % 2 == 0 { 0 } else { 4 },
+				a: 0xFF,
+			}
+		})
+		.collect();
+
+	Box::new(ImageData {
+		data,
+		size: [SIZE, SIZE],
+		offset: Vector2::new(SIZE as isize / 2, SIZE as isize),
+	})
+}
+
 pub fn import_patch(
 	path: &RelativePath,
 	asset_storage: &mut AssetStorage,
 ) -> anyhow::Result<Box<dyn ImportData>> {
-	let mut reader = Cursor::new(asset_storage.source().load(path)?);
+	let data = asset_storage.source().load(path)?;
+	Ok(Box::new(parse_patch(&data)?))
+}
+
+/// Reads an image in the Doom patch format, which is stored as columns of vertical "posts".
+pub fn parse_patch(lump: &[u8]) -> anyhow::Result<ImageData> {
+	let mut reader = Cursor::new(lump);
 
 	let size = [
 		reader.read_u16::<LE>()? as usize,
@@ -90,10 +243,26 @@ pub fn import_patch(
 		reader.read_i16::<LE>()? as isize,
 		reader.read_i16::<LE>()? as isize,
 	);
-	let mut column_offsets = Vec::new();
 
-	for _ in 0..size[0] {
-		column_offsets.push(reader.read_u32::<LE>()? as u64);
+	ensure!(
+		8 + size[0] * 4 <= lump.len(),
+		"Patch is {} columns wide, but has no room for the column offsets",
+		size[0]
+	);
+
+	let mut column_offsets = Vec::with_capacity(size[0]);
+
+	for col in 0..size[0] {
+		let column_offset = reader.read_u32::<LE>()? as u64;
+
+		ensure!(
+			column_offset < lump.len() as u64,
+			"Column {} has offset {} past the end of the patch",
+			col,
+			column_offset
+		);
+
+		column_offsets.push(column_offset);
 	}
 
 	let mut data = vec![IAColor::default(); size[0] * size[1]];
@@ -107,19 +276,28 @@ pub fn import_patch(
 			let post_height = reader.read_u8()? as usize;
 			let mut post_pixels = vec![0u8; post_height];
 			reader.read_u8()?; // Padding byte
-			reader.read_exact(&mut post_pixels)?;
+			reader
+				.read_exact(&mut post_pixels)
+				.with_context(|| format!("Post in column {} is cut off", col))?;
 			reader.read_u8()?; // Padding byte
 
+			ensure!(
+				start_row + post_height <= size[1],
+				"Post in column {} extends below the image",
+				col
+			);
+
 			// Paint the pixels onto the main image
 			for i in 0..post_pixels.len() {
-				assert!(start_row + i < size[1]);
 				data[size[0] * (start_row as usize + i) + col].i = post_pixels[i];
 				data[size[0] * (start_row as usize + i) + col].a = 0xFF;
 			}
 
-			start_row = reader.read_u8()? as usize;
+			start_row = reader
+				.read_u8()
+				.with_context(|| format!("Column {} has no end marker", col))? as usize;
 		}
 	}
 
-	Ok(Box::new(ImageData { data, size, offset }))
+	Ok(ImageData { data, size, offset })
 }
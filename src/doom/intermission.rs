@@ -0,0 +1,208 @@
+use crate::{
+	common::{
+		assets::{AssetHandle, AssetStorage},
+		gamestate::{GameState, GameStateMachine},
+		input::{Bindings, InputState},
+		profiler,
+	},
+	doom::{
+		image::Image,
+		input::{BoolInput, FloatInput},
+		language::StringTable,
+		mapinfo::map_info,
+		music::play_music,
+		profile::GameProfile,
+		stats::{format_time, par_time, LevelStats},
+		ui::{HudFont, UiAlignment, UiImage, UiTransform},
+	},
+};
+use legion::{systems::ResourceSet, Read, Resources, World, Write};
+use nalgebra::Vector2;
+use relative_path::RelativePath;
+
+/// The level that was just finished, and where to go once the intermission is over.
+#[derive(Clone, Debug, Default)]
+pub struct IntermissionInfo {
+	pub map: String,
+	pub next_map: Option<String>,
+}
+
+const LINE_HEIGHT: f32 = 12.0;
+
+/// Sets up the intermission screen, with the world already cleared.
+pub fn enter_intermission(resources: &mut Resources) {
+	let music = <Read<GameProfile>>::fetch(resources).intermission_music();
+	play_music(music, resources);
+}
+
+/// Shows the statistics of the finished level, and moves on to the next one when the player
+/// presses use or fire.
+pub fn intermission_system(
+	resources: &mut Resources,
+) -> Box<dyn FnMut(&mut World, &mut Resources)> {
+	let font = {
+		let (mut asset_storage, game_profile) =
+			<(Write<AssetStorage>, Read<GameProfile>)>::fetch_mut(resources);
+		HudFont::load(&mut asset_storage, *game_profile)
+	};
+	let mut background: Option<AssetHandle<Image>> = None;
+	let mut shown = false;
+	let mut was_pressed = true;
+
+	Box::new(move |world, resources| {
+		let _profile = profiler::scope("intermission_system");
+
+		let (
+			mut asset_storage,
+			bindings,
+			game_profile,
+			input_state,
+			mut game_state,
+			info,
+			level_stats,
+			string_table,
+		) = <(
+			Write<AssetStorage>,
+			Read<Bindings<BoolInput, FloatInput>>,
+			Read<GameProfile>,
+			Read<InputState>,
+			Write<GameStateMachine>,
+			Read<IntermissionInfo>,
+			Read<LevelStats>,
+			Read<StringTable>,
+		)>::fetch_mut(resources);
+
+		if *game_state.current() != GameState::Intermission {
+			// Don't let a button that's still held from gameplay skip the intermission
+			background = None;
+			shown = false;
+			was_pressed = true;
+			return;
+		}
+
+		let pressed = bindings.bool_value(&BoolInput::Use, &input_state)
+			|| bindings.bool_value(&BoolInput::Attack, &input_state);
+
+		if pressed && !was_pressed {
+			game_state.set(match &info.next_map {
+				Some(map) => GameState::Loading { map: map.clone() },
+				None => GameState::Title,
+			});
+		}
+
+		was_pressed = pressed;
+
+		if shown {
+			return;
+		}
+
+		// Doom 2 has a single background, Doom 1 has one for each episode,
+		// Heretic has maps for its first three
+		let background = background.get_or_insert_with(|| {
+			let map = info.map.to_ascii_lowercase();
+			let name = if *game_profile == GameProfile::Heretic && map.starts_with('e') {
+				format!("mape{}", map.get(1..2).unwrap_or_default())
+			} else if map.starts_with('e') && map.len() >= 2 {
+				format!("wimap{}", map.as_bytes()[1].saturating_sub(b'1').min(2))
+			} else {
+				String::from("interpic")
+			};
+
+			if asset_storage.source().exists(RelativePath::new(&name)) {
+				asset_storage.load(&format!("{}.patch", name))
+			} else {
+				asset_storage.load(game_profile.border_flat())
+			}
+		});
+
+		// The images aren't available until the renderer has processed them
+		if !font.is_ready(&asset_storage) || asset_storage.get(background).is_none() {
+			return;
+		}
+
+		shown = true;
+
+		world.push((
+			UiTransform {
+				position: Vector2::new(0.0, 0.0),
+				depth: 1.0,
+				alignment: [UiAlignment::Middle, UiAlignment::Middle],
+				size: Vector2::new(320.0, 200.0),
+				stretch: [false; 2],
+			},
+			UiImage {
+				image: background.clone(),
+			},
+		));
+
+		let mut lines = vec![
+			string_table.format(
+				"WI_FINISHED",
+				&[&map_info(&asset_storage, &info.map)
+					.and_then(|entry| entry.level_name.as_deref())
+					.and_then(|name| string_table.resolve(name))
+					.unwrap_or(&info.map)],
+			),
+			String::new(),
+			string_table.format(
+				"WI_KILLS",
+				&[&LevelStats::percentage(
+					level_stats.kills,
+					level_stats.total_kills,
+				)],
+			),
+			string_table.format(
+				"WI_ITEMS",
+				&[&LevelStats::percentage(
+					level_stats.items,
+					level_stats.total_items,
+				)],
+			),
+			string_table.format(
+				"WI_SECRETS",
+				&[&LevelStats::percentage(
+					level_stats.secrets,
+					level_stats.total_secrets,
+				)],
+			),
+			String::new(),
+			string_table.format("WI_TIME", &[&format_time(level_stats.time)]),
+		];
+
+		if let Some(par) = par_time(&info.map, &asset_storage, *game_profile) {
+			lines.push(string_table.format("WI_PAR", &[&format_time(par)]));
+
+			if level_stats.time <= par {
+				lines.push(
+					string_table.format("WI_UNDERPAR", &[&format_time(par - level_stats.time)]),
+				);
+			} else {
+				lines.push(
+					string_table.format("WI_OVERPAR", &[&format_time(level_stats.time - par)]),
+				);
+			}
+		}
+
+		let top = 100.0 - (lines.len() as f32 * LINE_HEIGHT * 0.5).floor();
+
+		for (i, text) in lines.iter().enumerate() {
+			let width = font.width(text, &asset_storage);
+
+			font.spawn_text(
+				world,
+				&asset_storage,
+				text,
+				UiTransform {
+					position: Vector2::new(
+						160.0 - (width * 0.5).floor(),
+						top + i as f32 * LINE_HEIGHT,
+					),
+					depth: 2.0,
+					alignment: [UiAlignment::Middle, UiAlignment::Middle],
+					size: Vector2::new(0.0, 0.0),
+					stretch: [false; 2],
+				},
+			);
+		}
+	})
+}
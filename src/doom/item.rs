@@ -0,0 +1,269 @@
+use crate::{
+	common::{
+		assets::{AssetHandle, AssetStorage},
+		configvars::ConfigVariables,
+		events::{register_reader, Events},
+		frame::FrameState,
+		profiler,
+		quadtree::Quadtree,
+	},
+	doom::{
+		client::User,
+		components::Transform,
+		entitytemplate::{EntityTemplate, EntityTemplateRef},
+		game::GameMode,
+		health::Dead,
+		map::spawn::{spawn_entity, SpawnRequest},
+		message::HudMessage,
+		palette::PaletteEffects,
+		physics::TouchEvent,
+		sound::Sound,
+		stats::{CountItem, LevelStats},
+	},
+};
+use fnv::FnvHashSet;
+use legion::{
+	component,
+	systems::{ResourceSet, Runnable},
+	Entity, IntoQuery, Read, Resources, SystemBuilder, World, Write,
+};
+use nalgebra::Vector3;
+use serde::Deserialize;
+use std::{collections::VecDeque, time::Duration};
+
+/// How long it takes for a picked up item to come back.
+pub const ITEM_RESPAWN_TIME: Duration = Duration::from_secs(30);
+
+/// Marks an item that was dropped by a dying monster, rather than placed in the map.
+/// Dropped items don't respawn after being picked up. The original game also gives
+/// half as much ammo for them, which isn't done here because items don't give ammo yet.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Dropped;
+
+/// Returns a request to spawn the drop item of `template`, if it has one,
+/// on the floor below where an entity with that template died.
+pub fn drop_item_request(template: &EntityTemplate, transform: &Transform) -> Option<SpawnRequest> {
+	template.drop_item.map(|drop_item| SpawnRequest {
+		template_name: drop_item,
+		transform: Transform {
+			position: Vector3::new(transform.position[0], transform.position[1], f32::NAN),
+			rotation: transform.rotation,
+		},
+		velocity: Vector3::zeros(),
+		dropped: true,
+	})
+}
+
+/// An item that a player can pick up by touching it.
+#[derive(Clone, Debug)]
+pub struct Pickup {
+	/// Sound played when the item is picked up.
+	pub sound: AssetHandle<Sound>,
+	/// Message shown when the item is picked up.
+	/// If it starts with `$`, it names a string in the `StringTable`.
+	pub message: Option<String>,
+}
+
+/// A power that a player gets for a while when picking up an item.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum Power {
+	Invulnerability,
+	RadiationSuit,
+}
+
+/// An item that gives a power when picked up, along with its `Pickup`.
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PowerPickup {
+	pub power: Power,
+	/// How long the power lasts, in frames.
+	pub duration: u32,
+}
+
+/// How much the screen flashes when picking up an item, in frames.
+const BONUS_COUNT: u32 = 6;
+
+/// An item that was picked up, waiting to be spawned again.
+#[derive(Clone, Debug)]
+pub struct ItemRespawn {
+	pub template_handle: AssetHandle<EntityTemplate>,
+	/// Where the item was originally spawned.
+	pub transform: Transform,
+	/// Game time at which the item reappears.
+	pub time: Duration,
+}
+
+/// Items that will respawn, in the order they were picked up.
+#[derive(Clone, Debug, Default)]
+pub struct ItemRespawnQueue {
+	queue: VecDeque<ItemRespawn>,
+}
+
+impl ItemRespawnQueue {
+	/// Schedules an item to reappear at `transform` after `ITEM_RESPAWN_TIME`.
+	/// Should be called when a player picks up an item.
+	/// Only deathmatch games with `sv_itemrespawn` enabled will actually respawn it.
+	pub fn push(
+		&mut self,
+		template_handle: AssetHandle<EntityTemplate>,
+		transform: Transform,
+		frame_state: &FrameState,
+	) {
+		self.queue.push_back(ItemRespawn {
+			template_handle,
+			transform,
+			time: frame_state.time + ITEM_RESPAWN_TIME,
+		});
+	}
+
+	pub fn clear(&mut self) {
+		self.queue.clear();
+	}
+}
+
+pub fn item_respawn_system(
+	resources: &mut Resources,
+) -> Box<dyn FnMut(&mut World, &mut Resources)> {
+	resources.insert(ItemRespawnQueue::default());
+	let sound_handle: AssetHandle<Sound> =
+		<Write<AssetStorage>>::fetch_mut(resources).load("dsitmbk.sound");
+
+	Box::new(move |world, resources| {
+		let _profile = profiler::scope("item_respawn_system");
+
+		let (due, fog_handle) = {
+			let (asset_storage, config_variables, frame_state, game_mode, mut queue) =
+				<(
+					Read<AssetStorage>,
+					Read<ConfigVariables>,
+					Read<FrameState>,
+					Read<GameMode>,
+					Write<ItemRespawnQueue>,
+				)>::fetch_mut(resources);
+
+			if *game_mode != GameMode::Deathmatch || !config_variables.get::<bool>("sv_itemrespawn")
+			{
+				queue.clear();
+				return;
+			}
+
+			let mut due = Vec::new();
+
+			while queue
+				.queue
+				.front()
+				.map_or(false, |item| item.time <= frame_state.time)
+			{
+				due.extend(queue.queue.pop_front());
+			}
+
+			if due.is_empty() {
+				return;
+			}
+
+			(due, asset_storage.handle_for::<EntityTemplate>("ifog"))
+		};
+
+		for item in due {
+			spawn_entity(world, resources, item.template_handle, item.transform);
+
+			// Flash and sound where the item appeared
+			if let Some(fog_handle) = &fog_handle {
+				let fog_entity = spawn_entity(world, resources, fog_handle.clone(), item.transform);
+				<Write<Vec<(AssetHandle<Sound>, Entity)>>>::fetch_mut(resources)
+					.push((sound_handle.clone(), fog_entity));
+			}
+		}
+	})
+}
+
+/// Lets living players pick up items by touching them.
+/// Items don't have any effect on the player yet, they only flash the screen, show their
+/// message and are counted.
+pub fn pickup_system(resources: &mut Resources) -> impl Runnable {
+	register_reader::<TouchEvent>(resources, "pickup_system");
+
+	SystemBuilder::new("pickup_system")
+		.write_resource::<Events<TouchEvent>>()
+		.read_resource::<FrameState>()
+		.write_resource::<HudMessage>()
+		.write_resource::<ItemRespawnQueue>()
+		.write_resource::<LevelStats>()
+		.write_resource::<Quadtree>()
+		.write_resource::<Vec<(AssetHandle<Sound>, Entity)>>()
+		.with_query(
+			<&mut PaletteEffects>::query().filter(component::<User>() & !component::<Dead>()),
+		)
+		.with_query(<(
+			&EntityTemplateRef,
+			&Pickup,
+			&Transform,
+			Option<&CountItem>,
+			Option<&Dropped>,
+			Option<&PowerPickup>,
+		)>::query())
+		.build(move |command_buffer, world, resources, queries| {
+			let _profile = profiler::scope("pickup_system");
+
+			let (
+				touch_event_channel,
+				frame_state,
+				hud_message,
+				item_respawn_queue,
+				level_stats,
+				quadtree,
+				sound_queue,
+			) = resources;
+
+			let (mut world0, world) = world.split_for_query(&queries.0);
+			let mut picked_up = FnvHashSet::default();
+
+			for touch_event in touch_event_channel.read("pickup_system") {
+				if picked_up.contains(&touch_event.touched) {
+					continue;
+				}
+
+				let palette_effects = match queries.0.get_mut(&mut world0, touch_event.toucher) {
+					Ok(palette_effects) => palette_effects,
+					Err(_) => continue,
+				};
+
+				let (template_ref, pickup, transform, count_item, dropped, power_pickup) =
+					match queries.1.get(&world, touch_event.touched) {
+						Ok(x) => x,
+						Err(_) => continue,
+					};
+
+				palette_effects.bonus_count += BONUS_COUNT;
+
+				if let Some(power_pickup) = power_pickup {
+					match power_pickup.power {
+						Power::Invulnerability => {
+							palette_effects.invulnerability = power_pickup.duration
+						}
+						Power::RadiationSuit => {
+							palette_effects.radiation_suit = power_pickup.duration
+						}
+					}
+				}
+
+				sound_queue.push((pickup.sound.clone(), touch_event.toucher));
+
+				if let Some(message) = &pickup.message {
+					hud_message.show(message);
+				}
+
+				if count_item.is_some() {
+					level_stats.items += 1;
+				}
+
+				if dropped.is_none() {
+					item_respawn_queue.push(template_ref.0.clone(), *transform, frame_state);
+				}
+
+				picked_up.insert(touch_event.touched);
+				command_buffer.remove(touch_event.touched);
+				quadtree.remove(touch_event.touched);
+			}
+		})
+}
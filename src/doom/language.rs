@@ -0,0 +1,225 @@
+use crate::{
+	common::{assets::AssetStorage, configvars::ConfigVariables},
+	doom::{
+		data::strings::STRINGS,
+		decorate::{Token, Tokenizer},
+	},
+};
+use anyhow::{bail, Context};
+use fnv::FnvHashMap;
+use legion::{systems::ResourceSet, Read, Resources};
+use relative_path::RelativePath;
+use std::fmt::{Display, Write};
+
+/// Lump that replaces the built-in text, in the format of ZDoom's LANGUAGE lumps.
+const LANGUAGE_LUMP: &str = "language";
+
+/// The text that is shown to the player, by string name.
+/// Text elsewhere that starts with `$`, such as a level name in MAPINFO or a pickup message,
+/// refers to one of these by the rest of its name.
+#[derive(Clone, Debug)]
+pub struct StringTable {
+	strings: FnvHashMap<String, String>,
+}
+
+impl Default for StringTable {
+	fn default() -> StringTable {
+		StringTable {
+			strings: STRINGS
+				.iter()
+				.map(|(&name, &text)| (name.to_owned(), text.to_owned()))
+				.collect(),
+		}
+	}
+}
+
+impl StringTable {
+	pub fn get(&self, name: &str) -> Option<&str> {
+		self.strings
+			.get(&name.to_ascii_uppercase())
+			.map(String::as_str)
+	}
+
+	/// Returns `text` itself, or the string it refers to if it starts with `$`.
+	/// Returns `None` if it refers to a string that doesn't exist.
+	pub fn resolve<'a>(&'a self, text: &'a str) -> Option<&'a str> {
+		if text.starts_with('$') {
+			self.get(&text[1..])
+		} else {
+			Some(text)
+		}
+	}
+
+	/// Returns the text of a string with each `{}` replaced by the next of `args`.
+	/// A missing string gives its name instead, so that it stands out.
+	pub fn format(&self, name: &str, args: &[&dyn Display]) -> String {
+		let text = self.get(name).unwrap_or(name);
+		let mut parts = text.split("{}");
+		let mut args = args.iter();
+		let mut result = parts.next().unwrap_or_default().to_owned();
+
+		for part in parts {
+			if let Some(arg) = args.next() {
+				write!(result, "{}", arg).ok();
+			}
+
+			result.push_str(part);
+		}
+
+		result
+	}
+
+	/// Adds the strings defined in the text of a LANGUAGE lump, replacing any with the same name.
+	/// Sections marked `default` are always used, and those for `locale` override them.
+	/// Returns how many strings were added.
+	pub fn parse_language(&mut self, text: &str, locale: &str) -> anyhow::Result<usize> {
+		let sections = parse_sections(text)?;
+		let mut count = 0;
+
+		for use_default in [true, false].iter() {
+			for (names, strings) in &sections {
+				let is_default = names.iter().any(|name| name == "default");
+				let is_locale = names.iter().any(|name| name.eq_ignore_ascii_case(locale));
+
+				if (*use_default && is_default) || (!*use_default && is_locale && !is_default) {
+					for (name, text) in strings {
+						self.strings.insert(name.to_ascii_uppercase(), text.clone());
+						count += 1;
+					}
+				}
+			}
+		}
+
+		Ok(count)
+	}
+}
+
+/// Loads the strings into a `StringTable` resource: the built-in text first, then the
+/// LANGUAGE lump and the locale file in `lang_file` if there are any.
+/// `lang` picks which sections of those are used.
+pub fn load_strings(resources: &mut Resources) -> anyhow::Result<()> {
+	let mut string_table = StringTable::default();
+
+	{
+		let (asset_storage, config_variables) =
+			<(Read<AssetStorage>, Read<ConfigVariables>)>::fetch(resources);
+		let locale = config_variables.get::<String>("lang");
+
+		let path = RelativePath::new(LANGUAGE_LUMP);
+
+		if asset_storage.source().exists(path) {
+			let data = asset_storage.source().load(path)?;
+			let count = string_table
+				.parse_language(&String::from_utf8_lossy(&data), &locale)
+				.context("Couldn't parse LANGUAGE")?;
+			log::info!("Loaded {} strings from LANGUAGE", count);
+		}
+
+		let file = config_variables.get::<String>("lang_file");
+
+		if !file.is_empty() {
+			let text = std::fs::read_to_string(&file)
+				.with_context(|| format!("Couldn't read \"{}\"", file))?;
+			let count = string_table
+				.parse_language(&text, &locale)
+				.with_context(|| format!("Couldn't parse \"{}\"", file))?;
+			log::info!("Loaded {} strings from \"{}\"", count, file);
+		}
+	}
+
+	resources.insert(string_table);
+	Ok(())
+}
+
+type Section = (Vec<String>, Vec<(String, String)>);
+
+/// Splits LANGUAGE text into sections, each with its locale names and its strings.
+/// Strings before the first section header belong to every locale.
+fn parse_sections(text: &str) -> anyhow::Result<Vec<Section>> {
+	let mut tokens = Tokenizer::new(text)
+		.filter(|token| !matches!(token, Ok(Token::Newline)))
+		.collect::<Result<Vec<_>, _>>()?;
+	tokens.reverse();
+
+	let mut sections: Vec<Section> = vec![(vec!["default".to_owned()], Vec::new())];
+
+	while let Some(token) = tokens.pop() {
+		match token {
+			// The tokenizer keeps the brackets of a header as part of the words
+			Token::Word(word) if word.starts_with('[') => {
+				let mut names = Vec::new();
+				let mut word = word;
+
+				loop {
+					let end = word.ends_with(']');
+					names.extend(
+						word.trim_matches(|c| c == '[' || c == ']')
+							.split_whitespace()
+							.map(str::to_owned),
+					);
+
+					if end {
+						break;
+					}
+
+					word = match tokens.pop() {
+						Some(Token::Word(word)) => word,
+						token => bail!("Expected a locale name, found {:?}", token),
+					};
+				}
+
+				sections.push((names, Vec::new()));
+			}
+			// Conditions like `$ifgame(doom)` are ignored, the string is always used
+			Token::Symbol('$') => {
+				while let Some(token) = tokens.pop() {
+					if token == Token::Symbol(')') {
+						break;
+					}
+				}
+			}
+			Token::Word(name) => {
+				match tokens.pop() {
+					Some(Token::Symbol('=')) => {}
+					token => bail!("Expected '=' after \"{}\", found {:?}", name, token),
+				}
+
+				// Consecutive strings are joined together
+				let mut text = String::new();
+
+				loop {
+					match tokens.pop() {
+						Some(Token::String(string)) => text.push_str(&unescape(&string)),
+						Some(Token::Symbol(';')) => break,
+						token => bail!("Expected a string for \"{}\", found {:?}", name, token),
+					}
+				}
+
+				sections.last_mut().unwrap().1.push((name, text));
+			}
+			token => bail!("Expected a string name, found {:?}", token),
+		}
+	}
+
+	Ok(sections)
+}
+
+fn unescape(string: &str) -> String {
+	let mut result = String::with_capacity(string.len());
+	let mut chars = string.chars();
+
+	while let Some(c) = chars.next() {
+		if c == '\\' {
+			match chars.next() {
+				Some('n') => result.push('\n'),
+				Some('t') => result.push('\t'),
+				Some(c) => result.push(c),
+				None => {}
+			}
+		} else {
+			result.push(c);
+		}
+	}
+
+	result
+}
@@ -2,6 +2,7 @@ use crate::{
 	common::{
 		assets::AssetStorage,
 		frame::{FrameRng, FrameState},
+		profiler,
 		spawn::{ComponentAccessor, SpawnFrom},
 		time::Timer,
 	},
@@ -11,19 +12,22 @@ use crate::{
 	},
 };
 use legion::{
-	systems::{ResourceSet, Runnable},
+	systems::{ParallelRunnable, ResourceSet},
 	IntoQuery, Read, Resources, SystemBuilder,
 };
 use rand::Rng;
+use serde::Deserialize;
 use std::time::Duration;
 
-pub fn light_flash_system() -> impl Runnable {
+pub fn light_flash_system() -> impl ParallelRunnable {
 	SystemBuilder::new("light_flash_system")
 		.read_resource::<AssetStorage>()
 		.read_resource::<FrameState>()
 		.with_query(<(&SectorRef, &mut FrameRng, &mut LightFlash)>::query())
 		.with_query(<&mut MapDynamic>::query())
 		.build(move |_, world, resources, queries| {
+			let _profile = profiler::scope("light_flash_system");
+
 			let (asset_storage, frame_state) = resources;
 			let (mut world0, mut world) = world.split_for_query(&queries.0);
 
@@ -145,13 +149,15 @@ impl SpawnFrom<LightFlashDef> for LightFlash {
 	}
 }
 
-pub fn light_glow_system() -> impl Runnable {
+pub fn light_glow_system() -> impl ParallelRunnable {
 	SystemBuilder::new("light_glow_system")
 		.read_resource::<AssetStorage>()
 		.read_resource::<FrameState>()
 		.with_query(<(&SectorRef, &mut LightGlow)>::query())
 		.with_query(<&mut MapDynamic>::query())
 		.build(move |_, world, resources, queries| {
+			let _profile = profiler::scope("light_glow_system");
+
 			let (asset_storage, frame_state) = resources;
 			let (mut world0, mut world) = world.split_for_query(&queries.0);
 
@@ -197,3 +203,16 @@ pub struct LightGlow {
 	pub speed: f32,
 	pub state: bool,
 }
+
+/// A point light that brightens the map around an entity, if `r_dynlights` is enabled.
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct DynamicLight {
+	pub colour: [f32; 3],
+	pub radius: f32,
+}
+
+/// The light given off by a player's weapon while its flash sprite is showing.
+pub const MUZZLE_FLASH_LIGHT: DynamicLight = DynamicLight {
+	colour: [1.0, 0.8, 0.5],
+	radius: 160.0,
+};
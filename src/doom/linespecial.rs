@@ -0,0 +1,453 @@
+use crate::{
+	common::{
+		assets::{AssetHandle, AssetStorage},
+		events::{register_reader, Events},
+		frame::FrameState,
+		gamestate::{GameState, GameStateMachine},
+		geometry::Side,
+		profiler,
+	},
+	doom::{
+		acs::AcsState,
+		client::{UseAction, UseEvent, User},
+		data::{FRAME_RATE, FRAME_TIME},
+		door::{self, DoorActive, DoorParams, DoorState},
+		exit,
+		floor::{self, FloorActive, FloorParams, FloorTargetHeight},
+		health::Dead,
+		intermission::IntermissionInfo,
+		map::{LineSpecial, LinedefRef, Map, MapDynamic},
+		physics::{TouchAction, TouchEvent},
+		plat::{self, PlatActive, PlatParams, PlatTargetHeight},
+		profile::GameProfile,
+		progression::ExitType,
+		sound::Sound,
+		switch::{self, SwitchActive, SwitchParams},
+	},
+};
+use legion::{
+	component,
+	storage::Component,
+	systems::{CommandBuffer, ResourceSet},
+	Entity, EntityStore, IntoQuery, Read, Resources, World, Write,
+};
+use std::time::Duration;
+
+/// A Hexen-format linedef special, run when a player crosses the linedef.
+#[derive(Clone, Debug)]
+pub struct LineSpecialTouch {
+	pub special: LineSpecial,
+}
+
+pub fn line_special_touch_system(
+	resources: &mut Resources,
+) -> Box<dyn FnMut(&mut World, &mut Resources)> {
+	register_reader::<TouchEvent>(resources, "line_special_touch_system");
+
+	Box::new(move |world, resources| {
+		let _profile = profiler::scope("line_special_touch_system");
+
+		let touch_events: Vec<TouchEvent> = <Write<Events<TouchEvent>>>::fetch_mut(resources)
+			.read("line_special_touch_system")
+			.filter(|touch_event| touch_event.collision.is_none())
+			.copied()
+			.collect();
+
+		for touch_event in touch_events {
+			// Only players activate lines by crossing them
+			if <&User>::query()
+				.filter(!component::<Dead>())
+				.get(world, touch_event.toucher)
+				.is_err()
+			{
+				continue;
+			}
+
+			let (linedef_index, special) =
+				match <(&LinedefRef, &TouchAction)>::query().get(world, touch_event.touched) {
+					Ok((linedef_ref, TouchAction::LineSpecialTouch(touch))) => {
+						(linedef_ref.index, touch.special)
+					}
+					_ => continue,
+				};
+
+			if execute_line_special(
+				special.special,
+				special_args(&special),
+				Some(linedef_index),
+				world,
+				resources,
+			) && !special.repeatable
+			{
+				if let Some(mut entry) = world.entry(touch_event.touched) {
+					entry.remove_component::<TouchAction>();
+				}
+			}
+		}
+	})
+}
+
+/// A Hexen-format linedef special, run when a player uses the linedef.
+/// Switch textures on the linedef are flipped when the special does something.
+#[derive(Clone, Debug)]
+pub struct LineSpecialUse {
+	pub special: LineSpecial,
+}
+
+pub fn line_special_use_system(
+	resources: &mut Resources,
+) -> Box<dyn FnMut(&mut World, &mut Resources)> {
+	register_reader::<UseEvent>(resources, "line_special_use_system");
+	let switch_sound: AssetHandle<Sound> =
+		<Write<AssetStorage>>::fetch_mut(resources).load("dsswtchn.sound");
+
+	Box::new(move |world, resources| {
+		let _profile = profiler::scope("line_special_use_system");
+
+		let use_events: Vec<UseEvent> = <Write<Events<UseEvent>>>::fetch_mut(resources)
+			.read("line_special_use_system")
+			.copied()
+			.collect();
+
+		for use_event in use_events {
+			let entity = use_event.linedef_entity;
+			let (linedef_index, special) = match <(&LinedefRef, &UseAction)>::query()
+				.filter(!component::<SwitchActive>())
+				.get(world, entity)
+			{
+				Ok((linedef_ref, UseAction::LineSpecialUse(line_special_use))) => {
+					(linedef_ref.index, line_special_use.special)
+				}
+				_ => continue,
+			};
+
+			if !execute_line_special(
+				special.special,
+				special_args(&special),
+				Some(linedef_index),
+				world,
+				resources,
+			) {
+				continue;
+			}
+
+			let mut command_buffer = CommandBuffer::new(world);
+
+			{
+				let (asset_storage, frame_state, mut sound_queue) = <(
+					Read<AssetStorage>,
+					Read<FrameState>,
+					Write<Vec<(AssetHandle<Sound>, Entity)>>,
+				)>::fetch_mut(resources);
+
+				if let Some(map_dynamic) = <&mut MapDynamic>::query().iter_mut(world).next() {
+					let map = asset_storage.get(&map_dynamic.map).unwrap();
+					let switch_params = SwitchParams {
+						sound: Some(switch_sound.clone()),
+						retrigger_time: if special.repeatable {
+							Some(35 * FRAME_TIME)
+						} else {
+							None
+						},
+					};
+
+					if !special.repeatable {
+						command_buffer.remove_component::<UseAction>(entity);
+					}
+
+					switch::activate(
+						&switch_params,
+						&mut command_buffer,
+						&mut sound_queue,
+						&frame_state,
+						linedef_index,
+						map,
+						map_dynamic,
+					);
+				}
+			}
+
+			command_buffer.flush(world);
+		}
+	})
+}
+
+fn special_args(special: &LineSpecial) -> [i32; 5] {
+	let mut args = [0; 5];
+
+	for (arg, &value) in args.iter_mut().zip(special.args.iter()) {
+		*arg = value as i32;
+	}
+
+	args
+}
+
+/// What a special does to the sectors it affects.
+enum SectorAction {
+	Door(DoorParams),
+	Floor(FloorParams),
+	Plat(PlatParams),
+}
+
+/// Runs Hexen linedef special `special`, with arguments `args`.
+/// `line` is the linedef that triggered it, if any; specials with a sector tag of 0
+/// affect the sector behind it.
+/// Returns whether the special did something.
+pub fn execute_line_special(
+	special: u8,
+	args: [i32; 5],
+	line: Option<usize>,
+	world: &mut World,
+	resources: &mut Resources,
+) -> bool {
+	// Speeds are in eighths of a map unit per frame
+	let speed = args[1] as f32 / 8.0 * FRAME_RATE;
+
+	let action = {
+		let mut asset_storage = <Write<AssetStorage>>::fetch_mut(resources);
+
+		match special {
+			// Door_Close
+			10 => SectorAction::Door(DoorParams {
+				start_state: DoorState::Open,
+				end_state: DoorState::Closed,
+				speed,
+				wait_time: Duration::default(),
+				can_reverse: true,
+
+				open_sound: Some(asset_storage.load("dsdoropn.sound")),
+				close_sound: Some(asset_storage.load("dsdorcls.sound")),
+			}),
+			// Door_Open
+			11 => SectorAction::Door(DoorParams {
+				start_state: DoorState::Closed,
+				end_state: DoorState::Open,
+				speed,
+				wait_time: Duration::default(),
+				can_reverse: true,
+
+				open_sound: Some(asset_storage.load("dsdoropn.sound")),
+				close_sound: Some(asset_storage.load("dsdorcls.sound")),
+			}),
+			// Door_Raise, Door_LockedRaise
+			// There are no keys to check yet, so locked doors open for anyone
+			12 | 13 => SectorAction::Door(DoorParams {
+				start_state: DoorState::Closed,
+				end_state: DoorState::Closed,
+				speed,
+				wait_time: args[2] as u32 * FRAME_TIME,
+				can_reverse: true,
+
+				open_sound: Some(asset_storage.load("dsdoropn.sound")),
+				close_sound: Some(asset_storage.load("dsdorcls.sound")),
+			}),
+			// Floor_LowerByValue, Floor_LowerToLowest, Floor_RaiseByValue,
+			// Floor_RaiseToHighest, Floor_RaiseToNearest
+			20 | 21 | 23 | 24 | 25 => {
+				let (target_height_base, target_height_offset) = match special {
+					20 => (FloorTargetHeight::Current, -args[2] as f32),
+					21 => (FloorTargetHeight::LowestNeighbourFloor, 0.0),
+					23 => (FloorTargetHeight::Current, args[2] as f32),
+					24 => (FloorTargetHeight::HighestNeighbourFloor, 0.0),
+					_ => (FloorTargetHeight::LowestNeighbourFloorAbove, 0.0),
+				};
+
+				SectorAction::Floor(FloorParams {
+					speed,
+					target_height_base,
+					target_height_offset,
+					move_sound: Some(asset_storage.load("dsstnmov.sound")),
+					move_sound_time: 8 * FRAME_TIME,
+					finish_sound: Some(asset_storage.load("dspstop.sound")),
+				})
+			}
+			// Plat_DownWaitUpStay
+			62 => SectorAction::Plat(PlatParams {
+				speed,
+				wait_time: args[2] as u32 * FRAME_TIME,
+				can_reverse: true,
+
+				start_sound: Some(asset_storage.load("dspstart.sound")),
+				move_sound: None,
+				move_sound_time: 8 * FRAME_TIME,
+				finish_sound: Some(asset_storage.load("dspstop.sound")),
+
+				low_height_base: PlatTargetHeight::LowestNeighbourFloor,
+				low_height_offset: 0.0,
+				high_height_base: PlatTargetHeight::Current,
+				high_height_offset: 0.0,
+			}),
+			// Teleport_NewMap
+			74 => {
+				<Write<GameStateMachine>>::fetch_mut(resources).set(GameState::Loading {
+					map: format!("MAP{:02}", args[0]),
+				});
+				return true;
+			}
+			// ACS_Execute, ACS_Suspend, ACS_Terminate, ACS_LockedExecute, ACS_ExecuteAlways
+			80..=83 | 226 => {
+				drop(asset_storage);
+				return execute_acs_special(special, args, line, world, resources);
+			}
+			// Exit_Normal, Exit_Secret
+			243 | 244 => {
+				let (game_profile, mut game_state, mut intermission_info) =
+					<(
+						Read<GameProfile>,
+						Write<GameStateMachine>,
+						Write<IntermissionInfo>,
+					)>::fetch_mut(resources);
+				let map_dynamic = match <&MapDynamic>::query().iter(world).next() {
+					Some(map_dynamic) => map_dynamic,
+					None => return false,
+				};
+
+				exit::exit_level(
+					if special == 243 {
+						ExitType::Normal
+					} else {
+						ExitType::Secret
+					},
+					&asset_storage,
+					*game_profile,
+					map_dynamic,
+					&mut game_state,
+					&mut intermission_info,
+				);
+				return true;
+			}
+			_ => {
+				log::debug!("Line special {} is not supported", special);
+				return false;
+			}
+		}
+	};
+
+	let (asset_storage, frame_state) = <(Read<AssetStorage>, Read<FrameState>)>::fetch(resources);
+	let mut command_buffer = CommandBuffer::new(world);
+	let mut activated = false;
+
+	{
+		let map_dynamic = match <&MapDynamic>::query().iter(world).next() {
+			Some(map_dynamic) => map_dynamic,
+			None => return false,
+		};
+		let map = asset_storage.get(&map_dynamic.map).unwrap();
+		let sectors = tagged_sectors(args[0], line, map);
+
+		match &action {
+			SectorAction::Door(params) => {
+				for sector_index in idle_sectors::<DoorActive>(&sectors, world, map_dynamic) {
+					door::activate(
+						params,
+						&mut command_buffer,
+						&frame_state,
+						sector_index,
+						map,
+						map_dynamic,
+					);
+					activated = true;
+				}
+			}
+			SectorAction::Floor(params) => {
+				for sector_index in idle_sectors::<FloorActive>(&sectors, world, map_dynamic) {
+					floor::activate(
+						params,
+						&mut command_buffer,
+						&frame_state,
+						sector_index,
+						map,
+						map_dynamic,
+					);
+					activated = true;
+				}
+			}
+			SectorAction::Plat(params) => {
+				for sector_index in idle_sectors::<PlatActive>(&sectors, world, map_dynamic) {
+					plat::activate(
+						params,
+						&mut command_buffer,
+						&frame_state,
+						sector_index,
+						map,
+						map_dynamic,
+					);
+					activated = true;
+				}
+			}
+		}
+	}
+
+	command_buffer.flush(world);
+	activated
+}
+
+fn execute_acs_special(
+	special: u8,
+	args: [i32; 5],
+	line: Option<usize>,
+	world: &World,
+	resources: &Resources,
+) -> bool {
+	// Scripts can only be started on the current map
+	if args[1] != 0 {
+		let asset_storage = <Read<AssetStorage>>::fetch(resources);
+		let current_map = <&MapDynamic>::query()
+			.iter(world)
+			.next()
+			.and_then(|map_dynamic| asset_storage.name_of(&map_dynamic.map));
+
+		if current_map != Some(&format!("map{:02}.map", args[1])) {
+			log::warn!(
+				"Can't run script {} on map {}, only on the current map",
+				args[0],
+				args[1]
+			);
+			return false;
+		}
+	}
+
+	let mut acs_state = <Write<AcsState>>::fetch_mut(resources);
+	let number = args[0] as u16;
+
+	match special {
+		// ACS_Execute, and ACS_LockedExecute since there are no keys yet
+		80 | 83 => acs_state.execute(number, [args[2], args[3], args[4]], line, false),
+		81 => acs_state.suspend(number),
+		82 => acs_state.terminate(number),
+		226 => acs_state.execute(number, [args[2], args[3], args[4]], line, true),
+		_ => unreachable!(),
+	}
+
+	true
+}
+
+/// Returns the sectors that a special with sector tag `tag` acts on.
+fn tagged_sectors(tag: i32, line: Option<usize>, map: &Map) -> Vec<usize> {
+	if tag != 0 {
+		return map.sectors_with_tag(tag as u16).to_vec();
+	}
+
+	line.and_then(|line| map.linedefs[line].sidedefs[Side::Left as usize].as_ref())
+		.map(|sidedef| vec![sidedef.sector_index])
+		.unwrap_or_default()
+}
+
+/// Returns the sectors in `sectors` that don't have a `T` already.
+fn idle_sectors<T: Component>(
+	sectors: &[usize],
+	world: &World,
+	map_dynamic: &MapDynamic,
+) -> Vec<usize> {
+	sectors
+		.iter()
+		.copied()
+		.filter(|&sector_index| {
+			world
+				.entry_ref(map_dynamic.sectors[sector_index].entity)
+				.unwrap()
+				.get_component::<T>()
+				.is_err()
+		})
+		.collect()
+}
@@ -0,0 +1,282 @@
+use crate::{
+	common::{
+		assets::{AssetHandle, AssetStorage},
+		configvars::ConfigVariables,
+	},
+	doom::{
+		image::{Palette, RGBAColor},
+		map::{
+			load::LinedefFlags,
+			meshes::{make_meshes, MapMeshes},
+			textures::TextureArrays,
+			visibility::Visibility,
+			MapDynamic,
+		},
+	},
+};
+use anyhow::{ensure, Context};
+use fnv::FnvHashMap;
+use legion::{systems::ResourceSet, IntoQuery, Read, Resources, World, Write as WriteResource};
+use nalgebra::Vector2;
+use std::{
+	fs::File,
+	io::{BufWriter, Write},
+	path::Path,
+};
+
+/// Writes the meshes of a loaded map to a Wavefront OBJ file, for looking at in a 3D editor.
+/// The meshes are built the same way as for drawing, in the map's current state,
+/// so doors and lifts are exported where they are at the moment.
+/// Each polygon uses the material named after its texture, and sky polygons use `sky`.
+/// The map's Z axis points up, so it's turned into Y for the file.
+pub fn export_map(
+	name: &str,
+	path: &Path,
+	world: &World,
+	resources: &Resources,
+) -> anyhow::Result<()> {
+	let (asset_storage, config_variables) =
+		<(Read<AssetStorage>, Read<ConfigVariables>)>::fetch(resources);
+	let texture_arrays = resources
+		.get::<TextureArrays>()
+		.context("Map textures are only loaded with video output")?;
+
+	let map_name = format!("{}.map", name.to_ascii_lowercase());
+	let mut query = <&MapDynamic>::query();
+	let map_dynamic = query
+		.iter(world)
+		.find(|map_dynamic| asset_storage.name_of(&map_dynamic.map) == Some(map_name.as_str()))
+		.with_context(|| format!("Map {} is not loaded", name))?;
+	let map = asset_storage.get(&map_dynamic.map).unwrap();
+
+	let mut meshes = MapMeshes::default();
+	make_meshes(
+		map,
+		map_dynamic,
+		&Visibility::all(map),
+		&texture_arrays,
+		1.0,
+		config_variables.get::<bool>("r_texelalign"),
+		&mut meshes,
+	)
+	.context("Couldn't generate map mesh")?;
+
+	let materials: FnvHashMap<(usize, u32), &str> = texture_arrays
+		.layers()
+		.filter_map(|(handle, layer)| {
+			let name = asset_storage.name_of(handle)?;
+			Some(((layer.array, layer.layer), name))
+		})
+		.collect();
+
+	let mut file = BufWriter::new(
+		File::create(path).with_context(|| format!("Couldn't create {}", path.display()))?,
+	);
+	writeln!(file, "o {}", name.to_ascii_uppercase())?;
+
+	// Indices in OBJ files start at 1, and count over all meshes in the file
+	let mut first_index = 1;
+
+	let mut arrays: Vec<_> = meshes.normal.iter().collect();
+	arrays.sort_unstable_by_key(|(array, _)| **array);
+
+	for (&array, (vertices, indices)) in arrays {
+		for vertex in vertices {
+			let [x, y, z] = vertex.in_position;
+			let [u, v] = vertex.in_texture_coord;
+			writeln!(file, "v {} {} {}", x, z, -y)?;
+			writeln!(file, "vt {} {}", u, -v)?;
+		}
+
+		// Group the polygons by texture, so that each material is only switched to once
+		let mut polygons: Vec<&[u32]> = indices
+			.split(|&index| index == u32::max_value())
+			.filter(|polygon| !polygon.is_empty())
+			.collect();
+		polygons.sort_by_key(|polygon| vertices[polygon[0] as usize].in_texture_layer as u32);
+		let mut current_layer = None;
+
+		for polygon in polygons {
+			let layer = vertices[polygon[0] as usize].in_texture_layer as u32;
+
+			if current_layer != Some(layer) {
+				let material = materials.get(&(array, layer)).copied().unwrap_or("unknown");
+				writeln!(file, "usemtl {}", material)?;
+				current_layer = Some(layer);
+			}
+
+			write!(file, "f")?;
+
+			for &index in polygon {
+				let index = first_index + index as usize;
+				write!(file, " {}/{}", index, index)?;
+			}
+
+			writeln!(file)?;
+		}
+
+		first_index += vertices.len();
+	}
+
+	// The sky has no texture coordinates, so it goes last to keep the indices
+	// of the other vertices the same as those of their texture coordinates
+	let (vertices, indices) = &meshes.sky;
+
+	if !indices.is_empty() {
+		for vertex in vertices {
+			let [x, y, z] = vertex.in_position;
+			writeln!(file, "v {} {} {}", x, z, -y)?;
+		}
+
+		writeln!(file, "usemtl sky")?;
+
+		for polygon in indices
+			.split(|&index| index == u32::max_value())
+			.filter(|polygon| !polygon.is_empty())
+		{
+			write!(file, "f")?;
+
+			for &index in polygon {
+				write!(file, " {}", first_index + index as usize)?;
+			}
+
+			writeln!(file)?;
+		}
+	}
+
+	file.flush()?;
+	Ok(())
+}
+
+/// Colours of the lines on the original automap, as palette indices.
+const AUTOMAP_WALL: usize = 176;
+const AUTOMAP_FLOOR_CHANGE: usize = 64;
+const AUTOMAP_CEILING_CHANGE: usize = 231;
+const AUTOMAP_NO_CHANGE: usize = 96;
+
+/// The largest image that can be exported, in pixels along each side.
+const AUTOMAP_MAX_SIZE: u32 = 16384;
+
+/// Draws the lines of the current map from above to a PNG file, like the automap with the whole
+/// map revealed. The map is scaled to fit the image, which is `height` pixels high if given,
+/// or otherwise as high as the shape of the map needs.
+pub fn export_automap(
+	path: &Path,
+	width: u32,
+	height: Option<u32>,
+	world: &World,
+	resources: &mut Resources,
+) -> anyhow::Result<()> {
+	let colors = {
+		let mut asset_storage = <WriteResource<AssetStorage>>::fetch_mut(resources);
+		let handle: AssetHandle<Palette> = asset_storage.load("playpal.palette");
+		let palette = asset_storage.get(&handle).unwrap();
+		[
+			palette[AUTOMAP_NO_CHANGE],
+			palette[AUTOMAP_CEILING_CHANGE],
+			palette[AUTOMAP_FLOOR_CHANGE],
+			palette[AUTOMAP_WALL],
+		]
+	};
+
+	let asset_storage = <Read<AssetStorage>>::fetch(resources);
+	let mut query = <&MapDynamic>::query();
+	let map_dynamic = query.iter(world).next().context("No map is loaded")?;
+	let map = asset_storage.get(&map_dynamic.map).unwrap();
+
+	let extent = map.bbox.max() - map.bbox.min();
+	let middle = map.bbox.middle();
+	let height = height.unwrap_or_else(|| (width as f32 * extent[1] / extent[0]).ceil() as u32);
+	ensure!(
+		(16..=AUTOMAP_MAX_SIZE).contains(&width) && (16..=AUTOMAP_MAX_SIZE).contains(&height),
+		"Image size {}x{} must be between 16 and {} pixels on each side",
+		width,
+		height,
+		AUTOMAP_MAX_SIZE
+	);
+
+	// Leave a small border, and make the lines thicker for large images so they stay visible
+	let border = (width.min(height) / 32) as f32;
+	let scale = f32::min(
+		(width as f32 - 2.0 * border) / extent[0],
+		(height as f32 - 2.0 * border) / extent[1],
+	);
+	let radius = (width.max(height) / 2048) as i64;
+
+	// Image rows go down, so Y is flipped
+	let to_pixel = |point: Vector2<f32>| {
+		Vector2::new(
+			width as f32 * 0.5 + (point[0] - middle[0]) * scale,
+			height as f32 * 0.5 - (point[1] - middle[1]) * scale,
+		)
+	};
+
+	// Sort the lines by colour, so that the walls are drawn on top of the others
+	let mut lines: Vec<(usize, Vector2<f32>, Vector2<f32>)> = map
+		.linedefs
+		.iter()
+		.filter(|linedef| !linedef.flags.contains(LinedefFlags::NOAUTOMAP))
+		.map(|linedef| {
+			let color_index = match &linedef.sidedefs {
+				[Some(front_sidedef), Some(back_sidedef)]
+					if !linedef.flags.contains(LinedefFlags::SECRET) =>
+				{
+					let front = map_dynamic.sectors[front_sidedef.sector_index].interval;
+					let back = map_dynamic.sectors[back_sidedef.sector_index].interval;
+
+					if front.min != back.min {
+						2
+					} else if front.max != back.max {
+						1
+					} else {
+						0
+					}
+				}
+				_ => 3,
+			};
+
+			(
+				color_index,
+				to_pixel(linedef.line.point),
+				to_pixel(linedef.line.point + linedef.line.dir),
+			)
+		})
+		.collect();
+	lines.sort_by_key(|(color_index, _, _)| *color_index);
+
+	let mut pixels = vec![0u8; width as usize * height as usize * 3];
+
+	for (color_index, start, end) in lines {
+		let color = colors[color_index];
+		let steps = (end - start).amax().ceil().max(1.0) as usize;
+
+		for step in 0..=steps {
+			let point = start + (end - start) * (step as f32 / steps as f32);
+			let (center_x, center_y) = (point[0].round() as i64, point[1].round() as i64);
+
+			for y in center_y - radius..=center_y + radius {
+				for x in center_x - radius..=center_x + radius {
+					if x < 0 || y < 0 || x >= width as i64 || y >= height as i64 {
+						continue;
+					}
+
+					let index = (y as usize * width as usize + x as usize) * 3;
+					pixels[index..index + 3].copy_from_slice(&rgb(color));
+				}
+			}
+		}
+	}
+
+	let file = File::create(path).with_context(|| format!("Couldn't create {}", path.display()))?;
+	let mut encoder = png::Encoder::new(BufWriter::new(file), width, height);
+	encoder.set_color(png::ColorType::RGB);
+	encoder.set_depth(png::BitDepth::Eight);
+	encoder.write_header()?.write_image_data(&pixels)?;
+
+	Ok(())
+}
+
+#[inline]
+fn rgb(color: RGBAColor) -> [u8; 3] {
+	[color.r, color.g, color.b]
+}
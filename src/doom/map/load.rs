@@ -1,16 +1,21 @@
 use crate::{
 	common::{
-		assets::{AssetHandle, AssetStorage, ImportData},
+		assets::{AssetHandle, AssetStorage, DataSource, ImportData},
+		cache::CacheKey,
 		geometry::{Angle, Interval, Line2, Plane2, Plane3, Side, AABB2},
 	},
 	doom::{
 		data::anims::{AnimData, ANIMS, SWITCHES},
 		image::Image,
 		map::{
-			textures::TextureType, Anim, Linedef, Map, Node, NodeChild, Sector, SectorSlot, Seg,
-			Sidedef, SidedefSlot, Subsector, Thing, ThingFlags,
+			reject::Reject, textures::TextureType, Anim, LineActivation, LineSpecial, Linedef, Map,
+			Node, NodeChild, Sector, SectorSlot, Seg, Sidedef, SidedefSlot, Subsector, Thing,
+			ThingFlags,
 		},
+		mapinfo::{MapInfo, MAPINFO_NAME},
 		physics::{CollisionPlane, SolidMask},
+		profile::is_heretic_data,
+		progression::default_sky,
 		wad::read_string,
 	},
 };
@@ -21,7 +26,7 @@ use fnv::FnvHashMap;
 use nalgebra::{Vector2, Vector3};
 use relative_path::RelativePath;
 use serde::Deserialize;
-use std::{cmp::Ordering, io::Read};
+use std::{cmp::Ordering, io::Read, slice::ChunksExact};
 
 pub struct MapData {
 	pub linedefs: Vec<u8>,
@@ -31,7 +36,11 @@ pub struct MapData {
 	pub ssectors: Vec<u8>,
 	pub nodes: Vec<u8>,
 	pub sectors: Vec<u8>,
+	/// Missing in some maps, which is fine since it's only used to speed up sight checks.
+	pub reject: Option<Vec<u8>>,
 	pub gl_data: Option<GLMapData>,
+	/// Whether the map is in the Hexen format, which has linedefs and things with arguments.
+	pub hexen_format: bool,
 }
 
 pub struct GLMapData {
@@ -65,12 +74,24 @@ pub fn import_map(
 		ssectors: source.load(&path.with_extension("ssectors"))?,
 		nodes: source.load(&path.with_extension("nodes"))?,
 		sectors: source.load(&path.with_extension("sectors"))?,
+		reject: source.load(&path.with_extension("reject")).ok(),
 		gl_data,
+		hexen_format: is_hexen_format(source, path),
 	};
 
+	// The map information can give the map a different sky
+	let map_name = path.file_stem().unwrap_or_default();
+	let heretic = is_heretic_data(source);
+	let mapinfo_handle = asset_storage.load::<MapInfo>(MAPINFO_NAME);
+	let sky = asset_storage
+		.get(&mapinfo_handle)
+		.and_then(|map_info| map_info.get(map_name))
+		.and_then(|entry| entry.sky.clone())
+		.unwrap_or_else(|| default_sky(map_name, heretic));
+
 	Ok(Box::new(build_map(
 		map_data,
-		"sky1.texture",
+		&format!("{}.texture", sky),
 		asset_storage,
 	)?))
 }
@@ -90,13 +111,43 @@ pub fn build_map(
 		ssectors: ssectors_data,
 		nodes: nodes_data,
 		sectors: sectors_data,
+		reject: reject_data,
 		gl_data,
+		hexen_format,
 	} = map_data;
 
+	// A REJECT table that was built before can be found again by the geometry it was built for
+	let mut geometry: Vec<&[u8]> = vec![
+		linedefs_data.as_slice(),
+		sidedefs_data.as_slice(),
+		vertexes_data.as_slice(),
+		segs_data.as_slice(),
+		ssectors_data.as_slice(),
+		nodes_data.as_slice(),
+		sectors_data.as_slice(),
+	];
+
+	if let Some(gl_data) = &gl_data {
+		geometry.extend_from_slice(&[
+			gl_data.gl_vert.as_slice(),
+			gl_data.gl_segs.as_slice(),
+			gl_data.gl_ssect.as_slice(),
+			gl_data.gl_nodes.as_slice(),
+		]);
+	}
+
+	let reject_key = CacheKey::new("reject", &geometry);
+
 	let vertexes = build_vertexes(&vertexes_data)?;
 	let mut sectors = build_sectors(&sectors_data, asset_storage)?;
-	let mut sidedefs = build_sidedefs(&sidedefs_data, &sectors, asset_storage)?;
-	let linedefs = build_linedefs(&linedefs_data, &vertexes, &mut sectors, &mut sidedefs)?;
+	let sidedefs = build_sidedefs(&sidedefs_data, &sectors, asset_storage)?;
+	let linedefs = build_linedefs(
+		&linedefs_data,
+		&vertexes,
+		&mut sectors,
+		&sidedefs,
+		hexen_format,
+	)?;
 
 	// Load GL nodes if available
 	let (mut subsectors, mut nodes) = if let Some(gl_data) = gl_data {
@@ -141,6 +192,30 @@ pub fn build_map(
 	// Add linedefs to nodes
 	add_node_linedefs(&mut nodes, &mut subsectors, &linedefs);
 
+	// Index sectors and linedefs by tag, so that specials can find them quickly
+	let mut sector_tags: FnvHashMap<u16, Vec<usize>> = FnvHashMap::default();
+
+	for (i, sector) in sectors.iter().enumerate() {
+		sector_tags.entry(sector.sector_tag).or_default().push(i);
+	}
+
+	let mut linedef_tags: FnvHashMap<u16, Vec<usize>> = FnvHashMap::default();
+
+	for (i, linedef) in linedefs.iter().enumerate() {
+		linedef_tags.entry(linedef.sector_tag).or_default().push(i);
+	}
+
+	let mut reject = Reject::new(sectors.len(), reject_data, reject_key);
+
+	if reject.is_empty() {
+		if let Some(data) = asset_storage
+			.cache()
+			.and_then(|cache| cache.load(reject_key))
+		{
+			reject = Reject::new(sectors.len(), Some(data), reject_key);
+		}
+	}
+
 	// Create map-wide bounding box
 	let mut bbox = AABB2::empty();
 
@@ -153,16 +228,35 @@ pub fn build_map(
 		anims: get_anims(&ANIMS, asset_storage),
 		bbox,
 		linedefs,
+		linedef_tags,
 		nodes,
+		reject,
 		sectors,
+		sector_tags,
 		subsectors,
 		sky,
 		switches: get_switches(asset_storage),
 	})
 }
 
+/// Splits a map lump into its fixed-size records.
+/// Some editors leave extra bytes at the end of a lump, these are ignored like the original game does.
+fn records<'a>(data: &'a [u8], size: usize, lump_name: &str) -> ChunksExact<'a, u8> {
+	let chunks = data.chunks_exact(size);
+
+	if !chunks.remainder().is_empty() {
+		log::warn!(
+			"{} lump has {} bytes left over after its last record",
+			lump_name,
+			chunks.remainder().len()
+		);
+	}
+
+	chunks
+}
+
 fn build_vertexes(data: &[u8]) -> anyhow::Result<Vec<Vector2<f32>>> {
-	let chunks = data.chunks(4);
+	let chunks = records(data, 4, "VERTEXES");
 	let mut ret = Vec::with_capacity(chunks.len());
 
 	for mut chunk in chunks {
@@ -176,7 +270,7 @@ fn build_vertexes(data: &[u8]) -> anyhow::Result<Vec<Vector2<f32>>> {
 }
 
 fn build_sectors(data: &[u8], asset_storage: &mut AssetStorage) -> anyhow::Result<Vec<Sector>> {
-	let chunks = data.chunks(26);
+	let chunks = records(data, 26, "SECTORS");
 	let mut ret = Vec::with_capacity(chunks.len());
 
 	for mut reader in chunks {
@@ -237,12 +331,12 @@ fn build_sidedefs(
 	data: &[u8],
 	sectors: &[Sector],
 	asset_storage: &mut AssetStorage,
-) -> anyhow::Result<Vec<Option<Sidedef>>> {
-	let chunks = data.chunks(30);
+) -> anyhow::Result<Vec<Sidedef>> {
+	let chunks = records(data, 30, "SIDEDEFS");
 	let mut ret = Vec::with_capacity(chunks.len());
 
 	for (i, mut reader) in chunks.enumerate() {
-		ret.push(Some(Sidedef {
+		ret.push(Sidedef {
 			texture_offset: Vector2::new(
 				reader.read_i16::<LE>()? as f32,
 				reader.read_i16::<LE>()? as f32,
@@ -300,7 +394,7 @@ fn build_sidedefs(
 
 				sector_index
 			},
-		}));
+		});
 	}
 
 	Ok(ret)
@@ -324,9 +418,10 @@ fn build_linedefs(
 	data: &[u8],
 	vertexes: &[Vector2<f32>],
 	sectors: &mut [Sector],
-	sidedefs: &mut [Option<Sidedef>],
+	sidedefs: &[Sidedef],
+	hexen_format: bool,
 ) -> anyhow::Result<Vec<Linedef>> {
-	let chunks = data.chunks(14);
+	let chunks = records(data, if hexen_format { 16 } else { 14 }, "LINEDEFS");
 	let mut ret = Vec::with_capacity(chunks.len());
 
 	for (i, mut chunk) in chunks.enumerate() {
@@ -336,9 +431,38 @@ fn build_linedefs(
 			chunk.read_u16::<LE>()? as usize,
 		];
 
-		let flags = LinedefFlags::from_bits_truncate(chunk.read_u16::<LE>()?);
-		let special_type = chunk.read_u16::<LE>()?;
-		let sector_tag = chunk.read_u16::<LE>()?;
+		let raw_flags = chunk.read_u16::<LE>()?;
+		let flags = LinedefFlags::from_bits_truncate(raw_flags);
+
+		// Hexen replaces the special type and sector tag with a special and its arguments
+		let (special_type, sector_tag, line_special) = if hexen_format {
+			let special = chunk.read_u8()?;
+			let mut args = [0u8; 5];
+			chunk.read_exact(&mut args)?;
+
+			let line_special = if special == 0 {
+				None
+			} else {
+				Some(LineSpecial {
+					special,
+					args,
+					activation: match (raw_flags >> 10) & 0b111 {
+						0 => LineActivation::Cross,
+						1 => LineActivation::Use,
+						2 => LineActivation::MonsterCross,
+						3 => LineActivation::Impact,
+						4 => LineActivation::Push,
+						5 => LineActivation::ProjectileCross,
+						x => bail!("Linedef {} has invalid activation type {}", i, x),
+					},
+					repeatable: raw_flags & 0x0200 != 0,
+				})
+			};
+
+			(0, 0, line_special)
+		} else {
+			(chunk.read_u16::<LE>()?, chunk.read_u16::<LE>()?, None)
+		};
 
 		let sidedef_indices = [
 			match chunk.read_u16::<LE>()? as usize {
@@ -370,9 +494,10 @@ fn build_linedefs(
 		}
 
 		// Put it all together
+		// Some WADs are compressed by letting identical sidedefs be shared by several linedefs
 		let mut sidedefs = [
-			sidedef_indices[0].map(|x| sidedefs[x].take().unwrap()),
-			sidedef_indices[1].map(|x| sidedefs[x].take().unwrap()),
+			sidedef_indices[0].map(|x| sidedefs[x].clone()),
+			sidedef_indices[1].map(|x| sidedefs[x].clone()),
 		];
 
 		if let [Some(ref mut front_sidedef), Some(ref mut back_sidedef)] = &mut sidedefs {
@@ -458,6 +583,7 @@ fn build_linedefs(
 				Some(special_type)
 			},
 			sector_tag,
+			line_special,
 			sidedefs,
 		});
 	}
@@ -470,7 +596,7 @@ fn build_segs(
 	vertexes: &[Vector2<f32>],
 	linedefs: &[Linedef],
 ) -> anyhow::Result<Vec<Seg>> {
-	let chunks = data.chunks(12);
+	let chunks = records(data, 12, "SEGS");
 	let mut ret = Vec::with_capacity(chunks.len());
 
 	for (i, mut chunk) in chunks.enumerate() {
@@ -529,7 +655,7 @@ fn build_ssectors(
 	segs: &[Seg],
 	linedefs: &[Linedef],
 ) -> anyhow::Result<Vec<Subsector>> {
-	let chunks = data.chunks(4);
+	let chunks = records(data, 4, "SSECTORS");
 	let mut ret = Vec::with_capacity(chunks.len());
 
 	for (i, mut chunk) in chunks.enumerate() {
@@ -579,7 +705,9 @@ fn build_ssectors(
 }
 
 fn build_nodes(data: &[u8], ssectors: &[Subsector]) -> anyhow::Result<Vec<Node>> {
-	let chunks = data.chunks(28);
+	ensure!(!data.is_empty(), "NODES lump is empty");
+
+	let chunks = records(data, 28, "NODES");
 	let mut ret = Vec::with_capacity(chunks.len());
 	let len = chunks.len();
 
@@ -661,7 +789,9 @@ fn build_nodes(data: &[u8], ssectors: &[Subsector]) -> anyhow::Result<Vec<Node>>
 		});
 	}
 
-	Ok(ret.into_iter().rev().collect())
+	let nodes: Vec<Node> = ret.into_iter().rev().collect();
+	check_node_tree(&nodes)?;
+	Ok(nodes)
 }
 
 fn build_gl_vert(mut data: &[u8]) -> anyhow::Result<Vec<Vector2<f32>>> {
@@ -670,7 +800,7 @@ fn build_gl_vert(mut data: &[u8]) -> anyhow::Result<Vec<Vector2<f32>>> {
 
 	ensure!(&buf == b"gNd2", "No gNd2 signature found in GL_VERT lump");
 
-	let chunks = data.chunks(8);
+	let chunks = records(data, 8, "GL_VERT");
 	let mut ret = Vec::with_capacity(chunks.len());
 
 	for mut chunk in chunks {
@@ -689,7 +819,7 @@ fn build_gl_segs(
 	gl_vert: &[Vector2<f32>],
 	linedefs: &[Linedef],
 ) -> anyhow::Result<Vec<Seg>> {
-	let chunks = data.chunks(10);
+	let chunks = records(data, 10, "GL_SEGS");
 	let mut ret = Vec::with_capacity(chunks.len());
 
 	for (i, mut chunk) in chunks.enumerate() {
@@ -780,7 +910,7 @@ fn build_gl_ssect(
 	gl_segs: &[Seg],
 	linedefs: &[Linedef],
 ) -> anyhow::Result<Vec<Subsector>> {
-	let chunks = data.chunks(4);
+	let chunks = records(data, 4, "GL_SSECT");
 	let mut ret = Vec::with_capacity(chunks.len());
 
 	for (i, mut chunk) in chunks.enumerate() {
@@ -832,7 +962,9 @@ fn build_gl_ssect(
 }
 
 fn build_gl_nodes(data: &[u8], gl_ssect: &[Subsector]) -> anyhow::Result<Vec<Node>> {
-	let chunks = data.chunks(28);
+	ensure!(!data.is_empty(), "GL_NODES lump is empty");
+
+	let chunks = records(data, 28, "GL_NODES");
 	let mut ret = Vec::with_capacity(chunks.len());
 	let len = chunks.len();
 
@@ -914,11 +1046,47 @@ fn build_gl_nodes(data: &[u8], gl_ssect: &[Subsector]) -> anyhow::Result<Vec<Nod
 		});
 	}
 
-	Ok(ret.into_iter().rev().collect())
+	let nodes: Vec<Node> = ret.into_iter().rev().collect();
+	check_node_tree(&nodes)?;
+	Ok(nodes)
 }
 
-pub fn build_things(data: &[u8]) -> anyhow::Result<Vec<Thing>> {
-	let chunks = data.chunks(10);
+/// Checks that every node can be reached from the root node only once,
+/// so that walking the tree always comes to an end.
+fn check_node_tree(nodes: &[Node]) -> anyhow::Result<()> {
+	let mut visited = vec![false; nodes.len()];
+	let mut stack = vec![0];
+
+	while let Some(index) = stack.pop() {
+		ensure!(
+			!visited[index],
+			"Node {} is reached more than once in the node tree",
+			nodes.len() - index - 1
+		);
+		visited[index] = true;
+
+		for child in nodes[index].child_indices.iter() {
+			if let NodeChild::Node(child_index) = child {
+				stack.push(*child_index);
+			}
+		}
+	}
+
+	Ok(())
+}
+
+/// Returns whether the map at `path` is in the Hexen format, which is recognised by its
+/// BEHAVIOR lump.
+pub fn is_hexen_format(source: &(impl DataSource + ?Sized), path: &RelativePath) -> bool {
+	source.exists(&path.with_extension("behavior"))
+}
+
+pub fn build_things(data: &[u8], hexen_format: bool) -> anyhow::Result<Vec<Thing>> {
+	if hexen_format {
+		return build_hexen_things(data);
+	}
+
+	let chunks = records(data, 10, "THINGS");
 	let mut ret = Vec::with_capacity(chunks.len());
 
 	for mut chunk in chunks {
@@ -927,7 +1095,8 @@ pub fn build_things(data: &[u8]) -> anyhow::Result<Vec<Thing>> {
 				chunk.read_i16::<LE>()? as f32,
 				chunk.read_i16::<LE>()? as f32,
 			),
-			angle: Angle::from_degrees(chunk.read_u16::<LE>()? as f64),
+			// The original game rounds angles down to multiples of 45 degrees
+			angle: Angle::from_degrees((chunk.read_u16::<LE>()? / 45 * 45) as f64),
 			r#type: chunk.read_u16::<LE>()?,
 			flags: ThingFlags::from_bits_truncate(chunk.read_u16::<LE>()?),
 		});
@@ -936,6 +1105,41 @@ pub fn build_things(data: &[u8]) -> anyhow::Result<Vec<Thing>> {
 	Ok(ret)
 }
 
+/// Reads things in the Hexen format. Thing IDs, heights and specials are skipped,
+/// and the flags are converted to their Doom equivalents.
+fn build_hexen_things(data: &[u8]) -> anyhow::Result<Vec<Thing>> {
+	let chunks = records(data, 20, "THINGS");
+	let mut ret = Vec::with_capacity(chunks.len());
+
+	for mut chunk in chunks {
+		let _tid = chunk.read_u16::<LE>()?;
+		let position = Vector2::new(
+			chunk.read_i16::<LE>()? as f32,
+			chunk.read_i16::<LE>()? as f32,
+		);
+		let _height = chunk.read_i16::<LE>()?;
+		let angle = Angle::from_degrees((chunk.read_u16::<LE>()? / 45 * 45) as f64);
+		let r#type = chunk.read_u16::<LE>()?;
+		let hexen_flags = chunk.read_u16::<LE>()?;
+
+		// Skill and ambush flags are the same, but the game modes are given separately
+		let mut flags = ThingFlags::from_bits_truncate(hexen_flags & 0b1111);
+
+		if hexen_flags & 0x0100 == 0 {
+			flags |= ThingFlags::MULTIPLAYER;
+		}
+
+		ret.push(Thing {
+			position,
+			angle,
+			r#type,
+			flags,
+		});
+	}
+
+	Ok(ret)
+}
+
 fn generate_subsector_planes(segs: &[Seg]) -> (AABB2, Vec<CollisionPlane>) {
 	let bbox = {
 		let mut bbox = AABB2::empty();
@@ -1299,6 +1503,11 @@ fn add_node_linedefs<'a>(
 			}
 		}
 
+		// Subsectors that can't be reached from the root node have no common parent
+		if depth == 0 {
+			continue;
+		}
+
 		// Add linedef to node
 		let index = subsector_paths[subs[0]][depth - 1];
 		nodes[index].linedefs.push(linedef_index);
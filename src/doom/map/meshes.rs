@@ -1,24 +1,33 @@
 use crate::{
-	common::assets::{AssetHandle, AssetStorage},
+	common::assets::AssetHandle,
 	doom::{
 		image::Image,
 		map::{
-			textures::TextureType, LinedefFlags, Map, MapDynamic, SectorSlot, Side, SidedefSlot,
+			textures::{TextureArrays, TextureLayer, TextureType},
+			visibility::Visibility,
+			Linedef, LinedefFlags, Map, MapDynamic, SectorSlot, Side, SidedefSlot,
 		},
 	},
 };
 use fnv::FnvHashMap;
-use legion::{systems::ResourceSet, Read, Resources};
 use nalgebra::Vector2;
-use vulkano::{image::Dimensions, impl_vertex};
+use rayon::prelude::*;
+use vulkano::impl_vertex;
 
 #[derive(Clone, Debug, Default)]
 pub struct VertexData {
 	pub in_position: [f32; 3],
 	pub in_texture_coord: [f32; 2],
+	pub in_texture_layer: f32,
 	pub in_light_level: f32,
 }
-impl_vertex!(VertexData, in_position, in_texture_coord, in_light_level);
+impl_vertex!(
+	VertexData,
+	in_position,
+	in_texture_coord,
+	in_texture_layer,
+	in_light_level
+);
 
 #[derive(Clone, Debug, Default)]
 pub struct SkyVertexData {
@@ -26,26 +35,105 @@ pub struct SkyVertexData {
 }
 impl_vertex!(SkyVertexData, in_position);
 
+/// Meshes for the map, with one mesh for each texture array and one for the sky.
+/// These are kept around between frames, so that their memory can be reused.
+#[derive(Clone, Debug, Default)]
+pub struct MapMeshes {
+	pub normal: FnvHashMap<usize, (Vec<VertexData>, Vec<u32>)>,
+	pub sky: (Vec<SkyVertexData>, Vec<u32>),
+}
+
+impl MapMeshes {
+	fn clear(&mut self) {
+		for (vertices, indices) in self.normal.values_mut() {
+			vertices.clear();
+			indices.clear();
+		}
+
+		self.sky.0.clear();
+		self.sky.1.clear();
+	}
+
+	/// Adds the contents of `other` to the end of these meshes.
+	fn append(&mut self, other: &MapMeshes) {
+		for (array, (vertices, indices)) in other.normal.iter() {
+			let (self_vertices, self_indices) = self.normal.entry(*array).or_default();
+			append_mesh(self_vertices, self_indices, vertices, indices);
+		}
+
+		append_mesh(&mut self.sky.0, &mut self.sky.1, &other.sky.0, &other.sky.1);
+	}
+}
+
+fn append_mesh<V: Clone>(
+	vertices: &mut Vec<V>,
+	indices: &mut Vec<u32>,
+	other_vertices: &[V],
+	other_indices: &[u32],
+) {
+	let offset = vertices.len() as u32;
+	vertices.extend_from_slice(other_vertices);
+
+	// Primitive restarts stay as they are, the rest now point further along
+	indices.extend(other_indices.iter().map(|&index| {
+		if index == u32::max_value() {
+			index
+		} else {
+			index + offset
+		}
+	}));
+}
+
+/// Steps per map unit that vertices are rounded to when texels are aligned.
+/// This is fine enough to keep fractional vertices from GL nodes in place, but coarse enough
+/// that vertices that should be in the same place, but differ by rounding errors, end up equal.
+const SNAP_STEPS: f32 = 16.0;
+
+#[inline]
+fn snap(value: f32) -> f32 {
+	(value * SNAP_STEPS).round() / SNAP_STEPS
+}
+
+/// Builds the meshes for the parts of the map that are visible.
+/// If `texel_align` is set, the meshes are built to match the original game's texture alignment
+/// more closely: vertices are snapped together so that there are no cracks between walls and flats,
+/// and texture coordinates are kept on whole texels and close to zero, so that they don't lose
+/// precision far away from the origin.
 pub fn make_meshes(
 	map: &Map,
 	map_dynamic: &MapDynamic,
-	resources: &Resources,
-) -> anyhow::Result<(
-	FnvHashMap<AssetHandle<Image>, (Vec<VertexData>, Vec<u32>)>,
-	FnvHashMap<AssetHandle<Image>, (Vec<VertexData>, Vec<u32>)>,
-	(Vec<SkyVertexData>, Vec<u32>),
-)> {
+	visibility: &Visibility,
+	texture_arrays: &TextureArrays,
+	fraction: f32,
+	texel_align: bool,
+	meshes: &mut MapMeshes,
+) -> anyhow::Result<()> {
 	#[inline]
 	fn push_wall(
 		vertices: &mut Vec<VertexData>,
 		indices: &mut Vec<u32>,
-		vert_h: [Vector2<f32>; 2],
-		vert_v: [f32; 2],
-		tex_v: [f32; 2],
-		offset: Vector2<f32>,
-		dimensions: Dimensions,
+		mut vert_h: [Vector2<f32>; 2],
+		mut vert_v: [f32; 2],
+		mut tex_v: [f32; 2],
+		mut offset: Vector2<f32>,
+		texture_layer: TextureLayer,
 		light_level: f32,
+		texel_align: bool,
 	) {
+		let dimensions = texture_layer.dimensions;
+
+		if texel_align {
+			let size = [dimensions.width() as f32, dimensions.height() as f32];
+			vert_h = [vert_h[0].map(snap), vert_h[1].map(snap)];
+			vert_v = [snap(vert_v[0]), snap(vert_v[1])];
+
+			// Shift by whole textures, so that the top left corner is on the first repetition
+			offset = offset.map(f32::round);
+			offset[0] = offset[0].rem_euclid(size[0]);
+			let shift = ((offset[1] + tex_v[0]) / size[1]).floor() * size[1];
+			tex_v = [tex_v[0] - shift, tex_v[1] - shift];
+		}
+
 		let width = (vert_h[1] - vert_h[0]).norm();
 		indices.push(u32::max_value());
 
@@ -57,6 +145,7 @@ pub fn make_meshes(
 					(offset[0] + width * h as f32) / dimensions.width() as f32,
 					(offset[1] + tex_v[v]) / dimensions.height() as f32,
 				],
+				in_texture_layer: texture_layer.layer as f32,
 				in_light_level: light_level,
 			});
 		}
@@ -66,9 +155,15 @@ pub fn make_meshes(
 	fn push_sky_wall(
 		vertices: &mut Vec<SkyVertexData>,
 		indices: &mut Vec<u32>,
-		vert_h: [Vector2<f32>; 2],
-		vert_v: [f32; 2],
+		mut vert_h: [Vector2<f32>; 2],
+		mut vert_v: [f32; 2],
+		texel_align: bool,
 	) {
+		if texel_align {
+			vert_h = [vert_h[0].map(snap), vert_h[1].map(snap)];
+			vert_v = [snap(vert_v[0]), snap(vert_v[1])];
+		}
+
 		indices.push(u32::max_value());
 
 		for (h, v) in [(1, 0), (0, 0), (0, 1), (1, 1)].iter().copied() {
@@ -84,20 +179,43 @@ pub fn make_meshes(
 		vertices: &mut Vec<VertexData>,
 		indices: &mut Vec<u32>,
 		iter: impl Iterator<Item = &'a Vector2<f32>>,
-		vert_z: f32,
-		dimensions: Dimensions,
+		mut vert_z: f32,
+		texture_layer: TextureLayer,
 		light_level: f32,
+		texel_align: bool,
 	) {
+		let dimensions = texture_layer.dimensions;
+		let size = Vector2::new(dimensions.width() as f32, dimensions.height() as f32);
+		let mut origin: Option<Vector2<f32>> = None;
 		indices.push(u32::max_value());
 
+		if texel_align {
+			vert_z = snap(vert_z);
+		}
+
 		for vert in iter {
+			let (vert, tex_origin) = if texel_align {
+				// Shift by whole textures, so that the coordinates stay close to zero
+				let vert = vert.map(snap);
+				let origin = *origin.get_or_insert_with(|| {
+					Vector2::new(
+						(vert[0] / size[0]).floor() * size[0],
+						(vert[1] / size[1]).floor() * size[1],
+					)
+				});
+				(vert, origin)
+			} else {
+				(*vert, Vector2::zeros())
+			};
+
 			indices.push(vertices.len() as u32);
 			vertices.push(VertexData {
 				in_position: [vert[0], vert[1], vert_z],
 				in_texture_coord: [
-					vert[0] / dimensions.width() as f32,
-					-vert[1] / dimensions.height() as f32,
+					(vert[0] - tex_origin[0]) / size[0],
+					-(vert[1] - tex_origin[1]) / size[1],
 				],
+				in_texture_layer: texture_layer.layer as f32,
 				in_light_level: light_level,
 			});
 		}
@@ -109,27 +227,35 @@ pub fn make_meshes(
 		indices: &mut Vec<u32>,
 		iter: impl Iterator<Item = &'a Vector2<f32>>,
 		vert_z: f32,
+		texel_align: bool,
 	) {
 		indices.push(u32::max_value());
 
 		for vert in iter {
+			let in_position = if texel_align {
+				[snap(vert[0]), snap(vert[1]), snap(vert_z)]
+			} else {
+				[vert[0], vert[1], vert_z]
+			};
+
 			indices.push(vertices.len() as u32);
-			vertices.push(SkyVertexData {
-				in_position: [vert[0], vert[1], vert_z],
-			});
+			vertices.push(SkyVertexData { in_position });
 		}
 	}
 
-	let mut flat_meshes: FnvHashMap<AssetHandle<Image>, (Vec<VertexData>, Vec<u32>)> =
-		FnvHashMap::default();
-	let mut wall_meshes: FnvHashMap<AssetHandle<Image>, (Vec<VertexData>, Vec<u32>)> =
-		FnvHashMap::default();
-	let mut sky_mesh: (Vec<SkyVertexData>, Vec<u32>) = (Vec::new(), Vec::new());
+	meshes.clear();
 
-	let asset_storage = <Read<AssetStorage>>::fetch(resources);
+	// Redirect animation frames
+	let texture_layer = |handle: &AssetHandle<Image>| {
+		let handle = match map_dynamic.anim_states.get(handle) {
+			Some(anim_state) => &map.anims[handle].frames[anim_state.frame],
+			None => handle,
+		};
+		texture_arrays.layer(handle)
+	};
 
-	// Walls
-	for (linedef_index, linedef) in map.linedefs.iter().enumerate() {
+	// Walls, built on all threads into separate meshes that are appended afterwards
+	let push_linedef = |meshes: &mut MapMeshes, linedef_index: usize, linedef: &Linedef| {
 		let linedef_dynamic = &map_dynamic.linedefs[linedef_index];
 
 		for side in [Side::Right, Side::Left].iter().copied() {
@@ -146,6 +272,7 @@ pub fn make_meshes(
 			}
 
 			let front_sector_dynamic = &map_dynamic.sectors[front_sidedef.sector_index];
+			let front_interval = front_sector_dynamic.interpolated_interval(fraction);
 
 			// Swap the vertices if we're on the left side of the linedef
 			let linedef_vertices = match side {
@@ -156,14 +283,13 @@ pub fn make_meshes(
 			// Two-sided or one-sided sidedef?
 			if let Some(back_sidedef) = &linedef.sidedefs[!side as usize] {
 				let back_sector_dynamic = &map_dynamic.sectors[back_sidedef.sector_index];
-				let intersection = front_sector_dynamic
-					.interval
-					.intersection(back_sector_dynamic.interval);
+				let back_interval = back_sector_dynamic.interpolated_interval(fraction);
+				let intersection = front_interval.intersection(back_interval);
 				let spans = [
-					front_sector_dynamic.interval.max,
+					front_interval.max,
 					intersection.max,
 					intersection.min,
-					front_sector_dynamic.interval.min,
+					front_interval.min,
 				];
 
 				// Top section
@@ -171,34 +297,38 @@ pub fn make_meshes(
 					TextureType::None => (),
 					TextureType::Sky => {
 						push_sky_wall(
-							&mut sky_mesh.0,
-							&mut sky_mesh.1,
+							&mut meshes.sky.0,
+							&mut meshes.sky.1,
 							linedef_vertices,
 							[spans[0], spans[1]],
+							texel_align,
 						);
 					}
 					TextureType::Normal(handle) => {
-						let dimensions = asset_storage.get(handle).unwrap().image.dimensions();
-						let (ref mut vertices, ref mut indices) = wall_meshes
-							.entry(handle.clone())
-							.or_insert((vec![], vec![]));
-
-						let tex_v = if linedef.flags.contains(LinedefFlags::DONTPEGTOP) {
-							[0.0, spans[0] - spans[1]]
-						} else {
-							[spans[1] - spans[0], 0.0]
-						};
-
-						push_wall(
-							vertices,
-							indices,
-							linedef_vertices,
-							[spans[0], spans[1]],
-							tex_v,
-							texture_offset,
-							dimensions,
-							front_sector_dynamic.light_level,
-						);
+						if let Some(texture_layer) = texture_layer(handle) {
+							let (ref mut vertices, ref mut indices) = meshes
+								.normal
+								.entry(texture_layer.array)
+								.or_insert((vec![], vec![]));
+
+							let tex_v = if linedef.flags.contains(LinedefFlags::DONTPEGTOP) {
+								[0.0, spans[0] - spans[1]]
+							} else {
+								[spans[1] - spans[0], 0.0]
+							};
+
+							push_wall(
+								vertices,
+								indices,
+								linedef_vertices,
+								[spans[0], spans[1]],
+								tex_v,
+								texture_offset,
+								texture_layer,
+								front_sector_dynamic.light_level,
+								texel_align,
+							);
+						}
 					}
 				}
 
@@ -207,30 +337,30 @@ pub fn make_meshes(
 					TextureType::None => (),
 					TextureType::Sky => unimplemented!(),
 					TextureType::Normal(handle) => {
-						let dimensions = asset_storage.get(handle).unwrap().image.dimensions();
-						let (ref mut vertices, ref mut indices) = wall_meshes
-							.entry(handle.clone())
-							.or_insert((vec![], vec![]));
-
-						let tex_v = if linedef.flags.contains(LinedefFlags::DONTPEGBOTTOM) {
-							[
-								front_sector_dynamic.interval.max - spans[2],
-								front_sector_dynamic.interval.max - spans[3],
-							]
-						} else {
-							[0.0, spans[2] - spans[3]]
-						};
-
-						push_wall(
-							vertices,
-							indices,
-							linedef_vertices,
-							[spans[2], spans[3]],
-							tex_v,
-							texture_offset,
-							dimensions,
-							front_sector_dynamic.light_level,
-						);
+						if let Some(texture_layer) = texture_layer(handle) {
+							let (ref mut vertices, ref mut indices) = meshes
+								.normal
+								.entry(texture_layer.array)
+								.or_insert((vec![], vec![]));
+
+							let tex_v = if linedef.flags.contains(LinedefFlags::DONTPEGBOTTOM) {
+								[front_interval.max - spans[2], front_interval.max - spans[3]]
+							} else {
+								[0.0, spans[2] - spans[3]]
+							};
+
+							push_wall(
+								vertices,
+								indices,
+								linedef_vertices,
+								[spans[2], spans[3]],
+								tex_v,
+								texture_offset,
+								texture_layer,
+								front_sector_dynamic.light_level,
+								texel_align,
+							);
+						}
 					}
 				}
 
@@ -239,27 +369,40 @@ pub fn make_meshes(
 					TextureType::None => (),
 					TextureType::Sky => unimplemented!(),
 					TextureType::Normal(handle) => {
-						let dimensions = asset_storage.get(handle).unwrap().image.dimensions();
-						let (ref mut vertices, ref mut indices) = wall_meshes
-							.entry(handle.clone())
-							.or_insert((vec![], vec![]));
-
-						let tex_v = if linedef.flags.contains(LinedefFlags::DONTPEGBOTTOM) {
-							[spans[2] - spans[1], 0.0]
-						} else {
-							[0.0, spans[1] - spans[2]]
-						};
-
-						push_wall(
-							vertices,
-							indices,
-							linedef_vertices,
-							[spans[1], spans[2]],
-							tex_v,
-							texture_offset,
-							dimensions,
-							front_sector_dynamic.light_level,
-						);
+						if let Some(texture_layer) = texture_layer(handle) {
+							let (ref mut vertices, ref mut indices) = meshes
+								.normal
+								.entry(texture_layer.array)
+								.or_insert((vec![], vec![]));
+
+							// Middle textures of two-sided walls don't repeat vertically,
+							// so cut the wall off where the texture ends
+							let height = texture_layer.dimensions.height() as f32;
+							let texture_top = if linedef.flags.contains(LinedefFlags::DONTPEGBOTTOM)
+							{
+								spans[2] + height
+							} else {
+								spans[1]
+							};
+							let vert_v = [
+								f32::min(spans[1], texture_top + texture_offset[1]),
+								f32::max(spans[2], texture_top + texture_offset[1] - height),
+							];
+
+							if vert_v[0] > vert_v[1] {
+								push_wall(
+									vertices,
+									indices,
+									linedef_vertices,
+									vert_v,
+									[texture_top - vert_v[0], texture_top - vert_v[1]],
+									texture_offset,
+									texture_layer,
+									front_sector_dynamic.light_level,
+									texel_align,
+								);
+							}
+						}
 					}
 				}
 			} else {
@@ -267,98 +410,125 @@ pub fn make_meshes(
 					TextureType::None => (),
 					TextureType::Sky => unimplemented!(),
 					TextureType::Normal(handle) => {
-						let dimensions = asset_storage.get(handle).unwrap().image.dimensions();
-						let (ref mut vertices, ref mut indices) = wall_meshes
-							.entry(handle.clone())
-							.or_insert((vec![], vec![]));
-
-						let tex_v = if linedef.flags.contains(LinedefFlags::DONTPEGBOTTOM) {
-							[-front_sector_dynamic.interval.len(), 0.0]
-						} else {
-							[0.0, front_sector_dynamic.interval.len()]
-						};
-
-						push_wall(
-							vertices,
-							indices,
-							linedef_vertices,
-							[
-								front_sector_dynamic.interval.max,
-								front_sector_dynamic.interval.min,
-							],
-							tex_v,
-							texture_offset,
-							dimensions,
-							front_sector_dynamic.light_level,
-						);
+						if let Some(texture_layer) = texture_layer(handle) {
+							let (ref mut vertices, ref mut indices) = meshes
+								.normal
+								.entry(texture_layer.array)
+								.or_insert((vec![], vec![]));
+
+							let tex_v = if linedef.flags.contains(LinedefFlags::DONTPEGBOTTOM) {
+								[-front_interval.len(), 0.0]
+							} else {
+								[0.0, front_interval.len()]
+							};
+
+							push_wall(
+								vertices,
+								indices,
+								linedef_vertices,
+								[front_interval.max, front_interval.min],
+								tex_v,
+								texture_offset,
+								texture_layer,
+								front_sector_dynamic.light_level,
+								texel_align,
+							);
+						}
 					}
 				}
 			}
 		}
+	};
+
+	let parts: Vec<MapMeshes> = map
+		.linedefs
+		.par_iter()
+		.enumerate()
+		.filter(|(linedef_index, _)| visibility.linedefs[*linedef_index])
+		.fold(MapMeshes::default, |mut part, (linedef_index, linedef)| {
+			push_linedef(&mut part, linedef_index, linedef);
+			part
+		})
+		.collect();
+
+	for part in &parts {
+		meshes.append(part);
 	}
 
+	let MapMeshes {
+		normal: meshes,
+		sky: sky_mesh,
+	} = meshes;
+
 	// Flats
-	for (i, sector) in map.sectors.iter().enumerate() {
-		let sector_dynamic = &map_dynamic.sectors[i];
-
-		for segs in sector.subsectors.iter().map(|i| &map.subsectors[*i].segs) {
-			// Floor
-			let iter = segs.iter().map(|seg| &seg.line.point).rev();
-
-			match &sector.textures[SectorSlot::Floor as usize] {
-				TextureType::None => (),
-				TextureType::Sky => push_sky_flat(
-					&mut sky_mesh.0,
-					&mut sky_mesh.1,
-					iter,
-					sector_dynamic.interval.min,
-				),
-				TextureType::Normal(handle) => {
-					let dimensions = asset_storage.get(handle).unwrap().image.dimensions();
-					let (ref mut vertices, ref mut indices) = flat_meshes
-						.entry(handle.clone())
+	for subsector in visibility.subsectors.iter().map(|i| &map.subsectors[*i]) {
+		let sector = &map.sectors[subsector.sector_index];
+		let sector_dynamic = &map_dynamic.sectors[subsector.sector_index];
+		let interval = sector_dynamic.interpolated_interval(fraction);
+		let segs = &subsector.segs;
+
+		// Floor
+		let iter = segs.iter().map(|seg| &seg.line.point).rev();
+
+		match &sector.textures[SectorSlot::Floor as usize] {
+			TextureType::None => (),
+			TextureType::Sky => push_sky_flat(
+				&mut sky_mesh.0,
+				&mut sky_mesh.1,
+				iter,
+				interval.min,
+				texel_align,
+			),
+			TextureType::Normal(handle) => {
+				if let Some(texture_layer) = texture_layer(handle) {
+					let (ref mut vertices, ref mut indices) = meshes
+						.entry(texture_layer.array)
 						.or_insert((vec![], vec![]));
 
 					push_flat(
 						vertices,
 						indices,
 						iter,
-						sector_dynamic.interval.min,
-						dimensions,
+						interval.min,
+						texture_layer,
 						sector_dynamic.light_level,
+						texel_align,
 					);
 				}
 			}
+		}
 
-			// Ceiling
-			let iter = segs.iter().map(|seg| &seg.line.point);
-
-			match &sector.textures[SectorSlot::Ceiling as usize] {
-				TextureType::None => (),
-				TextureType::Sky => push_sky_flat(
-					&mut sky_mesh.0,
-					&mut sky_mesh.1,
-					iter,
-					sector_dynamic.interval.max,
-				),
-				TextureType::Normal(handle) => {
-					let dimensions = asset_storage.get(handle).unwrap().image.dimensions();
-					let (ref mut vertices, ref mut indices) = flat_meshes
-						.entry(handle.clone())
+		// Ceiling
+		let iter = segs.iter().map(|seg| &seg.line.point);
+
+		match &sector.textures[SectorSlot::Ceiling as usize] {
+			TextureType::None => (),
+			TextureType::Sky => push_sky_flat(
+				&mut sky_mesh.0,
+				&mut sky_mesh.1,
+				iter,
+				interval.max,
+				texel_align,
+			),
+			TextureType::Normal(handle) => {
+				if let Some(texture_layer) = texture_layer(handle) {
+					let (ref mut vertices, ref mut indices) = meshes
+						.entry(texture_layer.array)
 						.or_insert((vec![], vec![]));
 
 					push_flat(
 						vertices,
 						indices,
 						iter,
-						sector_dynamic.interval.max,
-						dimensions,
+						interval.max,
+						texture_layer,
 						sector_dynamic.light_level,
+						texel_align,
 					);
 				}
 			}
 		}
 	}
 
-	Ok((flat_meshes, wall_meshes, sky_mesh))
+	Ok(())
 }
@@ -1,7 +1,11 @@
+pub mod export;
 pub mod load;
 pub mod meshes;
+pub mod reject;
+pub mod sight;
 pub mod spawn;
 pub mod textures;
+pub mod visibility;
 
 use crate::{
 	common::{
@@ -11,7 +15,7 @@ use crate::{
 	},
 	doom::{
 		image::Image,
-		map::{load::LinedefFlags, textures::TextureType},
+		map::{load::LinedefFlags, reject::Reject, textures::TextureType},
 		physics::{CollisionPlane, SolidMask},
 	},
 };
@@ -22,13 +26,18 @@ use nalgebra::Vector2;
 use serde::Deserialize;
 use std::{fmt::Debug, time::Duration};
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Map {
 	pub anims: FnvHashMap<AssetHandle<Image>, Anim>,
 	pub bbox: AABB2,
 	pub linedefs: Vec<Linedef>,
+	/// Indices of the linedefs with each sector tag.
+	pub linedef_tags: FnvHashMap<u16, Vec<usize>>,
 	pub nodes: Vec<Node>,
+	pub reject: Reject,
 	pub sectors: Vec<Sector>,
+	/// Indices of the sectors with each sector tag.
+	pub sector_tags: FnvHashMap<u16, Vec<usize>>,
 	pub subsectors: Vec<Subsector>,
 	pub sky: AssetHandle<Image>,
 	pub switches: FnvHashMap<AssetHandle<Image>, AssetHandle<Image>>,
@@ -67,8 +76,8 @@ bitflags! {
 		const EASY = 0b00000000_00000001;
 		const NORMAL = 0b00000000_00000010;
 		const HARD = 0b00000000_00000100;
-		const DEAF = 0b00000000_00001000;
-		const DMONLY = 0b00000000_00010000;
+		const AMBUSH = 0b00000000_00001000;
+		const MULTIPLAYER = 0b00000000_00010000;
 	}
 }
 
@@ -82,9 +91,32 @@ pub struct Linedef {
 	pub solid_mask: SolidMask,
 	pub special_type: Option<u16>,
 	pub sector_tag: u16,
+	/// The special of a linedef in a Hexen format map, which takes the place of the special
+	/// type and sector tag.
+	pub line_special: Option<LineSpecial>,
 	pub sidedefs: [Option<Sidedef>; 2],
 }
 
+/// A special as given in the Hexen map format, with its own arguments
+/// and what activates it.
+#[derive(Clone, Copy, Debug)]
+pub struct LineSpecial {
+	pub special: u8,
+	pub args: [u8; 5],
+	pub activation: LineActivation,
+	pub repeatable: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineActivation {
+	Cross,
+	Use,
+	MonsterCross,
+	Impact,
+	Push,
+	ProjectileCross,
+}
+
 #[derive(Clone, Debug)]
 pub struct LinedefDynamic {
 	pub entity: Entity,
@@ -170,6 +202,19 @@ pub struct SectorDynamic {
 	pub entity: Entity,
 	pub light_level: f32,
 	pub interval: Interval,
+	/// The interval as it was at the start of the current game frame.
+	pub previous_interval: Interval,
+	/// The entity that made the last noise that was heard in this sector.
+	pub sound_target: Option<Entity>,
+}
+
+impl SectorDynamic {
+	/// The floor and ceiling heights to draw the sector at, `fraction` of the way
+	/// between the previous and current game frame.
+	#[inline]
+	pub fn interpolated_interval(&self, fraction: f32) -> Interval {
+		self.previous_interval.lerp(self.interval, fraction)
+	}
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -179,6 +224,24 @@ pub struct SectorRef {
 }
 
 impl Map {
+	/// Returns the indices of all sectors with the given tag.
+	#[inline]
+	pub fn sectors_with_tag(&self, sector_tag: u16) -> &[usize] {
+		self.sector_tags
+			.get(&sector_tag)
+			.map(Vec::as_slice)
+			.unwrap_or_default()
+	}
+
+	/// Returns the indices of all linedefs with the given tag.
+	#[inline]
+	pub fn linedefs_with_tag(&self, sector_tag: u16) -> &[usize] {
+		self.linedef_tags
+			.get(&sector_tag)
+			.map(Vec::as_slice)
+			.unwrap_or_default()
+	}
+
 	pub fn find_subsector(&self, point: Vector2<f32>) -> &Subsector {
 		let mut child = NodeChild::Node(0);
 
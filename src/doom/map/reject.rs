@@ -0,0 +1,245 @@
+use crate::{
+	common::{
+		cache::{CacheKey, DiskCache},
+		geometry::{Line2, AABB2},
+	},
+	doom::map::{Map, NodeChild},
+};
+use nalgebra::Vector2;
+use std::{
+	sync::{Arc, RwLock},
+	time::Instant,
+};
+
+/// A table of which sectors can't possibly see each other, used to skip sight checks.
+/// It's read from the REJECT lump of the map, or built in the background if the lump
+/// is missing or empty.
+#[derive(Clone, Debug)]
+pub struct Reject {
+	sector_count: usize,
+	table: Arc<RwLock<Option<Vec<u8>>>>,
+	/// Where a built table is kept in the disk cache.
+	cache_key: CacheKey,
+}
+
+impl Reject {
+	/// Makes a table from the contents of a REJECT lump.
+	/// Lumps that are cut short are padded, so that the missing sector pairs are not rejected.
+	/// A table of all zeros rejects nothing, so it's left out.
+	pub fn new(sector_count: usize, data: Option<Vec<u8>>, cache_key: CacheKey) -> Reject {
+		let table = data
+			.filter(|data| data.iter().any(|&b| b != 0))
+			.map(|mut data| {
+				let len = (sector_count * sector_count + 7) / 8;
+
+				if data.len() < len {
+					log::warn!("REJECT lump is {} bytes short", len - data.len());
+					data.resize(len, 0);
+				}
+
+				data
+			});
+
+		Reject {
+			sector_count,
+			table: Arc::new(RwLock::new(table)),
+			cache_key,
+		}
+	}
+
+	/// Returns whether there is no table yet, so that it's worth building one.
+	pub fn is_empty(&self) -> bool {
+		self.table.read().unwrap().is_none()
+	}
+
+	/// Returns whether a thing in sector `from` can't possibly see a thing in sector `to`.
+	/// Without a table, nothing is rejected.
+	pub fn is_rejected(&self, from: usize, to: usize) -> bool {
+		// Don't wait for the table if it's being built
+		let table = match self.table.try_read() {
+			Ok(table) => table,
+			Err(_) => return false,
+		};
+
+		match &*table {
+			Some(table) => {
+				let bit = from * self.sector_count + to;
+				table[bit / 8] & (1 << (bit % 8)) != 0
+			}
+			None => false,
+		}
+	}
+}
+
+/// Builds a REJECT table for `map` on another thread, which replaces the empty table
+/// of the map when it's done. If the map is dropped in the meantime, such as when it's
+/// imported again, building stops. The finished table is written to `cache`,
+/// so that it doesn't have to be built again next time.
+///
+/// The table is coarse: sectors are only rejected if they aren't connected at all,
+/// or if no straight line between points spread around each sector avoids the one-sided walls.
+/// Heights are ignored, since doors and lifts can still open up the view.
+pub fn build_reject_in_background(map: Map, cache: Option<Arc<DiskCache>>) {
+	let result = std::thread::Builder::new()
+		.name("reject builder".into())
+		.spawn(move || {
+			let start_time = Instant::now();
+
+			if let Some(table) = build_reject(&map) {
+				if let Some(cache) = cache {
+					cache.store(map.reject.cache_key, &table);
+				}
+
+				*map.reject.table.write().unwrap() = Some(table);
+				log::debug!("REJECT table built in {:?}", Instant::now() - start_time);
+			}
+		});
+
+	if let Err(e) = result {
+		log::error!("Couldn't start building the REJECT table: {}", e);
+	}
+}
+
+fn build_reject(map: &Map) -> Option<Vec<u8>> {
+	let sector_count = map.sectors.len();
+	let mut table = vec![0u8; (sector_count * sector_count + 7) / 8];
+
+	let mut reject = |from: usize, to: usize| {
+		for &bit in &[from * sector_count + to, to * sector_count + from] {
+			table[bit / 8] |= 1 << (bit % 8);
+		}
+	};
+
+	let components = connected_components(map);
+	let samples: Vec<Vec<Vector2<f32>>> = (0..sector_count)
+		.map(|sector_index| sample_points(map, sector_index))
+		.collect();
+
+	for from in 0..sector_count {
+		// Stop when the map is no longer used by anything else
+		if Arc::strong_count(&map.reject.table) == 1 {
+			return None;
+		}
+
+		for to in from + 1..sector_count {
+			if components[from] != components[to] {
+				reject(from, to);
+				continue;
+			}
+
+			let visible = samples[from].iter().any(|&from_point| {
+				samples[to]
+					.iter()
+					.any(|&to_point| check_static_sight(map, from_point, to_point))
+			});
+
+			if !visible {
+				reject(from, to);
+			}
+		}
+	}
+
+	Some(table)
+}
+
+/// Numbers each sector by the group of sectors it's connected to through two-sided linedefs.
+fn connected_components(map: &Map) -> Vec<usize> {
+	let mut components = vec![usize::max_value(); map.sectors.len()];
+	let mut stack = Vec::new();
+
+	for start in 0..map.sectors.len() {
+		if components[start] != usize::max_value() {
+			continue;
+		}
+
+		components[start] = start;
+		stack.push(start);
+
+		while let Some(sector_index) = stack.pop() {
+			for &neighbour in &map.sectors[sector_index].neighbours {
+				if components[neighbour] == usize::max_value() {
+					components[neighbour] = start;
+					stack.push(neighbour);
+				}
+			}
+		}
+	}
+
+	components
+}
+
+/// Returns points spread around the inside of a sector: the middle of each of its subsectors,
+/// and the middle of each of its linedefs, moved a little way into the sector.
+fn sample_points(map: &Map, sector_index: usize) -> Vec<Vector2<f32>> {
+	let sector = &map.sectors[sector_index];
+	let mut points = Vec::new();
+
+	for &subsector_index in &sector.subsectors {
+		let segs = &map.subsectors[subsector_index].segs;
+
+		if !segs.is_empty() {
+			points.push(
+				segs.iter().map(|seg| seg.line.point).sum::<Vector2<f32>>() / segs.len() as f32,
+			);
+		}
+	}
+
+	for &linedef_index in &sector.linedefs {
+		let linedef = &map.linedefs[linedef_index];
+		let middle = linedef.line.point + linedef.line.dir * 0.5;
+
+		for (side, sidedef) in linedef.sidedefs.iter().enumerate() {
+			match sidedef {
+				Some(sidedef) if sidedef.sector_index == sector_index => {
+					// The normal points to the right side, where the front sidedef is
+					let direction = if side == 0 { 1.0 } else { -1.0 };
+					points.push(middle + linedef.normal * direction);
+				}
+				_ => (),
+			}
+		}
+	}
+
+	points
+}
+
+/// Returns whether the line between two points doesn't cross any one-sided linedefs.
+fn check_static_sight(map: &Map, from: Vector2<f32>, to: Vector2<f32>) -> bool {
+	if from == to {
+		return true;
+	}
+
+	let sight_line = Line2::new(from, to - from);
+	let mut bbox = AABB2::from_point(from);
+	bbox.add_point(to);
+
+	let mut blocked = false;
+
+	map.traverse_nodes(NodeChild::Node(0), &bbox, &mut |node: NodeChild| {
+		if blocked {
+			return;
+		}
+
+		let linedefs = match node {
+			NodeChild::Subsector(index) => &map.subsectors[index].linedefs,
+			NodeChild::Node(index) => &map.nodes[index].linedefs,
+		};
+
+		blocked = linedefs.iter().any(|&linedef_index| {
+			let linedef = &map.linedefs[linedef_index];
+
+			if linedef.sidedefs[1].is_some() || !bbox.overlaps(&linedef.bbox) {
+				return false;
+			}
+
+			match sight_line.intersect(&linedef.line) {
+				Some((sight_param, linedef_param)) => {
+					(0.0..=1.0).contains(&sight_param) && (0.0..=1.0).contains(&linedef_param)
+				}
+				None => false,
+			}
+		});
+	});
+
+	!blocked
+}
@@ -0,0 +1,154 @@
+use crate::{
+	common::geometry::{Line2, AABB2},
+	doom::map::{Map, MapDynamic, NodeChild},
+};
+use nalgebra::{Vector2, Vector3};
+
+/// Returns whether there is a clear line of sight between two points.
+/// Like the original game, only one-sided walls and the openings between sectors
+/// can block sight. Things don't.
+pub fn check_sight(
+	map: &Map,
+	map_dynamic: &MapDynamic,
+	from: Vector3<f32>,
+	to: Vector3<f32>,
+) -> bool {
+	let from2 = from.fixed_resize(0.0);
+	let to2 = to.fixed_resize(0.0);
+
+	if from2 == to2 {
+		return true;
+	}
+
+	// The REJECT table rules out most pairs of sectors without checking any lines
+	if map.reject.is_rejected(
+		map.find_subsector(from2).sector_index,
+		map.find_subsector(to2).sector_index,
+	) {
+		return false;
+	}
+
+	let sight_line = Line2::new(from2, to2 - from2);
+	let mut bbox = AABB2::from_point(from2);
+	bbox.add_point(to2);
+
+	let mut blocked = false;
+
+	map.traverse_nodes(NodeChild::Node(0), &bbox, &mut |node: NodeChild| {
+		if blocked {
+			return;
+		}
+
+		let linedefs = match node {
+			NodeChild::Subsector(index) => &map.subsectors[index].linedefs,
+			NodeChild::Node(index) => &map.nodes[index].linedefs,
+		};
+
+		for linedef_index in linedefs.iter().copied() {
+			let linedef = &map.linedefs[linedef_index];
+
+			if !bbox.overlaps(&linedef.bbox) {
+				continue;
+			}
+
+			let (sight_param, linedef_param) = match sight_line.intersect(&linedef.line) {
+				Some(x) => x,
+				None => continue,
+			};
+
+			if !(0.0..=1.0).contains(&sight_param) || !(0.0..=1.0).contains(&linedef_param) {
+				continue;
+			}
+
+			// Check whether the line passes through the opening between the two sectors
+			blocked = match &linedef.sidedefs {
+				[Some(front_sidedef), Some(back_sidedef)] => {
+					let opening = map_dynamic.sectors[front_sidedef.sector_index]
+						.interval
+						.intersection(map_dynamic.sectors[back_sidedef.sector_index].interval);
+					let z = from[2] + (to[2] - from[2]) * sight_param;
+					!opening.contains(z)
+				}
+				_ => true,
+			};
+
+			if blocked {
+				return;
+			}
+		}
+	});
+
+	!blocked
+}
+
+/// Returns whether the line between two points passes through a two-sided linedef
+/// that is closed off, like a closed door or a raised lift.
+/// Linedefs that border the sector with index `ignore_sector` are skipped,
+/// so that a door isn't in the way of its own sounds.
+pub fn check_closed_opening(
+	map: &Map,
+	map_dynamic: &MapDynamic,
+	from: Vector2<f32>,
+	to: Vector2<f32>,
+	ignore_sector: usize,
+) -> bool {
+	if from == to {
+		return false;
+	}
+
+	let line = Line2::new(from, to - from);
+	let mut bbox = AABB2::from_point(from);
+	bbox.add_point(to);
+
+	let mut closed = false;
+
+	map.traverse_nodes(NodeChild::Node(0), &bbox, &mut |node: NodeChild| {
+		if closed {
+			return;
+		}
+
+		let linedefs = match node {
+			NodeChild::Subsector(index) => &map.subsectors[index].linedefs,
+			NodeChild::Node(index) => &map.nodes[index].linedefs,
+		};
+
+		for linedef_index in linedefs.iter().copied() {
+			let linedef = &map.linedefs[linedef_index];
+
+			if !bbox.overlaps(&linedef.bbox) {
+				continue;
+			}
+
+			let (front_sidedef, back_sidedef) = match &linedef.sidedefs {
+				[Some(front_sidedef), Some(back_sidedef)] => (front_sidedef, back_sidedef),
+				_ => continue,
+			};
+
+			if front_sidedef.sector_index == ignore_sector
+				|| back_sidedef.sector_index == ignore_sector
+			{
+				continue;
+			}
+
+			let (line_param, linedef_param) = match line.intersect(&linedef.line) {
+				Some(x) => x,
+				None => continue,
+			};
+
+			if !(0.0..=1.0).contains(&line_param) || !(0.0..=1.0).contains(&linedef_param) {
+				continue;
+			}
+
+			closed = map_dynamic.sectors[front_sidedef.sector_index]
+				.interval
+				.intersection(map_dynamic.sectors[back_sidedef.sector_index].interval)
+				.is_empty_or_point();
+
+			if closed {
+				return;
+			}
+		}
+	});
+
+	closed
+}
@@ -1,29 +1,47 @@
 use crate::{
 	common::{
-		assets::{AssetHandle, AssetStorage},
+		assets::{AssetHandle, AssetName, AssetStorage},
+		configvars::ConfigVariables,
+		events::{add_events, Events},
 		frame::FrameState,
 		geometry::{Interval, AABB2, AABB3},
+		profiler,
 		quadtree::Quadtree,
 		spawn::{SpawnMerger, SpawnMergerHandlerSet},
 		time::Timer,
 	},
 	doom::{
-		components::{SpawnPoint, Transform},
-		entitytemplate::{EntityTemplate, EntityTemplateRef, EntityTypeId},
+		client::UseAction,
+		components::{DeathmatchSpawnPoint, SpawnPoint, Transform, Velocity},
+		entitytemplate::{
+			load_template_sprites, EntityTemplate, EntityTemplateRef, EntityTypeId, TemplateIndex,
+		},
+		game::{GameMode, Skill},
+		item::Dropped,
+		linespecial::{LineSpecialTouch, LineSpecialUse},
 		map::{
-			AnimState, LinedefDynamic, LinedefRef, Map, MapDynamic, SectorDynamic, SectorRef,
-			SidedefDynamic, Thing, ThingFlags,
+			AnimState, LineActivation, LinedefDynamic, LinedefRef, Map, MapDynamic, SectorDynamic,
+			SectorRef, SidedefDynamic, Thing, ThingFlags,
 		},
-		physics::BoxCollider,
+		monster::Ambush,
+		physics::{BoxCollider, TouchAction},
+		state::StateName,
 	},
 };
 use anyhow::bail;
 use legion::{
-	any,
+	any, component,
 	systems::{CommandBuffer, ResourceSet},
 	Entity, IntoQuery, Read, Resources, World, Write,
 };
 use nalgebra::{Vector2, Vector3};
+use rand::{seq::SliceRandom, Rng};
+
+/// Sent whenever an entity is spawned from a template.
+#[derive(Clone, Copy, Debug)]
+pub struct SpawnEvent {
+	pub entity: Entity,
+}
 
 #[derive(Clone, Debug)]
 pub struct SpawnContext {
@@ -38,6 +56,11 @@ pub fn spawn_entity(
 	template_handle: AssetHandle<EntityTemplate>,
 	transform: Transform,
 ) -> Entity {
+	load_template_sprites(
+		&template_handle,
+		&mut <Write<AssetStorage>>::fetch_mut(resources),
+	);
+
 	// Create spawn context and insert into resources, for SpawnFrom implementations to read
 	let spawn_context = {
 		let asset_storage = <Read<AssetStorage>>::fetch(resources);
@@ -76,6 +99,8 @@ pub fn spawn_entity(
 		}
 	};
 
+	<Write<Events<SpawnEvent>>>::fetch_mut(resources).single_write(SpawnEvent { entity });
+
 	// Add entity to quadtree
 	let mut quadtree = <Write<Quadtree>>::fetch_mut(resources);
 	if let Ok((entity, box_collider, transform)) =
@@ -88,36 +113,112 @@ pub fn spawn_entity(
 	entity
 }
 
+/// An entity to be spawned by `spawn_request_system`,
+/// for systems that don't have the access to spawn it themselves.
+#[derive(Clone, Debug)]
+pub struct SpawnRequest {
+	pub template_name: AssetName,
+	pub transform: Transform,
+	pub velocity: Vector3<f32>,
+	/// Whether to mark the entity as a dropped item.
+	pub dropped: bool,
+}
+
+pub fn spawn_request_system(
+	resources: &mut Resources,
+) -> Box<dyn FnMut(&mut World, &mut Resources)> {
+	add_events::<SpawnEvent>(resources);
+
+	Box::new(|world, resources| {
+		let _profile = profiler::scope("spawn_request_system");
+
+		let requests = std::mem::take(&mut *<Write<Vec<SpawnRequest>>>::fetch_mut(resources));
+
+		for request in requests {
+			let template_handle = match <Read<AssetStorage>>::fetch(resources)
+				.handle_for_name::<EntityTemplate>(request.template_name)
+			{
+				Some(handle) => handle,
+				None => {
+					log::warn!("Entity template not found: {}", request.template_name);
+					continue;
+				}
+			};
+
+			let entity = spawn_entity(world, resources, template_handle, request.transform);
+
+			if let Ok(velocity) = <&mut Velocity>::query().get_mut(world, entity) {
+				velocity.velocity = request.velocity;
+			}
+
+			if request.dropped {
+				world.entry(entity).unwrap().add_component(Dropped);
+			}
+		}
+	})
+}
+
 pub fn spawn_things(
 	things: Vec<Thing>,
 	world: &mut World,
 	resources: &mut Resources,
 ) -> anyhow::Result<()> {
+	let (skill_flag, game_mode, nomonsters) = {
+		let (config_variables, game_mode, skill) =
+			<(Read<ConfigVariables>, Read<GameMode>, Read<Skill>)>::fetch(resources);
+		(
+			skill.thing_flag(),
+			*game_mode,
+			config_variables.get::<bool>("nomonsters"),
+		)
+	};
+	let see_state = StateName::from("see").unwrap();
+
 	for (i, thing) in things.into_iter().enumerate() {
-		if thing.flags.intersects(ThingFlags::DMONLY) {
+		if !game_mode.is_multiplayer() && thing.flags.intersects(ThingFlags::MULTIPLAYER) {
 			continue;
 		}
 
-		if !thing.flags.intersects(ThingFlags::EASY) {
+		if !thing.flags.intersects(skill_flag) {
 			continue;
 		}
 
 		// Find entity template
-		let template_handle = {
-			let asset_storage = <Read<AssetStorage>>::fetch(resources);
-
-			let template_handle = match asset_storage
-				.iter::<EntityTemplate>()
-				.find(|(_, template)| template.type_id == Some(EntityTypeId::Thing(thing.r#type)))
-			{
-				Some((x, _)) => x.clone(),
+		let (template_handle, is_monster) = {
+			let (asset_storage, frame_state, template_index) =
+				<(Read<AssetStorage>, Read<FrameState>, Read<TemplateIndex>)>::fetch(resources);
+
+			let handle = template_index
+				.get(EntityTypeId::Thing(thing.r#type))
+				.cloned()
+				.and_then(|handle| {
+					let mut rng = frame_state.rng.lock().unwrap();
+					resolve_random_spawner(&asset_storage, handle, &mut *rng)
+				});
+
+			match handle.map(|x| {
+				let template = asset_storage.get(&x).unwrap();
+				(x, template)
+			}) {
+				Some((x, template)) => {
+					// Anything that can chase the player counts as a monster
+					let is_monster = template.states.contains_key(&see_state);
+
+					if nomonsters && is_monster {
+						continue;
+					}
+
+					if game_mode == GameMode::Deathmatch && template.not_in_deathmatch {
+						continue;
+					}
+
+					(x, is_monster)
+				}
 				None => {
 					log::warn!("Thing {} has invalid thing type {}", i, thing.r#type);
 					continue;
 				}
-			};
-
-			template_handle
+			}
 		};
 
 		// Use NAN to use the default spawn height
@@ -126,12 +227,44 @@ pub fn spawn_things(
 			rotation: Vector3::new(0.into(), 0.into(), thing.angle),
 		};
 
-		spawn_entity(world, resources, template_handle, transform);
+		let entity = spawn_entity(world, resources, template_handle, transform);
+
+		if is_monster && thing.flags.intersects(ThingFlags::AMBUSH) {
+			world.entry(entity).unwrap().add_component(Ambush);
+		}
 	}
 
 	Ok(())
 }
 
+/// How many random spawners can refer to each other in a chain, before giving up.
+const MAX_RANDOM_SPAWNER_DEPTH: usize = 8;
+
+/// Picks the template to spawn in place of a random spawner, by weight.
+/// Templates that aren't random spawners are returned as they are.
+fn resolve_random_spawner(
+	asset_storage: &AssetStorage,
+	mut handle: AssetHandle<EntityTemplate>,
+	rng: &mut impl Rng,
+) -> Option<AssetHandle<EntityTemplate>> {
+	for _ in 0..MAX_RANDOM_SPAWNER_DEPTH {
+		let template = asset_storage.get(&handle).unwrap();
+
+		if template.random_spawn.is_empty() {
+			return Some(handle);
+		}
+
+		let (name, _) = template
+			.random_spawn
+			.choose_weighted(rng, |(_, weight)| *weight)
+			.ok()?;
+		handle = asset_storage.handle_for_name::<EntityTemplate>(*name)?;
+	}
+
+	log::warn!("Random spawners nested too deeply");
+	None
+}
+
 pub fn spawn_player(
 	world: &mut World,
 	resources: &mut Resources,
@@ -147,15 +280,31 @@ pub fn spawn_player(
 	};
 
 	// Get spawn point transform
-	let transform = match <(&Transform, &SpawnPoint)>::query()
-		.iter(world)
-		.find_map(|(t, s)| {
-			if s.player_num == player_num {
-				Some(*t)
-			} else {
-				None
-			}
-		}) {
+	// In deathmatch, players appear at a random deathmatch start instead, if there are any
+	let deathmatch_transform = if *<Read<GameMode>>::fetch(resources) == GameMode::Deathmatch {
+		let spawn_points: Vec<Transform> = <&Transform>::query()
+			.filter(component::<DeathmatchSpawnPoint>())
+			.iter(world)
+			.copied()
+			.collect();
+		let frame_state = <Read<FrameState>>::fetch(resources);
+		let mut rng = frame_state.rng.lock().unwrap();
+		spawn_points.choose(&mut *rng).copied()
+	} else {
+		None
+	};
+
+	let transform = match deathmatch_transform.or_else(|| {
+		<(&Transform, &SpawnPoint)>::query()
+			.iter(world)
+			.find_map(|(t, s)| {
+				if s.player_num == player_num {
+					Some(*t)
+				} else {
+					None
+				}
+			})
+	}) {
 		Some(x) => x,
 		None => bail!("Spawn point for player {} not found", player_num),
 	};
@@ -169,10 +318,11 @@ pub fn spawn_map_entities(
 	map_handle: &AssetHandle<Map>,
 ) -> anyhow::Result<()> {
 	let mut command_buffer = CommandBuffer::new(world);
-	let (asset_storage, frame_state, handler_set) = <(
+	let (asset_storage, frame_state, handler_set, template_index) = <(
 		Read<AssetStorage>,
 		Read<FrameState>,
 		Read<SpawnMergerHandlerSet>,
+		Read<TemplateIndex>,
 	)>::fetch(resources);
 	let mut merger = SpawnMerger::new(&handler_set, &resources);
 
@@ -205,9 +355,9 @@ pub fn spawn_map_entities(
 	for (i, linedef) in map.linedefs.iter().enumerate() {
 		let entity = if let Some(special_type) = linedef.special_type {
 			// Fetch and add entity template
-			let (handle, template) = match asset_storage
-				.iter::<EntityTemplate>()
-				.find(|(_, template)| template.type_id == Some(EntityTypeId::Linedef(special_type)))
+			let (handle, template) = match template_index
+				.get(EntityTypeId::Linedef(special_type))
+				.map(|handle| (handle, asset_storage.get(handle).unwrap()))
 			{
 				Some(entry) => entry,
 				None => {
@@ -232,6 +382,31 @@ pub fn spawn_map_entities(
 			// Set entity template reference
 			command_buffer.add_component(entity, EntityTemplateRef(handle.clone()));
 
+			entity
+		} else if let Some(line_special) = linedef.line_special {
+			let entity = command_buffer.push(());
+
+			match line_special.activation {
+				LineActivation::Cross => command_buffer.add_component(
+					entity,
+					TouchAction::LineSpecialTouch(LineSpecialTouch {
+						special: line_special,
+					}),
+				),
+				LineActivation::Use => command_buffer.add_component(
+					entity,
+					UseAction::LineSpecialUse(LineSpecialUse {
+						special: line_special,
+					}),
+				),
+				activation => log::debug!(
+					"Linedef {} has special {} with unsupported activation {:?}",
+					i,
+					line_special.special,
+					activation
+				),
+			}
+
 			entity
 		} else {
 			command_buffer.push(())
@@ -263,9 +438,9 @@ pub fn spawn_map_entities(
 	for (i, sector) in map.sectors.iter().enumerate() {
 		let entity = if let Some(special_type) = sector.special_type {
 			// Fetch and add entity template
-			let (handle, template) = match asset_storage
-				.iter::<EntityTemplate>()
-				.find(|(_, template)| template.type_id == Some(EntityTypeId::Sector(special_type)))
+			let (handle, template) = match template_index
+				.get(EntityTypeId::Sector(special_type))
+				.map(|handle| (handle, asset_storage.get(handle).unwrap()))
 			{
 				Some(entry) => entry,
 				None => {
@@ -299,6 +474,8 @@ pub fn spawn_map_entities(
 			entity,
 			light_level: sector.light_level,
 			interval: sector.interval,
+			previous_interval: sector.interval,
+			sound_target: None,
 		});
 		command_buffer.add_component(
 			entity,
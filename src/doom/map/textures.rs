@@ -1,17 +1,29 @@
 use crate::{
-	common::assets::{AssetHandle, AssetStorage, ImportData},
+	common::{
+		assets::{AssetHandle, AssetStorage, ImportData},
+		cache::CacheKey,
+		video::{bcn, AsBytes, ImageUploader, RenderContext},
+	},
 	doom::{
-		image::{IAColor, Image, ImageData},
+		image::{IAColor, Image, ImageData, Palette, RGBAColor},
+		map::Map,
 		wad::read_string,
 	},
 };
-use anyhow::{anyhow, Context};
+use anyhow::{anyhow, bail, ensure, Context};
 use arrayvec::ArrayString;
-use byteorder::{ReadBytesExt, LE};
+use byteorder::{ReadBytesExt, WriteBytesExt, LE};
 use fnv::FnvHashMap;
 use nalgebra::Vector2;
 use relative_path::RelativePath;
-use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::{
+	io::{Cursor, Read, Seek, SeekFrom},
+	sync::Arc,
+};
+use vulkano::{
+	format::Format,
+	image::{Dimensions, ImageViewAccess},
+};
 
 pub fn import_flat(
 	path: &RelativePath,
@@ -48,6 +60,20 @@ pub fn import_wall(
 		.or(texture2.and_then(|t| t.get(name)))
 		.ok_or(anyhow!("Texture {} does not exist", name))?
 		.clone();
+
+	// Drawing all the patches takes a while for big textures, so the result is cached
+	let cache_key = match asset_storage.cache() {
+		Some(_) => Some(wall_cache_key(&texture_info, asset_storage)?),
+		None => None,
+	};
+
+	if let Some(bytes) = cache_key.and_then(|key| asset_storage.cache()?.load(key)) {
+		match ImageData::from_cache(&bytes) {
+			Ok(image_data) => return Ok(Box::new(image_data)),
+			Err(err) => log::warn!("Couldn't read texture {} from the cache: {}", name, err),
+		}
+	}
+
 	let mut data = vec![IAColor::default(); texture_info.size[0] * texture_info.size[1]];
 
 	texture_info
@@ -92,11 +118,43 @@ pub fn import_wall(
 			Ok(())
 		})?;
 
-	Ok(Box::new(ImageData {
+	let image_data = ImageData {
 		data,
 		size: texture_info.size,
 		offset: Vector2::zeros(),
-	}))
+	};
+
+	if let (Some(cache), Some(key)) = (asset_storage.cache(), cache_key) {
+		cache.store(key, &image_data.to_cache());
+	}
+
+	Ok(Box::new(image_data))
+}
+
+/// Makes the key for a composed texture, from its definition and the lumps of its patches.
+fn wall_cache_key(
+	texture_info: &TextureInfo,
+	asset_storage: &AssetStorage,
+) -> anyhow::Result<CacheKey> {
+	let mut definition = Vec::new();
+	definition.write_u32::<LE>(texture_info.size[0] as u32)?;
+	definition.write_u32::<LE>(texture_info.size[1] as u32)?;
+	let mut patches = Vec::with_capacity(texture_info.patches.len());
+
+	for patch_info in &texture_info.patches {
+		definition.extend_from_slice(patch_info.name.as_bytes());
+		definition.write_i32::<LE>(patch_info.offset[0] as i32)?;
+		definition.write_i32::<LE>(patch_info.offset[1] as i32)?;
+		patches.push(
+			asset_storage
+				.source()
+				.load(RelativePath::new(&patch_info.name))?,
+		);
+	}
+
+	let mut sources = vec![definition.as_slice()];
+	sources.extend(patches.iter().map(Vec::as_slice));
+	Ok(CacheKey::new("wall", &sources))
 }
 
 pub type PNames = Vec<ArrayString<[u8; 8]>>;
@@ -105,8 +163,17 @@ pub fn import_pnames(
 	path: &RelativePath,
 	asset_storage: &mut AssetStorage,
 ) -> anyhow::Result<Box<dyn ImportData>> {
-	let mut reader = Cursor::new(asset_storage.source().load(path)?);
+	let data = asset_storage.source().load(path)?;
+	let mut reader = Cursor::new(&data[..]);
 	let count = reader.read_u32::<LE>()? as usize;
+
+	ensure!(
+		count <= (data.len() - 4) / 8,
+		"PNAMES has {} names, but the lump only holds {}",
+		count,
+		(data.len() - 4) / 8
+	);
+
 	let mut ret = Vec::with_capacity(count);
 
 	for _ in 0..count {
@@ -136,9 +203,19 @@ pub fn import_textures(
 ) -> anyhow::Result<Box<dyn ImportData>> {
 	let pnames_handle = asset_storage.load::<PNames>("pnames");
 	let pnames = asset_storage.get(&pnames_handle).unwrap();
-	let mut reader = Cursor::new(asset_storage.source().load(path)?);
+	let data = asset_storage.source().load(path)?;
+	let mut reader = Cursor::new(&data[..]);
 
 	let count = reader.read_u32::<LE>()? as usize;
+
+	ensure!(
+		count <= (data.len() - 4) / 4,
+		"{} has {} textures, but the lump only holds {}",
+		path,
+		count,
+		(data.len() - 4) / 4
+	);
+
 	let mut offsets = Vec::with_capacity(count);
 
 	for _ in 0..count {
@@ -151,7 +228,7 @@ pub fn import_textures(
 			.map(|offset| {
 				reader.seek(SeekFrom::Start(offset))?;
 
-				let name = read_string(&mut reader)?;
+				let texture_name = read_string(&mut reader)?;
 				reader.read_u32::<LE>()?; // unused
 				let size = [reader.read_u16::<LE>()?, reader.read_u16::<LE>()?];
 				reader.read_u32::<LE>()?; // unused
@@ -165,13 +242,20 @@ pub fn import_textures(
 						reader.read_i16::<LE>()? as isize,
 					);
 					let index = reader.read_u16::<LE>()? as usize;
-					let name = format!("{}.patch", pnames[index]);
+					let name = match pnames.get(index) {
+						Some(name) => format!("{}.patch", name),
+						None => bail!(
+							"Texture \"{}\" has invalid patch index {}",
+							texture_name,
+							index
+						),
+					};
 					reader.read_u32::<LE>()?; // unused
 					patches.push(PatchInfo { offset, name })
 				}
 
 				Ok((
-					name.as_str().to_owned(),
+					texture_name.as_str().to_owned(),
 					TextureInfo {
 						size: [size[0] as usize, size[1] as usize],
 						patches,
@@ -198,3 +282,181 @@ impl TextureType {
 		}
 	}
 }
+
+/// Where a texture is stored within a `TextureArrays`.
+#[derive(Clone, Copy, Debug)]
+pub struct TextureLayer {
+	pub array: usize,
+	pub layer: u32,
+	pub dimensions: Dimensions,
+}
+
+/// The wall and flat textures of a map, packed into image arrays, one for each texture size.
+/// This lets the renderer draw everything that uses the same array with a single draw call.
+pub struct TextureArrays {
+	pub arrays: Vec<Arc<dyn ImageViewAccess + Send + Sync>>,
+	layers: FnvHashMap<AssetHandle<Image>, TextureLayer>,
+}
+
+/// Vulkan implementations are required to support at least this many array layers.
+const MAX_ARRAY_LAYERS: usize = 256;
+
+impl TextureArrays {
+	pub fn new(
+		map_handle: &AssetHandle<Map>,
+		render_context: &RenderContext,
+		asset_storage: &mut AssetStorage,
+		uploader: &mut ImageUploader,
+		compress: bool,
+	) -> anyhow::Result<TextureArrays> {
+		let map = asset_storage.get(map_handle).unwrap();
+		let mut handles: Vec<AssetHandle<Image>> = map
+			.linedefs
+			.iter()
+			.flat_map(|linedef| linedef.sidedefs.iter().flatten())
+			.flat_map(|sidedef| sidedef.textures.iter())
+			.chain(map.sectors.iter().flat_map(|sector| sector.textures.iter()))
+			.filter_map(|texture| match texture {
+				TextureType::Normal(handle) => Some(handle.clone()),
+				_ => None,
+			})
+			.chain(
+				map.anims
+					.values()
+					.flat_map(|anim| anim.frames.iter().cloned()),
+			)
+			.chain(
+				map.switches
+					.iter()
+					.flat_map(|(a, b)| vec![a.clone(), b.clone()]),
+			)
+			.collect();
+		handles.sort_by_cached_key(|handle| asset_storage.name_of(handle).map(str::to_owned));
+		handles.dedup();
+
+		// Group the textures by size
+		let palette_handle: AssetHandle<Palette> = asset_storage.load("playpal.palette");
+		let mut groups: FnvHashMap<[usize; 2], Vec<(AssetHandle<Image>, Vec<RGBAColor>)>> =
+			FnvHashMap::default();
+
+		for handle in handles {
+			let name = match asset_storage.name_of(&handle) {
+				Some(name) => name.to_owned(),
+				None => {
+					log::warn!("Texture has no name, it can't be put in a texture array");
+					continue;
+				}
+			};
+
+			let image_data_handle = asset_storage.load::<ImageData>(&name);
+			let image_data = asset_storage.get(&image_data_handle).unwrap();
+			let palette = asset_storage.get(&palette_handle).unwrap();
+			groups
+				.entry(image_data.size)
+				.or_insert_with(Vec::new)
+				.push((handle, image_data.to_rgba(palette)));
+		}
+
+		// Compressed formats are optional in Vulkan
+		let (opaque_format, alpha_format) = if compress {
+			let supported = |format: Format| {
+				let physical_device = render_context.device().physical_device();
+				format
+					.properties(physical_device)
+					.optimal_tiling_features
+					.sampled_image
+			};
+			(
+				Some(Format::BC1_RGBUnormBlock).filter(|&format| supported(format)),
+				Some(Format::BC3UnormBlock).filter(|&format| supported(format)),
+			)
+		} else {
+			(None, None)
+		};
+
+		// Create an array for each group
+		let mut arrays = Vec::new();
+		let mut layers = FnvHashMap::default();
+		let mut compressed = 0;
+
+		for (size, textures) in groups {
+			for chunk in textures.chunks(MAX_ARRAY_LAYERS) {
+				let dimensions = Dimensions::Dim2dArray {
+					width: size[0] as u32,
+					height: size[1] as u32,
+					array_layers: chunk.len() as u32,
+				};
+				let opaque = chunk
+					.iter()
+					.all(|(_, data)| data.iter().all(|colour| colour.a == 0xFF));
+
+				// Blocks are 4x4 texels, so only sizes that are a multiple of that can be compressed
+				let format = match (opaque, opaque_format, alpha_format) {
+					_ if size[0] % 4 != 0 || size[1] % 4 != 0 => None,
+					(true, Some(format), _) => Some(format),
+					(_, _, Some(format)) => Some(format),
+					_ => None,
+				};
+
+				let (data, format): (Vec<u8>, _) = match format {
+					Some(format) => {
+						compressed += chunk.len();
+						let compress_func = if format == Format::BC1_RGBUnormBlock {
+							bcn::compress_bc1
+						} else {
+							bcn::compress_bc3
+						};
+						let data = chunk
+							.iter()
+							.flat_map(|(_, data)| compress_func(data.as_bytes(), size[0], size[1]))
+							.collect();
+						(data, format)
+					}
+					None => {
+						let data = chunk
+							.iter()
+							.flat_map(|(_, data)| data.as_bytes().iter().copied())
+							.collect();
+						(data, Format::R8G8B8A8Unorm)
+					}
+				};
+
+				let image = uploader
+					.add(render_context, &data, dimensions, format)
+					.context("Couldn't create texture array")?;
+
+				for (layer, (handle, _)) in chunk.iter().enumerate() {
+					layers.insert(
+						handle.clone(),
+						TextureLayer {
+							array: arrays.len(),
+							layer: layer as u32,
+							dimensions,
+						},
+					);
+				}
+
+				arrays.push(image as Arc<dyn ImageViewAccess + Send + Sync>);
+			}
+		}
+
+		log::debug!(
+			"Packed {} textures into {} texture arrays, {} of them compressed",
+			layers.len(),
+			arrays.len(),
+			compressed,
+		);
+
+		Ok(TextureArrays { arrays, layers })
+	}
+
+	/// Returns where the given texture is stored, if it was packed.
+	pub fn layer(&self, handle: &AssetHandle<Image>) -> Option<TextureLayer> {
+		self.layers.get(handle).copied()
+	}
+
+	/// Returns all packed textures and where they are stored.
+	pub fn layers(&self) -> impl Iterator<Item = (&AssetHandle<Image>, TextureLayer)> {
+		self.layers.iter().map(|(handle, layer)| (handle, *layer))
+	}
+}
@@ -0,0 +1,223 @@
+use crate::{
+	common::geometry::{Interval, AABB2},
+	doom::map::{Map, MapDynamic, NodeChild, Seg},
+};
+use nalgebra::Vector2;
+use std::f32::consts::PI;
+
+/// The parts of a map that can be seen from a given position.
+#[derive(Clone, Debug, Default)]
+pub struct Visibility {
+	/// Indices of the visible subsectors, in front-to-back order.
+	pub subsectors: Vec<usize>,
+	/// For each linedef, whether any of its segs are visible.
+	pub linedefs: Vec<bool>,
+}
+
+impl Visibility {
+	/// Returns a visibility where the whole map can be seen.
+	pub fn all(map: &Map) -> Visibility {
+		Visibility {
+			subsectors: (0..map.subsectors.len()).collect(),
+			linedefs: vec![true; map.linedefs.len()],
+		}
+	}
+}
+
+/// Determines which parts of the map can be seen from `position`.
+/// The BSP tree is traversed front to back, while keeping track of the view angles
+/// that are already blocked by solid walls. Anything that lies entirely behind those
+/// walls is skipped.
+pub fn visibility(map: &Map, map_dynamic: &MapDynamic, position: Vector2<f32>) -> Visibility {
+	let mut traversal = Traversal {
+		map,
+		map_dynamic,
+		position,
+		clipper: Clipper::default(),
+		visibility: Visibility {
+			subsectors: Vec::new(),
+			linedefs: vec![false; map.linedefs.len()],
+		},
+	};
+
+	traversal.traverse(NodeChild::Node(0));
+	traversal.visibility
+}
+
+struct Traversal<'a> {
+	map: &'a Map,
+	map_dynamic: &'a MapDynamic,
+	position: Vector2<f32>,
+	clipper: Clipper,
+	visibility: Visibility,
+}
+
+impl<'a> Traversal<'a> {
+	fn traverse(&mut self, child: NodeChild) {
+		match child {
+			NodeChild::Subsector(index) => self.visit_subsector(index),
+			NodeChild::Node(index) => {
+				let node = &self.map.nodes[index];
+				let dot = self.position.dot(&node.plane.normal) - node.plane.distance;
+				let front = (dot <= 0.0) as usize;
+
+				// Visit the side the viewer is on first
+				for side in [front, 1 - front].iter().copied() {
+					if self.clipper.is_full() {
+						return;
+					}
+
+					if self.bbox_visible(&node.child_bboxes[side]) {
+						self.traverse(node.child_indices[side]);
+					}
+				}
+			}
+		}
+	}
+
+	fn visit_subsector(&mut self, index: usize) {
+		let subsector = &self.map.subsectors[index];
+		let mut visible = contains(&subsector.bbox, self.position);
+
+		for seg in subsector.segs.iter() {
+			// Segs facing away from the viewer are seen from the other subsector
+			if (self.position - seg.line.point).dot(&seg.normal) <= 0.0 {
+				continue;
+			}
+
+			let span = angle_span(
+				angle_to(self.position, seg.line.point + seg.line.dir),
+				angle_to(self.position, seg.line.point),
+			);
+
+			if !self.clipper.is_visible(&span) {
+				continue;
+			}
+
+			visible = true;
+
+			if let Some((linedef_index, _)) = seg.linedef {
+				self.visibility.linedefs[linedef_index] = true;
+
+				if self.is_solid(seg) {
+					self.clipper.add(&span);
+				}
+			}
+		}
+
+		if visible {
+			self.visibility.subsectors.push(index);
+		}
+	}
+
+	/// Whether a seg blocks the view entirely: either it has no back side,
+	/// or the sector behind it is closed off.
+	fn is_solid(&self, seg: &Seg) -> bool {
+		let (linedef_index, _) = match seg.linedef {
+			Some(x) => x,
+			None => return false,
+		};
+
+		match &self.map.linedefs[linedef_index].sidedefs {
+			[Some(front_sidedef), Some(back_sidedef)] => {
+				let front_interval = self.map_dynamic.sectors[front_sidedef.sector_index].interval;
+				let back_interval = self.map_dynamic.sectors[back_sidedef.sector_index].interval;
+				front_interval
+					.intersection(back_interval)
+					.is_empty_or_point()
+			}
+			_ => true,
+		}
+	}
+
+	fn bbox_visible(&self, bbox: &AABB2) -> bool {
+		if contains(bbox, self.position) {
+			return true;
+		}
+
+		// Measure the corner angles relative to the centre, so that they never wrap around
+		let centre = angle_to(self.position, bbox.middle());
+		let corners = [
+			Vector2::new(bbox[0].min, bbox[1].min),
+			Vector2::new(bbox[0].min, bbox[1].max),
+			Vector2::new(bbox[0].max, bbox[1].min),
+			Vector2::new(bbox[0].max, bbox[1].max),
+		];
+		let offsets = corners.iter().fold(Interval::empty(), |offsets, corner| {
+			offsets.add(wrap_angle(angle_to(self.position, *corner) - centre))
+		});
+
+		self.clipper.is_visible(&angle_span(
+			wrap_angle(centre + offsets.min),
+			wrap_angle(centre + offsets.max),
+		))
+	}
+}
+
+/// Sorted, non-overlapping ranges of view angles that are blocked by solid walls.
+#[derive(Clone, Debug, Default)]
+struct Clipper {
+	ranges: Vec<Interval>,
+}
+
+impl Clipper {
+	fn is_full(&self) -> bool {
+		self.ranges.len() == 1 && Interval::new(-PI, PI).is_inside(self.ranges[0])
+	}
+
+	fn is_visible(&self, span: &[Interval; 2]) -> bool {
+		span.iter().copied().any(|angles| {
+			!angles.is_empty() && !self.ranges.iter().any(|range| angles.is_inside(*range))
+		})
+	}
+
+	fn add(&mut self, span: &[Interval; 2]) {
+		for angles in span.iter().copied().filter(|angles| !angles.is_empty()) {
+			let mut merged = angles;
+			self.ranges.retain(|range| {
+				if range.overlaps(merged) {
+					merged = merged.union(*range);
+					false
+				} else {
+					true
+				}
+			});
+
+			let index = self
+				.ranges
+				.iter()
+				.position(|range| range.min > merged.min)
+				.unwrap_or(self.ranges.len());
+			self.ranges.insert(index, merged);
+		}
+	}
+}
+
+/// Returns the view angles covered by turning counterclockwise from `start` to `end`.
+/// The range is split in two if it crosses the boundary between -π and π.
+fn angle_span(start: f32, end: f32) -> [Interval; 2] {
+	if start <= end {
+		[Interval::new(start, end), Interval::empty()]
+	} else {
+		[Interval::new(start, PI), Interval::new(-PI, end)]
+	}
+}
+
+#[inline]
+fn angle_to(position: Vector2<f32>, point: Vector2<f32>) -> f32 {
+	let dir = point - position;
+	dir[1].atan2(dir[0])
+}
+
+#[inline]
+fn wrap_angle(angle: f32) -> f32 {
+	(angle + PI).rem_euclid(2.0 * PI) - PI
+}
+
+#[inline]
+fn contains(bbox: &AABB2, point: Vector2<f32>) -> bool {
+	bbox[0].min <= point[0]
+		&& bbox[0].max >= point[0]
+		&& bbox[1].min <= point[1]
+		&& bbox[1].max >= point[1]
+}
@@ -0,0 +1,381 @@
+use crate::{
+	common::assets::{AssetStorage, ImportData},
+	doom::decorate::{Token, Tokenizer},
+};
+use anyhow::{bail, Context};
+use fnv::FnvHashMap;
+use relative_path::RelativePath;
+
+/// Name that the map information is loaded as.
+pub const MAPINFO_NAME: &str = "mapinfo";
+
+/// Lumps that map information is read from, in order of preference.
+/// Only the first one that exists is used.
+const MAPINFO_LUMPS: [&str; 2] = ["umapinfo", "mapinfo"];
+
+/// Keywords that start a new definition in MAPINFO. In the old syntax without braces,
+/// these end the properties of the map before them.
+const DEFINITION_KEYWORDS: [&str; 16] = [
+	"map",
+	"defaultmap",
+	"adddefaultmap",
+	"gamedefaults",
+	"episode",
+	"clearepisodes",
+	"cluster",
+	"clusterdef",
+	"skill",
+	"clearskills",
+	"gameinfo",
+	"intermission",
+	"automap",
+	"doomednums",
+	"damagetype",
+	"include",
+];
+
+/// Overrides for maps, as defined in a UMAPINFO or MAPINFO lump.
+#[derive(Clone, Debug, Default)]
+pub struct MapInfo {
+	maps: FnvHashMap<String, MapInfoEntry>,
+}
+
+impl MapInfo {
+	pub fn get(&self, map: &str) -> Option<&MapInfoEntry> {
+		self.maps.get(&map.to_ascii_lowercase())
+	}
+}
+
+/// Overrides for a single map. Anything that isn't given keeps the default behaviour.
+#[derive(Clone, Debug, Default)]
+pub struct MapInfoEntry {
+	/// Name of the level, or of a string in the `StringTable` if it starts with `$`.
+	pub level_name: Option<String>,
+	/// Name of the music lump.
+	pub music: Option<String>,
+	/// Name of the sky texture.
+	pub sky: Option<String>,
+	/// Par time in seconds.
+	pub par_time: Option<u32>,
+	pub next: Option<String>,
+	pub next_secret: Option<String>,
+	/// Whether leaving the map ends the game, rather than going to another map.
+	pub end_game: bool,
+	/// Specials that are triggered when every monster of a kind has died.
+	/// If given, these replace the built-in ones, so an empty list disables them.
+	pub boss_actions: Option<Vec<BossAction>>,
+}
+
+/// Activates a linedef special on sectors with a tag, once all monsters of a kind are dead.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BossAction {
+	/// Name of the entity template of the monster.
+	pub template: String,
+	pub special: u16,
+	pub tag: u16,
+}
+
+/// Returns the entry for `map`, if there is map information and it has one.
+/// The map information is loaded together with the first map.
+pub fn map_info<'a>(asset_storage: &'a AssetStorage, map: &str) -> Option<&'a MapInfoEntry> {
+	let handle = asset_storage.handle_for::<MapInfo>(MAPINFO_NAME)?;
+	asset_storage.get(&handle)?.get(map)
+}
+
+pub fn import_mapinfo(
+	_path: &RelativePath,
+	asset_storage: &mut AssetStorage,
+) -> anyhow::Result<Box<dyn ImportData>> {
+	// Map information is optional, without it every map uses the defaults
+	let path = match MAPINFO_LUMPS
+		.iter()
+		.map(|lump| RelativePath::new(*lump))
+		.find(|path| asset_storage.source().exists(path))
+	{
+		Some(path) => path,
+		None => return Ok(Box::new(MapInfo::default())),
+	};
+
+	let data = asset_storage.source().load(path)?;
+	let map_info = parse_mapinfo(&String::from_utf8_lossy(&data))
+		.with_context(|| format!("Couldn't parse {}", path.as_str().to_ascii_uppercase()))?;
+	log::info!(
+		"Loaded {} maps from {}",
+		map_info.maps.len(),
+		path.as_str().to_ascii_uppercase()
+	);
+
+	Ok(Box::new(map_info))
+}
+
+pub fn placeholder_mapinfo() -> Box<dyn ImportData> {
+	Box::new(MapInfo::default())
+}
+
+/// Parses UMAPINFO, as well as the map definitions of both the old and new MAPINFO syntax.
+/// Everything that isn't a map definition, and properties that the engine doesn't use,
+/// are skipped.
+pub fn parse_mapinfo(text: &str) -> anyhow::Result<MapInfo> {
+	let mut tokens = Tokenizer::new(text).collect::<anyhow::Result<Vec<Token>>>()?;
+	tokens.reverse();
+	let mut parser = Parser { tokens };
+	let mut map_info = MapInfo::default();
+
+	while let Some(token) = parser.next() {
+		match token {
+			Token::Word(word) if word.eq_ignore_ascii_case("map") => {
+				let name = match parser.next() {
+					Some(Token::Word(name)) | Some(Token::String(name)) => {
+						name.to_ascii_lowercase()
+					}
+					token => bail!("Expected a map name, found {:?}", token),
+				};
+				let entry = map_info.maps.entry(name.clone()).or_default();
+				parser
+					.parse_map(entry)
+					.with_context(|| format!("Invalid definition for map {}", name))?;
+			}
+			// Other definitions aren't used
+			_ => {
+				parser.skip_line();
+
+				if parser.peek() == Some(&Token::Symbol('{')) {
+					parser.skip_block();
+				}
+			}
+		}
+	}
+
+	Ok(map_info)
+}
+
+struct Parser {
+	/// Remaining tokens, in reverse order.
+	tokens: Vec<Token>,
+}
+
+impl Parser {
+	/// Returns the next token, skipping newlines.
+	fn next(&mut self) -> Option<Token> {
+		self.skip_newlines();
+		self.tokens.pop()
+	}
+
+	fn peek(&mut self) -> Option<&Token> {
+		self.skip_newlines();
+		self.tokens.last()
+	}
+
+	fn skip_newlines(&mut self) {
+		while self.tokens.last() == Some(&Token::Newline) {
+			self.tokens.pop();
+		}
+	}
+
+	/// Returns whether the next token is on the same line.
+	fn same_line(&self) -> bool {
+		match self.tokens.last() {
+			Some(Token::Newline) | None => false,
+			_ => true,
+		}
+	}
+
+	/// Skips the rest of the current line.
+	fn skip_line(&mut self) {
+		while self.same_line() {
+			self.tokens.pop();
+		}
+	}
+
+	/// Skips a block in braces, including any blocks inside it.
+	fn skip_block(&mut self) {
+		let mut depth = 0;
+
+		while let Some(token) = self.next() {
+			match token {
+				Token::Symbol('{') => depth += 1,
+				Token::Symbol('}') => {
+					depth -= 1;
+
+					if depth == 0 {
+						break;
+					}
+				}
+				_ => {}
+			}
+		}
+	}
+
+	fn parse_map(&mut self, entry: &mut MapInfoEntry) -> anyhow::Result<()> {
+		// MAPINFO gives the level name after the map name
+		while self.same_line() {
+			match self.tokens.pop() {
+				Some(Token::String(name)) => entry.level_name = Some(name),
+				Some(Token::Word(word)) if word.eq_ignore_ascii_case("lookup") => {
+					// The name is looked up in the string table when it's shown
+					match self.tokens.pop() {
+						Some(Token::String(name)) | Some(Token::Word(name)) => {
+							entry.level_name = Some(format!("${}", name))
+						}
+						_ => {}
+					}
+				}
+				_ => {}
+			}
+		}
+
+		if self.peek() == Some(&Token::Symbol('{')) {
+			self.next();
+
+			loop {
+				match self.next() {
+					Some(Token::Symbol('}')) => break,
+					Some(Token::Word(key)) => {
+						let values = self.parse_values();
+						parse_property(entry, &key, &values)
+							.with_context(|| format!("Invalid value for \"{}\"", key))?;
+					}
+					token => bail!("Expected a property name, found {:?}", token),
+				}
+			}
+		} else {
+			// Old MAPINFO syntax, with one property per line until the next definition
+			while let Some(Token::Word(key)) = self.peek() {
+				if DEFINITION_KEYWORDS
+					.iter()
+					.any(|keyword| key.eq_ignore_ascii_case(keyword))
+				{
+					break;
+				}
+
+				let key = key.clone();
+				self.next();
+				let values = self.parse_values();
+				parse_property(entry, &key, &values)
+					.with_context(|| format!("Invalid value for \"{}\"", key))?;
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Returns the values of a property, up to the end of the line.
+	/// A list of values that ends a line with a comma carries on to the next line.
+	fn parse_values(&mut self) -> Vec<Token> {
+		let mut values = Vec::new();
+		let mut continues = false;
+
+		loop {
+			match self.tokens.last() {
+				Some(Token::Newline) if continues => {
+					self.tokens.pop();
+				}
+				Some(Token::Newline) | Some(Token::Symbol('}')) | None => break,
+				Some(_) => {
+					let token = self.tokens.pop().unwrap();
+					continues = token == Token::Symbol(',');
+
+					match token {
+						Token::Symbol('=') | Token::Symbol(',') => {}
+						token => values.push(token),
+					}
+				}
+			}
+		}
+
+		values
+	}
+}
+
+fn parse_property(entry: &mut MapInfoEntry, key: &str, values: &[Token]) -> anyhow::Result<()> {
+	match key.to_ascii_lowercase().as_str() {
+		"levelname" => entry.level_name = Some(string_value(values)?),
+		"music" => entry.music = Some(string_value(values)?.to_ascii_lowercase()),
+		"skytexture" | "sky1" => entry.sky = Some(string_value(values)?.to_ascii_lowercase()),
+		"partime" | "par" => entry.par_time = Some(number_value(values)? as u32),
+		"next" => {
+			let next = string_value(values)?.to_ascii_lowercase();
+
+			// MAPINFO ends the game with a special name instead of a separate property
+			if next.starts_with("endgame") || next == "endtitle" {
+				entry.end_game = true;
+			} else {
+				entry.next = Some(next);
+			}
+		}
+		"nextsecret" | "secretnext" => {
+			entry.next_secret = Some(string_value(values)?.to_ascii_lowercase())
+		}
+		"endgame" => {
+			entry.end_game = match values.first() {
+				None => true,
+				Some(Token::Word(word)) if word.eq_ignore_ascii_case("true") => true,
+				Some(Token::Word(word)) if word.eq_ignore_ascii_case("false") => false,
+				Some(token) => bail!("Expected true or false, found {:?}", token),
+			}
+		}
+		"bossaction" => {
+			let boss_actions = entry.boss_actions.get_or_insert_with(Vec::new);
+
+			match values {
+				[Token::Word(word)] if word.eq_ignore_ascii_case("clear") => boss_actions.clear(),
+				[Token::Word(class), Token::Number(special), Token::Number(tag)] => boss_actions
+					.push(BossAction {
+						template: template_name(class),
+						special: *special as u16,
+						tag: *tag as u16,
+					}),
+				_ => bail!(
+					"Expected a monster, a special and a tag, found {:?}",
+					values
+				),
+			}
+		}
+		_ => {}
+	}
+
+	Ok(())
+}
+
+fn string_value(values: &[Token]) -> anyhow::Result<String> {
+	match values.first() {
+		Some(Token::String(string)) | Some(Token::Word(string)) => Ok(string.clone()),
+		token => bail!("Expected a string, found {:?}", token),
+	}
+}
+
+fn number_value(values: &[Token]) -> anyhow::Result<f32> {
+	match values.first() {
+		Some(Token::Number(number)) => Ok(*number),
+		token => bail!("Expected a number, found {:?}", token),
+	}
+}
+
+/// Returns the name of the template for a DECORATE class name of one of the original monsters.
+/// Other names are assumed to refer to custom actors.
+fn template_name(class: &str) -> String {
+	let class = class.to_ascii_lowercase();
+	let name = match class.as_str() {
+		"zombieman" => "possessed",
+		"shotgunguy" => "shotguy",
+		"archvile" => "vile",
+		"revenant" => "undead",
+		"chaingunguy" => "chainguy",
+		"doomimp" => "troop",
+		"demon" => "sergeant",
+		"spectre" => "shadows",
+		"cacodemon" => "head",
+		"baronofhell" => "bruiser",
+		"hellknight" => "knight",
+		"lostsoul" => "skull",
+		"spidermastermind" => "spider",
+		"arachnotron" => "baby",
+		"cyberdemon" => "cyborg",
+		"painelemental" => "pain",
+		"wolfensteinss" => "wolfss",
+		"commanderkeen" => "keen",
+		_ => return class,
+	};
+
+	name.to_owned()
+}
@@ -0,0 +1,172 @@
+use crate::{
+	common::assets::AssetStorage,
+	doom::{
+		image::{Image, ImageData},
+		map::{textures::TextureArrays, Map},
+		sound::Sound,
+		sprite::Sprite,
+	},
+};
+use fnv::FnvHashSet;
+use legion::{systems::ResourceSet, Read, Resources};
+use std::mem::{size_of, size_of_val};
+use vulkano::{
+	format::Format,
+	image::{Dimensions, ImageAccess, ImageViewAccess},
+};
+
+/// Logs how many assets of each type are stored and roughly how much memory they take,
+/// for the `meminfo` command.
+///
+/// Assets are never freed, so the "unused" column counts those that nothing refers to anymore.
+/// If it keeps growing after every `map` command, something is loading assets it doesn't need.
+pub fn meminfo(resources: &Resources) {
+	let asset_storage = <Read<AssetStorage>>::fetch(resources);
+
+	// Images that belong to sprites are counted separately from the rest
+	let sprite_images: FnvHashSet<_> = asset_storage
+		.iter::<Sprite>()
+		.flat_map(|(_, sprite)| sprite.frames().iter().flatten())
+		.map(|info| info.handle.clone())
+		.collect();
+	let (mut sprite_count, mut sprite_bytes) = (0, 0);
+	let (mut texture_count, mut texture_bytes) = (0, 0);
+
+	for (handle, image) in asset_storage.iter::<Image>() {
+		let bytes = image_bytes(image.image.dimensions(), image.image.parent().format());
+
+		if sprite_images.contains(handle) {
+			sprite_count += 1;
+			sprite_bytes += bytes;
+		} else {
+			texture_count += 1;
+			texture_bytes += bytes;
+		}
+	}
+
+	let (array_count, array_bytes) = match resources.get::<TextureArrays>() {
+		Some(texture_arrays) => (
+			texture_arrays.arrays.len(),
+			texture_arrays
+				.arrays
+				.iter()
+				.map(|array| image_bytes(array.dimensions(), array.parent().format()))
+				.sum(),
+		),
+		None => (0, 0),
+	};
+
+	let image_data_bytes = asset_storage
+		.iter::<ImageData>()
+		.map(|(_, image_data)| size_of_val(image_data.data.as_slice()))
+		.sum();
+	let sound_bytes = asset_storage
+		.iter::<Sound>()
+		.map(|(_, sound)| size_of_val(&*sound.data))
+		.sum();
+	let map_bytes = asset_storage
+		.iter::<Map>()
+		.map(|(_, map)| map_bytes(map))
+		.sum();
+
+	let (image_count, unused_images) = asset_storage.usage::<Image>();
+	let (image_data_count, unused_image_data) = asset_storage.usage::<ImageData>();
+	let (sound_count, unused_sounds) = asset_storage.usage::<Sound>();
+	let (map_count, unused_maps) = asset_storage.usage::<Map>();
+
+	log::info!(
+		"{:<16}{:>8}{:>8}{:>12}",
+		"Storage",
+		"Count",
+		"Unused",
+		"Size"
+	);
+	log_row(
+		"Images",
+		image_count,
+		Some(unused_images),
+		sprite_bytes + texture_bytes,
+	);
+	log_row("  sprites", sprite_count, None, sprite_bytes);
+	log_row("  textures", texture_count, None, texture_bytes);
+	log_row("Texture arrays", array_count, None, array_bytes);
+	log_row(
+		"Image data",
+		image_data_count,
+		Some(unused_image_data),
+		image_data_bytes,
+	);
+	log_row("Sounds", sound_count, Some(unused_sounds), sound_bytes);
+	log_row("Maps", map_count, Some(unused_maps), map_bytes);
+	log::info!(
+		"GPU memory in images: {}",
+		format_bytes(sprite_bytes + texture_bytes + array_bytes)
+	);
+}
+
+fn log_row(name: &str, count: usize, unused: Option<usize>, bytes: usize) {
+	log::info!(
+		"{:<16}{:>8}{:>8}{:>12}",
+		name,
+		count,
+		unused.map_or_else(String::new, |unused| unused.to_string()),
+		format_bytes(bytes)
+	);
+}
+
+/// Images have no mipmaps, and are either RGBA with 8 bits per channel or compressed.
+fn image_bytes(dimensions: Dimensions, format: Format) -> usize {
+	let [width, height, depth] = dimensions.width_height_depth();
+	let texels =
+		width as usize * height as usize * depth as usize * dimensions.array_layers() as usize;
+
+	match format {
+		Format::BC1_RGBUnormBlock | Format::BC1_RGBAUnormBlock => texels / 2,
+		Format::BC3UnormBlock => texels,
+		_ => texels * 4,
+	}
+}
+
+/// The size of the map data, leaving out the small things.
+fn map_bytes(map: &Map) -> usize {
+	let sector_count = map.sectors.len();
+
+	size_of_val(map.linedefs.as_slice())
+		+ size_of_val(map.nodes.as_slice())
+		+ map
+			.nodes
+			.iter()
+			.map(|node| size_of_val(node.linedefs.as_slice()))
+			.sum::<usize>()
+		+ size_of_val(map.sectors.as_slice())
+		+ map
+			.sectors
+			.iter()
+			.map(|sector| {
+				(sector.linedefs.len() + sector.subsectors.len() + sector.neighbours.len())
+					* size_of::<usize>()
+			})
+			.sum::<usize>()
+		+ size_of_val(map.subsectors.as_slice())
+		+ map
+			.subsectors
+			.iter()
+			.map(|subsector| {
+				size_of_val(subsector.segs.as_slice())
+					+ size_of_val(subsector.collision_planes.as_slice())
+					+ size_of_val(subsector.linedefs.as_slice())
+			})
+			.sum::<usize>()
+		// The reject table has one bit for each pair of sectors
+		+ (sector_count * sector_count + 7) / 8
+}
+
+fn format_bytes(bytes: usize) -> String {
+	if bytes >= 1 << 20 {
+		format!("{:.1} MiB", bytes as f64 / (1 << 20) as f64)
+	} else if bytes >= 1 << 10 {
+		format!("{:.1} KiB", bytes as f64 / (1 << 10) as f64)
+	} else {
+		format!("{} B", bytes)
+	}
+}
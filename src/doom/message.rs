@@ -0,0 +1,110 @@
+use crate::{
+	common::{assets::AssetStorage, profiler},
+	doom::{
+		language::StringTable,
+		profile::GameProfile,
+		ui::{HudFont, UiAlignment, UiTransform},
+	},
+};
+use legion::{systems::ResourceSet, Entity, Read, Resources, World, Write};
+use nalgebra::Vector2;
+use std::time::{Duration, Instant};
+
+/// How long a message stays on screen.
+pub const MESSAGE_TIME: Duration = Duration::from_secs(4);
+
+const LINE_HEIGHT: f32 = 9.0;
+
+/// A message printed in the middle of the screen, such as by a script.
+/// Text that starts with `$` is looked up in the `StringTable` when it's shown.
+#[derive(Clone, Debug, Default)]
+pub struct HudMessage {
+	message: Option<(String, Instant)>,
+	changed: bool,
+}
+
+impl HudMessage {
+	/// Shows a message, replacing the one that was there.
+	pub fn show(&mut self, text: &str) {
+		self.message = Some((text.to_owned(), Instant::now() + MESSAGE_TIME));
+		self.changed = true;
+	}
+
+	pub fn clear(&mut self) {
+		self.message = None;
+		self.changed = true;
+	}
+}
+
+/// Keeps the message text in the HUD up to date.
+pub fn message_system(resources: &mut Resources) -> Box<dyn FnMut(&mut World, &mut Resources)> {
+	let font = {
+		let (mut asset_storage, game_profile) =
+			<(Write<AssetStorage>, Read<GameProfile>)>::fetch_mut(resources);
+		HudFont::load(&mut asset_storage, *game_profile)
+	};
+	let mut entities: Vec<Entity> = Vec::new();
+
+	Box::new(move |world, resources| {
+		let _profile = profiler::scope("message_system");
+
+		let (asset_storage, mut hud_message, string_table) =
+			<(Read<AssetStorage>, Write<HudMessage>, Read<StringTable>)>::fetch_mut(resources);
+
+		if hud_message
+			.message
+			.as_ref()
+			.map_or(false, |(_, expires)| *expires <= Instant::now())
+		{
+			hud_message.clear();
+		}
+
+		// The text entities are gone if the world was cleared
+		if entities.first().map_or(false, |e| !world.contains(*e)) {
+			entities.clear();
+			hud_message.changed = true;
+		}
+
+		if !hud_message.changed || !font.is_ready(&asset_storage) {
+			return;
+		}
+
+		hud_message.changed = false;
+
+		for entity in entities.drain(..) {
+			world.remove(entity);
+		}
+
+		let text = match &hud_message.message {
+			Some((text, _)) => string_table
+				.resolve(text)
+				.unwrap_or(text)
+				.to_ascii_uppercase(),
+			None => return,
+		};
+
+		// Centred on the screen, above the middle
+		let lines: Vec<&str> = text.lines().collect();
+		let top = 84.0 - (lines.len() as f32 * LINE_HEIGHT * 0.5).floor();
+
+		for (i, line) in lines.iter().enumerate() {
+			let width = font.width(line, &asset_storage);
+
+			entities.extend(font.spawn_text(
+				world,
+				&asset_storage,
+				line,
+				UiTransform {
+					position: Vector2::new(
+						160.0 - (width * 0.5).floor(),
+						top + i as f32 * LINE_HEIGHT,
+					),
+					depth: 20.0,
+					alignment: [UiAlignment::Middle, UiAlignment::Middle],
+					size: Vector2::new(0.0, 0.0),
+					stretch: [false; 2],
+				},
+			));
+		}
+	})
+}
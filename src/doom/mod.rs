@@ -1,36 +1,72 @@
+pub mod acs;
+pub mod boss;
 pub mod camera;
+pub mod caption;
+pub mod check;
 pub mod client;
 pub mod components;
 pub mod data;
+pub mod decal;
+pub mod decorate;
+pub mod demo;
+pub mod dialog;
 pub mod door;
 pub mod entitytemplate;
+pub mod exit;
 pub mod floor;
+pub mod fuzz;
+pub mod game;
+pub mod health;
 pub mod image;
 pub mod input;
+pub mod intermission;
+pub mod item;
+pub mod language;
 pub mod light;
+pub mod linespecial;
 pub mod map;
+pub mod mapinfo;
+pub mod meminfo;
+pub mod message;
+pub mod module;
+pub mod monster;
+pub mod music;
+pub mod palette;
+pub mod particle;
 pub mod physics;
 pub mod plat;
+pub mod profile;
+pub mod progression;
 pub mod psprite;
 pub mod render;
+#[cfg(feature = "scripting")]
+pub mod scripting;
 pub mod sectormove;
 pub mod sound;
+pub mod spectator;
 pub mod sprite;
 pub mod state;
+pub mod stats;
 pub mod switch;
 pub mod texture;
+pub mod title;
 pub mod ui;
 pub mod wad;
+pub mod wadtool;
 
 use crate::{
 	common::assets::{AssetStorage, ImportData},
 	doom::{
+		acs::behavior::import_behavior,
+		dialog::import_dialogs,
 		image::{import_palette, import_patch},
 		map::{
 			load::import_map,
 			textures::{import_flat, import_pnames, import_textures, import_wall},
 		},
-		sound::import_sound,
+		mapinfo::import_mapinfo,
+		music::import_music,
+		sound::{import_sndinfo, import_sound},
 		sprite::import_sprite,
 	},
 };
@@ -42,8 +78,11 @@ pub fn import(
 	asset_storage: &mut AssetStorage,
 ) -> anyhow::Result<Box<dyn ImportData>> {
 	let function = match path.extension() {
+		Some("behavior") => import_behavior,
+		Some("dialog") => import_dialogs,
 		Some("flat") => import_flat,
 		Some("map") => import_map,
+		Some("music") => import_music,
 		Some("palette") => import_palette,
 		Some("patch") => import_patch,
 		Some("sound") => import_sound,
@@ -51,7 +90,9 @@ pub fn import(
 		Some("texture") => import_wall,
 		Some(ext) => bail!("Unsupported file extension: {}", ext),
 		None => match path.file_name() {
+			Some("mapinfo") => import_mapinfo,
 			Some("pnames") => import_pnames,
+			Some("sndinfo") => import_sndinfo,
 			Some("texture1") | Some("texture2") => import_textures,
 			Some(name) => bail!("File has no extension: {}", name),
 			None => bail!("Path ends in '..'"),
@@ -0,0 +1,315 @@
+use crate::{
+	common::{
+		assets::{AssetStorage, Importer},
+		configvars::ConfigVariables,
+		module::GameModule,
+		spawn::SpawnMergerHandlerSet,
+		video::{RenderContext, RenderTarget},
+	},
+	doom::{
+		acs, boss, camera, caption, client, components, data, decal, decorate, dialog, door,
+		entitytemplate, exit, floor, game, health, image, intermission, item, language, light,
+		linespecial, map, mapinfo, message, monster, music, palette, particle, physics, plat,
+		profile::GameProfile, psprite, render, sectormove, sound, spectator, sprite, state, stats,
+		switch, texture, ui,
+	},
+};
+use anyhow::bail;
+use legion::{
+	systems::{Builder, ResourceSet},
+	Read, Resources, World, Write,
+};
+use nalgebra::Vector2;
+use std::time::Duration;
+
+/// Doom and the games built on it, like Doom II, Freedoom and Chex Quest.
+/// The differences between those are handled by the `GameProfile`.
+pub struct DoomModule {
+	game_profile: GameProfile,
+}
+
+impl DoomModule {
+	pub fn new(game_profile: GameProfile) -> DoomModule {
+		DoomModule { game_profile }
+	}
+}
+
+impl GameModule for DoomModule {
+	fn name(&self) -> &'static str {
+		"doom"
+	}
+
+	fn frame_time(&self) -> Duration {
+		data::FRAME_TIME
+	}
+
+	fn importer(&self) -> Importer {
+		crate::doom::import
+	}
+
+	fn add_asset_types(&self, asset_storage: &mut AssetStorage) {
+		asset_storage.add_storage::<acs::behavior::Behavior>(false);
+		asset_storage.add_storage::<dialog::Dialogs>(false);
+		asset_storage.add_storage::<entitytemplate::EntityTemplate>(false);
+		asset_storage.add_storage::<image::Image>(true);
+		asset_storage.add_storage::<image::ImageData>(false);
+		asset_storage.add_storage::<image::Palette>(false);
+		asset_storage.add_storage::<map::Map>(false);
+		asset_storage.add_storage::<mapinfo::MapInfo>(false);
+		asset_storage.add_storage::<music::Music>(false);
+		asset_storage.add_storage::<map::textures::PNames>(false);
+		asset_storage.add_storage::<map::textures::Textures>(false);
+		asset_storage.add_storage::<sprite::Sprite>(false);
+		asset_storage.add_storage::<sound::Sound>(false);
+		asset_storage.add_storage::<sound::SoundInfo>(false);
+		asset_storage.set_placeholder::<image::Image>(image::placeholder_image);
+		asset_storage.set_placeholder::<image::ImageData>(image::placeholder_image);
+		asset_storage.set_placeholder::<mapinfo::MapInfo>(mapinfo::placeholder_mapinfo);
+		asset_storage.set_placeholder::<music::Music>(music::placeholder_music);
+		asset_storage.set_placeholder::<sound::Sound>(sound::placeholder_sound);
+		asset_storage.set_placeholder::<sprite::Sprite>(sprite::placeholder_sprite);
+	}
+
+	#[rustfmt::skip]
+	fn register_components(&self, handler_set: &mut SpawnMergerHandlerSet) {
+		handler_set.register_clone::<camera::Camera>();
+		handler_set.register_clone::<client::UseAction>();
+		handler_set.register_clone::<client::User>();
+		handler_set.register_clone::<components::DeathmatchSpawnPoint>();
+		handler_set.register_clone::<components::NoGravity>();
+		handler_set.register_clone::<components::SpawnPoint>();
+		handler_set.register_clone::<dialog::Talker>();
+		handler_set.register_spawn::<components::TransformDef, components::Transform>();
+		handler_set.register_from::<components::VelocityDef, components::Velocity>();
+		handler_set.register_clone::<door::DoorActive>();
+		handler_set.register_spawn::<entitytemplate::EntityTemplateRefDef, entitytemplate::EntityTemplateRef>();
+		handler_set.register_clone::<floor::FloorActive>();
+		handler_set.register_clone::<health::Dead>();
+		handler_set.register_clone::<health::Health>();
+		handler_set.register_clone::<light::DynamicLight>();
+		handler_set.register_spawn::<light::LightFlashDef, light::LightFlash>();
+		handler_set.register_clone::<light::LightGlow>();
+		handler_set.register_clone::<map::LinedefRef>();
+		handler_set.register_clone::<map::MapDynamic>();
+		handler_set.register_clone::<map::SectorRef>();
+		handler_set.register_clone::<monster::Ambush>();
+		handler_set.register_clone::<monster::Target>();
+		handler_set.register_clone::<item::Dropped>();
+		handler_set.register_clone::<item::Pickup>();
+		handler_set.register_clone::<item::PowerPickup>();
+		handler_set.register_clone::<sound::SectorReverb>();
+		handler_set.register_clone::<palette::PaletteEffects>();
+		handler_set.register_clone::<particle::ParticleEmitter>();
+		handler_set.register_clone::<physics::BoxCollider>();
+		handler_set.register_clone::<physics::TouchAction>();
+		handler_set.register_clone::<plat::PlatActive>();
+		handler_set.register_clone::<psprite::PlayerSpriteRender>();
+		handler_set.register_clone::<sectormove::CeilingMove>();
+		handler_set.register_clone::<sectormove::FloorMove>();
+		handler_set.register_clone::<sound::AmbientSound>();
+		handler_set.register_clone::<sound::SoundPlaying>();
+		handler_set.register_clone::<sprite::SpriteRender>();
+		handler_set.register_spawn::<state::StateDef, state::State>();
+		handler_set.register_clone::<stats::CountItem>();
+		handler_set.register_clone::<stats::CountKill>();
+		handler_set.register_clone::<stats::SecretSector>();
+		handler_set.register_clone::<switch::SwitchActive>();
+		handler_set.register_clone::<texture::TextureScroll>();
+	}
+
+	fn add_resources(&self, resources: &mut Resources) {
+		resources.insert(data::get_bindings());
+		resources.insert(data::get_command_bindings());
+		resources.insert(Vec::<map::spawn::SpawnRequest>::new());
+		resources.insert(acs::AcsState::default());
+		resources.insert(boss::BossActions::default());
+		resources.insert(client::Client::default());
+		resources.insert(decal::Decals::default());
+		resources.insert(dialog::DialogState::default());
+		resources.insert(entitytemplate::TemplateIndex::default());
+		resources.insert(game::GameMode::default());
+		resources.insert(game::Skill::default());
+		resources.insert(game::FastMonsters::default());
+		resources.insert(intermission::IntermissionInfo::default());
+		resources.insert(language::StringTable::default());
+		resources.insert(message::HudMessage::default());
+		resources.insert(music::MusicPlayer::default());
+		resources.insert(particle::Particles::default());
+		resources.insert(stats::LevelStats::default());
+	}
+
+	fn load_data(&self, resources: &mut Resources) -> anyhow::Result<()> {
+		entitytemplate::load_default_templates(resources, data::MOBJS)?;
+		load_shared_data(resources)
+	}
+
+	#[rustfmt::skip]
+	fn add_update_systems(&self, builder: &mut Builder, resources: &mut Resources) {
+		builder
+			.add_system(sectormove::sector_interpolation_system())
+			.add_thread_local(client::player_command_system()).flush()
+			.add_thread_local_fn(dialog::dialog_system()).flush()
+			.add_thread_local(client::player_move_system()).flush()
+			.add_thread_local(spectator::spectator_system()).flush()
+			.add_thread_local(client::player_attack_system(resources)).flush()
+			.add_thread_local(client::player_use_system(resources)).flush()
+			.add_thread_local(physics::physics_system(resources)).flush()
+			.add_thread_local(camera::camera_system(resources)).flush()
+			.add_thread_local(psprite::player_sprite_move_system()).flush()
+			.add_thread_local(door::door_use_system(resources)).flush()
+			.add_thread_local(door::door_switch_system(resources)).flush()
+			.add_thread_local(door::door_touch_system(resources)).flush()
+			.add_thread_local(floor::floor_switch_system(resources)).flush()
+			.add_thread_local(floor::floor_touch_system(resources)).flush()
+			.add_thread_local(plat::plat_switch_system(resources)).flush()
+			.add_thread_local(plat::plat_touch_system(resources)).flush()
+			.add_thread_local(exit::exit_switch_system(resources)).flush()
+			.add_thread_local(exit::exit_touch_system(resources)).flush()
+			.add_thread_local_fn(linespecial::line_special_touch_system(resources)).flush()
+			.add_thread_local_fn(linespecial::line_special_use_system(resources)).flush()
+			.add_thread_local(sectormove::sector_move_system(resources)).flush()
+			.add_thread_local(door::door_active_system(resources)).flush()
+			.add_thread_local(floor::floor_active_system(resources)).flush()
+			.add_thread_local(plat::plat_active_system(resources)).flush()
+			.add_thread_local(switch::switch_active_system()).flush()
+			.add_thread_local(monster::monster_look_system()).flush()
+			.add_thread_local(health::damage_system(resources)).flush()
+			.add_thread_local(health::player_death_system()).flush()
+			.add_thread_local(item::pickup_system(resources)).flush()
+			.add_thread_local(stats::secret_system()).flush()
+			.add_thread_local_fn(boss::boss_system()).flush()
+			.add_thread_local_fn(acs::acs_system()).flush()
+			.add_thread_local_fn(health::player_respawn_system()).flush()
+			.add_thread_local(particle::particle_system()).flush()
+			.add_thread_local_fn(item::item_respawn_system(resources)).flush()
+			.add_thread_local_fn(monster::monster_respawn_system(resources)).flush()
+			.add_system(light::light_flash_system())
+			.add_system(light::light_glow_system())
+			.add_system(monster::monster_target_system())
+			.add_system(palette::palette_effects_system())
+			.add_system(stats::level_time_system())
+			.add_system(texture::texture_animation_system())
+			.add_system(texture::texture_scroll_system())
+			.add_system(state::state_system(resources))
+			.flush()
+			.add_thread_local_fn(map::spawn::spawn_request_system(resources)).flush();
+	}
+
+	fn add_output_systems(
+		&self,
+		builder: &mut Builder,
+		resources: &mut Resources,
+	) -> anyhow::Result<()> {
+		let (draw_list, samplers) = {
+			let render_context = resources.get::<RenderContext>().unwrap();
+			let render_target = resources.get::<RenderTarget>().unwrap();
+			let filter = <Read<ConfigVariables>>::fetch(resources).get::<String>("r_filter");
+			(
+				render::create_draw_list(&render_context, render_target.dimensions())?,
+				render::create_samplers(&render_context, &filter)?,
+			)
+		};
+		resources.insert(samplers);
+
+		builder
+			.add_thread_local_fn(render::render_system(draw_list))
+			.add_thread_local_fn(sound::sound_system())
+			.add_thread_local_fn(music::music_system())
+			.add_thread_local_fn(caption::caption_system(resources))
+			.add_thread_local_fn(message::message_system(resources))
+			.add_thread_local_fn(dialog::dialog_ui_system(resources))
+			.add_thread_local_fn(stats::hud_stats_system(resources))
+			.add_thread_local_fn(stats::hud_fps_system(resources))
+			.add_thread_local_fn(intermission::intermission_system(resources));
+
+		Ok(())
+	}
+
+	fn spawn_hud(&self, world: &mut World, resources: &mut Resources) {
+		let mut asset_storage = <Write<AssetStorage>>::fetch_mut(resources);
+
+		world.extend(vec![
+			(
+				ui::UiTransform {
+					position: Vector2::new(0.0, 168.0),
+					depth: 1.0,
+					alignment: [ui::UiAlignment::Near, ui::UiAlignment::Far],
+					size: Vector2::new(320.0, 32.0),
+					stretch: [true, false],
+				},
+				ui::UiImage {
+					image: asset_storage.load(self.game_profile.border_flat()),
+				},
+			),
+			(
+				ui::UiTransform {
+					position: Vector2::new(0.0, 168.0),
+					depth: 2.0,
+					alignment: [ui::UiAlignment::Middle, ui::UiAlignment::Far],
+					size: Vector2::new(320.0, 32.0),
+					stretch: [false; 2],
+				},
+				ui::UiImage {
+					image: asset_storage.load("stbar.patch"),
+				},
+			),
+			(
+				ui::UiTransform {
+					position: Vector2::new(104.0, 168.0),
+					depth: 3.0,
+					alignment: [ui::UiAlignment::Middle, ui::UiAlignment::Far],
+					size: Vector2::new(40.0, 32.0),
+					stretch: [false; 2],
+				},
+				ui::UiImage {
+					image: asset_storage.load("starms.patch"),
+				},
+			),
+			(
+				ui::UiTransform {
+					position: Vector2::new(143.0, 168.0),
+					depth: 10.0,
+					alignment: [ui::UiAlignment::Middle, ui::UiAlignment::Far],
+					size: Vector2::new(24.0, 29.0),
+					stretch: [false; 2],
+				},
+				ui::UiImage {
+					image: asset_storage.load("stfst00.patch"),
+				},
+			),
+		]);
+	}
+
+	fn first_map(&self) -> &'static str {
+		self.game_profile.first_map()
+	}
+
+	fn warp_map_name(&self, warp: &[u32]) -> anyhow::Result<String> {
+		if self.game_profile.has_episodes() {
+			match warp {
+				[episode] => Ok(format!("E{}M1", episode)),
+				[episode, map] => Ok(format!("E{}M{}", episode, map)),
+				_ => unreachable!(),
+			}
+		} else {
+			match warp {
+				[map] => Ok(format!("MAP{:02}", map)),
+				_ => bail!("This IWAD has no episodes, give \"-warp\" only a map number"),
+			}
+		}
+	}
+}
+
+/// Loads the strings, the sector and linedef types, and the templates from the data files.
+/// This comes after the built-in thing templates, which differ between games.
+pub fn load_shared_data(resources: &mut Resources) -> anyhow::Result<()> {
+	language::load_strings(resources)?;
+	data::sectors::load(resources);
+	data::linedefs::load(resources);
+	entitytemplate::load_templates(resources)?;
+	decorate::load_decorate(resources)?;
+	entitytemplate::check_templates(resources);
+	entitytemplate::index_templates(resources);
+
+	Ok(())
+}
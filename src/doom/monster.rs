@@ -0,0 +1,361 @@
+use crate::{
+	common::{
+		assets::{AssetHandle, AssetStorage},
+		frame::FrameState,
+		profiler,
+		quadtree::Quadtree,
+	},
+	doom::{
+		client::User,
+		components::Transform,
+		entitytemplate::{EntityTemplate, EntityTemplateRef},
+		game::{FastMonsters, Skill},
+		map::{sight::check_sight, spawn::spawn_entity, LinedefFlags, Map, MapDynamic},
+		physics::BoxCollider,
+		sound::Sound,
+		sprite::SpriteRender,
+		state::{State, StateName},
+	},
+};
+use legion::{
+	component,
+	systems::{ParallelRunnable, ResourceSet, Runnable},
+	Entity, IntoQuery, Read, Resources, SystemBuilder, World, Write,
+};
+use nalgebra::{Vector2, Vector3};
+use rand::seq::SliceRandom;
+use std::{collections::VecDeque, time::Duration};
+
+/// Monsters that were placed with the ambush flag.
+/// They ignore noise, and only wake up when they see a player.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Ambush;
+
+/// The entity that a monster is after.
+#[derive(Clone, Copy, Debug)]
+pub struct Target {
+	pub entity: Entity,
+	/// While this is above zero, the monster sticks with its current target,
+	/// even if something else attacks it. Counted in frames.
+	pub threshold: u32,
+}
+
+/// How long a monster keeps going after whoever last attacked it, in frames.
+const BASE_THRESHOLD: u32 = 100;
+
+impl Target {
+	/// Called when the monster is attacked by `source`.
+	/// Switches to the new target, unless the monster is still busy with its current one.
+	/// Returns whether the target changed.
+	pub fn retarget(&mut self, source: Entity) -> bool {
+		if self.threshold > 0 || self.entity == source {
+			return false;
+		}
+
+		self.entity = source;
+		self.threshold = BASE_THRESHOLD;
+		true
+	}
+}
+
+/// Players closer than this are noticed even when they're behind a monster.
+const MELEE_RANGE: f32 = 64.0;
+
+pub fn monster_look_system() -> impl Runnable {
+	SystemBuilder::new("monster_look_system")
+		.read_resource::<AssetStorage>()
+		.read_resource::<FastMonsters>()
+		.read_resource::<FrameState>()
+		.write_resource::<Vec<(AssetHandle<Sound>, Entity)>>()
+		.with_query(<&MapDynamic>::query())
+		.with_query(<(Entity, &Transform, &BoxCollider)>::query().filter(component::<User>()))
+		.with_query(
+			<(
+				Entity,
+				&Transform,
+				&BoxCollider,
+				&EntityTemplateRef,
+				&mut SpriteRender,
+				&mut State,
+				Option<&Ambush>,
+			)>::query()
+			.filter(!component::<User>()),
+		)
+		.build(move |command_buffer, world, resources, queries| {
+			let _profile = profiler::scope("monster_look_system");
+
+			let (asset_storage, fast_monsters, frame_state, sound_queue) = resources;
+
+			let players: Vec<(Entity, Vector3<f32>)> = queries
+				.1
+				.iter(world)
+				.map(|(entity, transform, box_collider)| {
+					let position =
+						transform.position + Vector3::new(0.0, 0.0, box_collider.height * 0.5);
+					(*entity, position)
+				})
+				.collect();
+
+			if players.is_empty() {
+				return;
+			}
+
+			let (mut world2, world) = world.split_for_query(&queries.2);
+			let map_dynamic = match queries.0.iter(&world).next() {
+				Some(x) => x,
+				None => return,
+			};
+			let map = asset_storage.get(&map_dynamic.map).unwrap();
+
+			let spawn_state = StateName::from("spawn").unwrap();
+			let see_state = StateName::from("see").unwrap();
+
+			for (entity, transform, box_collider, template_ref, sprite_render, state, ambush) in
+				queries.2.iter_mut(&mut world2)
+			{
+				// Only monsters that are still idle look around
+				if state.current.0 != spawn_state {
+					continue;
+				}
+
+				let template = asset_storage.get(&template_ref.0).unwrap();
+
+				if !template.states.contains_key(&see_state) {
+					continue;
+				}
+
+				let eye_position =
+					transform.position + Vector3::new(0.0, 0.0, box_collider.height * 0.75);
+				let forward = Vector3::new(
+					transform.rotation[2].cos() as f32,
+					transform.rotation[2].sin() as f32,
+					0.0,
+				);
+
+				let seen_player = players.iter().find(|(_, player_position)| {
+					let to_player = *player_position - eye_position;
+
+					// Monsters can only see what is in front of them
+					if to_player.dot(&forward) < 0.0
+						&& Vector2::new(to_player[0], to_player[1]).norm() > MELEE_RANGE
+					{
+						return false;
+					}
+
+					check_sight(map, map_dynamic, eye_position, *player_position)
+				});
+
+				// Ambushing monsters ignore noise, and wait until they see someone
+				let ssect =
+					map.find_subsector(Vector2::new(transform.position[0], transform.position[1]));
+				let heard_noise = match ambush {
+					Some(_) => None,
+					None => map_dynamic.sectors[ssect.sector_index].sound_target,
+				};
+
+				if let Some(target) = seen_player.map(|(e, _)| *e).or(heard_noise) {
+					command_buffer.add_component(
+						*entity,
+						Target {
+							entity: target,
+							threshold: 0,
+						},
+					);
+					state.set(
+						(see_state, 0),
+						&template.states,
+						sprite_render,
+						frame_state.time,
+						fast_monsters.0,
+					);
+
+					if let Some(sounds) = template.sounds.get("see") {
+						let mut rng = frame_state.rng.lock().unwrap();
+
+						if let Some(sound) = sounds.choose(&mut *rng) {
+							sound_queue.push((sound.clone(), *entity));
+						}
+					}
+				}
+			}
+		})
+}
+
+/// How long a monster stays dead on Nightmare before it comes back.
+pub const MONSTER_RESPAWN_TIME: Duration = Duration::from_secs(12);
+
+/// A monster that was killed, waiting to come back to life.
+#[derive(Clone, Debug)]
+pub struct MonsterRespawn {
+	pub template_handle: AssetHandle<EntityTemplate>,
+	/// Where the monster died.
+	pub transform: Transform,
+	pub ambush: bool,
+	/// The body that is left behind, which is removed when the monster respawns.
+	pub corpse: Option<Entity>,
+	/// Game time at which the monster reappears.
+	pub time: Duration,
+}
+
+/// Monsters that will respawn, in the order they were killed.
+#[derive(Clone, Debug, Default)]
+pub struct MonsterRespawnQueue {
+	queue: VecDeque<MonsterRespawn>,
+}
+
+impl MonsterRespawnQueue {
+	/// Schedules a monster to reappear at `transform` after `MONSTER_RESPAWN_TIME`.
+	/// Should be called when a monster is killed.
+	/// Only skills where `Skill::respawn_monsters` is true will actually respawn it.
+	pub fn push(
+		&mut self,
+		template_handle: AssetHandle<EntityTemplate>,
+		transform: Transform,
+		ambush: bool,
+		corpse: Option<Entity>,
+		frame_state: &FrameState,
+	) {
+		self.queue.push_back(MonsterRespawn {
+			template_handle,
+			transform,
+			ambush,
+			corpse,
+			time: frame_state.time + MONSTER_RESPAWN_TIME,
+		});
+	}
+
+	pub fn clear(&mut self) {
+		self.queue.clear();
+	}
+}
+
+pub fn monster_respawn_system(
+	resources: &mut Resources,
+) -> Box<dyn FnMut(&mut World, &mut Resources)> {
+	resources.insert(MonsterRespawnQueue::default());
+	let sound_handle: AssetHandle<Sound> =
+		<Write<AssetStorage>>::fetch_mut(resources).load("dstelept.sound");
+
+	Box::new(move |world, resources| {
+		let _profile = profiler::scope("monster_respawn_system");
+
+		let (due, fog_handle) = {
+			let (asset_storage, frame_state, skill, mut queue) = <(
+				Read<AssetStorage>,
+				Read<FrameState>,
+				Read<Skill>,
+				Write<MonsterRespawnQueue>,
+			)>::fetch_mut(resources);
+
+			if !skill.respawn_monsters() {
+				queue.clear();
+				return;
+			}
+
+			let mut due = Vec::new();
+
+			while queue
+				.queue
+				.front()
+				.map_or(false, |monster| monster.time <= frame_state.time)
+			{
+				due.extend(queue.queue.pop_front());
+			}
+
+			if due.is_empty() {
+				return;
+			}
+
+			(due, asset_storage.handle_for::<EntityTemplate>("tfog"))
+		};
+
+		for monster in due {
+			// The body disappears as the monster comes back
+			if let Some(corpse) = monster.corpse {
+				if world.remove(corpse) {
+					<Write<Quadtree>>::fetch_mut(resources).remove(corpse);
+				}
+			}
+
+			// Use NAN to use the default spawn height
+			let mut transform = monster.transform;
+			transform.position[2] = f32::NAN;
+			let entity = spawn_entity(world, resources, monster.template_handle, transform);
+
+			if monster.ambush {
+				if let Some(mut entry) = world.entry(entity) {
+					entry.add_component(Ambush);
+				}
+			}
+
+			// Flash and sound where the monster appeared
+			if let Some(fog_handle) = &fog_handle {
+				let fog_entity = spawn_entity(world, resources, fog_handle.clone(), transform);
+				<Write<Vec<(AssetHandle<Sound>, Entity)>>>::fetch_mut(resources)
+					.push((sound_handle.clone(), fog_entity));
+			}
+		}
+	})
+}
+
+/// Spreads a noise made by `source` through all the sectors that it can reach,
+/// starting from the sector with index `sector_index`, like `P_NoiseAlert` in the original game.
+/// Sound travels through two-sided linedefs that aren't closed off,
+/// but only through one linedef that has the BLOCKSOUND flag.
+pub fn noise_alert(map: &Map, map_dynamic: &mut MapDynamic, sector_index: usize, source: Entity) {
+	// The fewest BLOCKSOUND linedefs that the sound passed through to reach each sector
+	let mut blocks_passed: Vec<Option<u8>> = vec![None; map.sectors.len()];
+	let mut queue = vec![(sector_index, 0)];
+
+	while let Some((sector_index, blocks)) = queue.pop() {
+		if blocks_passed[sector_index].map_or(false, |b| b <= blocks) {
+			continue;
+		}
+
+		blocks_passed[sector_index] = Some(blocks);
+		map_dynamic.sectors[sector_index].sound_target = Some(source);
+
+		for linedef_index in map.sectors[sector_index].linedefs.iter().copied() {
+			let linedef = &map.linedefs[linedef_index];
+			let (front_index, back_index) = match &linedef.sidedefs {
+				[Some(front_sidedef), Some(back_sidedef)] => {
+					(front_sidedef.sector_index, back_sidedef.sector_index)
+				}
+				_ => continue,
+			};
+
+			// Closed doors stop the sound
+			let opening = map_dynamic.sectors[front_index]
+				.interval
+				.intersection(map_dynamic.sectors[back_index].interval);
+
+			if opening.is_empty_or_point() {
+				continue;
+			}
+
+			let other_index = if front_index == sector_index {
+				back_index
+			} else {
+				front_index
+			};
+
+			if !linedef.flags.intersects(LinedefFlags::BLOCKSOUND) {
+				queue.push((other_index, blocks));
+			} else if blocks == 0 {
+				queue.push((other_index, 1));
+			}
+		}
+	}
+}
+
+pub fn monster_target_system() -> impl ParallelRunnable {
+	SystemBuilder::new("monster_target_system")
+		.with_query(<&mut Target>::query())
+		.build(move |_, world, _, query| {
+			let _profile = profiler::scope("monster_target_system");
+
+			for target in query.iter_mut(world) {
+				target.threshold = target.threshold.saturating_sub(1);
+			}
+		})
+}
@@ -0,0 +1,521 @@
+use crate::{
+	common::{
+		assets::{AssetHandle, AssetStorage, ImportData},
+		audio::{Audio, SoundController},
+		configvars::ConfigVariables,
+		profiler,
+	},
+	doom::profile::GameProfile,
+};
+use anyhow::{bail, ensure};
+use byteorder::{ReadBytesExt, LE};
+use legion::{systems::ResourceSet, Read, Resources, World, Write};
+use relative_path::RelativePath;
+use rodio::Source;
+use std::{
+	io::{Cursor, Read as IoRead},
+	sync::Arc,
+	time::{Duration, Instant},
+};
+
+/// A song in the MUS format, as stored in the D_* lumps.
+#[derive(Clone, Debug)]
+pub struct Music {
+	events: Arc<[MusicEvent]>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct MusicEvent {
+	channel: usize,
+	kind: MusicEventKind,
+	/// Ticks to wait after this event, before playing the next one.
+	delay: u32,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum MusicEventKind {
+	ReleaseNote { note: u8 },
+	PlayNote { note: u8, volume: Option<u8> },
+	PitchBend { bend: u8 },
+	Controller { controller: u8, value: u8 },
+	Other,
+	ScoreEnd,
+}
+
+/// MUS ticks per second.
+const TICK_RATE: u32 = 140;
+
+/// The percussion channel, whose notes select a drum instead of a pitch.
+const PERCUSSION_CHANNEL: usize = 15;
+
+pub fn import_music(
+	path: &RelativePath,
+	asset_storage: &mut AssetStorage,
+) -> anyhow::Result<Box<dyn ImportData>> {
+	let mut reader = Cursor::new(asset_storage.source().load(path)?);
+	let mut signature = [0u8; 4];
+	reader.read_exact(&mut signature)?;
+
+	ensure!(signature == *b"MUS\x1a", "No MUS signature found");
+
+	let score_length = reader.read_u16::<LE>()? as u64;
+	let score_start = reader.read_u16::<LE>()? as u64;
+	reader.set_position(score_start);
+
+	let mut events = Vec::new();
+
+	while reader.position() < score_start + score_length {
+		let descriptor = reader.read_u8()?;
+		let channel = (descriptor & 0xF) as usize;
+		let kind = match (descriptor >> 4) & 0x7 {
+			0 => MusicEventKind::ReleaseNote {
+				note: reader.read_u8()? & 0x7F,
+			},
+			1 => {
+				let note = reader.read_u8()?;
+				let volume = if note & 0x80 != 0 {
+					Some(reader.read_u8()? & 0x7F)
+				} else {
+					None
+				};
+
+				MusicEventKind::PlayNote {
+					note: note & 0x7F,
+					volume,
+				}
+			}
+			2 => MusicEventKind::PitchBend {
+				bend: reader.read_u8()?,
+			},
+			3 => {
+				reader.read_u8()?;
+				MusicEventKind::Other
+			}
+			4 => MusicEventKind::Controller {
+				controller: reader.read_u8()?,
+				value: reader.read_u8()? & 0x7F,
+			},
+			5 => MusicEventKind::Other,
+			6 => MusicEventKind::ScoreEnd,
+			kind => bail!("Invalid MUS event type {}", kind),
+		};
+
+		// The last event in a group is followed by a delay
+		let mut delay = 0;
+
+		if descriptor & 0x80 != 0 {
+			loop {
+				let byte = reader.read_u8()?;
+				delay = delay * 128 + (byte & 0x7F) as u32;
+
+				if byte & 0x80 == 0 {
+					break;
+				}
+			}
+		}
+
+		events.push(MusicEvent {
+			channel,
+			kind,
+			delay,
+		});
+
+		if let MusicEventKind::ScoreEnd = kind {
+			break;
+		}
+	}
+
+	Ok(Box::new(Music {
+		events: events.into(),
+	}))
+}
+
+/// A song without any notes, to stand in for music that couldn't be loaded.
+pub fn placeholder_music() -> Box<dyn ImportData> {
+	Box::new(Music {
+		events: Vec::new().into(),
+	})
+}
+
+/// Returns the music lump that the original games play on a map.
+pub fn map_music(map_name: &str, game_profile: GameProfile) -> Option<String> {
+	const DOOM2_MUSIC: [&str; 32] = [
+		"runnin", "stalks", "countd", "betwee", "doom", "the_da", "shawn", "ddtblu", "in_cit",
+		"dead", "stlks2", "theda2", "doom2", "ddtbl2", "runni2", "dead2", "stlks3", "romero",
+		"shawn2", "messag", "count2", "ddtbl3", "ampie", "theda3", "adrian", "messg2", "romer2",
+		"tense", "shawn3", "openin", "evil", "ultima",
+	];
+
+	// Episode 4 reuses music from the other episodes
+	const EPISODE4_MUSIC: [&str; 9] = [
+		"e3m4", "e3m2", "e3m3", "e1m5", "e2m7", "e2m4", "e2m6", "e2m5", "e1m9",
+	];
+
+	let map_name = map_name.to_ascii_lowercase();
+	let bytes = map_name.as_bytes();
+
+	if map_name.starts_with("map") {
+		let number: usize = map_name[3..].parse().ok()?;
+		let name = DOOM2_MUSIC.get(number.checked_sub(1)?)?;
+		Some(format!("d_{}", name))
+	} else if bytes.len() == 4
+		&& bytes[0] == b'e'
+		&& bytes[1].is_ascii_digit()
+		&& bytes[2] == b'm'
+		&& bytes[3].is_ascii_digit()
+	{
+		match (bytes[1], bytes[3]) {
+			// Heretic's later episodes reuse the music of the first two
+			(episode, map) if game_profile == GameProfile::Heretic => {
+				let episode = match episode {
+					b'4' => b'1',
+					b'5' => b'2',
+					_ => episode,
+				};
+				Some(format!("mus_e{}m{}", episode as char, map as char))
+			}
+			(b'4', map) if map > b'0' => {
+				Some(format!("d_{}", EPISODE4_MUSIC[(map - b'1') as usize]))
+			}
+			_ => Some(format!("d_{}", map_name)),
+		}
+	} else {
+		None
+	}
+}
+
+/// Keeps track of the music that is currently playing.
+#[derive(Default)]
+pub struct MusicPlayer {
+	current: Option<PlayingMusic>,
+	/// Music that was replaced or stopped, and is still fading out.
+	fading_out: Vec<PlayingMusic>,
+}
+
+struct PlayingMusic {
+	name: String,
+	controller: SoundController,
+	volume: f32,
+}
+
+/// Starts playing the music lump with the given name, crossfading from the current music.
+/// Nothing happens if it's already playing.
+pub fn play_music(name: &str, resources: &mut Resources) {
+	let name = name.to_ascii_lowercase();
+	let audio = resources.get::<Audio>().map(|audio| audio.clone());
+	let (mut asset_storage, mut music_player) =
+		<(Write<AssetStorage>, Write<MusicPlayer>)>::fetch_mut(resources);
+
+	if let Some(current) = &music_player.current {
+		if current.name == name {
+			return;
+		}
+	}
+
+	// Fade out the old music, the new music fades in at the same time
+	if let Some(current) = music_player.current.take() {
+		music_player.fading_out.push(current);
+	}
+
+	// There's nowhere to play it on a dedicated server
+	let audio = match audio {
+		Some(audio) => audio,
+		None => return,
+	};
+
+	if !asset_storage.source().exists(RelativePath::new(&name)) {
+		log::warn!("Music lump {} not found", name);
+		return;
+	}
+
+	let handle: AssetHandle<Music> = asset_storage.load(&format!("{}.music", name));
+	let music = asset_storage.get(&handle).unwrap();
+	let controller = audio.play(Synth::new(music, true), [0.0, 0.0], 1.0);
+
+	log::debug!("Playing music {}", name);
+	music_player.current = Some(PlayingMusic {
+		name,
+		controller,
+		volume: 0.0,
+	});
+}
+
+/// Fades out the music that is currently playing.
+pub fn stop_music(resources: &mut Resources) {
+	let mut music_player = <Write<MusicPlayer>>::fetch_mut(resources);
+
+	if let Some(current) = music_player.current.take() {
+		music_player.fading_out.push(current);
+	}
+}
+
+/// Longest time step that a fade can advance by in one frame.
+/// Keeps the music from jumping ahead after a long pause, like loading a map.
+const MAX_FADE_STEP: Duration = Duration::from_millis(100);
+
+/// Crossfades between old and new music, over `mus_crossfade` seconds.
+pub fn music_system() -> Box<dyn FnMut(&mut World, &mut Resources)> {
+	let mut last_update = Instant::now();
+
+	Box::new(move |_world, resources| {
+		let _profile = profiler::scope("music_system");
+
+		let (config_variables, mut music_player) =
+			<(Read<ConfigVariables>, Write<MusicPlayer>)>::fetch_mut(resources);
+
+		let now = Instant::now();
+		let delta = (now - last_update).min(MAX_FADE_STEP);
+		last_update = now;
+
+		let crossfade = config_variables.get::<f32>("mus_crossfade");
+		let step = if crossfade > 0.0 {
+			delta.as_secs_f32() / crossfade
+		} else {
+			1.0
+		};
+
+		if let Some(current) = &mut music_player.current {
+			if current.volume < 1.0 {
+				current.volume = (current.volume + step).min(1.0);
+				current.controller.set_volumes([current.volume; 2]);
+			}
+		}
+
+		for music in music_player.fading_out.iter_mut() {
+			music.volume = (music.volume - step).max(0.0);
+			music.controller.set_volumes([music.volume; 2]);
+		}
+
+		music_player.fading_out.retain(|music| {
+			if music.volume > 0.0 {
+				true
+			} else {
+				music.controller.stop();
+				false
+			}
+		});
+	})
+}
+
+/// Switches to the music of another map, given as two digits like the `idmus` cheat.
+/// For Doom 1 the digits are the episode and map, for Doom 2 the map number.
+pub fn change_music_cheat(number: &str, resources: &mut Resources) {
+	if number.len() != 2 || !number.bytes().all(|b| b.is_ascii_digit()) {
+		log::error!("Usage: idmus <two digits>");
+		return;
+	}
+
+	let music = {
+		let (asset_storage, game_profile) =
+			<(Read<AssetStorage>, Read<GameProfile>)>::fetch(resources);
+		let candidates = [
+			format!("map{}", number),
+			format!("e{}m{}", &number[0..1], &number[1..2]),
+		];
+
+		candidates
+			.iter()
+			.filter_map(|map_name| map_music(map_name, *game_profile))
+			.find(|music| asset_storage.source().exists(RelativePath::new(music)))
+	};
+
+	match music {
+		Some(music) => {
+			log::info!("Music change");
+			play_music(&music, resources);
+		}
+		None => log::error!("Impossible selection"),
+	}
+}
+
+/// Plays music with a small built-in synthesiser.
+/// This is not General MIDI, so instruments are ignored and every channel sounds alike.
+struct Synth {
+	events: Arc<[MusicEvent]>,
+	looping: bool,
+	next_event: usize,
+	samples_until_event: u32,
+	channels: [SynthChannel; 16],
+	voices: Vec<Voice>,
+	noise: u32,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct SynthChannel {
+	volume: f32,
+	note_volume: f32,
+	pitch_factor: f32,
+}
+
+impl Default for SynthChannel {
+	fn default() -> SynthChannel {
+		SynthChannel {
+			volume: 100.0 / 127.0,
+			note_volume: 100.0 / 127.0,
+			pitch_factor: 1.0,
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Voice {
+	channel: usize,
+	note: u8,
+	frequency: f32,
+	phase: f32,
+	amplitude: f32,
+	released: bool,
+}
+
+const SAMPLE_RATE: u32 = 22050;
+
+/// The most notes that can play at once. Older notes are cut off beyond this.
+const MAX_VOICES: usize = 32;
+
+/// Overall volume, to leave room for many notes playing at once.
+const MASTER_VOLUME: f32 = 0.15;
+
+impl Synth {
+	fn new(music: &Music, looping: bool) -> Synth {
+		Synth {
+			events: music.events.clone(),
+			// A song without delays would loop forever without making a sound
+			looping: looping && music.events.iter().any(|event| event.delay > 0),
+			next_event: 0,
+			samples_until_event: 0,
+			channels: [SynthChannel::default(); 16],
+			voices: Vec::with_capacity(MAX_VOICES),
+			noise: 0x1234_5678,
+		}
+	}
+
+	/// Plays events until the next delay. Returns false when the song is over.
+	fn process_events(&mut self) -> bool {
+		loop {
+			let event = match self.events.get(self.next_event) {
+				Some(event) => *event,
+				None => return false,
+			};
+			self.next_event += 1;
+
+			let channel = &mut self.channels[event.channel];
+
+			match event.kind {
+				MusicEventKind::ReleaseNote { note } => {
+					for voice in self.voices.iter_mut() {
+						if voice.channel == event.channel && voice.note == note {
+							voice.released = true;
+						}
+					}
+				}
+				MusicEventKind::PlayNote { note, volume } => {
+					if let Some(volume) = volume {
+						channel.note_volume = volume as f32 / 127.0;
+					}
+
+					if self.voices.len() >= MAX_VOICES {
+						self.voices.remove(0);
+					}
+
+					self.voices.push(Voice {
+						channel: event.channel,
+						note,
+						frequency: 440.0 * 2.0f32.powf((note as f32 - 69.0) / 12.0),
+						phase: 0.0,
+						amplitude: channel.note_volume * channel.volume,
+						released: event.channel == PERCUSSION_CHANNEL,
+					});
+				}
+				MusicEventKind::PitchBend { bend } => {
+					// 64 steps per semitone, centred on 128
+					channel.pitch_factor = 2.0f32.powf((bend as f32 - 128.0) / 64.0 / 12.0);
+				}
+				MusicEventKind::Controller { controller, value } => {
+					if controller == 3 {
+						channel.volume = value as f32 / 127.0;
+					}
+				}
+				MusicEventKind::Other => {}
+				MusicEventKind::ScoreEnd => {
+					if !self.looping {
+						return false;
+					}
+
+					self.next_event = 0;
+				}
+			}
+
+			if event.delay > 0 {
+				self.samples_until_event = event.delay * SAMPLE_RATE / TICK_RATE;
+				return true;
+			}
+		}
+	}
+}
+
+impl Source for Synth {
+	#[inline]
+	fn current_frame_len(&self) -> Option<usize> {
+		None
+	}
+
+	#[inline]
+	fn channels(&self) -> u16 {
+		1
+	}
+
+	#[inline]
+	fn sample_rate(&self) -> u32 {
+		SAMPLE_RATE
+	}
+
+	#[inline]
+	fn total_duration(&self) -> Option<Duration> {
+		None
+	}
+}
+
+impl Iterator for Synth {
+	type Item = f32;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		while self.samples_until_event == 0 {
+			if !self.process_events() {
+				if self.voices.is_empty() {
+					return None;
+				}
+
+				// Let the last notes fade out
+				self.samples_until_event = u32::MAX;
+			}
+		}
+
+		self.samples_until_event -= 1;
+
+		let mut output = 0.0;
+		let channels = &self.channels;
+		let noise = &mut self.noise;
+
+		for voice in self.voices.iter_mut() {
+			if voice.channel == PERCUSSION_CHANNEL {
+				// Drums are bursts of noise that fade quickly
+				*noise ^= *noise << 13;
+				*noise ^= *noise >> 17;
+				*noise ^= *noise << 5;
+				output += voice.amplitude * (*noise as f32 / u32::MAX as f32 * 2.0 - 1.0);
+				voice.amplitude *= 0.9995;
+			} else {
+				// A triangle wave
+				let pitch_factor = channels[voice.channel].pitch_factor;
+				voice.phase =
+					(voice.phase + voice.frequency * pitch_factor / SAMPLE_RATE as f32).fract();
+				output += voice.amplitude * (4.0 * (voice.phase - 0.5).abs() - 1.0);
+				voice.amplitude *= if voice.released { 0.9990 } else { 0.99998 };
+			}
+		}
+
+		self.voices.retain(|voice| voice.amplitude > 0.001);
+
+		Some((output * MASTER_VOLUME).max(-1.0).min(1.0))
+	}
+}
@@ -0,0 +1,66 @@
+use crate::common::profiler;
+use legion::{systems::ParallelRunnable, IntoQuery, SystemBuilder};
+
+/// Player state that tints the screen, like the palette changes of the original game.
+/// All values are counted in frames, and count down by one every frame.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PaletteEffects {
+	/// Increased when taking damage, turns the screen red.
+	pub damage_count: u32,
+	/// Increased when picking up an item, flashes the screen yellow.
+	pub bonus_count: u32,
+	/// Time left on the radiation suit, turns the screen green.
+	pub radiation_suit: u32,
+	/// Time left on invulnerability, turns the screen into inverse grayscale.
+	pub invulnerability: u32,
+}
+
+/// Number of red damage tints.
+const NUM_RED_TINTS: u32 = 8;
+
+/// Number of yellow pickup tints.
+const NUM_BONUS_TINTS: u32 = 4;
+
+/// Powers start flickering when they have this many frames left.
+const POWER_FLICKER_TIME: u32 = 4 * 32;
+
+impl PaletteEffects {
+	/// Returns the colour to blend the screen with, where the alpha is the strength
+	/// of the blend, and whether to apply the inverse grayscale colormap.
+	pub fn tint(&self) -> ([f32; 4], bool) {
+		let colour = if self.damage_count > 0 {
+			let tint = ((self.damage_count + 7) >> 3).min(NUM_RED_TINTS);
+			[1.0, 0.0, 0.0, tint as f32 / (NUM_RED_TINTS + 1) as f32]
+		} else if self.bonus_count > 0 {
+			let tint = ((self.bonus_count + 7) >> 3).min(NUM_BONUS_TINTS);
+			[0.84, 0.73, 0.27, tint as f32 * 0.125]
+		} else if is_power_visible(self.radiation_suit) {
+			[0.0, 1.0, 0.0, 0.125]
+		} else {
+			[0.0, 0.0, 0.0, 0.0]
+		};
+
+		(colour, is_power_visible(self.invulnerability))
+	}
+}
+
+/// Powers that are about to run out flicker on and off.
+#[inline]
+fn is_power_visible(time_left: u32) -> bool {
+	time_left > POWER_FLICKER_TIME || time_left & 8 != 0
+}
+
+pub fn palette_effects_system() -> impl ParallelRunnable {
+	SystemBuilder::new("palette_effects_system")
+		.with_query(<&mut PaletteEffects>::query())
+		.build(move |_, world, _, query| {
+			let _profile = profiler::scope("palette_effects_system");
+
+			for palette_effects in query.iter_mut(world) {
+				palette_effects.damage_count = palette_effects.damage_count.saturating_sub(1);
+				palette_effects.bonus_count = palette_effects.bonus_count.saturating_sub(1);
+				palette_effects.radiation_suit = palette_effects.radiation_suit.saturating_sub(1);
+				palette_effects.invulnerability = palette_effects.invulnerability.saturating_sub(1);
+			}
+		})
+}
@@ -0,0 +1,159 @@
+use crate::{
+	common::{configvars::ConfigVariables, frame::FrameState, profiler},
+	doom::{components::Transform, data::GRAVITY},
+};
+use legion::{systems::Runnable, IntoQuery, SystemBuilder};
+use nalgebra::Vector3;
+use rand::Rng;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// The most particles that can exist at once. New particles are not spawned beyond this.
+pub const MAX_PARTICLES: usize = 4096;
+
+/// A small coloured dot that flies around for a short while.
+/// These are purely visual and don't interact with the world.
+#[derive(Clone, Copy, Debug)]
+pub struct Particle {
+	pub position: Vector3<f32>,
+	pub velocity: Vector3<f32>,
+	pub colour: [f32; 4],
+	pub size: f32,
+	pub time_left: Duration,
+	pub kind: ParticleKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum ParticleKind {
+	Blood,
+	Smoke,
+	Spark,
+}
+
+impl ParticleKind {
+	fn lifetime(self) -> Duration {
+		match self {
+			ParticleKind::Blood => Duration::from_millis(800),
+			ParticleKind::Smoke => Duration::from_millis(600),
+			ParticleKind::Spark => Duration::from_millis(300),
+		}
+	}
+
+	/// Whether the particle falls down, or floats in the air.
+	fn has_gravity(self) -> bool {
+		match self {
+			ParticleKind::Blood | ParticleKind::Spark => true,
+			ParticleKind::Smoke => false,
+		}
+	}
+}
+
+/// All particles in the current map.
+#[derive(Clone, Debug, Default)]
+pub struct Particles {
+	particles: Vec<Particle>,
+}
+
+impl Particles {
+	/// Spawns `count` particles at `position`, flying off in random directions
+	/// around `direction`, whose length is the average speed.
+	pub fn spawn(
+		&mut self,
+		kind: ParticleKind,
+		position: Vector3<f32>,
+		direction: Vector3<f32>,
+		count: usize,
+		rng: &mut impl Rng,
+	) {
+		let count = count.min(MAX_PARTICLES - self.particles.len());
+		let speed = direction.norm();
+
+		self.particles.extend((0..count).map(|_| {
+			let spread = Vector3::new(
+				rng.gen_range(-1.0, 1.0),
+				rng.gen_range(-1.0, 1.0),
+				rng.gen_range(-1.0, 1.0),
+			) * speed * 0.5;
+			let (colour, size) = match kind {
+				ParticleKind::Blood => ([rng.gen_range(0.4, 0.7), 0.0, 0.0, 1.0], 1.5),
+				ParticleKind::Smoke => {
+					let grey = rng.gen_range(0.3, 0.5);
+					([grey, grey, grey, 0.5], 3.0)
+				}
+				ParticleKind::Spark => ([1.0, rng.gen_range(0.6, 0.9), 0.3, 1.0], 1.0),
+			};
+
+			Particle {
+				position,
+				velocity: direction + spread,
+				colour,
+				size,
+				time_left: kind.lifetime().mul_f32(rng.gen_range(0.5, 1.0)),
+				kind,
+			}
+		}));
+	}
+
+	pub fn clear(&mut self) {
+		self.particles.clear();
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = &Particle> {
+		self.particles.iter()
+	}
+}
+
+/// Makes an entity leave a trail of particles behind it.
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct ParticleEmitter {
+	pub kind: ParticleKind,
+	pub count_per_frame: usize,
+}
+
+pub fn particle_system() -> impl Runnable {
+	SystemBuilder::new("particle_system")
+		.read_resource::<ConfigVariables>()
+		.read_resource::<FrameState>()
+		.write_resource::<Particles>()
+		.with_query(<(&Transform, &ParticleEmitter)>::query())
+		.build(move |_, world, resources, query| {
+			let _profile = profiler::scope("particle_system");
+
+			let (config_variables, frame_state, particles) = resources;
+			let delta_time = frame_state.delta_time.as_secs_f32();
+
+			// Move the existing particles
+			particles
+				.particles
+				.retain(|particle| particle.time_left > Duration::default());
+
+			for particle in particles.particles.iter_mut() {
+				particle.time_left = particle.time_left.saturating_sub(frame_state.delta_time);
+				particle.position += particle.velocity * delta_time;
+
+				if particle.kind.has_gravity() {
+					particle.velocity[2] -= GRAVITY * delta_time;
+				}
+			}
+
+			if !config_variables.get::<bool>("r_particles") {
+				particles.clear();
+				return;
+			}
+
+			// Spawn new particles from emitters
+			let mut rng = frame_state.rng.lock().unwrap();
+
+			for (transform, emitter) in query.iter(world) {
+				particles.spawn(
+					emitter.kind,
+					transform.position,
+					Vector3::new(0.0, 0.0, 8.0),
+					emitter.count_per_frame,
+					&mut *rng,
+				);
+			}
+
+			profiler::count("particles", particles.particles.len() as u64);
+		})
+}
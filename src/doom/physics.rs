@@ -1,15 +1,19 @@
 use crate::{
 	common::{
 		assets::AssetStorage,
+		events::{add_events, Events},
 		frame::FrameState,
 		geometry::{Interval, Plane3, AABB2, AABB3},
+		profiler,
 		quadtree::Quadtree,
 	},
 	doom::{
-		components::{Transform, Velocity},
+		components::{NoGravity, Transform, Velocity},
 		data::{FRICTION, GRAVITY},
 		door::DoorTouch,
+		exit::ExitTouch,
 		floor::FloorTouch,
+		linespecial::LineSpecialTouch,
 		map::{Map, MapDynamic, NodeChild, Subsector},
 		plat::PlatTouch,
 	},
@@ -21,7 +25,6 @@ use legion::{
 	component, systems::Runnable, Entity, EntityStore, IntoQuery, Resources, SystemBuilder,
 };
 use nalgebra::Vector3;
-use shrev::EventChannel;
 use smallvec::SmallVec;
 use std::time::Duration;
 
@@ -29,24 +32,31 @@ use std::time::Duration;
 pub struct PhysicsSystem;
 
 pub fn physics_system(resources: &mut Resources) -> impl Runnable {
-	resources.insert(EventChannel::<StepEvent>::new());
-	resources.insert(EventChannel::<TouchEvent>::new());
+	add_events::<StepEvent>(resources);
+	add_events::<TouchEvent>(resources);
 
 	SystemBuilder::new("physics_system")
 		.read_resource::<AssetStorage>()
 		.read_resource::<FrameState>()
 		.write_resource::<Quadtree>()
-		.write_resource::<EventChannel<StepEvent>>()
-		.write_resource::<EventChannel<TouchEvent>>()
+		.write_resource::<Events<StepEvent>>()
+		.write_resource::<Events<TouchEvent>>()
 		.with_query(<&MapDynamic>::query())
 		.with_query(
 			<(Entity, &Transform)>::query()
 				.filter(component::<BoxCollider>() & component::<Velocity>()),
 		)
-		.with_query(<(&mut Transform, &mut Velocity, &BoxCollider)>::query())
+		.with_query(<(
+			&mut Transform,
+			&mut Velocity,
+			&BoxCollider,
+			Option<&NoGravity>,
+		)>::query())
 		.read_component::<BoxCollider>() // used by EntityTracer
 		.read_component::<Transform>() // used by EntityTracer
 		.build(move |_, world, resources, queries| {
+			let _profile = profiler::scope("physics_system");
+
 			let (asset_storage, frame_state, quadtree, step_event_channel, touch_event_channel) =
 				resources;
 			let (world0, mut world) = world.split_for_query(&queries.0);
@@ -57,8 +67,9 @@ pub fn physics_system(resources: &mut Resources) -> impl Runnable {
 			let entities: Vec<Entity> = queries.1.iter(&world).map(|(e, _)| *e).collect();
 
 			for entity in entities {
-				let (transform, velocity, box_collider) =
+				let (transform, velocity, box_collider, no_gravity) =
 					queries.2.get_mut(&mut world, entity).unwrap();
+				let has_gravity = no_gravity.is_none();
 				let mut new_position = transform.position;
 				let mut new_velocity = velocity.velocity;
 				let entity_bbox =
@@ -100,7 +111,7 @@ pub fn physics_system(resources: &mut Resources) -> impl Runnable {
 						touched: collision.entity,
 						collision: None,
 					});
-				} else {
+				} else if has_gravity {
 					// Entity isn't on ground, apply gravity
 					new_velocity[2] -= GRAVITY * frame_state.delta_time.as_secs_f32();
 				}
@@ -119,7 +130,7 @@ pub fn physics_system(resources: &mut Resources) -> impl Runnable {
 				);
 
 				// Set new position and velocity
-				let (transform, velocity, _) = queries.2.get_mut(&mut world, entity).unwrap();
+				let (transform, velocity, _, _) = queries.2.get_mut(&mut world, entity).unwrap();
 				transform.position = new_position;
 				velocity.velocity = new_velocity;
 				quadtree.insert(entity, &AABB2::from(&entity_bbox.offset(new_position)));
@@ -261,7 +272,9 @@ pub struct TouchEventCollision {
 #[derive(Clone, Debug)]
 pub enum TouchAction {
 	DoorTouch(DoorTouch),
+	ExitTouch(ExitTouch),
 	FloorTouch(FloorTouch),
+	LineSpecialTouch(LineSpecialTouch),
 	PlatTouch(PlatTouch),
 }
 
@@ -500,53 +513,49 @@ impl<'a, W: EntityStore> EntityTracer<'a, W> {
 				}
 			});
 
-		self.quadtree
-			.traverse_nodes(&move_bbox2, &mut |entities: &[Entity]| {
-				for &entity in entities {
-					let (transform, box_collider) =
-						match <(&Transform, &BoxCollider)>::query().get(self.world, entity) {
-							Ok(x) => x,
-							_ => continue,
-						};
-
-					let other_bbox =
-						AABB3::from_radius_height(box_collider.radius, box_collider.height)
-							.offset(transform.position);
-
-					// Don't collide against self
-					if entity_bbox == &other_bbox {
-						continue;
-					}
+		for entity in self.quadtree.query_bbox(&move_bbox2) {
+			let (transform, box_collider) =
+				match <(&Transform, &BoxCollider)>::query().get(self.world, entity) {
+					Ok(x) => x,
+					_ => continue,
+				};
 
-					if !move_bbox.overlaps(&other_bbox) {
-						continue;
-					}
+			let other_bbox = AABB3::from_radius_height(box_collider.radius, box_collider.height)
+				.offset(transform.position);
 
-					let other_planes = other_bbox
-						.planes()
-						.iter()
-						.map(|p| CollisionPlane(*p, true))
-						.collect::<Vec<_>>(); // TODO make this not allocate
+			// Don't collide against self
+			if entity_bbox == &other_bbox {
+				continue;
+			}
 
-					if let Some((fraction, normal)) =
-						trace_planes(&entity_bbox, move_step, other_planes.iter())
-					{
-						if entity_solid_mask.intersects(box_collider.solid_mask) {
-							if fraction < trace_fraction {
-								trace_fraction = fraction;
-								trace_collision = Some(EntityTraceCollision {
-									entity,
-									normal,
-									step_z: Some(other_bbox[2].max + DISTANCE_EPSILON),
-								});
-								trace_touched.retain(|(f, _)| *f <= fraction);
-							}
-						} else if fraction <= trace_fraction {
-							trace_touched.push((fraction, entity));
-						}
+			if !move_bbox.overlaps(&other_bbox) {
+				continue;
+			}
+
+			let other_planes = other_bbox
+				.planes()
+				.iter()
+				.map(|p| CollisionPlane(*p, true))
+				.collect::<Vec<_>>(); // TODO make this not allocate
+
+			if let Some((fraction, normal)) =
+				trace_planes(&entity_bbox, move_step, other_planes.iter())
+			{
+				if entity_solid_mask.intersects(box_collider.solid_mask) {
+					if fraction < trace_fraction {
+						trace_fraction = fraction;
+						trace_collision = Some(EntityTraceCollision {
+							entity,
+							normal,
+							step_z: Some(other_bbox[2].max + DISTANCE_EPSILON),
+						});
+						trace_touched.retain(|(f, _)| *f <= fraction);
 					}
+				} else if fraction <= trace_fraction {
+					trace_touched.push((fraction, entity));
 				}
-			});
+			}
+		}
 
 		EntityTrace {
 			fraction: trace_fraction,
@@ -607,9 +616,17 @@ impl<'a, W: EntityStore> SectorTracer<'a, W> {
 			world: self.world,
 		};
 
-		for (entity, transform, box_collider) in
-			<(Entity, &Transform, &BoxCollider)>::query().iter(self.world)
-		{
+		// Only entities near the moving sector can be pushed by it
+		let sector_bbox = subsectors.clone().fold(AABB2::empty(), |bbox, subsector| {
+			bbox.union(&subsector.bbox)
+		});
+
+		for entity in self.quadtree.query_bbox(&sector_bbox) {
+			let (transform, box_collider) =
+				match <(&Transform, &BoxCollider)>::query().get(self.world, entity) {
+					Ok(x) => x,
+					Err(_) => continue,
+				};
 			let entity_bbox = AABB3::from_radius_height(box_collider.radius, box_collider.height)
 				.offset(transform.position);
 			let entity_bbox2 = AABB2::from(&entity_bbox);
@@ -639,7 +656,7 @@ impl<'a, W: EntityStore> SectorTracer<'a, W> {
 						}
 
 						if hit_fraction <= total_fraction {
-							trace_touched.push((hit_fraction, *entity));
+							trace_touched.push((hit_fraction, entity));
 						}
 
 						break;
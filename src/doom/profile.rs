@@ -0,0 +1,113 @@
+use crate::{common::assets::DataSource, doom::wad::WadLoader};
+use relative_path::RelativePath;
+
+/// Which game the IWAD belongs to.
+/// Most of the engine doesn't care, but some things that the original games hardcoded
+/// differ between them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameProfile {
+	Doom,
+	Doom2,
+	FreeDoom1,
+	FreeDoom2,
+	FreeDm,
+	ChexQuest,
+	Heretic,
+}
+
+impl GameProfile {
+	/// Works out the game from the name of the IWAD, which is the first WAD in `loader`,
+	/// and the lumps that it contains.
+	pub fn detect(loader: &WadLoader) -> GameProfile {
+		let iwad_name = loader
+			.wads()
+			.next()
+			.and_then(|path| path.file_stem())
+			.map(|stem| stem.to_string_lossy().to_ascii_lowercase())
+			.unwrap_or_default();
+		let has_maps = loader.exists(RelativePath::new("map01"));
+
+		if is_heretic_data(loader) {
+			GameProfile::Heretic
+		} else if iwad_name == "chex" {
+			// Chex Quest has no lumps of its own that tell it apart from Doom
+			GameProfile::ChexQuest
+		} else if loader.exists(RelativePath::new("freedoom")) {
+			if iwad_name == "freedm" {
+				GameProfile::FreeDm
+			} else if has_maps {
+				GameProfile::FreeDoom2
+			} else {
+				GameProfile::FreeDoom1
+			}
+		} else if has_maps {
+			GameProfile::Doom2
+		} else {
+			GameProfile::Doom
+		}
+	}
+
+	pub fn name(self) -> &'static str {
+		match self {
+			GameProfile::Doom => "Doom",
+			GameProfile::Doom2 => "Doom II",
+			GameProfile::FreeDoom1 => "Freedoom: Phase 1",
+			GameProfile::FreeDoom2 => "Freedoom: Phase 2",
+			GameProfile::FreeDm => "FreeDM",
+			GameProfile::ChexQuest => "Chex Quest",
+			GameProfile::Heretic => "Heretic",
+		}
+	}
+
+	/// Whether maps are named by episode and map number, like `E1M1`, rather than `MAP01`.
+	pub fn has_episodes(self) -> bool {
+		match self {
+			GameProfile::Doom
+			| GameProfile::FreeDoom1
+			| GameProfile::ChexQuest
+			| GameProfile::Heretic => true,
+			GameProfile::Doom2 | GameProfile::FreeDoom2 | GameProfile::FreeDm => false,
+		}
+	}
+
+	/// The map that a game starts on, when no other is given.
+	pub fn first_map(self) -> &'static str {
+		if self.has_episodes() {
+			"E1M1"
+		} else {
+			"MAP01"
+		}
+	}
+
+	/// The flat that fills the screen around the status bar and behind text screens.
+	pub fn border_flat(self) -> &'static str {
+		match self {
+			GameProfile::Heretic => "flat513.flat",
+			_ => "floor7_2.flat",
+		}
+	}
+
+	/// The music that plays on the intermission screen.
+	pub fn intermission_music(self) -> &'static str {
+		match self {
+			GameProfile::Doom2 | GameProfile::FreeDoom2 | GameProfile::FreeDm => "d_dm2int",
+			GameProfile::Heretic => "mus_intr",
+			_ => "d_inter",
+		}
+	}
+
+	/// The name of the HUD font patch for the character `ch`.
+	pub fn hud_font_patch(self, ch: u8) -> String {
+		match self {
+			// Heretic numbers its glyphs from the first printable character
+			GameProfile::Heretic => format!("fonta{:02}.patch", ch - b' '),
+			_ => format!("stcfn{:03}.patch", ch),
+		}
+	}
+}
+
+/// Returns whether `source` holds Heretic data rather than Doom data.
+/// Importers don't have access to the game profile, so they can use this to check instead.
+pub fn is_heretic_data(source: &(impl DataSource + ?Sized)) -> bool {
+	source.exists(RelativePath::new("mus_e1m1"))
+}
@@ -0,0 +1,237 @@
+use crate::doom::{
+	mapinfo::{BossAction, MapInfoEntry},
+	profile::GameProfile,
+};
+
+/// The way that a level was left.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExitType {
+	Normal,
+	Secret,
+}
+
+/// A map name split into its episode and map number.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MapNumber {
+	/// Doom 1 style, `ExMy`.
+	Episode(u8, u8),
+	/// Doom 2 style, `MAPxx`.
+	Map(u8),
+}
+
+impl MapNumber {
+	fn parse(name: &str) -> Option<MapNumber> {
+		let name = name.to_ascii_lowercase();
+		let bytes = name.as_bytes();
+
+		if name.starts_with("map") {
+			Some(MapNumber::Map(name[3..].parse().ok()?))
+		} else if bytes.len() == 4
+			&& bytes[0] == b'e'
+			&& bytes[1].is_ascii_digit()
+			&& bytes[2] == b'm'
+			&& bytes[3].is_ascii_digit()
+		{
+			Some(MapNumber::Episode(bytes[1] - b'0', bytes[3] - b'0'))
+		} else {
+			None
+		}
+	}
+
+	fn name(self) -> String {
+		match self {
+			MapNumber::Episode(episode, map) => format!("e{}m{}", episode, map),
+			MapNumber::Map(map) => format!("map{:02}", map),
+		}
+	}
+}
+
+/// Returns the map of each episode that has the secret exit, and the map that the secret map
+/// returns to.
+fn secret_maps(episode: u8, profile: GameProfile) -> Option<(u8, u8)> {
+	if profile == GameProfile::Heretic {
+		match episode {
+			1 => Some((6, 7)),
+			2 | 3 | 4 => Some((4, 5)),
+			5 => Some((3, 4)),
+			_ => None,
+		}
+	} else {
+		match episode {
+			1 => Some((3, 4)),
+			2 => Some((5, 6)),
+			3 => Some((6, 7)),
+			4 => Some((2, 3)),
+			_ => None,
+		}
+	}
+}
+
+/// Returns the map that the secret exit of `map` leads to, if it has one.
+fn secret_destination(map: MapNumber, profile: GameProfile) -> Option<MapNumber> {
+	match map {
+		MapNumber::Episode(episode, map) => {
+			let (secret_exit_map, _) = secret_maps(episode, profile)?;
+
+			if map == secret_exit_map {
+				Some(MapNumber::Episode(episode, 9))
+			} else {
+				None
+			}
+		}
+		MapNumber::Map(15) => Some(MapNumber::Map(31)),
+		MapNumber::Map(31) => Some(MapNumber::Map(32)),
+		MapNumber::Map(_) => None,
+	}
+}
+
+/// Returns the map that the normal exit of `map` leads to,
+/// or `None` if it's the last map of the episode.
+fn normal_destination(map: MapNumber, profile: GameProfile) -> Option<MapNumber> {
+	match map {
+		// The secret map goes back to the map after the one with the secret exit
+		MapNumber::Episode(episode, 9) => {
+			let (_, return_map) = secret_maps(episode, profile)?;
+			Some(MapNumber::Episode(episode, return_map))
+		}
+		MapNumber::Episode(_, 8) => None,
+		MapNumber::Episode(episode, map) => Some(MapNumber::Episode(episode, map + 1)),
+		MapNumber::Map(31) | MapNumber::Map(32) => Some(MapNumber::Map(16)),
+		MapNumber::Map(30) => None,
+		MapNumber::Map(map) => Some(MapNumber::Map(map + 1)),
+	}
+}
+
+/// Returns the name of the map that comes after `map` when it's left through `exit_type`.
+/// Destinations given by the map information take priority over the built-in ones.
+/// `exists` is used to check whether a map is present in the loaded data.
+/// A secret exit without a secret map behaves like a normal exit.
+/// Returns `None` at the end of an episode, or if the next map isn't there.
+pub fn next_map(
+	map: &str,
+	exit_type: ExitType,
+	map_info: Option<&MapInfoEntry>,
+	profile: GameProfile,
+	exists: impl Fn(&str) -> bool,
+) -> Option<String> {
+	if let Some(map_info) = map_info {
+		if map_info.end_game {
+			return None;
+		}
+
+		if exit_type == ExitType::Secret {
+			if let Some(name) = &map_info.next_secret {
+				return Some(name.clone());
+			}
+		}
+
+		if let Some(name) = &map_info.next {
+			return Some(name.clone());
+		}
+	}
+
+	let number = MapNumber::parse(map)?;
+
+	if exit_type == ExitType::Secret {
+		if let Some(name) = secret_destination(number, profile).map(MapNumber::name) {
+			if exists(&name) {
+				return Some(name);
+			}
+		}
+	}
+
+	normal_destination(number, profile)
+		.map(MapNumber::name)
+		.filter(|name| exists(name))
+}
+
+/// Returns whether leaving `map` through `exit_type` finishes the episode.
+pub fn ends_episode(
+	map: &str,
+	exit_type: ExitType,
+	map_info: Option<&MapInfoEntry>,
+	profile: GameProfile,
+) -> bool {
+	if let Some(map_info) = map_info {
+		if map_info.end_game {
+			return true;
+		} else if map_info.next.is_some()
+			|| (exit_type == ExitType::Secret && map_info.next_secret.is_some())
+		{
+			return false;
+		}
+	}
+
+	let number = match MapNumber::parse(map) {
+		Some(number) => number,
+		None => return false,
+	};
+
+	if exit_type == ExitType::Secret && secret_destination(number, profile).is_some() {
+		false
+	} else {
+		normal_destination(number, profile).is_none()
+	}
+}
+
+/// Returns the name of the sky texture that `map` has if the map information doesn't say.
+/// Importers don't know the game profile, so they only say whether it's Heretic.
+pub fn default_sky(map: &str, heretic: bool) -> String {
+	match MapNumber::parse(map) {
+		// Heretic's later episodes reuse the skies of the first three
+		Some(MapNumber::Episode(episode, _)) if heretic => match episode {
+			2 => String::from("sky2"),
+			3 | 5 => String::from("sky3"),
+			_ => String::from("sky1"),
+		},
+		Some(MapNumber::Episode(episode, _)) if (1..=4).contains(&episode) => {
+			format!("sky{}", episode)
+		}
+		Some(MapNumber::Map(map)) if map >= 21 => String::from("sky3"),
+		Some(MapNumber::Map(map)) if map >= 12 => String::from("sky2"),
+		_ => String::from("sky1"),
+	}
+}
+
+/// Returns the specials that the original game triggers on `map` once all of a boss monster
+/// are dead, for maps where the map information doesn't give any.
+pub fn default_boss_actions(map: &str, profile: GameProfile) -> Vec<BossAction> {
+	let boss_action = |template: &str, special, tag| BossAction {
+		template: template.to_owned(),
+		special,
+		tag,
+	};
+
+	// Heretic lowers the floor to the highest neighbour at the end of each episode
+	if profile == GameProfile::Heretic {
+		return match MapNumber::parse(map) {
+			Some(MapNumber::Episode(1, 8)) | Some(MapNumber::Episode(4, 8)) => {
+				vec![boss_action("head", 19, 666)]
+			}
+			Some(MapNumber::Episode(2, 8)) | Some(MapNumber::Episode(5, 8)) => {
+				vec![boss_action("minotaur", 19, 666)]
+			}
+			Some(MapNumber::Episode(3, 8)) => vec![boss_action("sorcerer1", 19, 666)],
+			_ => Vec::new(),
+		};
+	}
+
+	match MapNumber::parse(map) {
+		// Lower the floor to the lowest neighbour
+		Some(MapNumber::Episode(1, 8)) => vec![boss_action("bruiser", 38, 666)],
+		Some(MapNumber::Episode(4, 8)) => vec![boss_action("spider", 38, 666)],
+
+		// Exit the level
+		Some(MapNumber::Episode(2, 8)) => vec![boss_action("cyborg", 52, 0)],
+		Some(MapNumber::Episode(3, 8)) => vec![boss_action("spider", 52, 0)],
+
+		// Open the door fast
+		Some(MapNumber::Episode(4, 6)) => vec![boss_action("cyborg", 109, 666)],
+
+		// Lower the floor, then raise it by the shortest lower texture
+		Some(MapNumber::Map(7)) => {
+			vec![boss_action("fatso", 38, 666), boss_action("baby", 30, 667)]
+		}
+		_ => Vec::new(),
+	}
+}
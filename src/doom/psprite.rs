@@ -1,9 +1,15 @@
-use crate::doom::sprite::SpriteRender;
+use crate::{
+	common::{frame::FrameState, geometry::Angle, profiler},
+	doom::{camera::Camera, client::Client, components::Velocity, sprite::SpriteRender},
+};
+use legion::{systems::Runnable, Entity, IntoQuery, SystemBuilder};
 use nalgebra::Vector2;
 
 #[derive(Clone, Debug)]
 pub struct PlayerSpriteRender {
 	pub position: Vector2<f32>,
+	/// Offset that makes the weapon lag behind when looking around.
+	pub sway: Vector2<f32>,
 	pub slots: [Option<SpriteRender>; 2],
 }
 
@@ -11,3 +17,49 @@ pub enum PlayerSpriteSlot {
 	Weapon = 0,
 	Flash = 1,
 }
+
+/// How far the weapon sways for each unit of view rotation input.
+const SWAY_SCALE: f32 = 0.5;
+
+/// The furthest that the weapon can sway away from its resting position.
+const SWAY_MAX: f32 = 12.0;
+
+/// How quickly the weapon catches up with the view, as a fraction per second.
+const SWAY_SPEED: f32 = 10.0;
+
+pub fn player_sprite_move_system() -> impl Runnable {
+	SystemBuilder::new("player_sprite_move_system")
+		.read_resource::<Client>()
+		.read_resource::<FrameState>()
+		.with_query(<(Entity, &Velocity, &Camera, &mut PlayerSpriteRender)>::query())
+		.build(move |_, world, resources, query| {
+			let _profile = profiler::scope("player_sprite_move_system");
+
+			let (client, frame_state) = resources;
+			let delta_time = frame_state.delta_time.as_secs_f32();
+
+			for (entity, velocity, camera, player_sprite_render) in query.iter_mut(world) {
+				// Bob along the same path as the original game, but at its own pace,
+				// independently of the view bobbing
+				let bob_amplitude = camera.bob_amplitude(velocity);
+				let mut angle = Angle::from_units(
+					frame_state.time.as_secs_f64() / camera.weapon_bob_period.as_secs_f64(),
+				); // TODO replace with div_duration_f64 once it's stable
+				player_sprite_render.position[0] = 1.0 + bob_amplitude * angle.cos() as f32;
+
+				angle.0 &= 0x7FFF_FFFF;
+				player_sprite_render.position[1] = bob_amplitude * angle.sin() as f32;
+
+				// Sway opposite to the direction that the view is turning
+				let sway_target = if Some(*entity) == client.entity && !client.is_spectating() {
+					-Vector2::new(client.command.yaw, client.command.pitch) * SWAY_SCALE
+				} else {
+					Vector2::zeros()
+				};
+				let sway_target = sway_target.map(|x| num_traits::clamp(x, -SWAY_MAX, SWAY_MAX));
+
+				player_sprite_render.sway +=
+					(sway_target - player_sprite_render.sway) * (SWAY_SPEED * delta_time).min(1.0);
+			}
+		})
+}
@@ -0,0 +1,153 @@
+use crate::{
+	common::{
+		profiler,
+		video::{
+			definition::NumberedInstanceBufferDefinition, ring_buffer_pool, DrawContext, DrawStep,
+		},
+	},
+	doom::decal::{Decals, MAX_DECALS},
+};
+use anyhow::Context;
+use legion::{systems::ResourceSet, Read, Resources, World};
+use nalgebra::Vector3;
+use std::sync::Arc;
+use vulkano::{
+	buffer::{BufferUsage, CpuBufferPool},
+	framebuffer::{RenderPassAbstract, Subpass},
+	impl_vertex,
+	pipeline::{
+		blend::{AttachmentBlend, BlendFactor},
+		depth_stencil::{Compare, DepthStencil},
+		GraphicsPipeline, GraphicsPipelineAbstract,
+	},
+};
+
+/// How far decals are lifted off the wall, to keep them from fighting with it in the depth buffer.
+const DECAL_OFFSET: f32 = 0.25;
+
+pub struct DrawDecals {
+	instance_buffer_pool: CpuBufferPool<InstanceData>,
+	pipeline: Arc<dyn GraphicsPipelineAbstract + Send + Sync>,
+}
+
+impl DrawDecals {
+	pub fn new(
+		render_pass: &Arc<dyn RenderPassAbstract + Send + Sync>,
+	) -> anyhow::Result<DrawDecals> {
+		let device = render_pass.device();
+
+		// Create pipeline
+		let vert = decal_vert::Shader::load(device.clone()).context("Couldn't load shader")?;
+		let frag = decal_frag::Shader::load(device.clone()).context("Couldn't load shader")?;
+
+		let pipeline = Arc::new(
+			GraphicsPipeline::start()
+				.render_pass(
+					Subpass::from(render_pass.clone(), 0).context("Subpass index out of range")?,
+				)
+				.vertex_input(NumberedInstanceBufferDefinition::<InstanceData>::new(4))
+				.vertex_shader(vert.main_entry_point(), ())
+				.fragment_shader(frag.main_entry_point(), ())
+				.triangle_fan()
+				.viewports_dynamic_scissors_irrelevant(1)
+				.depth_stencil(DepthStencil {
+					depth_write: false,
+					depth_compare: Compare::LessOrEqual,
+					..DepthStencil::simple_depth_test()
+				})
+				// Multiply with what is already there, so that the decal is lit like the wall
+				.blend_collective(AttachmentBlend {
+					enabled: true,
+					color_source: BlendFactor::DstColor,
+					color_destination: BlendFactor::Zero,
+					alpha_source: BlendFactor::Zero,
+					alpha_destination: BlendFactor::One,
+					..AttachmentBlend::pass_through()
+				})
+				.build(device.clone())
+				.context("Couldn't create pipeline")?,
+		) as Arc<dyn GraphicsPipelineAbstract + Send + Sync>;
+
+		Ok(DrawDecals {
+			instance_buffer_pool: ring_buffer_pool(
+				device,
+				BufferUsage::vertex_buffer(),
+				MAX_DECALS,
+			)?,
+			pipeline,
+		})
+	}
+}
+
+impl DrawStep for DrawDecals {
+	fn draw(
+		&mut self,
+		draw_context: &mut DrawContext,
+		_world: &World,
+		resources: &Resources,
+	) -> anyhow::Result<()> {
+		let _profile = profiler::scope("draw_decals");
+
+		let decals = <Read<Decals>>::fetch(resources);
+		let instance_data: Vec<InstanceData> = decals
+			.iter()
+			.map(|decal| {
+				let half_size = decal.kind.size() * 0.5;
+				let up = Vector3::z();
+				let right = up.cross(&decal.normal).normalize();
+				let position = decal.position + decal.normal * DECAL_OFFSET;
+
+				InstanceData {
+					in_position: position.into(),
+					in_right: (right * half_size).into(),
+					in_up: (up * half_size).into(),
+					in_colour: decal.kind.colour(),
+				}
+			})
+			.collect();
+
+		if instance_data.is_empty() {
+			return Ok(());
+		}
+
+		let instance_buffer = self.instance_buffer_pool.chunk(instance_data)?;
+
+		draw_context.descriptor_sets.truncate(1);
+		draw_context
+			.commands
+			.draw(
+				self.pipeline.clone(),
+				&draw_context.dynamic_state,
+				vec![Arc::new(instance_buffer)],
+				draw_context.descriptor_sets.clone(),
+				(),
+			)
+			.context("Draw error")?;
+		draw_context.draw_calls += 1;
+
+		Ok(())
+	}
+}
+
+mod decal_vert {
+	vulkano_shaders::shader! {
+		ty: "vertex",
+		path: "shaders/decal.vert",
+	}
+}
+
+mod decal_frag {
+	vulkano_shaders::shader! {
+		ty: "fragment",
+		path: "shaders/decal.frag",
+	}
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct InstanceData {
+	pub in_position: [f32; 3],
+	pub in_right: [f32; 3],
+	pub in_up: [f32; 3],
+	pub in_colour: [f32; 3],
+}
+impl_vertex!(InstanceData, in_position, in_right, in_up, in_colour);
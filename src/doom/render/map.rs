@@ -1,20 +1,29 @@
 use crate::{
 	common::{
 		assets::AssetStorage,
-		video::{AsBytes, DrawContext, DrawStep},
+		configvars::ConfigVariables,
+		frame::FrameInterpolation,
+		profiler,
+		video::{ring_buffer_pool, AsBytes, DrawContext, DrawStep},
 	},
 	doom::{
+		camera::Camera,
 		client::Client,
 		components::Transform,
+		light::{DynamicLight, MUZZLE_FLASH_LIGHT},
 		map::{
-			meshes::{SkyVertexData, VertexData},
+			meshes::{make_meshes, MapMeshes, SkyVertexData, VertexData},
+			textures::TextureArrays,
+			visibility::visibility,
 			MapDynamic,
 		},
-		render::world::normal_frag,
+		psprite::{PlayerSpriteRender, PlayerSpriteSlot},
+		render::Samplers,
 	},
 };
 use anyhow::{anyhow, Context};
-use legion::{systems::ResourceSet, EntityStore, IntoQuery, Read, Resources, World};
+use legion::{systems::ResourceSet, IntoQuery, Read, Resources, World};
+use nalgebra::Vector3;
 use std::sync::Arc;
 use vulkano::{
 	buffer::{BufferUsage, CpuBufferPool},
@@ -22,16 +31,16 @@ use vulkano::{
 	device::DeviceOwned,
 	framebuffer::{RenderPassAbstract, Subpass},
 	pipeline::{GraphicsPipeline, GraphicsPipelineAbstract},
-	sampler::Sampler,
 };
 
 pub struct DrawMap {
 	index_buffer_pool: CpuBufferPool<u32>,
+	light_uniform_pool: CpuBufferPool<normal_frag::ty::DynamicLights>,
+	meshes: MapMeshes,
 	normal_pipeline: Arc<dyn GraphicsPipelineAbstract + Send + Sync>,
 	normal_texture_set_pool: FixedSizeDescriptorSetsPool,
 	sky_pipeline: Arc<dyn GraphicsPipelineAbstract + Send + Sync>,
 	sky_texture_set_pool: FixedSizeDescriptorSetsPool,
-	sky_uniform_pool: CpuBufferPool<sky_frag::ty::FragParams>,
 	vertex_buffer_pool: CpuBufferPool<u8>,
 }
 
@@ -85,15 +94,16 @@ impl DrawMap {
 		) as Arc<dyn GraphicsPipelineAbstract + Send + Sync>;
 
 		Ok(DrawMap {
-			index_buffer_pool: CpuBufferPool::new(device.clone(), BufferUsage::index_buffer()),
-			vertex_buffer_pool: CpuBufferPool::new(device.clone(), BufferUsage::vertex_buffer()),
+			meshes: MapMeshes::default(),
+			index_buffer_pool: ring_buffer_pool(device, BufferUsage::index_buffer(), 1 << 20)?,
+			vertex_buffer_pool: ring_buffer_pool(device, BufferUsage::vertex_buffer(), 16 << 20)?,
 
+			light_uniform_pool: CpuBufferPool::new(device.clone(), BufferUsage::uniform_buffer()),
 			normal_texture_set_pool: FixedSizeDescriptorSetsPool::new(
 				normal_pipeline.descriptor_set_layout(1).unwrap().clone(),
 			),
 			normal_pipeline,
 
-			sky_uniform_pool: CpuBufferPool::new(device.clone(), BufferUsage::uniform_buffer()),
 			sky_texture_set_pool: FixedSizeDescriptorSetsPool::new(
 				sky_pipeline.descriptor_set_layout(1).unwrap().clone(),
 			),
@@ -109,72 +119,74 @@ impl DrawStep for DrawMap {
 		world: &World,
 		resources: &Resources,
 	) -> anyhow::Result<()> {
-		let (asset_storage, client, sampler) =
-			<(Read<AssetStorage>, Read<Client>, Read<Arc<Sampler>>)>::fetch(resources);
-		let camera_entry = world.entry_ref(client.entity.unwrap()).unwrap();
-		let camera_transform = camera_entry.get_component::<Transform>().unwrap();
+		let _profile = profiler::scope("draw_map");
 
-		for map_dynamic in <&MapDynamic>::query().iter(world) {
-			let map = asset_storage.get(&map_dynamic.map).unwrap();
-			let (flat_meshes, wall_meshes, sky_mesh) =
-				crate::doom::map::meshes::make_meshes(map, map_dynamic, resources)
-					.context("Couldn't generate map mesh")?;
+		let (asset_storage, client, config_variables, frame_interpolation, samplers) =
+			<(
+				Read<AssetStorage>,
+				Read<Client>,
+				Read<ConfigVariables>,
+				Read<FrameInterpolation>,
+				Read<Samplers>,
+			)>::fetch(resources);
+		let camera_transform = client
+			.interpolated_view_transform(world, frame_interpolation.fraction)
+			.unwrap_or_default();
 
-			// Draw the walls
-			for (handle, mesh) in wall_meshes {
-				let vertex_buffer = self
-					.vertex_buffer_pool
-					.chunk(mesh.0.as_bytes().iter().copied())?;
-				let index_buffer = self.index_buffer_pool.chunk(mesh.1)?;
+		let dynamic_lights = if config_variables.get::<bool>("r_dynlights") {
+			dynamic_lights(world, camera_transform.position)
+		} else {
+			normal_frag::ty::DynamicLights {
+				light_positions: [[0.0; 4]; MAX_DYNAMIC_LIGHTS],
+				light_colours: [[0.0; 4]; MAX_DYNAMIC_LIGHTS],
+			}
+		};
+		let light_buffer = self.light_uniform_pool.next(dynamic_lights)?;
 
-				// Redirect animation frames
-				let handle = if let Some(anim_state) = map_dynamic.anim_states.get(&handle) {
-					let anim = &map.anims[&handle];
-					&anim.frames[anim_state.frame]
-				} else {
-					&handle
-				};
-				let image = &asset_storage.get(&handle).unwrap().image;
+		// Only present while a map is loaded
+		let texture_arrays = match resources.get::<TextureArrays>() {
+			Some(x) => x,
+			None => return Ok(()),
+		};
 
-				draw_context.descriptor_sets.truncate(1);
-				draw_context.descriptor_sets.push(Arc::new(
-					self.normal_texture_set_pool
-						.next()
-						.add_sampled_image(image.clone(), sampler.clone())?
-						.build()?,
-				));
+		for map_dynamic in <&MapDynamic>::query().iter(world) {
+			let map = asset_storage.get(&map_dynamic.map).unwrap();
+			let visibility = visibility(
+				map,
+				map_dynamic,
+				camera_transform.position.fixed_resize(0.0),
+			);
+			make_meshes(
+				map,
+				map_dynamic,
+				&visibility,
+				&texture_arrays,
+				frame_interpolation.fraction,
+				config_variables.get::<bool>("r_texelalign"),
+				&mut self.meshes,
+			)
+			.context("Couldn't generate map mesh")?;
 
-				draw_context.commands.draw_indexed(
-					self.normal_pipeline.clone(),
-					&draw_context.dynamic_state,
-					vec![Arc::new(vertex_buffer)],
-					index_buffer,
-					draw_context.descriptor_sets.clone(),
-					(),
-				)?;
-			}
+			// Draw the walls and flats, one draw per texture array
+			for (array, mesh) in self.meshes.normal.iter() {
+				if mesh.1.is_empty() {
+					continue;
+				}
 
-			// Draw the flats
-			for (handle, mesh) in flat_meshes {
 				let vertex_buffer = self
 					.vertex_buffer_pool
 					.chunk(mesh.0.as_bytes().iter().copied())?;
-				let index_buffer = self.index_buffer_pool.chunk(mesh.1)?;
-
-				// Redirect animation frames
-				let handle = if let Some(anim_state) = map_dynamic.anim_states.get(&handle) {
-					let anim = &map.anims[&handle];
-					&anim.frames[anim_state.frame]
-				} else {
-					&handle
-				};
-				let image = asset_storage.get(handle).unwrap();
+				let index_buffer = self.index_buffer_pool.chunk(mesh.1.iter().copied())?;
 
 				draw_context.descriptor_sets.truncate(1);
 				draw_context.descriptor_sets.push(Arc::new(
 					self.normal_texture_set_pool
 						.next()
-						.add_sampled_image(image.image.clone(), sampler.clone())?
+						.add_sampled_image(
+							texture_arrays.arrays[*array].clone(),
+							samplers.map.clone(),
+						)?
+						.add_buffer(light_buffer.clone())?
 						.build()?,
 				));
 
@@ -189,26 +201,22 @@ impl DrawStep for DrawMap {
 						(),
 					)
 					.context("Draw error")?;
+				draw_context.draw_calls += 1;
 			}
 
 			// Draw the sky
+			let sky_mesh = &self.meshes.sky;
 			let vertex_buffer = self
 				.vertex_buffer_pool
 				.chunk(sky_mesh.0.as_bytes().iter().copied())?;
-			let index_buffer = self.index_buffer_pool.chunk(sky_mesh.1)?;
+			let index_buffer = self.index_buffer_pool.chunk(sky_mesh.1.iter().copied())?;
 			let image = asset_storage.get(&map.sky).unwrap();
-			let sky_buffer = self.sky_uniform_pool.next(sky_frag::ty::FragParams {
-				screenSize: [800.0, 600.0],
-				pitch: camera_transform.rotation[1].to_degrees() as f32,
-				yaw: camera_transform.rotation[2].to_degrees() as f32,
-			})?;
 
 			draw_context.descriptor_sets.truncate(1);
 			draw_context.descriptor_sets.push(Arc::new(
 				self.sky_texture_set_pool
 					.next()
-					.add_sampled_image(image.image.clone(), sampler.clone())?
-					.add_buffer(sky_buffer)?
+					.add_sampled_image(image.image.clone(), samplers.map.clone())?
 					.build()?,
 			));
 
@@ -223,12 +231,64 @@ impl DrawStep for DrawMap {
 					(),
 				)
 				.context("Draw error")?;
+			draw_context.draw_calls += 1;
 		}
 
 		Ok(())
 	}
 }
 
+/// Must match the size of the arrays in the shader.
+const MAX_DYNAMIC_LIGHTS: usize = 32;
+
+/// Collects the dynamic lights closest to the camera.
+fn dynamic_lights(world: &World, camera_position: Vector3<f32>) -> normal_frag::ty::DynamicLights {
+	let mut lights: Vec<(Vector3<f32>, DynamicLight)> = <(&Transform, &DynamicLight)>::query()
+		.iter(world)
+		.map(|(transform, dynamic_light)| (transform.position, *dynamic_light))
+		.collect();
+
+	// Weapons light up the area around the player while firing
+	lights.extend(
+		<(&Transform, &PlayerSpriteRender, Option<&Camera>)>::query()
+			.iter(world)
+			.filter(|(_, player_sprite_render, _)| {
+				player_sprite_render.slots[PlayerSpriteSlot::Flash as usize].is_some()
+			})
+			.map(|(transform, _, camera)| {
+				let mut position = transform.position;
+
+				if let Some(camera) = camera {
+					position += camera.base;
+				}
+
+				(position, MUZZLE_FLASH_LIGHT)
+			}),
+	);
+
+	lights.sort_unstable_by(|a, b| {
+		let dist_a = (a.0 - camera_position).norm_squared();
+		let dist_b = (b.0 - camera_position).norm_squared();
+		dist_a.partial_cmp(&dist_b).unwrap()
+	});
+	lights.truncate(MAX_DYNAMIC_LIGHTS);
+	profiler::count("dynamic_lights", lights.len() as u64);
+
+	let mut dynamic_lights = normal_frag::ty::DynamicLights {
+		light_positions: [[0.0; 4]; MAX_DYNAMIC_LIGHTS],
+		light_colours: [[0.0; 4]; MAX_DYNAMIC_LIGHTS],
+	};
+
+	for (i, (position, dynamic_light)) in lights.into_iter().enumerate() {
+		let [r, g, b] = dynamic_light.colour;
+		dynamic_lights.light_positions[i] =
+			[position[0], position[1], position[2], dynamic_light.radius];
+		dynamic_lights.light_colours[i] = [r, g, b, 1.0];
+	}
+
+	dynamic_lights
+}
+
 mod normal_vert {
 	vulkano_shaders::shader! {
 		ty: "vertex",
@@ -238,6 +298,13 @@ mod normal_vert {
 
 pub use normal_vert::ty::Matrices;
 
+mod normal_frag {
+	vulkano_shaders::shader! {
+		ty: "fragment",
+		path: "shaders/map_normal.frag",
+	}
+}
+
 mod sky_vert {
 	vulkano_shaders::shader! {
 		ty: "vertex",
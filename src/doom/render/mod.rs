@@ -1,42 +1,444 @@
+pub mod decal;
 pub mod map;
+pub mod particle;
+pub mod perf;
+pub mod post;
 pub mod psprite;
 pub mod sprite;
 pub mod ui;
 pub mod world;
 
-use crate::common::video::{DrawList, RenderContext, RenderTarget};
-use legion::{systems::ResourceSet, Read, Resources, World, Write};
+use crate::{
+	common::{
+		assets::AssetStorage,
+		configvars::ConfigVariables,
+		input::MouseGrab,
+		profiler,
+		video::{
+			is_device_lost,
+			mode::{
+				refresh_rate, select_monitor, set_video_mode, MonitorInfo, MonitorWatcher,
+				VideoMode, WindowMode,
+			},
+			DrawList, ImageUploader, RenderContext, RenderTarget,
+		},
+	},
+	doom::{
+		image::{process_images, Image},
+		map::{textures::TextureArrays, MapDynamic},
+	},
+};
+use anyhow::Context;
+use legion::{systems::ResourceSet, IntoQuery, Read, Resources, World, Write};
+use std::{sync::Arc, time::Duration};
+use vulkano::{
+	sampler::{Filter, MipmapMode, Sampler, SamplerAddressMode},
+	swapchain::PresentMode,
+};
 
 pub fn render_system(mut draw_list: DrawList) -> Box<dyn FnMut(&mut World, &mut Resources)> {
+	let mut render_scale = RenderScale::new();
+	let mut video_mode = None;
+	let mut monitor_watcher = MonitorWatcher::new();
+
 	Box::new(move |world, resources| {
-		{
-			let (render_context, mut render_target) =
-				<(Read<RenderContext>, Write<RenderTarget>)>::fetch_mut(resources);
-
-			if render_target.needs_recreate() {
-				render_target
-					.recreate()
-					.expect("Couldn't recreate RenderTarget");
-
-				if render_target.dimensions() != draw_list.dimensions() {
-					draw_list
-						.resize(&render_context, render_target.dimensions())
-						.expect("Couldn't resize DrawList");
-				}
+		let _profile = profiler::scope("render_system");
+
+		if let Err(err) = render(
+			&mut draw_list,
+			&mut render_scale,
+			&mut video_mode,
+			&mut monitor_watcher,
+			world,
+			resources,
+		) {
+			if !is_device_lost(&err) {
+				panic!("Couldn't render: {:?}", err);
 			}
+
+			log::warn!("Lost the graphics device, restarting the renderer: {}", err);
+			draw_list = restart(world, resources).expect("Couldn't restart the renderer");
 		}
+	})
+}
+
+fn render(
+	draw_list: &mut DrawList,
+	render_scale: &mut RenderScale,
+	current_video_mode: &mut Option<VideoMode>,
+	monitor_watcher: &mut MonitorWatcher,
+	world: &World,
+	resources: &mut Resources,
+) -> anyhow::Result<()> {
+	{
+		let (config_variables, mut monitor_info, mut mouse_grab, render_context, mut render_target) =
+			<(
+				Read<ConfigVariables>,
+				Write<MonitorInfo>,
+				Write<MouseGrab>,
+				Read<RenderContext>,
+				Write<RenderTarget>,
+			)>::fetch_mut(resources);
+		let window = render_context.surface().window();
+
+		let video_mode = VideoMode {
+			window_mode: config_variables.get::<String>("vid_mode").parse()?,
+			dimensions: [
+				config_variables.get("vid_width"),
+				config_variables.get("vid_height"),
+			],
+			monitor: config_variables.get("vid_monitor"),
+		};
 
-		let (image, draw_future) = draw_list
-			.draw(world, resources)
-			.expect("Couldn't execute DrawList");
+		// Monitors can come and go at any time, so a fullscreen window
+		// may have to move to another one
+		let monitors_checked = monitor_watcher.check(window);
+
+		if monitors_checked == Some(true) && video_mode.window_mode != WindowMode::Windowed {
+			*current_video_mode = None;
+		}
 
-		{
-			let (render_context, mut render_target) =
-				<(Read<RenderContext>, Write<RenderTarget>)>::fetch_mut(resources);
+		if *current_video_mode != Some(video_mode) || monitors_checked.is_some() {
+			// A window may have been moved to another monitor, so this is checked regularly
+			let monitor = match video_mode.window_mode {
+				WindowMode::Windowed => window.current_monitor(),
+				_ => select_monitor(window, video_mode.monitor),
+			};
+			monitor_info.refresh_rate = refresh_rate(&monitor);
+		}
+
+		if *current_video_mode != Some(video_mode) {
+			set_video_mode(window, &video_mode);
+
+			// Switching modes can release the mouse on some platforms
+			mouse_grab.invalidate();
+
+			*current_video_mode = Some(video_mode);
+		}
+
+		render_target.set_present_modes(present_modes(&config_variables));
+
+		if render_target.needs_recreate() {
+			render_target
+				.recreate()
+				.context("Couldn't recreate RenderTarget")?;
+		}
 
+		// The scene is drawn at a scaled size, and scaled back up when presenting
+		let dimensions = if config_variables.get::<bool>("r_lowres") {
+			draw_list.set_pixel_aspect(LOWRES_PIXEL_ASPECT);
+			LOWRES_DIMENSIONS
+		} else {
+			draw_list.set_pixel_aspect(1.0);
+			let scale = render_scale.update(config_variables.get("r_renderscale"));
 			render_target
-				.present(&render_context.queues().graphics, image, draw_future)
-				.expect("Couldn't present swapchain");
+				.dimensions()
+				.map(|x| ((x as f32 * scale) as u32).max(1))
+		};
+
+		if dimensions != draw_list.dimensions() {
+			draw_list
+				.resize(&render_context, dimensions)
+				.context("Couldn't resize DrawList")?;
+		}
+	}
+
+	{
+		let (config_variables, render_context, mut samplers) =
+			<(Read<ConfigVariables>, Read<RenderContext>, Write<Samplers>)>::fetch_mut(resources);
+		let filter = config_variables.get::<String>("r_filter");
+
+		if filter != samplers.filter {
+			*samplers = create_samplers(&render_context, &filter)?;
+		}
+	}
+
+	// Sprites are imported the first time something that uses them is spawned,
+	// which can happen at any time during the game
+	{
+		let (render_context, mut asset_storage) =
+			<(Read<RenderContext>, Write<AssetStorage>)>::fetch_mut(resources);
+		let mut uploader = ImageUploader::new();
+		process_images(&render_context, &mut asset_storage, &mut uploader);
+		uploader.upload(&render_context)?;
+	}
+
+	let (image, draw_future) = draw_list
+		.draw(world, resources)
+		.context("Couldn't execute DrawList")?;
+
+	{
+		let _profile = profiler::scope("present");
+		let (config_variables, render_context, mut render_target) = <(
+			Read<ConfigVariables>,
+			Read<RenderContext>,
+			Write<RenderTarget>,
+		)>::fetch_mut(resources);
+
+		// Low resolution mode is shown at 4:3 like the original, however the window is shaped
+		let aspect_ratio = if config_variables.get::<bool>("r_lowres") {
+			Some(4.0 / 3.0)
+		} else {
+			None
+		};
+
+		render_target
+			.present(
+				&render_context.queues().graphics,
+				image,
+				aspect_ratio,
+				draw_future,
+			)
+			.context("Couldn't present swapchain")?;
+	}
+
+	Ok(())
+}
+
+/// Rebuilds the renderer from scratch, including all GPU resources.
+/// Images are imported again from the asset source.
+fn restart(world: &World, resources: &mut Resources) -> anyhow::Result<DrawList> {
+	let (filter, compress) = {
+		let config_variables = <Read<ConfigVariables>>::fetch(resources);
+		(
+			config_variables.get::<String>("r_filter"),
+			config_variables.get::<bool>("r_compress"),
+		)
+	};
+
+	let (render_target, samplers, draw_list, texture_arrays) = {
+		let (mut render_context, mut asset_storage) =
+			<(Write<RenderContext>, Write<AssetStorage>)>::fetch_mut(resources);
+		render_context.recreate()?;
+
+		let render_target = RenderTarget::new(
+			render_context.surface().clone(),
+			render_context.device().clone(),
+		)
+		.context("Couldn't create RenderTarget")?;
+		let samplers = create_samplers(&render_context, &filter)?;
+		let draw_list = create_draw_list(&render_context, render_target.dimensions())?;
+
+		asset_storage.reimport::<Image>();
+		let mut uploader = ImageUploader::new();
+		process_images(&render_context, &mut asset_storage, &mut uploader);
+
+		let texture_arrays = match <&MapDynamic>::query().iter(world).next() {
+			Some(map_dynamic) => Some(TextureArrays::new(
+				&map_dynamic.map,
+				&render_context,
+				&mut asset_storage,
+				&mut uploader,
+				compress,
+			)?),
+			None => None,
+		};
+		uploader.upload(&render_context)?;
+
+		(render_target, samplers, draw_list, texture_arrays)
+	};
+
+	resources.insert(render_target);
+	resources.insert(samplers);
+
+	if let Some(texture_arrays) = texture_arrays {
+		resources.insert(texture_arrays);
+	}
+
+	log::info!("Renderer restarted");
+
+	Ok(draw_list)
+}
+
+/// Picks the present modes to use from the `r_presentmode` and `r_vsync` settings.
+/// Without vsync, mailbox is preferred because it doesn't tear; immediate mode has to be
+/// asked for explicitly.
+fn present_modes(config_variables: &ConfigVariables) -> &'static [PresentMode] {
+	match config_variables.get::<String>("r_presentmode").as_str() {
+		"fifo" => &[PresentMode::Fifo],
+		"mailbox" => &[PresentMode::Mailbox],
+		"immediate" => &[PresentMode::Immediate],
+		_ => {
+			if config_variables.get::<bool>("r_vsync") {
+				&[PresentMode::Fifo]
+			} else {
+				&[PresentMode::Mailbox]
+			}
+		}
+	}
+}
+
+/// Resolution of the original game, used by `r_lowres`.
+const LOWRES_DIMENSIONS: [u32; 2] = [320, 200];
+
+/// The original game's pixels were stretched vertically to fill a 4:3 screen.
+const LOWRES_PIXEL_ASPECT: f32 = 1.2;
+
+/// The frame time that automatic render scaling tries to stay under.
+const AUTO_SCALE_TARGET: Duration = Duration::from_micros(16_667);
+
+/// How many frames to wait between adjustments of the automatic render scale.
+const AUTO_SCALE_INTERVAL: u32 = 30;
+
+/// Keeps track of the scale of the rendered image relative to the window.
+struct RenderScale {
+	auto_scale: f32,
+	frames_since_change: u32,
+}
+
+impl RenderScale {
+	fn new() -> RenderScale {
+		RenderScale {
+			auto_scale: 1.0,
+			frames_since_change: 0,
+		}
+	}
+
+	/// Returns the scale to render at this frame. A `setting` of 0 means automatic,
+	/// which lowers the scale when recent frames took too long, and raises it again
+	/// once there is time to spare.
+	fn update(&mut self, setting: f32) -> f32 {
+		if setting > 0.0 {
+			return setting;
+		}
+
+		self.frames_since_change += 1;
+
+		if self.frames_since_change >= AUTO_SCALE_INTERVAL {
+			let frame_times = profiler::frame_times();
+			let recent = &frame_times[frame_times
+				.len()
+				.saturating_sub(AUTO_SCALE_INTERVAL as usize)..];
+
+			if !recent.is_empty() {
+				let average = recent.iter().sum::<Duration>() / recent.len() as u32;
+				let old_scale = self.auto_scale;
+
+				if average > AUTO_SCALE_TARGET.mul_f32(1.1) {
+					self.auto_scale = (self.auto_scale - 0.1).max(0.5);
+				} else if average < AUTO_SCALE_TARGET.mul_f32(0.8) {
+					self.auto_scale = (self.auto_scale + 0.1).min(1.0);
+				}
+
+				if self.auto_scale != old_scale {
+					log::debug!("Render scale set to {:.1}", self.auto_scale);
+				}
+			}
+
+			self.frames_since_change = 0;
 		}
+
+		self.auto_scale
+	}
+}
+
+pub fn create_draw_list(
+	render_context: &RenderContext,
+	dimensions: [u32; 2],
+) -> anyhow::Result<DrawList> {
+	let mut draw_list =
+		DrawList::new(render_context, dimensions).context("Couldn't create DrawList")?;
+	draw_list.add_step(world::DrawWorld::new(render_context).context("Couldn't create DrawWorld")?);
+	draw_list
+		.add_step(map::DrawMap::new(draw_list.render_pass()).context("Couldn't create DrawMap")?);
+	draw_list.add_step(
+		decal::DrawDecals::new(draw_list.render_pass()).context("Couldn't create DrawDecals")?,
+	);
+	draw_list.add_step(
+		sprite::DrawSprites::new(render_context, draw_list.render_pass())
+			.context("Couldn't create DrawSprites")?,
+	);
+	draw_list.add_step(
+		particle::DrawParticles::new(draw_list.render_pass())
+			.context("Couldn't create DrawParticles")?,
+	);
+	draw_list.add_step(
+		psprite::DrawPlayerSprites::new(render_context, draw_list.render_pass())
+			.context("Couldn't create DrawPlayerSprites")?,
+	);
+	draw_list.add_step(
+		ui::DrawUi::new(render_context, draw_list.render_pass())
+			.context("Couldn't create DrawUi")?,
+	);
+	draw_list.add_post_step(
+		post::DrawPost::new(draw_list.render_pass()).context("Couldn't create DrawPost")?,
+	);
+	draw_list.add_post_step(
+		perf::DrawPerf::new(render_context, draw_list.render_pass())
+			.context("Couldn't create DrawPerf")?,
+	);
+
+	Ok(draw_list)
+}
+
+/// Texture samplers for the different kinds of things that are drawn.
+pub struct Samplers {
+	/// The `r_filter` setting that the samplers were created with.
+	pub filter: String,
+	/// Repeating, filtered according to `r_filter`. For walls and flats.
+	pub map: Arc<Sampler>,
+	/// Clamped to the edges, filtered according to `r_filter`. For sprites.
+	pub sprite: Arc<Sampler>,
+	/// Clamped to the edges, always unfiltered. For the UI.
+	pub ui: Arc<Sampler>,
+}
+
+pub fn create_samplers(render_context: &RenderContext, filter: &str) -> anyhow::Result<Samplers> {
+	let device = render_context.device();
+	let (mag_filter, min_filter, max_anisotropy) = match filter {
+		"linear" => (Filter::Linear, Filter::Linear, 1.0),
+		"anisotropic" if device.enabled_features().sampler_anisotropy => (
+			Filter::Linear,
+			Filter::Linear,
+			device
+				.physical_device()
+				.limits()
+				.max_sampler_anisotropy()
+				.min(16.0),
+		),
+		"anisotropic" => {
+			log::warn!("Anisotropic filtering is not supported, using linear filtering");
+			(Filter::Linear, Filter::Linear, 1.0)
+		}
+		_ => (Filter::Nearest, Filter::Nearest, 1.0),
+	};
+
+	let create = |mag_filter, min_filter, address_mode, max_anisotropy| {
+		Sampler::new(
+			device.clone(),
+			mag_filter,
+			min_filter,
+			MipmapMode::Nearest,
+			address_mode,
+			address_mode,
+			address_mode,
+			0.0,
+			max_anisotropy,
+			0.0,
+			0.0,
+		)
+		.context("Couldn't create texture sampler")
+	};
+
+	Ok(Samplers {
+		filter: filter.to_owned(),
+		map: create(
+			mag_filter,
+			min_filter,
+			SamplerAddressMode::Repeat,
+			max_anisotropy,
+		)?,
+		sprite: create(
+			mag_filter,
+			min_filter,
+			SamplerAddressMode::ClampToEdge,
+			max_anisotropy,
+		)?,
+		ui: create(
+			Filter::Nearest,
+			Filter::Nearest,
+			SamplerAddressMode::ClampToEdge,
+			1.0,
+		)?,
 	})
 }
@@ -0,0 +1,131 @@
+use crate::{
+	common::{
+		profiler,
+		video::{
+			definition::NumberedInstanceBufferDefinition, ring_buffer_pool, DrawContext, DrawStep,
+		},
+	},
+	doom::particle::{Particles, MAX_PARTICLES},
+};
+use anyhow::Context;
+use legion::{systems::ResourceSet, Read, Resources, World};
+use std::sync::Arc;
+use vulkano::{
+	buffer::{BufferUsage, CpuBufferPool},
+	framebuffer::{RenderPassAbstract, Subpass},
+	impl_vertex,
+	pipeline::{
+		depth_stencil::{Compare, DepthStencil},
+		GraphicsPipeline, GraphicsPipelineAbstract,
+	},
+};
+
+pub struct DrawParticles {
+	instance_buffer_pool: CpuBufferPool<InstanceData>,
+	pipeline: Arc<dyn GraphicsPipelineAbstract + Send + Sync>,
+}
+
+impl DrawParticles {
+	pub fn new(
+		render_pass: &Arc<dyn RenderPassAbstract + Send + Sync>,
+	) -> anyhow::Result<DrawParticles> {
+		let device = render_pass.device();
+
+		// Create pipeline
+		let vert = particle_vert::Shader::load(device.clone()).context("Couldn't load shader")?;
+		let frag = particle_frag::Shader::load(device.clone()).context("Couldn't load shader")?;
+
+		let pipeline = Arc::new(
+			GraphicsPipeline::start()
+				.render_pass(
+					Subpass::from(render_pass.clone(), 0).context("Subpass index out of range")?,
+				)
+				.vertex_input(NumberedInstanceBufferDefinition::<InstanceData>::new(4))
+				.vertex_shader(vert.main_entry_point(), ())
+				.fragment_shader(frag.main_entry_point(), ())
+				.triangle_fan()
+				.viewports_dynamic_scissors_irrelevant(1)
+				.depth_stencil(DepthStencil {
+					depth_write: false,
+					depth_compare: Compare::LessOrEqual,
+					..DepthStencil::simple_depth_test()
+				})
+				.blend_alpha_blending()
+				.build(device.clone())
+				.context("Couldn't create pipeline")?,
+		) as Arc<dyn GraphicsPipelineAbstract + Send + Sync>;
+
+		Ok(DrawParticles {
+			instance_buffer_pool: ring_buffer_pool(
+				device,
+				BufferUsage::vertex_buffer(),
+				MAX_PARTICLES,
+			)?,
+			pipeline,
+		})
+	}
+}
+
+impl DrawStep for DrawParticles {
+	fn draw(
+		&mut self,
+		draw_context: &mut DrawContext,
+		_world: &World,
+		resources: &Resources,
+	) -> anyhow::Result<()> {
+		let _profile = profiler::scope("draw_particles");
+
+		let particles = <Read<Particles>>::fetch(resources);
+		let instance_data: Vec<InstanceData> = particles
+			.iter()
+			.map(|particle| InstanceData {
+				in_position: particle.position.into(),
+				in_size: particle.size,
+				in_colour: particle.colour,
+			})
+			.collect();
+
+		if instance_data.is_empty() {
+			return Ok(());
+		}
+
+		let instance_buffer = self.instance_buffer_pool.chunk(instance_data)?;
+
+		draw_context.descriptor_sets.truncate(1);
+		draw_context
+			.commands
+			.draw(
+				self.pipeline.clone(),
+				&draw_context.dynamic_state,
+				vec![Arc::new(instance_buffer)],
+				draw_context.descriptor_sets.clone(),
+				(),
+			)
+			.context("Draw error")?;
+		draw_context.draw_calls += 1;
+
+		Ok(())
+	}
+}
+
+mod particle_vert {
+	vulkano_shaders::shader! {
+		ty: "vertex",
+		path: "shaders/particle.vert",
+	}
+}
+
+mod particle_frag {
+	vulkano_shaders::shader! {
+		ty: "fragment",
+		path: "shaders/particle.frag",
+	}
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct InstanceData {
+	pub in_position: [f32; 3],
+	pub in_size: f32,
+	pub in_colour: [f32; 4],
+}
+impl_vertex!(InstanceData, in_position, in_size, in_colour);
@@ -0,0 +1,202 @@
+use crate::{
+	common::{
+		configvars::ConfigVariables,
+		geometry::{ortho_matrix, Interval, AABB3},
+		profiler,
+		video::{
+			definition::NumberedInstanceBufferDefinition, ring_buffer_pool, DrawContext, DrawStep,
+			RenderContext,
+		},
+	},
+	doom::{data::FRAME_TIME, render::ui::UiParams},
+};
+use anyhow::Context;
+use legion::{systems::ResourceSet, Read, Resources, World};
+use nalgebra::Vector3;
+use std::sync::Arc;
+use vulkano::{
+	buffer::{BufferUsage, CpuBufferPool},
+	descriptor::descriptor_set::FixedSizeDescriptorSetsPool,
+	framebuffer::{RenderPassAbstract, Subpass},
+	impl_vertex,
+	pipeline::{GraphicsPipeline, GraphicsPipelineAbstract},
+};
+
+const GRAPH_POSITION: [f32; 2] = [4.0, 4.0];
+const GRAPH_SIZE: [f32; 2] = [128.0, 50.0];
+
+/// Vertical scale of the graph, in UI units per millisecond of frame time.
+const MS_SCALE: f32 = 1.0;
+
+pub struct DrawPerf {
+	instance_buffer_pool: CpuBufferPool<InstanceData>,
+	matrix_uniform_pool: CpuBufferPool<Matrices>,
+	matrix_set_pool: FixedSizeDescriptorSetsPool,
+	pipeline: Arc<dyn GraphicsPipelineAbstract + Send + Sync>,
+}
+
+impl DrawPerf {
+	pub fn new(
+		render_context: &RenderContext,
+		render_pass: &Arc<dyn RenderPassAbstract + Send + Sync>,
+	) -> anyhow::Result<DrawPerf> {
+		let device = render_pass.device();
+
+		// Create pipeline
+		let vert = perf_vert::Shader::load(device.clone()).context("Couldn't load shader")?;
+		let frag = perf_frag::Shader::load(device.clone()).context("Couldn't load shader")?;
+
+		let pipeline = Arc::new(
+			GraphicsPipeline::start()
+				.render_pass(
+					Subpass::from(render_pass.clone(), 1).context("Subpass index out of range")?,
+				)
+				.vertex_input(NumberedInstanceBufferDefinition::<InstanceData>::new(4))
+				.vertex_shader(vert.main_entry_point(), ())
+				.fragment_shader(frag.main_entry_point(), ())
+				.triangle_fan()
+				.viewports_dynamic_scissors_irrelevant(1)
+				.build(device.clone())
+				.context("Couldn't create pipeline")?,
+		) as Arc<dyn GraphicsPipelineAbstract + Send + Sync>;
+
+		let layout = pipeline.descriptor_set_layout(0).unwrap();
+		let matrix_set_pool = FixedSizeDescriptorSetsPool::new(layout.clone());
+
+		Ok(DrawPerf {
+			instance_buffer_pool: ring_buffer_pool(
+				device,
+				BufferUsage::vertex_buffer(),
+				(profiler::MAX_FRAMES + 2) * 4,
+			)?,
+			matrix_uniform_pool: CpuBufferPool::new(
+				render_context.device().clone(),
+				BufferUsage::uniform_buffer(),
+			),
+			matrix_set_pool,
+			pipeline,
+		})
+	}
+}
+
+impl DrawStep for DrawPerf {
+	fn draw(
+		&mut self,
+		draw_context: &mut DrawContext,
+		_world: &World,
+		resources: &Resources,
+	) -> anyhow::Result<()> {
+		let config_variables = <Read<ConfigVariables>>::fetch(resources);
+
+		if !config_variables.get::<bool>("showperf") {
+			return Ok(());
+		}
+
+		let ui_params = UiParams::new(draw_context);
+		let viewport = &mut draw_context.dynamic_state.viewports.as_mut().unwrap()[0];
+		viewport.origin = [0.0, 0.0];
+		viewport.dimensions = ui_params.framebuffer_dimensions.into();
+
+		let proj = ortho_matrix(AABB3::from_intervals(Vector3::new(
+			Interval::new(0.0, ui_params.dimensions[0]),
+			Interval::new(0.0, ui_params.dimensions[1]),
+			Interval::new(1000.0, 0.0),
+		)));
+
+		// Create matrix UBO
+		draw_context.descriptor_sets.truncate(0);
+		draw_context.descriptor_sets.push(Arc::new(
+			self.matrix_set_pool
+				.next()
+				.add_buffer(
+					self.matrix_uniform_pool
+						.next(Matrices { proj: proj.into() })?,
+				)?
+				.build()?,
+		));
+
+		// Background, followed by a line marking the length of one game frame
+		let tick_height = FRAME_TIME.as_secs_f32() * 1000.0 * MS_SCALE;
+		let mut instance_data = vec![
+			InstanceData {
+				in_position: GRAPH_POSITION,
+				in_size: GRAPH_SIZE,
+				in_color: [0.0, 0.0, 0.0, 1.0],
+			},
+			InstanceData {
+				in_position: [
+					GRAPH_POSITION[0],
+					GRAPH_POSITION[1] + GRAPH_SIZE[1] - tick_height,
+				],
+				in_size: [GRAPH_SIZE[0], 0.5],
+				in_color: [0.5, 0.5, 0.5, 1.0],
+			},
+		];
+
+		// One bar per frame, newest on the right
+		let frame_times = profiler::frame_times();
+		let bar_width = GRAPH_SIZE[0] / profiler::MAX_FRAMES as f32;
+		let first_bar = profiler::MAX_FRAMES - frame_times.len();
+
+		instance_data.extend(frame_times.into_iter().enumerate().map(|(i, frame_time)| {
+			let ms = frame_time.as_secs_f32() * 1000.0;
+			let height = (ms * MS_SCALE).min(GRAPH_SIZE[1]);
+			let in_color = if ms <= 1000.0 / 60.0 {
+				[0.0, 1.0, 0.0, 1.0]
+			} else if ms <= 1000.0 / 30.0 {
+				[1.0, 1.0, 0.0, 1.0]
+			} else {
+				[1.0, 0.0, 0.0, 1.0]
+			};
+
+			InstanceData {
+				in_position: [
+					GRAPH_POSITION[0] + (first_bar + i) as f32 * bar_width,
+					GRAPH_POSITION[1] + GRAPH_SIZE[1] - height,
+				],
+				in_size: [bar_width, height],
+				in_color,
+			}
+		}));
+
+		let instance_buffer = self.instance_buffer_pool.chunk(instance_data)?;
+
+		draw_context
+			.commands
+			.draw(
+				self.pipeline.clone(),
+				&draw_context.dynamic_state,
+				vec![Arc::new(instance_buffer)],
+				draw_context.descriptor_sets.clone(),
+				(),
+			)
+			.context("Draw error")?;
+		draw_context.draw_calls += 1;
+
+		Ok(())
+	}
+}
+
+pub mod perf_vert {
+	vulkano_shaders::shader! {
+		ty: "vertex",
+		path: "shaders/perf.vert",
+	}
+}
+
+pub use perf_vert::ty::Matrices;
+
+pub mod perf_frag {
+	vulkano_shaders::shader! {
+		ty: "fragment",
+		path: "shaders/perf.frag",
+	}
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct InstanceData {
+	pub in_position: [f32; 2],
+	pub in_size: [f32; 2],
+	pub in_color: [f32; 4],
+}
+impl_vertex!(InstanceData, in_position, in_size, in_color);
@@ -0,0 +1,133 @@
+use crate::{
+	common::{
+		profiler,
+		video::{DrawContext, DrawStep},
+	},
+	doom::{
+		client::{Client, ClientView},
+		palette::PaletteEffects,
+	},
+};
+use anyhow::Context;
+use legion::{systems::ResourceSet, IntoQuery, Read, Resources, World};
+use std::sync::Arc;
+use vulkano::{
+	descriptor::descriptor_set::FixedSizeDescriptorSetsPool,
+	framebuffer::{FramebufferAbstract, RenderPassAbstract, Subpass},
+	pipeline::{
+		vertex::{BufferlessDefinition, BufferlessVertices},
+		GraphicsPipeline, GraphicsPipelineAbstract,
+	},
+};
+
+/// Copies the scene to the final image, applying the player's palette effects.
+pub struct DrawPost {
+	pipeline: Arc<dyn GraphicsPipelineAbstract + Send + Sync>,
+	scene_set_pool: FixedSizeDescriptorSetsPool,
+}
+
+impl DrawPost {
+	pub fn new(
+		render_pass: &Arc<dyn RenderPassAbstract + Send + Sync>,
+	) -> anyhow::Result<DrawPost> {
+		let device = render_pass.device();
+
+		// Create pipeline
+		let vert = post_vert::Shader::load(device.clone()).context("Couldn't load shader")?;
+		let frag = post_frag::Shader::load(device.clone()).context("Couldn't load shader")?;
+
+		let pipeline = Arc::new(
+			GraphicsPipeline::start()
+				.render_pass(
+					Subpass::from(render_pass.clone(), 1).context("Subpass index out of range")?,
+				)
+				.vertex_input(BufferlessDefinition)
+				.vertex_shader(vert.main_entry_point(), ())
+				.fragment_shader(frag.main_entry_point(), ())
+				.triangle_list()
+				.viewports_dynamic_scissors_irrelevant(1)
+				.build(device.clone())
+				.context("Couldn't create pipeline")?,
+		) as Arc<dyn GraphicsPipelineAbstract + Send + Sync>;
+
+		let layout = pipeline.descriptor_set_layout(0).unwrap();
+		let scene_set_pool = FixedSizeDescriptorSetsPool::new(layout.clone());
+
+		Ok(DrawPost {
+			pipeline,
+			scene_set_pool,
+		})
+	}
+}
+
+impl DrawStep for DrawPost {
+	fn draw(
+		&mut self,
+		draw_context: &mut DrawContext,
+		world: &World,
+		resources: &Resources,
+	) -> anyhow::Result<()> {
+		let _profile = profiler::scope("draw_post");
+
+		let viewport = &mut draw_context.dynamic_state.viewports.as_mut().unwrap()[0];
+		viewport.origin = [0.0, 0.0];
+		viewport.dimensions = [
+			draw_context.framebuffer.width() as f32,
+			draw_context.framebuffer.height() as f32,
+		];
+
+		// Only show effects when looking through the player's own eyes
+		let client = <Read<Client>>::fetch(resources);
+		let palette_effects = match (client.entity, client.view) {
+			(Some(entity), ClientView::Player) => <&PaletteEffects>::query()
+				.get(world, entity)
+				.ok()
+				.copied()
+				.unwrap_or_default(),
+			_ => PaletteEffects::default(),
+		};
+		let (tint, invulnerability) = palette_effects.tint();
+
+		draw_context.descriptor_sets.truncate(0);
+		draw_context.descriptor_sets.push(Arc::new(
+			self.scene_set_pool
+				.next()
+				.add_image(draw_context.scene_attachment.clone())?
+				.build()?,
+		));
+
+		draw_context
+			.commands
+			.draw(
+				self.pipeline.clone(),
+				&draw_context.dynamic_state,
+				BufferlessVertices {
+					vertices: 3,
+					instances: 1,
+				},
+				draw_context.descriptor_sets.clone(),
+				post_frag::ty::PushConstants {
+					tint,
+					invulnerability: invulnerability as u32 as f32,
+				},
+			)
+			.context("Draw error")?;
+		draw_context.draw_calls += 1;
+
+		Ok(())
+	}
+}
+
+pub mod post_vert {
+	vulkano_shaders::shader! {
+		ty: "vertex",
+		path: "shaders/post.vert",
+	}
+}
+
+pub mod post_frag {
+	vulkano_shaders::shader! {
+		ty: "fragment",
+		path: "shaders/post.frag",
+	}
+}
@@ -2,15 +2,20 @@ use crate::{
 	common::{
 		assets::{AssetHandle, AssetStorage},
 		geometry::{ortho_matrix, Interval, AABB3},
+		profiler,
 		video::{
-			definition::NumberedInstanceBufferDefinition, DrawContext, DrawStep, RenderContext,
+			definition::NumberedInstanceBufferDefinition, ring_buffer_pool, DrawContext, DrawStep,
+			RenderContext,
 		},
 	},
 	doom::{
-		client::Client,
+		client::{Client, ClientView},
 		image::Image,
 		psprite::PlayerSpriteRender,
-		render::ui::{ui_frag, ui_vert, InstanceData, Matrices, UiParams},
+		render::{
+			ui::{ui_frag, ui_vert, InstanceData, Matrices, UiParams},
+			Samplers,
+		},
 		ui::UiAlignment,
 	},
 };
@@ -23,7 +28,6 @@ use vulkano::{
 	descriptor::descriptor_set::FixedSizeDescriptorSetsPool,
 	framebuffer::{RenderPassAbstract, Subpass},
 	pipeline::{GraphicsPipeline, GraphicsPipelineAbstract},
-	sampler::Sampler,
 };
 
 pub struct DrawPlayerSprites {
@@ -63,7 +67,7 @@ impl DrawPlayerSprites {
 		let matrix_set_pool = FixedSizeDescriptorSetsPool::new(layout.clone());
 
 		Ok(DrawPlayerSprites {
-			instance_buffer_pool: CpuBufferPool::new(device.clone(), BufferUsage::vertex_buffer()),
+			instance_buffer_pool: ring_buffer_pool(device, BufferUsage::vertex_buffer(), 32)?,
 			matrix_uniform_pool: CpuBufferPool::new(
 				render_context.device().clone(),
 				BufferUsage::uniform_buffer(),
@@ -84,7 +88,9 @@ impl DrawStep for DrawPlayerSprites {
 		world: &World,
 		resources: &Resources,
 	) -> anyhow::Result<()> {
-		let ui_params = UiParams::new(&draw_context.framebuffer);
+		let _profile = profiler::scope("draw_player_sprites");
+
+		let ui_params = UiParams::new(draw_context);
 		let viewport = &mut draw_context.dynamic_state.viewports.as_mut().unwrap()[0];
 		viewport.origin = [0.0, 0.0];
 		viewport.dimensions = ui_params.framebuffer_dimensions.into();
@@ -107,12 +113,12 @@ impl DrawStep for DrawPlayerSprites {
 				.build()?,
 		));
 
-		let (asset_storage, client, sampler) =
-			<(Read<AssetStorage>, Read<Client>, Read<Arc<Sampler>>)>::fetch(resources);
+		let (asset_storage, client, samplers) =
+			<(Read<AssetStorage>, Read<Client>, Read<Samplers>)>::fetch(resources);
 
-		let client_entity = match client.entity {
-			Some(e) => e,
-			None => return Ok(()),
+		let client_entity = match (client.entity, client.view) {
+			(Some(e), ClientView::Player) => e,
+			_ => return Ok(()),
 		};
 
 		let player_sprite_render = match <&PlayerSpriteRender>::query().get(world, client_entity) {
@@ -125,20 +131,24 @@ impl DrawStep for DrawPlayerSprites {
 		for sprite_render in player_sprite_render.slots.iter().flatten() {
 			// Set up instance data
 			let sprite = asset_storage.get(&sprite_render.sprite).unwrap();
-			let frame = &sprite.frames()[sprite_render.frame];
 
 			// This frame has no images, nothing to draw
-			if frame.is_empty() {
-				continue;
-			} else if frame.len() > 1 {
+			let frame = match sprite.frames().get(sprite_render.frame) {
+				Some(frame) if !frame.is_empty() => frame,
+				_ => continue,
+			};
+
+			if frame.len() > 1 {
 				bail!("Player sprite has rotation images");
 			}
 
 			let image_handle = &frame[0].handle;
 			let image = asset_storage.get(image_handle).unwrap();
 			let position = player_sprite_render.position
+				+ player_sprite_render.sway
 				+ ui_params.align([UiAlignment::Middle, UiAlignment::Far])
-				- image.offset + Vector2::new(0.0, 16.0);
+				- image.offset
+				+ Vector2::new(0.0, 16.0);
 
 			let instance_data = InstanceData {
 				in_position: position.into(),
@@ -156,7 +166,7 @@ impl DrawStep for DrawPlayerSprites {
 			draw_context.descriptor_sets.push(Arc::new(
 				self.texture_set_pool
 					.next()
-					.add_sampled_image(image.image.clone(), sampler.clone())?
+					.add_sampled_image(image.image.clone(), samplers.sprite.clone())?
 					.build()?,
 			));
 
@@ -172,6 +182,7 @@ impl DrawStep for DrawPlayerSprites {
 					(),
 				)
 				.context("Draw error")?;
+			draw_context.draw_calls += 1;
 		}
 
 		Ok(())
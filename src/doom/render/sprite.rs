@@ -1,21 +1,33 @@
 use crate::{
 	common::{
 		assets::{AssetHandle, AssetStorage},
-		geometry::Angle,
+		configvars::ConfigVariables,
+		frame::FrameInterpolation,
+		geometry::{frustum_planes, Angle},
+		profiler,
 		video::{
-			definition::NumberedInstanceBufferDefinition, DrawContext, DrawStep, RenderContext,
+			definition::NumberedInstanceBufferDefinition, ring_buffer_pool, DrawContext, DrawStep,
+			RenderContext,
 		},
 	},
 	doom::{
-		client::Client, components::Transform, image::Image, map::MapDynamic,
-		render::world::normal_frag, sprite::SpriteRender,
+		client::{Client, ClientView},
+		components::Transform,
+		image::Image,
+		map::MapDynamic,
+		render::{
+			world::{normal_frag, projection_matrix, view_matrix},
+			Samplers,
+		},
+		sprite::SpriteRender,
 	},
 };
 use anyhow::Context;
 use fnv::FnvHashMap;
-use legion::{systems::ResourceSet, Entity, EntityStore, IntoQuery, Read, Resources, World};
+use legion::{systems::ResourceSet, Entity, IntoQuery, Read, Resources, World};
 use nalgebra::{Matrix4, Vector2};
-use std::{collections::hash_map::Entry, sync::Arc};
+use rayon::prelude::*;
+use std::sync::Arc;
 use vulkano::{
 	buffer::{BufferUsage, CpuBufferPool},
 	descriptor::{descriptor_set::FixedSizeDescriptorSetsPool, PipelineLayoutAbstract},
@@ -23,10 +35,10 @@ use vulkano::{
 	framebuffer::{RenderPassAbstract, Subpass},
 	impl_vertex,
 	pipeline::{GraphicsPipeline, GraphicsPipelineAbstract},
-	sampler::Sampler,
 };
 
 pub struct DrawSprites {
+	batches: FnvHashMap<AssetHandle<Image>, Vec<InstanceData>>,
 	instance_buffer_pool: CpuBufferPool<InstanceData>,
 	pipeline: Arc<dyn GraphicsPipelineAbstract + Send + Sync>,
 	texture_set_pool: FixedSizeDescriptorSetsPool,
@@ -61,7 +73,8 @@ impl DrawSprites {
 		) as Arc<dyn GraphicsPipelineAbstract + Send + Sync>;
 
 		Ok(DrawSprites {
-			instance_buffer_pool: CpuBufferPool::new(device.clone(), BufferUsage::vertex_buffer()),
+			batches: FnvHashMap::default(),
+			instance_buffer_pool: ring_buffer_pool(device, BufferUsage::vertex_buffer(), 16384)?,
 			texture_set_pool: FixedSizeDescriptorSetsPool::new(
 				pipeline.descriptor_set_layout(1).unwrap().clone(),
 			),
@@ -78,79 +91,163 @@ impl DrawStep for DrawSprites {
 		world: &World,
 		resources: &Resources,
 	) -> anyhow::Result<()> {
-		let (asset_storage, client, sampler) =
-			<(Read<AssetStorage>, Read<Client>, Read<Arc<Sampler>>)>::fetch(resources);
-		let camera_entry = world.entry_ref(client.entity.unwrap()).unwrap();
-		let camera_transform = camera_entry.get_component::<Transform>().unwrap();
+		let _profile = profiler::scope("draw_sprites");
+
+		let (asset_storage, client, config_variables, frame_interpolation, samplers) =
+			<(
+				Read<AssetStorage>,
+				Read<Client>,
+				Read<ConfigVariables>,
+				Read<FrameInterpolation>,
+				Read<Samplers>,
+			)>::fetch(resources);
+		let camera_transform = client
+			.interpolated_view_transform(world, frame_interpolation.fraction)
+			.unwrap_or_default();
 
-		let map_dynamic = <&MapDynamic>::query().iter(world).next().unwrap();
+		// The viewport was already set up for the 3D view by DrawWorld
+		let viewport_dimensions =
+			draw_context.dynamic_state.viewports.as_ref().unwrap()[0].dimensions;
+		let frustum = frustum_planes(
+			&(projection_matrix(viewport_dimensions, draw_context.pixel_aspect)
+				* view_matrix(&camera_transform)),
+		);
+		let max_distance = config_variables.get::<f32>("r_spritedistance");
+
+		let map_dynamic = match <&MapDynamic>::query().iter(world).next() {
+			Some(x) => x,
+			None => return Ok(()),
+		};
 		let map = asset_storage.get(&map_dynamic.map).unwrap();
 
 		// Group draws into batches by texture
-		let mut batches: FnvHashMap<&AssetHandle<Image>, Vec<InstanceData>> = FnvHashMap::default();
-
-		for (entity, sprite_render, transform) in
-			<(Entity, &SpriteRender, &Transform)>::query().iter(world)
-		{
-			// Don't draw the player's own sprite
-			if let Some(view_entity) = client.entity {
-				if *entity == view_entity {
-					continue;
-				}
-			}
+		// The batches are kept between frames to reuse their memory,
+		// but those that weren't used in the previous frame are thrown away
+		self.batches.retain(|_, batch| !batch.is_empty());
 
-			let sprite = asset_storage.get(&sprite_render.sprite).unwrap();
-			let frame = &sprite.frames()[sprite_render.frame];
+		for batch in self.batches.values_mut() {
+			batch.clear();
+		}
 
-			// This frame has no images, nothing to draw
-			if frame.is_empty() {
-				continue;
-			}
+		// Work out the instance data on all threads, each collecting its own part
+		let sprites: Vec<(&Entity, &SpriteRender, &Transform)> =
+			<(Entity, &SpriteRender, &Transform)>::query()
+				.iter(world)
+				.collect();
+		let asset_storage: &AssetStorage = &asset_storage;
+		let skip_entity = match (client.entity, client.view) {
+			// Don't draw the player's own sprite, unless watching from elsewhere
+			(Some(view_entity), ClientView::Player) => Some(view_entity),
+			_ => None,
+		};
 
-			// Figure out which rotation image to use
-			// Treat non-rotating frames specially for efficiency
-			let index = if frame.len() == 1 {
-				0
-			} else {
-				let to_view_vec = camera_transform.position - transform.position;
-				let to_view_angle =
-					Angle::from_radians(f64::atan2(to_view_vec[1] as f64, to_view_vec[0] as f64));
-				let delta = to_view_angle - transform.rotation[2]
-					+ Angle::from_units(0.5 / frame.len() as f64);
-				(delta.to_units_unsigned() * frame.len() as f64) as usize % frame.len()
-			};
-
-			let image_info = &frame[index];
-
-			// Determine light level
-			let light_level = if sprite_render.full_bright {
-				1.0
-			} else {
-				let ssect =
-					map.find_subsector(Vector2::new(transform.position[0], transform.position[1]));
-				map_dynamic.sectors[ssect.sector_index].light_level
-			};
-
-			// Set up instance data
-			let instance_data = InstanceData {
-				in_transform: Matrix4::new_translation(&transform.position).into(),
-				in_flip: image_info.flip,
-				in_light_level: light_level,
-			};
-
-			// Add to batches
-			match batches.entry(&image_info.handle) {
-				Entry::Occupied(mut entry) => {
-					entry.get_mut().push(instance_data);
-				}
-				Entry::Vacant(entry) => {
-					entry.insert(vec![instance_data]);
+		let parts: Vec<SpriteInstances> = sprites
+			.par_iter()
+			.fold(
+				SpriteInstances::default,
+				|mut part, (entity, sprite_render, transform)| {
+					if Some(**entity) == skip_entity {
+						return part;
+					}
+
+					let sprite = asset_storage.get(&sprite_render.sprite).unwrap();
+
+					// This frame has no images, nothing to draw
+					let frame = match sprite.frames().get(sprite_render.frame) {
+						Some(frame) if !frame.is_empty() => frame,
+						_ => return part,
+					};
+
+					// Figure out which rotation image to use
+					// Treat non-rotating frames specially for efficiency
+					let index = if frame.len() == 1 {
+						0
+					} else {
+						let to_view_vec = camera_transform.position - transform.position;
+						let to_view_angle = Angle::from_radians(f64::atan2(
+							to_view_vec[1] as f64,
+							to_view_vec[0] as f64,
+						));
+						let delta = to_view_angle - transform.rotation[2]
+							+ Angle::from_units(0.5 / frame.len() as f64);
+						(delta.to_units_unsigned() * frame.len() as f64) as usize % frame.len()
+					};
+
+					let image_info = &frame[index];
+
+					// Cull sprites that are too far away or outside the view frustum,
+					// using a sphere that encloses the image regardless of orientation
+					if max_distance > 0.0
+						&& (transform.position - camera_transform.position).norm() > max_distance
+					{
+						part.culled += 1;
+						return part;
+					}
+
+					let image = asset_storage.get(&image_info.handle).unwrap();
+					let radius = image.offset.norm() + image.size().norm();
+
+					if frustum.iter().any(|plane| {
+						transform.position.dot(&plane.normal) - plane.distance < -radius
+					}) {
+						part.culled += 1;
+						return part;
+					}
+
+					let ssect = map
+						.find_subsector(Vector2::new(transform.position[0], transform.position[1]));
+					let sector_dynamic = &map_dynamic.sectors[ssect.sector_index];
+
+					// Determine light level
+					let light_level = if sprite_render.full_bright {
+						1.0
+					} else {
+						sector_dynamic.light_level
+					};
+
+					// Set up instance data
+					part.instances.push((
+						image_info.handle.clone(),
+						InstanceData {
+							in_transform: Matrix4::new_translation(&transform.position).into(),
+							in_flip: image_info.flip,
+							in_light_level: light_level,
+							in_floor_height: sector_dynamic.interval.min,
+						},
+					));
+
+					part
+				},
+			)
+			.collect();
+
+		// Merge the parts into batches, in the order they were found
+		let mut drawn = 0;
+		let mut culled = 0;
+
+		for part in parts {
+			drawn += part.instances.len() as u64;
+			culled += part.culled;
+
+			for (handle, instance_data) in part.instances {
+				match self.batches.get_mut(&handle) {
+					Some(batch) => batch.push(instance_data),
+					None => {
+						self.batches.insert(handle, vec![instance_data]);
+					}
 				}
 			}
 		}
 
+		profiler::count("sprites_drawn", drawn);
+		profiler::count("sprites_culled", culled);
+
 		// Draw the batches
-		for (image_handle, instance_data) in batches {
+		for (image_handle, instance_data) in self.batches.iter() {
+			if instance_data.is_empty() {
+				continue;
+			}
+
 			let image = asset_storage.get(image_handle).unwrap();
 			let matrix = Matrix4::new_translation(&-image.offset.fixed_resize(0.0))
 				* Matrix4::new_nonuniform_scaling(&image.size().fixed_resize(1.0));
@@ -159,14 +256,16 @@ impl DrawStep for DrawSprites {
 			draw_context.descriptor_sets.push(Arc::new(
 				self.texture_set_pool
 					.next()
-					.add_sampled_image(image.image.clone(), sampler.clone())?
+					.add_sampled_image(image.image.clone(), samplers.sprite.clone())?
 					.add_buffer(self.texture_uniform_pool.next(ImageMatrix {
 						image_matrix: matrix.into(),
 					})?)?
 					.build()?,
 			));
 
-			let instance_buffer = self.instance_buffer_pool.chunk(instance_data)?;
+			let instance_buffer = self
+				.instance_buffer_pool
+				.chunk(instance_data.iter().cloned())?;
 
 			draw_context
 				.commands
@@ -178,12 +277,20 @@ impl DrawStep for DrawSprites {
 					(),
 				)
 				.context("Draw error")?;
+			draw_context.draw_calls += 1;
 		}
 
 		Ok(())
 	}
 }
 
+/// Sprite instances that were prepared on one thread, before they're sorted into batches.
+#[derive(Default)]
+struct SpriteInstances {
+	instances: Vec<(AssetHandle<Image>, InstanceData)>,
+	culled: u64,
+}
+
 mod sprite_vert {
 	vulkano_shaders::shader! {
 		ty: "vertex",
@@ -205,5 +312,13 @@ pub struct InstanceData {
 	pub in_transform: [[f32; 4]; 4],
 	pub in_flip: f32,
 	pub in_light_level: f32,
+	/// Parts of the sprite below this height are not drawn.
+	pub in_floor_height: f32,
 }
-impl_vertex!(InstanceData, in_transform, in_flip, in_light_level);
+impl_vertex!(
+	InstanceData,
+	in_transform,
+	in_flip,
+	in_light_level,
+	in_floor_height
+);
@@ -2,12 +2,15 @@ use crate::{
 	common::{
 		assets::{AssetHandle, AssetStorage},
 		geometry::{ortho_matrix, Interval, AABB3},
+		profiler,
 		video::{
-			definition::NumberedInstanceBufferDefinition, DrawContext, DrawStep, RenderContext,
+			definition::NumberedInstanceBufferDefinition, ring_buffer_pool, DrawContext, DrawStep,
+			RenderContext,
 		},
 	},
 	doom::{
 		image::Image,
+		render::Samplers,
 		ui::{UiAlignment, UiImage, UiTransform},
 	},
 };
@@ -21,7 +24,6 @@ use vulkano::{
 	framebuffer::{FramebufferAbstract, RenderPassAbstract, Subpass},
 	impl_vertex,
 	pipeline::{GraphicsPipeline, GraphicsPipelineAbstract},
-	sampler::Sampler,
 };
 
 pub struct DrawUi {
@@ -61,7 +63,7 @@ impl DrawUi {
 		let matrix_set_pool = FixedSizeDescriptorSetsPool::new(layout.clone());
 
 		Ok(DrawUi {
-			instance_buffer_pool: CpuBufferPool::new(device.clone(), BufferUsage::vertex_buffer()),
+			instance_buffer_pool: ring_buffer_pool(device, BufferUsage::vertex_buffer(), 4096)?,
 			matrix_uniform_pool: CpuBufferPool::new(
 				render_context.device().clone(),
 				BufferUsage::uniform_buffer(),
@@ -82,7 +84,9 @@ impl DrawStep for DrawUi {
 		world: &World,
 		resources: &Resources,
 	) -> anyhow::Result<()> {
-		let ui_params = UiParams::new(&draw_context.framebuffer);
+		let _profile = profiler::scope("draw_ui");
+
+		let ui_params = UiParams::new(draw_context);
 		let viewport = &mut draw_context.dynamic_state.viewports.as_mut().unwrap()[0];
 		viewport.origin = [0.0, 0.0];
 		viewport.dimensions = ui_params.framebuffer_dimensions.into();
@@ -114,7 +118,7 @@ impl DrawStep for DrawUi {
 
 		// Group draws into batches by texture, preserving depth order
 		let mut batches: Vec<(AssetHandle<Image>, Vec<InstanceData>)> = Vec::new();
-		let (asset_storage, sampler) = <(Read<AssetStorage>, Read<Arc<Sampler>>)>::fetch(resources);
+		let (asset_storage, samplers) = <(Read<AssetStorage>, Read<Samplers>)>::fetch(resources);
 
 		for (ui_image, ui_transform) in entities
 			.into_iter()
@@ -145,7 +149,7 @@ impl DrawStep for DrawUi {
 			draw_context.descriptor_sets.push(Arc::new(
 				self.texture_set_pool
 					.next()
-					.add_sampled_image(image.image.clone(), sampler.clone())?
+					.add_sampled_image(image.image.clone(), samplers.ui.clone())?
 					.build()?,
 			));
 
@@ -161,6 +165,7 @@ impl DrawStep for DrawUi {
 					(),
 				)
 				.context("Draw error")?;
+			draw_context.draw_calls += 1;
 		}
 
 		Ok(())
@@ -199,10 +204,13 @@ pub struct UiParams {
 }
 
 impl UiParams {
-	pub fn new<T: FramebufferAbstract + Send + Sync>(framebuffer: &T) -> UiParams {
+	pub fn new(draw_context: &DrawContext) -> UiParams {
+		let framebuffer = &draw_context.framebuffer;
 		let framebuffer_dimensions =
 			Vector2::new(framebuffer.width() as f32, framebuffer.height() as f32);
-		let ratio = (framebuffer_dimensions[0] / framebuffer_dimensions[1]) / (4.0 / 3.0);
+		let ratio = (framebuffer_dimensions[0]
+			/ (framebuffer_dimensions[1] * draw_context.pixel_aspect))
+			/ (4.0 / 3.0);
 
 		// If the current aspect ratio is wider than 4:3, stretch horizontally.
 		// If narrower, stretch vertically.
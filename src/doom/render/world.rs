@@ -1,17 +1,18 @@
 use crate::{
 	common::{
+		frame::FrameInterpolation,
 		geometry::{perspective_matrix, Interval},
+		profiler,
 		video::{DrawContext, DrawStep, RenderContext},
 	},
 	doom::{
-		camera::Camera,
 		client::Client,
 		components::Transform,
 		render::{map::Matrices, ui::UiParams},
 	},
 };
 use anyhow::Context;
-use legion::{systems::ResourceSet, EntityStore, Read, Resources, World};
+use legion::{systems::ResourceSet, Read, Resources, World};
 use nalgebra::{Matrix4, Vector3};
 use std::sync::Arc;
 use vulkano::{
@@ -69,7 +70,9 @@ impl DrawStep for DrawWorld {
 		world: &World,
 		resources: &Resources,
 	) -> anyhow::Result<()> {
-		let ui_params = UiParams::new(&draw_context.framebuffer);
+		let _profile = profiler::scope("draw_world");
+
+		let ui_params = UiParams::new(draw_context);
 
 		let viewport = &mut draw_context.dynamic_state.viewports.as_mut().unwrap()[0];
 		viewport.origin = [0.0, 0.0];
@@ -79,35 +82,22 @@ impl DrawStep for DrawWorld {
 			(1.0 - 32.0 / ui_params.dimensions[1]) * ui_params.framebuffer_dimensions[1],
 		];
 
-		// Projection matrix
-		// Doom had non-square pixels, with a resolution of 320x200 (16:10) running on a 4:3
-		// screen. This caused everything to be stretched vertically by some degree, and the game
-		// art was made with that in mind.
-		// The 1.2 factor here applies the same stretching as in the original.
-		let aspect_ratio = (viewport.dimensions[0] / viewport.dimensions[1]) * 1.2;
-		let proj = perspective_matrix(90.0, aspect_ratio, Interval::new(1.0, 20000.0));
+		let proj = projection_matrix(viewport.dimensions, draw_context.pixel_aspect);
 
 		// View matrix
-		let client = <Read<Client>>::fetch(resources);
-		let camera_entry = world.entry_ref(client.entity.unwrap()).unwrap();
-
-		let Transform {
-			mut position,
-			rotation,
-		} = *camera_entry.get_component::<Transform>().unwrap();
-
-		if let Ok(camera) = camera_entry.get_component::<Camera>() {
-			position += camera.base + camera.offset;
-		}
-
-		let view = Matrix4::new_rotation(Vector3::new(-rotation[0].to_radians() as f32, 0.0, 0.0))
-			* Matrix4::new_rotation(Vector3::new(0.0, -rotation[1].to_radians() as f32, 0.0))
-			* Matrix4::new_rotation(Vector3::new(0.0, 0.0, -rotation[2].to_radians() as f32))
-			* Matrix4::new_translation(&-position);
+		let (client, frame_interpolation) =
+			<(Read<Client>, Read<FrameInterpolation>)>::fetch(resources);
+		let transform = client
+			.interpolated_view_transform(world, frame_interpolation.fraction)
+			.unwrap_or_default();
+		let view = view_matrix(&transform);
 
 		// Billboard matrix
-		let billboard =
-			Matrix4::new_rotation(Vector3::new(0.0, 0.0, rotation[2].to_radians() as f32));
+		let billboard = Matrix4::new_rotation(Vector3::new(
+			0.0,
+			0.0,
+			transform.rotation[2].to_radians() as f32,
+		));
 
 		// Create matrix UBO
 		draw_context.descriptor_sets.truncate(0);
@@ -126,6 +116,27 @@ impl DrawStep for DrawWorld {
 	}
 }
 
+/// The projection matrix for the 3D view, given the dimensions of its viewport
+/// and the shape of its pixels on the screen.
+pub fn projection_matrix(viewport_dimensions: [f32; 2], pixel_aspect: f32) -> Matrix4<f32> {
+	// Doom had non-square pixels, with a resolution of 320x200 (16:10) running on a 4:3
+	// screen. This caused everything to be stretched vertically by some degree, and the game
+	// art was made with that in mind.
+	// The 1.2 factor here applies the same stretching as in the original.
+	let aspect_ratio = (viewport_dimensions[0] / (viewport_dimensions[1] * pixel_aspect)) * 1.2;
+	perspective_matrix(90.0, aspect_ratio, Interval::new(1.0, 20000.0))
+}
+
+/// The view matrix for a camera at the given transform.
+pub fn view_matrix(transform: &Transform) -> Matrix4<f32> {
+	let Transform { position, rotation } = transform;
+
+	Matrix4::new_rotation(Vector3::new(-rotation[0].to_radians() as f32, 0.0, 0.0))
+		* Matrix4::new_rotation(Vector3::new(0.0, -rotation[1].to_radians() as f32, 0.0))
+		* Matrix4::new_rotation(Vector3::new(0.0, 0.0, -rotation[2].to_radians() as f32))
+		* Matrix4::new_translation(&-position)
+}
+
 pub mod normal_frag {
 	vulkano_shaders::shader! {
 		ty: "fragment",
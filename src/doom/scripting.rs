@@ -0,0 +1,283 @@
+use crate::{
+	common::{
+		assets::{AssetName, AssetStorage},
+		events::{register_reader, Events},
+	},
+	doom::{
+		components::Transform,
+		entitytemplate::EntityTemplateRef,
+		health::DeathEvent,
+		linespecial::execute_line_special,
+		map::spawn::{SpawnEvent, SpawnRequest},
+		message::HudMessage,
+	},
+};
+use anyhow::{anyhow, Context};
+use legion::{systems::ResourceSet, IntoQuery, Read, Resources, World, Write};
+use nalgebra::Vector3;
+use rhai::{
+	Array, Dynamic, Engine, EvalAltResult, FuncArgs, ImmutableString, Scope, AST, FLOAT, INT,
+};
+use std::{
+	path::PathBuf,
+	sync::{Arc, Mutex},
+};
+
+/// Gameplay scripts written in Rhai, loaded from files given on the command line.
+///
+/// Scripts can define any of these functions, which are called when something happens:
+/// * `on_map_load(name)`: after a map has been loaded and its things spawned.
+/// * `on_spawn(template, x, y, z)`: after an entity has been spawned from a template.
+/// * `on_death(template, x, y, z)`: after an entity's health has run out.
+/// * `on_tick()`: once every game frame.
+/// * `on_command(name, args)`: for console commands that the game doesn't know.
+///   It should return `true` if it handled the command.
+///
+/// They can call these functions to affect the game, which take effect once the script returns:
+/// * `message(text)`: shows a message on the HUD.
+/// * `spawn(template, x, y, z)`: spawns an entity from a template, with floating point coordinates.
+/// * `line_special(special, args)`: runs a Hexen line special with up to five arguments.
+/// * `open_door(tag, speed)` and `close_door(tag, speed)`
+/// * `raise_floor(tag, speed, distance)` and `lower_floor(tag, speed, distance)`
+///
+/// Speeds are in eighths of a map unit per frame, like the line specials.
+pub struct Scripting {
+	engine: Engine,
+	ast: AST,
+	scope: Scope<'static>,
+	actions: Arc<Mutex<Vec<ScriptAction>>>,
+}
+
+/// Changes to the game made by scripts.
+/// They are queued up while a script runs, because scripts can't access the game directly.
+#[derive(Clone, Debug)]
+enum ScriptAction {
+	LineSpecial {
+		special: u8,
+		args: [i32; 5],
+	},
+	Message(String),
+	Spawn {
+		template_name: String,
+		position: Vector3<f32>,
+	},
+}
+
+impl Scripting {
+	/// Compiles the scripts in `paths`, and runs their top-level statements.
+	pub fn new(paths: &[PathBuf], resources: &mut Resources) -> anyhow::Result<Scripting> {
+		let actions = Arc::new(Mutex::new(Vec::new()));
+		let mut engine = Engine::new();
+		engine.on_print(|text| log::info!("{}", text));
+		register_functions(&mut engine, &actions);
+
+		let mut ast = AST::default();
+
+		for path in paths {
+			let file_ast = engine
+				.compile_file(path.clone())
+				.map_err(|err| anyhow!("{}", err))
+				.with_context(|| format!("Couldn't compile script {}", path.display()))?;
+			ast = ast.merge(&file_ast);
+		}
+
+		let mut scope = Scope::new();
+		engine
+			.consume_ast_with_scope(&mut scope, &ast)
+			.map_err(|err| anyhow!("{}", err))
+			.context("Couldn't run scripts")?;
+
+		if !paths.is_empty() {
+			log::info!("Loaded {} scripts", paths.len());
+		}
+
+		register_reader::<SpawnEvent>(resources, "scripting");
+		register_reader::<DeathEvent>(resources, "scripting");
+
+		Ok(Scripting {
+			engine,
+			ast,
+			scope,
+			actions,
+		})
+	}
+
+	/// Calls a hook function of the scripts, if they define it.
+	fn call_hook(&mut self, name: &str, args: impl FuncArgs) -> Option<Dynamic> {
+		match self
+			.engine
+			.call_fn::<_, Dynamic>(&mut self.scope, &self.ast, name, args)
+		{
+			Ok(result) => Some(result),
+			Err(err) => {
+				match &*err {
+					// Hooks are optional
+					EvalAltResult::ErrorFunctionNotFound(signature, _)
+						if signature.starts_with(name) => {}
+					_ => log::error!("Script error in {}: {}", name, err),
+				}
+
+				None
+			}
+		}
+	}
+}
+
+fn register_functions(engine: &mut Engine, actions: &Arc<Mutex<Vec<ScriptAction>>>) {
+	let queue = actions.clone();
+	engine.register_fn("message", move |text: ImmutableString| {
+		queue
+			.lock()
+			.unwrap()
+			.push(ScriptAction::Message(text.to_string()));
+	});
+
+	let queue = actions.clone();
+	engine.register_fn(
+		"spawn",
+		move |template_name: ImmutableString, x: FLOAT, y: FLOAT, z: FLOAT| {
+			queue.lock().unwrap().push(ScriptAction::Spawn {
+				template_name: template_name.to_string(),
+				position: Vector3::new(x as f32, y as f32, z as f32),
+			});
+		},
+	);
+
+	let queue = actions.clone();
+	engine.register_fn("line_special", move |special: INT, args: Array| {
+		let mut special_args = [0; 5];
+
+		for (special_arg, arg) in special_args.iter_mut().zip(args) {
+			*special_arg = arg.as_int().unwrap_or(0) as i32;
+		}
+
+		queue.lock().unwrap().push(ScriptAction::LineSpecial {
+			special: special as u8,
+			args: special_args,
+		});
+	});
+
+	// Door_Open, Door_Close
+	for &(name, special) in &[("open_door", 11), ("close_door", 10)] {
+		let queue = actions.clone();
+		engine.register_fn(name, move |tag: INT, speed: INT| {
+			queue.lock().unwrap().push(ScriptAction::LineSpecial {
+				special,
+				args: [tag as i32, speed as i32, 0, 0, 0],
+			});
+		});
+	}
+
+	// Floor_RaiseByValue, Floor_LowerByValue
+	for &(name, special) in &[("raise_floor", 23), ("lower_floor", 20)] {
+		let queue = actions.clone();
+		engine.register_fn(name, move |tag: INT, speed: INT, distance: INT| {
+			queue.lock().unwrap().push(ScriptAction::LineSpecial {
+				special,
+				args: [tag as i32, speed as i32, distance as i32, 0, 0],
+			});
+		});
+	}
+}
+
+/// Calls the hooks for entities that were spawned or died, then `on_tick`.
+/// This runs once every game frame, after all the systems.
+pub fn tick(world: &mut World, resources: &mut Resources) {
+	{
+		let (asset_storage, mut spawn_event_channel, mut death_event_channel, mut scripting) =
+			<(
+				Read<AssetStorage>,
+				Write<Events<SpawnEvent>>,
+				Write<Events<DeathEvent>>,
+				Write<Scripting>,
+			)>::fetch_mut(resources);
+		let scripting = &mut *scripting;
+
+		// Scripts only get to know the template and position of the entity
+		let world: &World = world;
+		let mut query = <(&EntityTemplateRef, &Transform)>::query();
+		let mut entity_args = |entity| {
+			let (template_ref, transform) = query.get(world, entity).ok()?;
+			let name = asset_storage.name_of(&template_ref.0)?.to_owned();
+			let position = transform.position;
+			Some((
+				name,
+				position[0] as FLOAT,
+				position[1] as FLOAT,
+				position[2] as FLOAT,
+			))
+		};
+
+		let spawned: Vec<_> = spawn_event_channel
+			.read("scripting")
+			.filter_map(|event| entity_args(event.entity))
+			.collect();
+		let died: Vec<_> = death_event_channel
+			.read("scripting")
+			.filter_map(|event| entity_args(event.entity))
+			.collect();
+
+		for args in spawned {
+			scripting.call_hook("on_spawn", args);
+		}
+
+		for args in died {
+			scripting.call_hook("on_death", args);
+		}
+
+		scripting.call_hook("on_tick", ());
+	}
+
+	apply_actions(world, resources);
+}
+
+/// Calls `on_map_load` for a newly loaded map.
+pub fn map_loaded(name: &str, world: &mut World, resources: &mut Resources) {
+	<Write<Scripting>>::fetch_mut(resources).call_hook("on_map_load", (name.to_owned(),));
+	apply_actions(world, resources);
+}
+
+/// Offers a console command to the scripts, returning whether they handled it.
+pub fn command(args: &[String], world: &mut World, resources: &mut Resources) -> bool {
+	let script_args: Array = args[1..].iter().cloned().map(Dynamic::from).collect();
+	let handled = <Write<Scripting>>::fetch_mut(resources)
+		.call_hook("on_command", (args[0].clone(), script_args))
+		.and_then(|result| result.as_bool().ok())
+		.unwrap_or(false);
+
+	apply_actions(world, resources);
+	handled
+}
+
+fn apply_actions(world: &mut World, resources: &mut Resources) {
+	let actions = {
+		let scripting = <Read<Scripting>>::fetch(resources);
+		let mut actions = scripting.actions.lock().unwrap();
+		std::mem::take(&mut *actions)
+	};
+
+	for action in actions {
+		match action {
+			ScriptAction::LineSpecial { special, args } => {
+				execute_line_special(special, args, None, world, resources);
+			}
+			ScriptAction::Message(text) => {
+				<Write<HudMessage>>::fetch_mut(resources).show(&text);
+			}
+			ScriptAction::Spawn {
+				template_name,
+				position,
+			} => {
+				<Write<Vec<SpawnRequest>>>::fetch_mut(resources).push(SpawnRequest {
+					template_name: AssetName::new(&template_name),
+					transform: Transform {
+						position,
+						..Transform::default()
+					},
+					velocity: Vector3::zeros(),
+					dropped: false,
+				});
+			}
+		}
+	}
+}
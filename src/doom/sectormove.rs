@@ -2,7 +2,9 @@ use crate::{
 	common::{
 		assets::{AssetHandle, AssetStorage},
 		audio::Sound,
+		events::{add_events, Events},
 		frame::FrameState,
+		profiler,
 		quadtree::Quadtree,
 		time::Timer,
 	},
@@ -12,8 +14,11 @@ use crate::{
 		physics::{BoxCollider, SectorTracer},
 	},
 };
-use legion::{systems::Runnable, world::SubWorld, Entity, IntoQuery, Resources, SystemBuilder};
-use shrev::EventChannel;
+use legion::{
+	systems::{ParallelRunnable, Runnable},
+	world::SubWorld,
+	Entity, IntoQuery, Resources, SystemBuilder,
+};
 
 #[derive(Clone, Debug)]
 pub struct FloorMove(pub SectorMove);
@@ -42,14 +47,30 @@ pub enum SectorMoveEventType {
 	TargetReached,
 }
 
+/// Remembers the sector heights at the start of each game frame, so that their movement
+/// can be drawn smoothly.
+pub fn sector_interpolation_system() -> impl ParallelRunnable {
+	SystemBuilder::new("sector_interpolation_system")
+		.with_query(<&mut MapDynamic>::query())
+		.build(move |_, world, _, query| {
+			let _profile = profiler::scope("sector_interpolation_system");
+
+			for map_dynamic in query.iter_mut(world) {
+				for sector_dynamic in map_dynamic.sectors.iter_mut() {
+					sector_dynamic.previous_interval = sector_dynamic.interval;
+				}
+			}
+		})
+}
+
 pub fn sector_move_system(resources: &mut Resources) -> impl Runnable {
-	resources.insert(EventChannel::<SectorMoveEvent>::new());
+	add_events::<SectorMoveEvent>(resources);
 
 	SystemBuilder::new("sector_move_system")
 		.read_resource::<AssetStorage>()
 		.read_resource::<FrameState>()
 		.read_resource::<Quadtree>()
-		.write_resource::<EventChannel<SectorMoveEvent>>()
+		.write_resource::<Events<SectorMoveEvent>>()
 		.write_resource::<Vec<(AssetHandle<Sound>, Entity)>>()
 		.with_query(<&mut MapDynamic>::query())
 		.with_query(<&mut Transform>::query())
@@ -58,6 +79,8 @@ pub fn sector_move_system(resources: &mut Resources) -> impl Runnable {
 		.read_component::<BoxCollider>() // used by SectorTracer
 		.read_component::<Transform>() // used by SectorTracer
 		.build(move |_, world, resources, queries| {
+			let _profile = profiler::scope("sector_move_system");
+
 			let (asset_storage, frame_state, quadtree, sector_move_event_channel, sound_queue) =
 				resources;
 
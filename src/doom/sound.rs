@@ -1,22 +1,37 @@
 use crate::{
 	common::{
 		assets::{AssetHandle, AssetStorage, ImportData},
-		audio::{SoundController, SoundSource},
+		audio::{Audio, AudioDeviceControl, LowPass, Reverb, SoundController, SoundSource},
+		configvars::ConfigVariables,
 		geometry::Angle,
+		profiler,
 	},
-	doom::{client::Client, components::Transform},
+	doom::{
+		caption::{CaptionDirection, Captions},
+		client::{Client, ClientView},
+		components::{Transform, Velocity},
+		data::captions::caption_for,
+		language::StringTable,
+		map::{sight::check_closed_opening, Map, MapDynamic},
+		profile::{is_heretic_data, GameProfile},
+	},
+	heretic,
 };
-use anyhow::ensure;
+use anyhow::{bail, ensure, Context};
 use byteorder::{ReadBytesExt, LE};
-use crossbeam_channel::Sender;
+use fnv::FnvHashMap;
 use legion::{
 	systems::{CommandBuffer, ResourceSet},
 	Entity, IntoQuery, Read, Resources, World, Write,
 };
-use nalgebra::Vector2;
+use nalgebra::{Vector2, Vector3};
 use relative_path::RelativePath;
 use rodio::Source;
-use std::io::{Cursor, Read as IoRead};
+use serde::Deserialize;
+use std::{
+	io::{Cursor, Read as IoRead},
+	time::Duration,
+};
 
 pub use crate::common::audio::Sound;
 
@@ -24,7 +39,28 @@ pub fn import_sound(
 	path: &RelativePath,
 	asset_storage: &mut AssetStorage,
 ) -> anyhow::Result<Box<dyn ImportData>> {
-	let mut reader = Cursor::new(asset_storage.source().load(path)?);
+	// Mods can point sound names at different lumps
+	let path = {
+		let handle = asset_storage.load::<SoundInfo>("sndinfo");
+		let sound_info = asset_storage.get(&handle).unwrap();
+		let name = path.with_extension("").as_str().to_ascii_lowercase();
+		let path = RelativePath::new(sound_info.resolve(&name)).with_extension("sound");
+
+		// Heretic's sound lumps don't have the DS prefix that the engine asks for
+		if asset_storage.source().exists(&path) || !path.as_str().starts_with("ds") {
+			path
+		} else {
+			RelativePath::new(&path.as_str()[2..]).to_owned()
+		}
+	};
+
+	let data = asset_storage.source().load(&path)?;
+	Ok(Box::new(parse_sound(&data)?))
+}
+
+/// Reads a sound in the Doom format: 8-bit unsigned samples after a short header.
+pub fn parse_sound(lump: &[u8]) -> anyhow::Result<Sound> {
+	let mut reader = Cursor::new(lump);
 	let signature = reader.read_u16::<LE>()?;
 
 	ensure!(signature == 3, "No Doom sound file signature found");
@@ -32,6 +68,18 @@ pub fn import_sound(
 	let sample_rate = reader.read_u16::<LE>()? as u32;
 	let sample_count = reader.read_u32::<LE>()? as usize;
 
+	ensure!(
+		sample_count >= 32,
+		"Sound has only {} samples",
+		sample_count
+	);
+	ensure!(
+		sample_count <= lump.len() - 8,
+		"Sound has {} samples, but the lump only holds {}",
+		sample_count,
+		lump.len() - 8
+	);
+
 	// Read in the samples
 	let mut data = vec![0u8; sample_count - 32];
 	let mut padding = [0u8; 16];
@@ -45,39 +93,326 @@ pub fn import_sound(
 		.map(|x| ((x ^ 0x80) as i16) << 8)
 		.collect::<Vec<i16>>();
 
-	Ok(Box::new(Sound {
+	Ok(Sound {
 		sample_rate,
 		data: data.into(),
-	}))
+	})
+}
+
+/// How many aliases can refer to each other in a chain, before giving up.
+const MAX_ALIAS_DEPTH: usize = 8;
+
+/// Sound names mapped to the lumps they play, as defined in the SNDINFO lump.
+#[derive(Clone, Debug, Default)]
+pub struct SoundInfo {
+	aliases: FnvHashMap<String, String>,
+	/// Sound names that play one of several sounds, picked at random.
+	random: FnvHashMap<String, Vec<String>>,
+	/// Looping sounds played by ambient sound things, by their number.
+	ambient: FnvHashMap<u16, AmbientSoundDef>,
+}
+
+/// An ambient sound as defined with `$ambient` in SNDINFO.
+#[derive(Clone, Debug)]
+pub struct AmbientSoundDef {
+	pub sound: String,
+	pub volume: f32,
+	/// Whether the sound comes from the thing's position, or is heard everywhere equally.
+	pub positional: bool,
+}
+
+impl SoundInfo {
+	/// Returns the lump that the sound name refers to.
+	/// Names without an alias are assumed to be lump names already.
+	pub fn resolve<'a>(&'a self, mut name: &'a str) -> &'a str {
+		for _ in 0..MAX_ALIAS_DEPTH {
+			match self.aliases.get(name) {
+				Some(target) => name = target,
+				None => break,
+			}
+		}
+
+		name
+	}
+
+	/// Returns the sounds to pick from, if the name refers to a random group.
+	pub fn random_group(&self, name: &str) -> Option<&[String]> {
+		self.random.get(name).map(Vec::as_slice)
+	}
+
+	pub fn ambient(&self, index: u16) -> Option<&AmbientSoundDef> {
+		self.ambient.get(&index)
+	}
+}
+
+/// Loads the sound with the given name. If SNDINFO defines it as a random group,
+/// every sound in the group is loaded, to pick from whenever it is played.
+pub fn load_sound_group(asset_storage: &mut AssetStorage, name: &str) -> Vec<AssetHandle<Sound>> {
+	let handle = asset_storage.load::<SoundInfo>("sndinfo");
+	let name = name.to_ascii_lowercase();
+	let names = match asset_storage.get(&handle).unwrap().random_group(&name) {
+		Some(group) => group.to_vec(),
+		None => vec![name],
+	};
+
+	names
+		.iter()
+		.map(|name| asset_storage.load(&format!("{}.sound", name)))
+		.collect()
+}
+
+pub fn import_sndinfo(
+	path: &RelativePath,
+	asset_storage: &mut AssetStorage,
+) -> anyhow::Result<Box<dyn ImportData>> {
+	// SNDINFO is optional, without one every sound plays its own lump
+	if !asset_storage.source().exists(path) {
+		// Heretic has its ambient sounds built in
+		if is_heretic_data(asset_storage.source()) {
+			return Ok(Box::new(parse_sndinfo(heretic::data::SNDINFO)));
+		}
+
+		return Ok(Box::new(SoundInfo::default()));
+	}
+
+	let data = asset_storage.source().load(path)?;
+	Ok(Box::new(parse_sndinfo(&String::from_utf8_lossy(&data))))
+}
+
+pub fn parse_sndinfo(text: &str) -> SoundInfo {
+	let mut sound_info = SoundInfo::default();
+
+	// A $random group that is still open, its sounds can be spread over several lines
+	let mut random_group: Option<(String, Vec<String>)> = None;
+
+	for (line_number, line) in text.lines().enumerate() {
+		// Strip comments
+		let line = match line.find("//") {
+			Some(index) => &line[..index],
+			None => line,
+		};
+		let line = match line.find(';') {
+			Some(index) => &line[..index],
+			None => line,
+		};
+
+		let line = line.replace('{', " { ").replace('}', " } ");
+		let mut words: Vec<String> = line
+			.split_whitespace()
+			.map(|word| word.trim_matches('"').to_ascii_lowercase())
+			.collect();
+
+		if words.len() >= 3 && words[0] == "$random" && words[2] == "{" {
+			random_group = Some((words[1].clone(), Vec::new()));
+			words.drain(..3);
+		}
+
+		if let Some((name, mut group)) = random_group.take() {
+			match words.iter().position(|word| word == "}") {
+				Some(index) => {
+					group.extend(words.drain(..index));
+					words.remove(0);
+
+					if group.is_empty() {
+						log::warn!("SNDINFO: random group {} is empty", name);
+					} else {
+						sound_info.random.insert(name, group);
+					}
+				}
+				None => {
+					group.append(&mut words);
+					random_group = Some((name, group));
+				}
+			}
+		}
+
+		match words.as_slice() {
+			[] => {}
+			[command, name, target] if command == "$alias" => {
+				sound_info.aliases.insert(name.clone(), target.clone());
+			}
+			[command, index, name, rest @ ..] if command == "$ambient" => {
+				match parse_ambient(index, name, rest) {
+					Ok((index, def)) => {
+						sound_info.ambient.insert(index, def);
+					}
+					Err(e) => log::warn!("SNDINFO line {}: {}", line_number + 1, e),
+				}
+			}
+			[command, ..] if command.starts_with('$') => {
+				log::warn!(
+					"SNDINFO line {}: unsupported command {}",
+					line_number + 1,
+					command
+				);
+			}
+			[name, lump] => {
+				sound_info.aliases.insert(name.clone(), lump.clone());
+			}
+			_ => log::warn!(
+				"SNDINFO line {}: expected a sound name and a lump name",
+				line_number + 1
+			),
+		}
+	}
+
+	sound_info
+}
+
+/// Parses the arguments of an `$ambient` command.
+/// Only continuous sounds are supported, not periodic or random ones.
+fn parse_ambient(
+	index: &str,
+	name: &str,
+	rest: &[String],
+) -> anyhow::Result<(u16, AmbientSoundDef)> {
+	let index = index
+		.parse::<u16>()
+		.ok()
+		.filter(|index| (1..=64).contains(index))
+		.with_context(|| format!("invalid ambient sound number {}", index))?;
+
+	let mut def = AmbientSoundDef {
+		sound: name.to_owned(),
+		volume: 1.0,
+		positional: true,
+	};
+	let mut continuous = false;
+
+	for word in rest {
+		match word.as_str() {
+			"point" => def.positional = true,
+			"surround" | "world" => def.positional = false,
+			"continuous" => continuous = true,
+			"periodic" | "random" => bail!("{} ambient sounds are not supported", word),
+			// The last number is the volume, any before it are the attenuation
+			_ => {
+				def.volume = word
+					.parse::<f32>()
+					.with_context(|| format!("invalid ambient sound parameter {}", word))?
+			}
+		}
+	}
+
+	ensure!(continuous, "ambient sound {} has no type", index);
+	Ok((index, def))
+}
+
+/// A silent sound, to stand in for sounds that couldn't be loaded.
+pub fn placeholder_sound() -> Box<dyn ImportData> {
+	Box::new(Sound {
+		sample_rate: 11025,
+		data: Vec::new().into(),
+	})
 }
 
 pub fn sound_system() -> Box<dyn FnMut(&mut World, &mut Resources)> {
+	let mut ambient_playing: FnvHashMap<Entity, (SoundController, AmbientSoundDef)> =
+		FnvHashMap::default();
+
 	Box::new(move |world, resources| {
-		let (asset_storage, client, sound_sender, mut sound_queue) = <(
-			Read<AssetStorage>,
+		let _profile = profiler::scope("sound_system");
+
+		let (
+			mut asset_storage,
+			audio,
+			mut audio_device_control,
+			mut captions,
+			client,
+			config_variables,
+			game_profile,
+			string_table,
+			mut sound_queue,
+		) = <(
+			Write<AssetStorage>,
+			Read<Audio>,
+			Write<AudioDeviceControl>,
+			Write<Captions>,
 			Read<Client>,
-			Read<Sender<Box<dyn Source<Item = f32> + Send>>>,
+			Read<ConfigVariables>,
+			Read<GameProfile>,
+			Read<StringTable>,
 			Write<Vec<(AssetHandle<Sound>, Entity)>>,
 		)>::fetch_mut(resources);
 
+		audio_device_control.set_device(&config_variables.get::<String>("snd_device"));
+
 		let mut command_buffer = CommandBuffer::new(world);
 
 		{
-			let client_transform = *<&Transform>::query()
-				.get(world, client.entity.unwrap())
-				.unwrap();
+			let client_transform = client.view_transform(world).unwrap_or_default();
+			let client_velocity = if config_variables.get::<bool>("snd_doppler") {
+				Some(listener_velocity(world, &client))
+			} else {
+				None
+			};
+
+			// Sounds echo according to the sector the listener is in
+			let reverb = if config_variables.get::<bool>("snd_reverb") && !sound_queue.is_empty() {
+				listener_reverb(world, &asset_storage, &client_transform)
+			} else {
+				None
+			};
+			let show_captions = config_variables.get::<bool>("snd_captions");
+
+			// Sounds are muffled underwater and behind closed doors
+			let cutoffs = if config_variables.get::<bool>("snd_lowpass") {
+				lowpass_cutoffs(world, &asset_storage, &client_transform, &sound_queue)
+			} else {
+				vec![None; sound_queue.len()]
+			};
 
 			// Play new sounds
-			for (handle, entity) in sound_queue.drain(..) {
+			for ((handle, entity), cutoff) in sound_queue.drain(..).zip(cutoffs) {
 				let sound = asset_storage.get(&handle).unwrap();
-				let (controller, source) = SoundController::new(SoundSource::new(&sound));
-				let (transform, sound_playing) = <(&Transform, Option<&mut SoundPlaying>)>::query()
-					.get_mut(world, entity)
-					.unwrap();
+				let (transform, velocity, sound_playing) =
+					<(&Transform, Option<&Velocity>, Option<&mut SoundPlaying>)>::query()
+						.get_mut(world, entity)
+						.unwrap();
+
+				if show_captions {
+					let caption = asset_storage
+						.name_of(&handle)
+						.and_then(|name| caption_for(*game_profile, name))
+						.and_then(|caption| string_table.resolve(caption));
+
+					if let Some(caption) = caption {
+						let position = if Some(entity) == client.entity {
+							None
+						} else {
+							Some((
+								CaptionDirection::new(&client_transform, transform),
+								(transform.position - client_transform.position).norm(),
+							))
+						};
+						captions.push(caption, position);
+					}
+				}
 
 				// Set distance falloff and stereo panning
-				let volumes = calculate_volumes(&client_transform, transform);
-				controller.set_volumes(volumes.into());
+				let volumes = calculate_volumes(&client_transform, transform).into();
+				let pitch = match client_velocity {
+					Some(client_velocity) => calculate_doppler(
+						&client_transform,
+						client_velocity,
+						transform,
+						velocity.map_or(Vector3::zeros(), |v| v.velocity),
+					),
+					None => 1.0,
+				};
+
+				let mut source: Box<dyn Source<Item = f32> + Send> =
+					Box::new(SoundSource::new(&sound).convert_samples());
+
+				if let Some(cutoff) = cutoff {
+					source = Box::new(LowPass::new(source, cutoff));
+				}
+
+				if let Some(preset) = reverb {
+					source = Box::new(preset.apply(source));
+				}
+
+				let controller = audio.play(source, volumes, pitch);
 
 				// Stop old sound on this entity, if any
 				if let Some(mut sound_playing) = sound_playing {
@@ -86,22 +421,91 @@ pub fn sound_system() -> Box<dyn FnMut(&mut World, &mut Resources)> {
 				} else {
 					command_buffer.add_component(entity, SoundPlaying { controller });
 				}
-
-				sound_sender.send(Box::from(source.convert_samples())).ok();
 			}
 
 			// Update currently playing sounds
-			for (entity, transform, sound_playing) in
-				<(Entity, &Transform, &mut SoundPlaying)>::query().iter_mut(world)
+			for (entity, transform, velocity, sound_playing) in
+				<(Entity, &Transform, Option<&Velocity>, &mut SoundPlaying)>::query()
+					.iter_mut(world)
 			{
 				if sound_playing.controller.is_done() {
 					command_buffer.remove_component::<SoundPlaying>(*entity);
 					continue;
 				}
 
-				// Set distance falloff and stereo panning
+				// Follow the entity as it moves
 				let volumes = calculate_volumes(&client_transform, transform);
 				sound_playing.controller.set_volumes(volumes.into());
+
+				sound_playing.controller.set_pitch(match client_velocity {
+					Some(client_velocity) => calculate_doppler(
+						&client_transform,
+						client_velocity,
+						transform,
+						velocity.map_or(Vector3::zeros(), |v| v.velocity),
+					),
+					None => 1.0,
+				});
+			}
+
+			// Stop ambient sounds whose entity is gone
+			ambient_playing.retain(|entity, (controller, _)| {
+				if <&AmbientSound>::query().get(world, *entity).is_ok() {
+					true
+				} else {
+					controller.stop();
+					false
+				}
+			});
+
+			// Start new ambient sounds
+			let new_ambient: Vec<(Entity, u16)> = <(Entity, &AmbientSound)>::query()
+				.iter(world)
+				.filter(|(entity, _)| !ambient_playing.contains_key(entity))
+				.map(|(entity, ambient_sound)| (*entity, ambient_sound.index))
+				.collect();
+
+			for (entity, index) in new_ambient {
+				let sound_info_handle = asset_storage.load::<SoundInfo>("sndinfo");
+				let def = asset_storage
+					.get(&sound_info_handle)
+					.unwrap()
+					.ambient(index)
+					.cloned();
+				let def = match def {
+					Some(def) => def,
+					None => {
+						log::warn!("Ambient sound {} is not defined in SNDINFO", index);
+						command_buffer.remove_component::<AmbientSound>(entity);
+						continue;
+					}
+				};
+
+				let handle = match load_sound_group(&mut asset_storage, &def.sound)
+					.into_iter()
+					.next()
+				{
+					Some(handle) => handle,
+					None => continue,
+				};
+				let sound = asset_storage.get(&handle).unwrap();
+				let source = SoundSource::new(&sound).repeat_infinite().convert_samples();
+				let controller = audio.play(source, [0.0; 2], 1.0);
+				ambient_playing.insert(entity, (controller, def));
+			}
+
+			// Update ambient sounds
+			for (entity, (controller, def)) in ambient_playing.iter() {
+				let volumes = if def.positional {
+					match <&Transform>::query().get(world, *entity) {
+						Ok(transform) => calculate_volumes(&client_transform, transform),
+						Err(_) => continue,
+					}
+				} else {
+					Vector2::new(1.0, 1.0)
+				};
+
+				controller.set_volumes((volumes * def.volume).into());
 			}
 		}
 
@@ -109,6 +513,162 @@ pub fn sound_system() -> Box<dyn FnMut(&mut World, &mut Resources)> {
 	})
 }
 
+/// Returns the reverb preset of the sector that the listener is in.
+fn listener_reverb(
+	world: &World,
+	asset_storage: &AssetStorage,
+	client_transform: &Transform,
+) -> Option<ReverbPreset> {
+	let map_dynamic = <&MapDynamic>::query().iter(world).next()?;
+	let map: &Map = asset_storage.get(&map_dynamic.map)?;
+	let ssect = map.find_subsector(client_transform.position.fixed_resize(0.0));
+	let sector_entity = map_dynamic.sectors[ssect.sector_index].entity;
+
+	<&SectorReverb>::query()
+		.get(world, sector_entity)
+		.ok()
+		.map(|sector_reverb| sector_reverb.preset)
+}
+
+/// Echo settings for different kinds of spaces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum ReverbPreset {
+	Room,
+	Hall,
+	Cave,
+	Underwater,
+}
+
+impl ReverbPreset {
+	/// Returns the delay in milliseconds, feedback and mix.
+	fn parameters(self) -> (u64, f32, f32) {
+		match self {
+			ReverbPreset::Room => (40, 0.3, 0.3),
+			ReverbPreset::Hall => (90, 0.5, 0.4),
+			ReverbPreset::Cave => (160, 0.6, 0.5),
+			ReverbPreset::Underwater => (25, 0.7, 0.8),
+		}
+	}
+
+	pub fn apply<S: Source<Item = f32>>(self, source: S) -> Reverb<S> {
+		let (delay, feedback, mix) = self.parameters();
+		Reverb::new(source, Duration::from_millis(delay), feedback, mix)
+	}
+}
+
+/// Makes sounds echo while the listener is inside the sector.
+#[derive(Clone, Copy, Debug)]
+pub struct SectorReverb {
+	pub preset: ReverbPreset,
+}
+
+/// Low-pass cutoff for sounds heard underwater, in Hz.
+const UNDERWATER_CUTOFF: f32 = 500.0;
+
+/// Low-pass cutoff for sounds coming from behind a closed door, in Hz.
+const DOOR_CUTOFF: f32 = 1000.0;
+
+/// Returns the low-pass cutoff frequency for each of the sounds in `sound_queue`,
+/// or `None` if the sound should be heard clearly.
+fn lowpass_cutoffs(
+	world: &World,
+	asset_storage: &AssetStorage,
+	client_transform: &Transform,
+	sound_queue: &[(AssetHandle<Sound>, Entity)],
+) -> Vec<Option<f32>> {
+	let (map, map_dynamic) = match <&MapDynamic>::query()
+		.iter(world)
+		.next()
+		.and_then(|map_dynamic| Some((asset_storage.get(&map_dynamic.map)?, map_dynamic)))
+	{
+		Some(x) => x,
+		None => return vec![None; sound_queue.len()],
+	};
+
+	if listener_underwater(map, map_dynamic, client_transform) {
+		return vec![Some(UNDERWATER_CUTOFF); sound_queue.len()];
+	}
+
+	let listener_position = client_transform.position.fixed_resize(0.0);
+
+	sound_queue
+		.iter()
+		.map(|(_, entity)| {
+			let position = <&Transform>::query()
+				.get(world, *entity)
+				.ok()?
+				.position
+				.fixed_resize(0.0);
+			let sector_index = map.find_subsector(position).sector_index;
+
+			if check_closed_opening(map, map_dynamic, listener_position, position, sector_index) {
+				Some(DOOR_CUTOFF)
+			} else {
+				None
+			}
+		})
+		.collect()
+}
+
+/// Returns whether the listener is below a deep water surface,
+/// made with a linedef of type 242 tagged to the listener's sector.
+/// The floor of the linedef's front sector is the height of the water surface.
+fn listener_underwater(map: &Map, map_dynamic: &MapDynamic, client_transform: &Transform) -> bool {
+	let ssect = map.find_subsector(client_transform.position.fixed_resize(0.0));
+	let sector_tag = map.sectors[ssect.sector_index].sector_tag;
+
+	if sector_tag == 0 {
+		return false;
+	}
+
+	map.linedefs
+		.iter()
+		.filter(|linedef| linedef.special_type == Some(242) && linedef.sector_tag == sector_tag)
+		.filter_map(|linedef| linedef.sidedefs[0].as_ref())
+		.any(|sidedef| {
+			client_transform.position[2] < map_dynamic.sectors[sidedef.sector_index].interval.min
+		})
+}
+
+/// Returns how fast the listener is moving.
+fn listener_velocity(world: &World, client: &Client) -> Vector3<f32> {
+	let entity = match client.view {
+		ClientView::Player => client.entity,
+		ClientView::Chase(entity) => Some(entity),
+		ClientView::Free(_) => None,
+	};
+
+	entity
+		.and_then(|entity| <&Velocity>::query().get(world, entity).ok())
+		.map_or(Vector3::zeros(), |velocity| velocity.velocity)
+}
+
+/// Speed of sound in map units per second, taking 32 units as a metre.
+const SPEED_OF_SOUND: f32 = 343.0 * 32.0;
+
+/// Returns the change in pitch for a sound, caused by the sound source and the listener
+/// moving towards or away from each other.
+fn calculate_doppler(
+	client_transform: &Transform,
+	client_velocity: Vector3<f32>,
+	entity_transform: &Transform,
+	entity_velocity: Vector3<f32>,
+) -> f32 {
+	let to_entity_vec = entity_transform.position - client_transform.position;
+	let distance = to_entity_vec.norm();
+
+	if distance < 1.0 {
+		return 1.0;
+	}
+
+	let direction = to_entity_vec / distance;
+	let client_approach = client_velocity.dot(&direction);
+	let entity_approach = -entity_velocity.dot(&direction);
+	let pitch = (SPEED_OF_SOUND + client_approach) / (SPEED_OF_SOUND - entity_approach);
+
+	pitch.max(0.5).min(2.0)
+}
+
 fn calculate_volumes(client_transform: &Transform, entity_transform: &Transform) -> Vector2<f32> {
 	let to_entity_vec = entity_transform.position - client_transform.position;
 
@@ -140,6 +700,12 @@ fn calculate_volumes(client_transform: &Transform, entity_transform: &Transform)
 	volumes * distance_factor
 }
 
+/// Plays a looping sound defined with `$ambient` in SNDINFO, for as long as the entity exists.
+#[derive(Clone, Copy, Debug)]
+pub struct AmbientSound {
+	pub index: u16,
+}
+
 #[derive(Clone, Debug)]
 pub struct SoundPlaying {
 	pub controller: SoundController,
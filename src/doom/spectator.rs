@@ -0,0 +1,106 @@
+use crate::{
+	common::{frame::FrameState, geometry::angles_to_axes, profiler},
+	doom::{
+		client::{Client, ClientView},
+		components::{Transform, Velocity},
+		data::FRAME_RATE,
+		physics::BoxCollider,
+	},
+};
+use legion::{
+	systems::{ResourceSet, Runnable},
+	Entity, IntoQuery, Resources, SystemBuilder, World, Write,
+};
+
+const SPECTATOR_SPEED: f32 = 16.0 * FRAME_RATE;
+
+pub fn spectator_system() -> impl Runnable {
+	SystemBuilder::new("spectator_system")
+		.read_resource::<FrameState>()
+		.write_resource::<Client>()
+		.with_query(<&Transform>::query())
+		.build(move |_, world, resources, query| {
+			let _profile = profiler::scope("spectator_system");
+
+			let (frame_state, client) = resources;
+			let command = client.command;
+
+			match &mut client.view {
+				ClientView::Player => {}
+				ClientView::Free(transform) => {
+					transform.rotation[1] += (command.pitch * 1e6) as i32;
+					transform.rotation[1].0 =
+						num_traits::clamp(transform.rotation[1].0, -0x4000_0000, 0x4000_0000);
+					transform.rotation[2] -= (command.yaw * 1e6) as i32;
+
+					// Fly in the direction the camera is facing
+					let axes = angles_to_axes(transform.rotation);
+					let move_dir = axes[0] * command.forward.max(-1.0).min(1.0)
+						+ axes[1] * command.strafe.max(-1.0).min(1.0);
+					transform.position +=
+						move_dir * SPECTATOR_SPEED * frame_state.delta_time.as_secs_f32();
+				}
+				ClientView::Chase(entity) => {
+					// Stop following entities that no longer exist
+					if query.get(world, *entity).is_err() {
+						log::info!("Spectated entity is gone, returning to player view");
+						client.view = ClientView::Player;
+					}
+				}
+			}
+		})
+}
+
+/// Handles the `spectate` console command.
+pub fn spectate(mode: Option<&str>, world: &World, resources: &mut Resources) {
+	let mut client = <Write<Client>>::fetch_mut(resources);
+
+	let mode = mode.unwrap_or(if client.is_spectating() {
+		"off"
+	} else {
+		"free"
+	});
+
+	client.view = match mode {
+		"off" => ClientView::Player,
+		"free" => match client.view_transform(world) {
+			Some(transform) => ClientView::Free(transform),
+			None => {
+				log::error!("There is nothing to spectate");
+				return;
+			}
+		},
+		"chase" => match client.entity {
+			Some(entity) => ClientView::Chase(entity),
+			None => {
+				log::error!("There is no player to follow");
+				return;
+			}
+		},
+		"next" => {
+			// Cycle through everything that moves around by itself
+			let entities: Vec<Entity> = <(Entity, &Transform, &Velocity, &BoxCollider)>::query()
+				.iter(world)
+				.map(|(entity, _, _, _)| *entity)
+				.collect();
+
+			let current = match client.view {
+				ClientView::Chase(entity) => entities.iter().position(|e| *e == entity),
+				_ => None,
+			};
+			let next = current.map_or(0, |i| i + 1);
+
+			match entities.get(next).or_else(|| entities.first()) {
+				Some(entity) => ClientView::Chase(*entity),
+				None => {
+					log::error!("There is nothing to follow");
+					return;
+				}
+			}
+		}
+		_ => {
+			log::error!("Usage: spectate [off|free|chase|next]");
+			return;
+		}
+	};
+}
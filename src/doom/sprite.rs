@@ -2,7 +2,7 @@ use crate::{
 	common::assets::{AssetHandle, AssetStorage, ImportData},
 	doom::image::Image,
 };
-use anyhow::{bail, Context};
+use anyhow::{bail, ensure, Context};
 use lazy_static::lazy_static;
 use regex::Regex;
 use relative_path::RelativePath;
@@ -84,14 +84,42 @@ impl SpriteBuilder {
 	}
 }
 
+lazy_static! {
+	static ref SPRITENAME: Regex = Regex::new(r#"^....[a-z][0-9](?:[a-z][0-9])?$"#).unwrap();
+}
+
+/// Returns whether there are any lumps for the given frame of a sprite, without importing it.
+/// This is used to check sprites that were loaded with `load_deferred`.
+pub fn sprite_has_frame(asset_storage: &AssetStorage, path: &RelativePath, frame: usize) -> bool {
+	let stem = match path.file_stem() {
+		Some(stem) => stem,
+		None => return false,
+	};
+
+	asset_storage
+		.source()
+		.names()
+		.filter(|n| n.starts_with(stem) && SPRITENAME.is_match(n))
+		.any(|lump_name| {
+			let bytes = lump_name.as_bytes();
+			let frames = if bytes.len() == 8 {
+				&[bytes[4], bytes[6]][..]
+			} else {
+				&[bytes[4]][..]
+			};
+			frames.iter().any(|&f| (f - b'a') as usize == frame)
+		})
+}
+
+/// A sprite without any frames, to stand in for sprites that couldn't be loaded.
+pub fn placeholder_sprite() -> Box<dyn ImportData> {
+	Box::new(Sprite { frames: Vec::new() })
+}
+
 pub fn import_sprite(
 	path: &RelativePath,
 	asset_storage: &mut AssetStorage,
 ) -> anyhow::Result<Box<dyn ImportData>> {
-	lazy_static! {
-		static ref SPRITENAME: Regex = Regex::new(r#"^....[a-z][0-9](?:[a-z][0-9])?$"#).unwrap();
-	}
-
 	let stem = path.file_stem().context("Empty file name")?;
 
 	let mut image_names = Vec::new();
@@ -139,6 +167,8 @@ pub fn import_sprite(
 		image_names.push(format!("{}.patch", lump_name));
 	}
 
+	ensure!(!info.is_empty(), "No sprite lumps found");
+
 	info.sort_unstable_by(|a, b| Ord::cmp(&a.0, &b.0).then(Ord::cmp(&a.1, &b.1)));
 	let mut slice = info.as_slice();
 	let mut frames: Vec<Vec<SpriteImageInfoIntermediate>> = vec![Vec::new(); max_frame + 1];
@@ -153,18 +183,19 @@ pub fn import_sprite(
 		slice = &slice[next_pos..];
 
 		if current.len() == 1 {
-			let rotation = current[0].1;
-			assert_eq!(rotation, -1);
+			ensure!(
+				current[0].1 == -1,
+				"Frame {} has only one rotation image",
+				frame
+			);
 			frames[frame] = current.iter().map(|r| r.2).collect();
 		} else if current.len() == 8 {
-			frames[frame] = current
-				.iter()
-				.enumerate()
-				.map(|(i, r)| {
-					assert_eq!(i as isize, r.1);
-					r.2
-				})
-				.collect();
+			ensure!(
+				current.iter().enumerate().all(|(i, r)| i as isize == r.1),
+				"Frame {} has missing or duplicate rotation images",
+				frame
+			);
+			frames[frame] = current.iter().map(|r| r.2).collect();
 		} else {
 			bail!("Frame {} has an invalid number of rotations", frame);
 		}
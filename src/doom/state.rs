@@ -1,18 +1,30 @@
 use crate::{
 	common::{
-		assets::AssetStorage,
+		assets::{AssetHandle, AssetName, AssetStorage},
 		frame::FrameState,
+		profiler,
 		spawn::{ComponentAccessor, SpawnFrom},
 		time::Timer,
 	},
-	doom::{entitytemplate::EntityTemplateRef, map::spawn::SpawnContext, sprite::SpriteRender},
+	doom::{
+		components::Transform,
+		data::FRAME_TIME,
+		entitytemplate::EntityTemplateRef,
+		game::FastMonsters,
+		map::spawn::{SpawnContext, SpawnRequest},
+		physics::{BoxCollider, SolidMask},
+		sound::Sound,
+		sprite::SpriteRender,
+	},
 };
 use arrayvec::ArrayString;
 use legion::{
-	systems::{ResourceSet, Runnable},
+	systems::{ParallelRunnable, ResourceSet},
 	Entity, IntoQuery, Read, Resources, SystemBuilder,
 };
-use std::time::Duration;
+use nalgebra::Vector3;
+use rand::seq::SliceRandom;
+use std::{collections::HashMap, time::Duration};
 
 pub type StateName = ArrayString<[u8; 16]>;
 
@@ -20,6 +32,43 @@ pub type StateName = ArrayString<[u8; 16]>;
 pub struct StateInfo {
 	pub sprite: SpriteRender,
 	pub next: Option<(Duration, Option<(StateName, usize)>)>,
+	/// Run when the state system advances into this state.
+	pub action: Option<StateAction>,
+}
+
+/// Something an entity does when it enters a state.
+#[derive(Clone, Debug)]
+pub enum StateAction {
+	/// Stops the entity from blocking others, once it has become a corpse.
+	Fall,
+	/// Plays one of the sounds of the entity's template, by name.
+	Sound(String),
+	/// Plays a specific sound, or one of a group of sounds at random.
+	PlaySound(Vec<AssetHandle<Sound>>),
+	/// Fires a projectile in the direction the entity is facing.
+	SpawnProjectile {
+		/// Name of the template of the projectile.
+		template: AssetName,
+		/// Speed of the projectile, in map units per frame.
+		speed: f32,
+	},
+}
+
+/// Height above the entity's feet that projectiles are fired from.
+const PROJECTILE_HEIGHT: f32 = 32.0;
+
+/// When monsters are fast, projectiles are fired at least this fast, in map units per frame.
+/// This matches the speed that imp, cacodemon and baron fireballs get in the original game.
+const FAST_PROJECTILE_SPEED: f32 = 20.0;
+
+/// Returns how long to stay in the state named `state_name`, which normally lasts `duration`.
+/// Fast monsters chase and attack in half the time.
+pub fn wait_time(state_name: StateName, duration: Duration, fast_monsters: bool) -> Duration {
+	if fast_monsters && (state_name.as_str() == "see" || state_name.as_str() == "missile") {
+		duration / 2
+	} else {
+		duration
+	}
 }
 
 #[derive(Clone, Debug)]
@@ -28,6 +77,28 @@ pub struct State {
 	pub timer: Option<Timer>,
 }
 
+impl State {
+	/// Switches to a new state right away, instead of waiting for the current state to end.
+	pub fn set(
+		&mut self,
+		new: (StateName, usize),
+		states: &HashMap<StateName, Vec<StateInfo>>,
+		sprite_render: &mut SpriteRender,
+		time: Duration,
+		fast_monsters: bool,
+	) {
+		let new_state = states
+			.get(&new.0)
+			.and_then(|x| x.get(new.1))
+			.expect("Invalid state name");
+		self.current = new;
+		*sprite_render = new_state.sprite.clone();
+		self.timer = new_state
+			.next
+			.map(|(next_time, _)| Timer::new(time, wait_time(new.0, next_time, fast_monsters)));
+	}
+}
+
 #[derive(Clone, Copy, Debug, Default)]
 pub struct StateDef;
 
@@ -57,16 +128,31 @@ impl SpawnFrom<StateDef> for State {
 	}
 }
 
-pub fn state_system(_resources: &mut Resources) -> impl Runnable {
+pub fn state_system(_resources: &mut Resources) -> impl ParallelRunnable {
 	SystemBuilder::new("state_system")
 		.read_resource::<AssetStorage>()
+		.read_resource::<FastMonsters>()
 		.read_resource::<FrameState>()
-		.with_query(<(Entity, &EntityTemplateRef, &mut SpriteRender, &mut State)>::query())
+		.write_resource::<Vec<(AssetHandle<Sound>, Entity)>>()
+		.write_resource::<Vec<SpawnRequest>>()
+		.with_query(<(
+			Entity,
+			&EntityTemplateRef,
+			&mut SpriteRender,
+			&mut State,
+			Option<&mut BoxCollider>,
+			Option<&Transform>,
+		)>::query())
 		.build(move |_command_buffer, world, resources, query| {
-			let (asset_storage, frame_state) = resources;
+			let _profile = profiler::scope("state_system");
 
-			for (_entity, template_ref, sprite_render, state) in query.iter_mut(world) {
-				let states = &asset_storage.get(&template_ref.0).unwrap().states;
+			let (asset_storage, fast_monsters, frame_state, sound_queue, spawn_queue) = resources;
+
+			for (entity, template_ref, sprite_render, state, mut box_collider, transform) in
+				query.iter_mut(world)
+			{
+				let template = asset_storage.get(&template_ref.0).unwrap();
+				let states = &template.states;
 				let State { current, timer } = state;
 
 				while timer.map_or(false, |t| t.is_elapsed(frame_state.time)) {
@@ -84,10 +170,62 @@ pub fn state_system(_resources: &mut Resources) -> impl Runnable {
 					*sprite_render = new_state.sprite.clone();
 
 					if let Some((time, _)) = new_state.next {
-						timer.as_mut().unwrap().restart_with(time);
+						timer.as_mut().unwrap().restart_with(wait_time(
+							new.0,
+							time,
+							fast_monsters.0,
+						));
 					} else {
 						*timer = None;
 					}
+
+					match &new_state.action {
+						Some(StateAction::Fall) => {
+							if let Some(box_collider) = box_collider.as_mut() {
+								box_collider.solid_mask = SolidMask::empty();
+							}
+						}
+						Some(StateAction::Sound(name)) => {
+							if let Some(sounds) = template.sounds.get(name) {
+								let mut rng = frame_state.rng.lock().unwrap();
+
+								if let Some(sound) = sounds.choose(&mut *rng) {
+									sound_queue.push((sound.clone(), *entity));
+								}
+							}
+						}
+						Some(StateAction::PlaySound(sounds)) => {
+							let mut rng = frame_state.rng.lock().unwrap();
+
+							if let Some(sound) = sounds.choose(&mut *rng) {
+								sound_queue.push((sound.clone(), *entity));
+							}
+						}
+						Some(StateAction::SpawnProjectile { template, speed }) => {
+							if let Some(transform) = transform {
+								let yaw = transform.rotation[2];
+								let direction =
+									Vector3::new(yaw.cos() as f32, yaw.sin() as f32, 0.0);
+								let speed = if fast_monsters.0 {
+									speed.max(FAST_PROJECTILE_SPEED)
+								} else {
+									*speed
+								};
+
+								spawn_queue.push(SpawnRequest {
+									template_name: *template,
+									transform: Transform {
+										position: transform.position
+											+ Vector3::new(0.0, 0.0, PROJECTILE_HEIGHT),
+										rotation: transform.rotation,
+									},
+									velocity: direction * speed / FRAME_TIME.as_secs_f32(),
+									dropped: false,
+								});
+							}
+						}
+						None => {}
+					}
 				}
 			}
 		})
@@ -0,0 +1,320 @@
+use crate::{
+	common::{
+		assets::AssetStorage, configvars::ConfigVariables, gamestate::GameStateMachine, profiler,
+	},
+	doom::{
+		client::User,
+		components::Transform,
+		data::{partimes::PAR_TIMES, FRAME_RATE},
+		health::Dead,
+		language::StringTable,
+		map::MapDynamic,
+		mapinfo::map_info,
+		profile::GameProfile,
+		ui::{HudFont, UiAlignment, UiTransform},
+	},
+};
+use legion::{
+	component,
+	systems::{ResourceSet, Runnable},
+	Entity, EntityStore, IntoQuery, Read, Resources, SystemBuilder, World, Write,
+};
+use nalgebra::Vector2;
+use std::time::{Duration, Instant};
+
+/// Marks a monster that counts towards the kill total when it dies.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CountKill;
+
+/// Marks an item that counts towards the item total when it is picked up.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CountItem;
+
+/// Marks a sector that counts as a secret the first time a player enters it.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SecretSector;
+
+/// How many of the level's monsters, items and secrets the players have found so far.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LevelStats {
+	pub kills: u32,
+	pub total_kills: u32,
+	pub items: u32,
+	pub total_items: u32,
+	pub secrets: u32,
+	pub total_secrets: u32,
+	/// Time spent in the level so far, in tics.
+	pub time: u32,
+}
+
+impl LevelStats {
+	/// Counts the totals for a freshly spawned level, with nothing found yet.
+	pub fn new(world: &World) -> LevelStats {
+		LevelStats {
+			total_kills: <&CountKill>::query().iter(world).count() as u32,
+			total_items: <&CountItem>::query().iter(world).count() as u32,
+			total_secrets: <&SecretSector>::query().iter(world).count() as u32,
+			..LevelStats::default()
+		}
+	}
+
+	/// Returns the percentage of `count` out of `total`, as shown on the intermission screen.
+	/// A level with nothing to find counts as fully completed.
+	pub fn percentage(count: u32, total: u32) -> u32 {
+		if total == 0 {
+			100
+		} else {
+			count * 100 / total
+		}
+	}
+}
+
+/// Returns the par time of a map in tics, if the map information gives one
+/// or it's one of the original levels.
+/// Heretic has no par times of its own.
+pub fn par_time(map: &str, asset_storage: &AssetStorage, game_profile: GameProfile) -> Option<u32> {
+	map_info(asset_storage, map)
+		.and_then(|entry| entry.par_time)
+		.or_else(|| {
+			if game_profile == GameProfile::Heretic {
+				None
+			} else {
+				PAR_TIMES.get(&map.to_ascii_lowercase()).copied()
+			}
+		})
+		.map(|seconds| seconds * FRAME_RATE as u32)
+}
+
+/// Formats a number of tics as minutes and seconds, like the intermission screen does.
+pub fn format_time(tics: u32) -> String {
+	let seconds = tics / FRAME_RATE as u32;
+
+	if seconds >= 3600 {
+		format!(
+			"{}:{:02}:{:02}",
+			seconds / 3600,
+			seconds / 60 % 60,
+			seconds % 60
+		)
+	} else {
+		format!("{}:{:02}", seconds / 60, seconds % 60)
+	}
+}
+
+/// Advances the level timer by one tic.
+pub fn level_time_system() -> impl Runnable {
+	SystemBuilder::new("level_time_system")
+		.write_resource::<LevelStats>()
+		.build(move |_, _, resources, _| {
+			let _profile = profiler::scope("level_time_system");
+
+			let level_stats = resources;
+			level_stats.time += 1;
+		})
+}
+
+/// Counts a secret when a living player stands on the floor of a secret sector.
+pub fn secret_system() -> impl Runnable {
+	SystemBuilder::new("secret_system")
+		.read_resource::<AssetStorage>()
+		.read_resource::<StringTable>()
+		.write_resource::<LevelStats>()
+		.with_query(<&MapDynamic>::query())
+		.with_query(<&Transform>::query().filter(component::<User>() & !component::<Dead>()))
+		.read_component::<SecretSector>()
+		.build(move |command_buffer, world, resources, queries| {
+			let _profile = profiler::scope("secret_system");
+
+			let (asset_storage, string_table, level_stats) = resources;
+
+			let map_dynamic = match queries.0.iter(world).next() {
+				Some(map_dynamic) => map_dynamic,
+				None => return,
+			};
+			let map = asset_storage.get(&map_dynamic.map).unwrap();
+
+			for transform in queries.1.iter(world) {
+				let ssect =
+					map.find_subsector(Vector2::new(transform.position[0], transform.position[1]));
+				let sector_dynamic = &map_dynamic.sectors[ssect.sector_index];
+
+				// Only counts once the player has landed
+				if transform.position[2] > sector_dynamic.interval.min {
+					continue;
+				}
+
+				let is_secret = world
+					.entry_ref(sector_dynamic.entity)
+					.map_or(false, |entry| entry.get_component::<SecretSector>().is_ok());
+
+				if is_secret {
+					command_buffer.remove_component::<SecretSector>(sector_dynamic.entity);
+					level_stats.secrets += 1;
+					log::info!("{}", string_table.format("SECRETMESSAGE", &[]));
+				}
+			}
+		})
+}
+
+/// Shows the level statistics in the corner of the screen when `hud_stats` is enabled,
+/// and the level time when `hud_timer` is enabled.
+pub fn hud_stats_system(resources: &mut Resources) -> Box<dyn FnMut(&mut World, &mut Resources)> {
+	let font = {
+		let (mut asset_storage, game_profile) =
+			<(Write<AssetStorage>, Read<GameProfile>)>::fetch_mut(resources);
+		HudFont::load(&mut asset_storage, *game_profile)
+	};
+	let mut entities: Vec<Entity> = Vec::new();
+	let mut shown: Vec<String> = Vec::new();
+
+	Box::new(move |world, resources| {
+		let _profile = profiler::scope("hud_stats_system");
+
+		let (asset_storage, config_variables, game_state, level_stats, string_table) =
+			<(
+				Read<AssetStorage>,
+				Read<ConfigVariables>,
+				Read<GameStateMachine>,
+				Read<LevelStats>,
+				Read<StringTable>,
+			)>::fetch(resources);
+
+		// The text entities are gone if the world was cleared
+		if entities.first().map_or(false, |e| !world.contains(*e)) {
+			entities.clear();
+			shown.clear();
+		}
+
+		let mut lines = Vec::new();
+		let in_level = game_state.current().has_world();
+
+		if in_level && config_variables.get::<bool>("hud_stats") {
+			lines.push(
+				string_table.format("HUD_KILLS", &[&level_stats.kills, &level_stats.total_kills]),
+			);
+			lines.push(
+				string_table.format("HUD_ITEMS", &[&level_stats.items, &level_stats.total_items]),
+			);
+			lines.push(string_table.format(
+				"HUD_SECRETS",
+				&[&level_stats.secrets, &level_stats.total_secrets],
+			));
+		}
+
+		if in_level && config_variables.get::<bool>("hud_timer") {
+			lines.push(string_table.format("HUD_TIME", &[&format_time(level_stats.time)]));
+		}
+
+		if lines == shown || !font.is_ready(&asset_storage) {
+			return;
+		}
+
+		for entity in entities.drain(..) {
+			world.remove(entity);
+		}
+
+		for (i, text) in lines.iter().enumerate() {
+			entities.extend(font.spawn_text(
+				world,
+				&asset_storage,
+				text,
+				UiTransform {
+					position: Vector2::new(2.0, 2.0 + i as f32 * 9.0),
+					depth: 20.0,
+					alignment: [UiAlignment::Near, UiAlignment::Near],
+					size: Vector2::new(0.0, 0.0),
+					stretch: [false; 2],
+				},
+			));
+		}
+
+		shown = lines;
+	})
+}
+
+/// How many frames the numbers shown by `showfps` are averaged over.
+const FPS_FRAMES: usize = 35;
+
+/// How often the numbers shown by `showfps` are updated.
+const FPS_UPDATE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Shows the frame rate, the time per frame and the time per game tick
+/// in the top right corner when `showfps` is enabled.
+pub fn hud_fps_system(resources: &mut Resources) -> Box<dyn FnMut(&mut World, &mut Resources)> {
+	let font = {
+		let (mut asset_storage, game_profile) =
+			<(Write<AssetStorage>, Read<GameProfile>)>::fetch_mut(resources);
+		HudFont::load(&mut asset_storage, *game_profile)
+	};
+	let mut entities: Vec<Entity> = Vec::new();
+	let mut last_update = Instant::now();
+
+	Box::new(move |world, resources| {
+		let _profile = profiler::scope("hud_fps_system");
+
+		let (asset_storage, config_variables) =
+			<(Read<AssetStorage>, Read<ConfigVariables>)>::fetch(resources);
+
+		// The text entities are gone if the world was cleared
+		if entities.first().map_or(false, |e| !world.contains(*e)) {
+			entities.clear();
+		}
+
+		if !config_variables.get::<bool>("showfps") {
+			for entity in entities.drain(..) {
+				world.remove(entity);
+			}
+
+			return;
+		}
+
+		// Changing the text every frame would make it unreadable
+		if (!entities.is_empty() && last_update.elapsed() < FPS_UPDATE_INTERVAL)
+			|| !font.is_ready(&asset_storage)
+		{
+			return;
+		}
+
+		last_update = Instant::now();
+
+		let frame_times = profiler::frame_times();
+		let recent = &frame_times[frame_times.len().saturating_sub(FPS_FRAMES)..];
+		let frame_time = if recent.is_empty() {
+			Duration::default()
+		} else {
+			recent.iter().sum::<Duration>() / recent.len() as u32
+		};
+		let fps = if frame_time > Duration::default() {
+			1.0 / frame_time.as_secs_f32()
+		} else {
+			0.0
+		};
+		let tick_time = profiler::average_scope_time("update", FPS_FRAMES).unwrap_or_default();
+
+		let lines = [
+			format!("{:.0} FPS", fps),
+			format!("FRAME {:.1} MS", frame_time.as_secs_f32() * 1000.0),
+			format!("TICK {:.1} MS", tick_time.as_secs_f32() * 1000.0),
+		];
+
+		for entity in entities.drain(..) {
+			world.remove(entity);
+		}
+
+		for (i, text) in lines.iter().enumerate() {
+			let width = font.width(text, &asset_storage);
+			entities.extend(font.spawn_text(
+				world,
+				&asset_storage,
+				text,
+				UiTransform {
+					position: Vector2::new(318.0 - width, 2.0 + i as f32 * 9.0),
+					depth: 20.0,
+					alignment: [UiAlignment::Far, UiAlignment::Near],
+					size: Vector2::new(0.0, 0.0),
+					stretch: [false; 2],
+				},
+			));
+		}
+	})
+}
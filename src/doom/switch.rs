@@ -3,6 +3,7 @@ use crate::{
 		assets::{AssetHandle, AssetStorage},
 		audio::Sound,
 		frame::FrameState,
+		profiler,
 		time::Timer,
 	},
 	doom::{
@@ -38,6 +39,8 @@ pub fn switch_active_system() -> impl Runnable {
 		.with_query(<(Entity, &LinedefRef, &mut SwitchActive)>::query())
 		.with_query(<&mut MapDynamic>::query())
 		.build(move |command_buffer, world, resources, queries| {
+			let _profile = profiler::scope("switch_active_system");
+
 			let (asset_storage, frame_state, sound_queue) = resources;
 
 			let (mut world0, mut world) = world.split_for_query(&queries.0);
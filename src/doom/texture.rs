@@ -1,8 +1,8 @@
 use crate::{
-	common::{assets::AssetStorage, frame::FrameState},
+	common::{assets::AssetStorage, frame::FrameState, profiler},
 	doom::map::{LinedefRef, MapDynamic},
 };
-use legion::{systems::Runnable, IntoQuery, SystemBuilder};
+use legion::{systems::ParallelRunnable, IntoQuery, SystemBuilder};
 use nalgebra::Vector2;
 
 #[derive(Clone, Copy, Debug)]
@@ -10,12 +10,14 @@ pub struct TextureScroll {
 	pub speed: Vector2<f32>,
 }
 
-pub fn texture_animation_system() -> impl Runnable {
+pub fn texture_animation_system() -> impl ParallelRunnable {
 	SystemBuilder::new("texture_animation_system")
 		.read_resource::<AssetStorage>()
 		.read_resource::<FrameState>()
 		.with_query(<&mut MapDynamic>::query())
 		.build(move |_, world, resources, query| {
+			let _profile = profiler::scope("texture_animation_system");
+
 			let (asset_storage, frame_state) = resources;
 
 			for map_dynamic in query.iter_mut(world) {
@@ -31,12 +33,14 @@ pub fn texture_animation_system() -> impl Runnable {
 		})
 }
 
-pub fn texture_scroll_system() -> impl Runnable {
+pub fn texture_scroll_system() -> impl ParallelRunnable {
 	SystemBuilder::new("texture_scroll_system")
 		.read_resource::<FrameState>()
 		.with_query(<(&LinedefRef, &TextureScroll)>::query())
 		.with_query(<&mut MapDynamic>::query())
 		.build(move |_, world, frame_state, queries| {
+			let _profile = profiler::scope("texture_scroll_system");
+
 			let (world0, mut world) = world.split_for_query(&queries.0);
 
 			// Scroll textures
@@ -0,0 +1,108 @@
+use crate::{
+	common::{
+		assets::AssetStorage, configvars::ConfigVariables, gamestate::GameState,
+		video::RenderContext,
+	},
+	doom::{language::StringTable, mapinfo::map_info},
+};
+use legion::{systems::ResourceSet, Read, Resources};
+use std::time::Duration;
+
+/// How often the frame rate in the title is updated.
+const FPS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Keeps the window title up to date with the game state, the current map,
+/// and the frame rate if `vid_titlefps` is on.
+#[derive(Debug)]
+pub struct WindowTitle {
+	base: &'static str,
+	level: Option<String>,
+	paused: bool,
+	frames: u32,
+	frame_time: Duration,
+	fps: Option<f32>,
+	current: String,
+}
+
+impl WindowTitle {
+	pub fn new(base: &'static str) -> WindowTitle {
+		WindowTitle {
+			base,
+			level: None,
+			paused: false,
+			frames: 0,
+			frame_time: Duration::default(),
+			fps: None,
+			current: base.to_owned(),
+		}
+	}
+
+	/// Called after every change of game state. The level stays in the title
+	/// until the game goes back to the title screen, so that it is still shown
+	/// during the intermission.
+	pub fn set_state(&mut self, state: &GameState, resources: &Resources) {
+		match state {
+			GameState::Startup | GameState::Title => self.level = None,
+			GameState::Loading { map } => {
+				let (asset_storage, string_table) =
+					<(Read<AssetStorage>, Read<StringTable>)>::fetch(resources);
+				let map = map.to_ascii_uppercase();
+				let level_name = map_info(&asset_storage, &map)
+					.and_then(|entry| entry.level_name.as_deref())
+					.and_then(|name| string_table.resolve(name));
+
+				self.level = Some(match level_name {
+					Some(level_name) => format!("{}: {}", map, level_name),
+					None => map,
+				});
+			}
+			_ => (),
+		}
+
+		self.paused = *state == GameState::Paused;
+	}
+
+	/// Called once per frame with the time since the previous frame.
+	pub fn update(&mut self, delta: Duration, resources: &Resources) {
+		let render_context = match resources.get::<RenderContext>() {
+			Some(render_context) => render_context,
+			None => return,
+		};
+
+		if <Read<ConfigVariables>>::fetch(resources).get::<bool>("vid_titlefps") {
+			self.frames += 1;
+			self.frame_time += delta;
+
+			if self.frame_time >= FPS_INTERVAL {
+				self.fps = Some(self.frames as f32 / self.frame_time.as_secs_f32());
+				self.frames = 0;
+				self.frame_time = Duration::default();
+			}
+		} else {
+			self.fps = None;
+			self.frames = 0;
+			self.frame_time = Duration::default();
+		}
+
+		let mut title = self.base.to_owned();
+
+		if let Some(level) = &self.level {
+			title.push_str(" - ");
+			title.push_str(level);
+		}
+
+		if self.paused {
+			title.push_str(" (paused)");
+		}
+
+		if let Some(fps) = self.fps {
+			title.push_str(&format!(" - {:.0} fps", fps));
+		}
+
+		// Setting the title can be slow on some platforms, so only do it when it changes
+		if title != self.current {
+			render_context.surface().window().set_title(&title);
+			self.current = title;
+		}
+	}
+}
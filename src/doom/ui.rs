@@ -1,5 +1,9 @@
-use crate::{common::assets::AssetHandle, doom::image::Image};
+use crate::{
+	common::assets::{AssetHandle, AssetStorage},
+	doom::{image::Image, profile::GameProfile},
+};
 use derivative::Derivative;
+use legion::{Entity, World};
 use nalgebra::Vector2;
 
 #[derive(Clone, Copy, Debug, Default)]
@@ -23,3 +27,92 @@ pub enum UiAlignment {
 	Middle = 1,
 	Far = 2,
 }
+
+/// The small font used for HUD messages, made from the STCFN patches, or FONTA in Heretic.
+/// Only has upper case letters, digits and some punctuation.
+pub struct HudFont {
+	glyphs: Vec<AssetHandle<Image>>,
+}
+
+/// The characters that the font has images for.
+const FONT_START: u8 = b'!';
+const FONT_END: u8 = b'_';
+
+const SPACE_WIDTH: f32 = 4.0;
+
+impl HudFont {
+	pub fn load(asset_storage: &mut AssetStorage, game_profile: GameProfile) -> HudFont {
+		HudFont {
+			glyphs: (FONT_START..=FONT_END)
+				.map(|c| asset_storage.load(&game_profile.hud_font_patch(c)))
+				.collect(),
+		}
+	}
+
+	/// Whether the images are ready to be drawn.
+	/// They aren't available until the renderer has processed them.
+	pub fn is_ready(&self, asset_storage: &AssetStorage) -> bool {
+		self.glyphs
+			.iter()
+			.all(|handle| asset_storage.get(handle).is_some())
+	}
+
+	fn glyph<'a>(
+		&'a self,
+		c: u8,
+		asset_storage: &'a AssetStorage,
+	) -> Option<(&'a AssetHandle<Image>, &'a Image)> {
+		let c = c.to_ascii_uppercase();
+
+		if (FONT_START..=FONT_END).contains(&c) {
+			let handle = &self.glyphs[(c - FONT_START) as usize];
+			Some((handle, asset_storage.get(handle)?))
+		} else {
+			None
+		}
+	}
+
+	/// Returns the width of `text` in UI units.
+	pub fn width(&self, text: &str, asset_storage: &AssetStorage) -> f32 {
+		text.bytes()
+			.map(|c| {
+				self.glyph(c, asset_storage)
+					.map_or(SPACE_WIDTH, |(_, image)| image.size()[0])
+			})
+			.sum()
+	}
+
+	/// Spawns a UI entity for each character of `text`, starting at `transform`,
+	/// and returns them so that they can be removed again.
+	pub fn spawn_text(
+		&self,
+		world: &mut World,
+		asset_storage: &AssetStorage,
+		text: &str,
+		transform: UiTransform,
+	) -> Vec<Entity> {
+		let mut position = transform.position;
+		let mut entities = Vec::with_capacity(text.len());
+
+		for c in text.bytes() {
+			match self.glyph(c, asset_storage) {
+				Some((handle, image)) => {
+					entities.push(world.push((
+						UiTransform {
+							position,
+							size: image.size(),
+							..transform
+						},
+						UiImage {
+							image: handle.clone(),
+						},
+					)));
+					position[0] += image.size()[0];
+				}
+				None => position[0] += SPACE_WIDTH,
+			}
+		}
+
+		entities
+	}
+}
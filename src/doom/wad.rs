@@ -1,5 +1,5 @@
 use crate::common::assets::DataSource;
-use anyhow::{bail, ensure};
+use anyhow::{anyhow, bail, ensure};
 use arrayvec::ArrayString;
 use byteorder::{ReadBytesExt, LE};
 use relative_path::RelativePath;
@@ -13,11 +13,42 @@ use std::{
 	vec::Vec,
 };
 
-struct Lump {
-	path: PathBuf,
-	name: String,
-	offset: u64,
-	size: usize,
+/// An entry in the lump directory of a WAD.
+#[derive(Clone, Debug)]
+pub struct Lump {
+	/// The WAD file that the lump is in.
+	pub path: PathBuf,
+	pub name: String,
+	pub offset: u64,
+	pub size: usize,
+	pub namespace: Namespace,
+	/// Whether the lump was replaced by a later WAD in a way that a lookup by name
+	/// wouldn't catch, so it should not be found at all.
+	pub hidden: bool,
+}
+
+/// The section of a WAD that a lump is in, given by the marker lumps around it.
+/// Lumps with the same name in different sections don't replace each other.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Namespace {
+	Global,
+	Sprites,
+	Flats,
+	Patches,
+}
+
+impl Namespace {
+	/// Returns the namespace that a marker lump starts, or `Global` if it ends one.
+	/// Both the vanilla names and the doubled ones used by PWADs for merging are recognised.
+	fn from_marker(name: &str) -> Option<Namespace> {
+		match name {
+			"s_start" | "ss_start" => Some(Namespace::Sprites),
+			"f_start" | "ff_start" => Some(Namespace::Flats),
+			"p_start" | "pp_start" => Some(Namespace::Patches),
+			"s_end" | "ss_end" | "f_end" | "ff_end" | "p_end" | "pp_end" => Some(Namespace::Global),
+			_ => None,
+		}
+	}
 }
 
 #[derive(Default)]
@@ -39,6 +70,7 @@ impl WadLoader {
 	pub fn add<P: AsRef<Path>>(&mut self, path: P) -> anyhow::Result<()> {
 		let path = path.as_ref();
 		let file = File::open(path)?;
+		let file_length = file.metadata()?.len();
 		let mut reader = BufReader::new(file);
 
 		log::info!("Adding {}", path.display());
@@ -52,26 +84,62 @@ impl WadLoader {
 		let dir_length = reader.read_u32::<LE>()? as usize;
 		let dir_offset = reader.read_u32::<LE>()? as u64;
 
+		// Entries are 16 bytes each
+		ensure!(
+			dir_offset + dir_length as u64 * 16 <= file_length,
+			"Lump directory with {} entries at offset {} extends past the end of the file",
+			dir_length,
+			dir_offset
+		);
+
 		// Read WAD header, reserve space for new entries
 		self.lumps.reserve(dir_length);
 
 		// Read lump directory
 		reader.seek(SeekFrom::Start(dir_offset))?;
+		let first_index = self.lumps.len();
+		let mut namespace = Namespace::Global;
 
 		for _ in 0..dir_length {
 			let offset = reader.read_u32::<LE>()? as u64;
 			let size = reader.read_u32::<LE>()? as usize;
 			let name = read_string(&mut reader)?;
 
-			self.lump_names.insert(name.as_str().to_owned());
+			// Markers are sometimes left with a junk offset, which doesn't matter when empty
+			ensure!(
+				size == 0 || offset + size as u64 <= file_length,
+				"Lump \"{}\" with size {} at offset {} extends past the end of the file",
+				name,
+				size,
+				offset
+			);
+
+			// Markers themselves are left in the global namespace
+			let marker = Namespace::from_marker(&name);
+			let lump_namespace = if marker.is_some() {
+				Namespace::Global
+			} else {
+				namespace
+			};
+			namespace = marker.unwrap_or(namespace);
+
 			self.lumps.push(Lump {
 				path: path.into(),
 				name: name.as_str().to_owned(),
 				offset,
 				size,
+				namespace: lump_namespace,
+				hidden: false,
 			});
 		}
 
+		self.hide_replaced_sprite_frames(first_index);
+		self.lump_names = self
+			.lumps
+			.iter()
+			.filter(|lump| !lump.hidden)
+			.map(|lump| lump.name.clone())
+			.collect();
 		self.wads.push(path.into());
 
 		Ok(())
@@ -81,24 +149,62 @@ impl WadLoader {
 		self.wads.iter().map(PathBuf::as_path)
 	}
 
-	fn index_for_name(&self, path: &RelativePath) -> anyhow::Result<usize> {
-		let lump_name = path.file_stem().unwrap();
+	/// Returns the directories of all added WADs, in the order the lumps were added.
+	pub fn lumps(&self) -> &[Lump] {
+		&self.lumps
+	}
 
-		// Find the index of this lump in the list
-		let index = match self
-			.lumps
+	/// Sprite frames that the lumps from `first_index` onwards replace are hidden
+	/// in earlier WADs, like merging them into the IWAD would do.
+	/// A frame can be drawn with one image for all angles, or one for each angle,
+	/// so replacing it can leave behind images that have a different name.
+	fn hide_replaced_sprite_frames(&mut self, first_index: usize) {
+		let (old, new) = self.lumps.split_at_mut(first_index);
+		let replaced: HashSet<String> = new
 			.iter()
-			.enumerate()
-			.rev()
-			.filter_map(|(i, lump)| {
-				if lump.name == lump_name {
-					Some(i)
-				} else {
-					None
-				}
-			})
-			.next()
+			.filter(|lump| lump.namespace == Namespace::Sprites)
+			.flat_map(|lump| sprite_frames(&lump.name))
+			.collect();
+
+		if replaced.is_empty() {
+			return;
+		}
+
+		for lump in old
+			.iter_mut()
+			.filter(|lump| lump.namespace == Namespace::Sprites)
 		{
+			if sprite_frames(&lump.name)
+				.iter()
+				.any(|frame| replaced.contains(frame))
+			{
+				lump.hidden = true;
+			}
+		}
+	}
+
+	fn index_for_name(&self, path: &RelativePath) -> anyhow::Result<usize> {
+		let lump_name = match path.file_stem() {
+			Some(lump_name) => lump_name,
+			None => bail!("Empty lump name"),
+		};
+
+		// Look in the namespace that fits the type first, then anywhere,
+		// so that lumps outside of markers are still found
+		let preferred = |namespace: Namespace| match path.extension() {
+			Some("flat") => namespace == Namespace::Flats,
+			Some("patch") => namespace != Namespace::Flats,
+			_ => namespace == Namespace::Global,
+		};
+		let find = |in_namespace: bool| {
+			self.lumps.iter().rposition(|lump| {
+				lump.name == lump_name
+					&& !lump.hidden && (!in_namespace || preferred(lump.namespace))
+			})
+		};
+
+		// Find the index of this lump in the list
+		let index = match find(true).or_else(|| find(false)) {
 			Some(index) => index,
 			None => bail!("Lump \"{}\" not found", lump_name),
 		};
@@ -114,13 +220,18 @@ impl WadLoader {
 			Some("sectors") => 8,
 			Some("reject") => 9,
 			Some("blockmap") => 10,
+			Some("behavior") => 11,
 			_ => 0,
 		};
 
 		let ret = index + offset;
-		let lump = &self.lumps[ret];
 
-		if offset != 0 && path.extension().unwrap() != lump.name {
+		if offset != 0
+			&& self
+				.lumps
+				.get(ret)
+				.map_or(true, |lump| path.extension().unwrap() != lump.name)
+		{
 			bail!(
 				"Lump \"{}\" for map \"{}\" not found",
 				path.extension().unwrap(),
@@ -150,16 +261,41 @@ impl DataSource for WadLoader {
 		self.index_for_name(path).is_ok()
 	}
 
+	fn location(&self, path: &RelativePath) -> Option<&Path> {
+		let index = self.index_for_name(path).ok()?;
+		Some(&self.lumps[index].path)
+	}
+
 	fn names<'a>(&'a self) -> Box<dyn Iterator<Item = &str> + 'a> {
 		Box::from(self.lump_names.iter().map(String::as_str))
 	}
 }
 
+/// Returns the sprite name and frame letter of each frame that a sprite lump has an image for.
+/// Lumps with 8-letter names are used for two frames, the second one mirrored.
+fn sprite_frames(name: &str) -> Vec<String> {
+	let mut frames = Vec::with_capacity(2);
+
+	if let Some(first) = name.get(0..5) {
+		frames.push(first.to_owned());
+	}
+
+	if let (8, Some(sprite), Some(frame)) = (name.len(), name.get(0..4), name.get(6..7)) {
+		frames.push(format!("{}{}", sprite, frame));
+	}
+
+	frames
+}
+
+/// Reads an 8-byte name, which is padded with zeros if it's shorter.
+/// Like the original game, anything after the first zero byte is ignored.
 pub fn read_string<R: Read>(reader: &mut R) -> anyhow::Result<ArrayString<[u8; 8]>> {
 	let mut buf = [0u8; 8];
 	reader.read_exact(&mut buf)?;
-	let mut string =
-		ArrayString::from(std::str::from_utf8(&mut buf)?.trim_end_matches('\0')).unwrap();
+	let end = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+	let name = str::from_utf8(&buf[..end])
+		.map_err(|_| anyhow!("Name {:?} is not valid text", &buf[..end]))?;
+	let mut string = ArrayString::from(name).unwrap();
 	string.make_ascii_lowercase();
 	Ok(string)
 }
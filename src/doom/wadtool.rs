@@ -0,0 +1,112 @@
+use crate::{
+	common::assets::DataSource,
+	doom::{profile::GameProfile, wad::WadLoader},
+};
+use anyhow::{bail, Context};
+use clap::ArgMatches;
+use relative_path::RelativePath;
+use std::{
+	io::Write,
+	path::{Path, PathBuf},
+};
+
+/// Runs one of the `wad` subcommands, which look inside WAD files without starting the game.
+pub fn run(arg_matches: &ArgMatches) -> anyhow::Result<()> {
+	match arg_matches.subcommand() {
+		("list", Some(matches)) => list(Path::new(matches.value_of("FILE").unwrap())),
+		("extract", Some(matches)) => {
+			let mut loader = WadLoader::new();
+			crate::load_wads(&mut loader, matches)?;
+			extract(
+				&loader,
+				matches.value_of("LUMP").unwrap(),
+				matches.value_of("output"),
+			)
+		}
+		("info", Some(matches)) => {
+			let mut loader = WadLoader::new();
+			crate::load_wads(&mut loader, matches)?;
+			info(&loader);
+			Ok(())
+		}
+		_ => bail!("No wad command given, try \"wad --help\""),
+	}
+}
+
+/// Prints the lump directory of a single WAD file.
+fn list(path: &Path) -> anyhow::Result<()> {
+	let mut loader = WadLoader::new();
+	loader
+		.add(path)
+		.context(format!("Couldn't load {}", path.display()))?;
+
+	println!(
+		"{:>5}  {:<8}  {:>10}  {:>10}  NAMESPACE",
+		"INDEX", "NAME", "SIZE", "OFFSET"
+	);
+
+	for (index, lump) in loader.lumps().iter().enumerate() {
+		println!(
+			"{:>5}  {:<8}  {:>10}  {:>10}  {:?}",
+			index, lump.name, lump.size, lump.offset, lump.namespace
+		);
+	}
+
+	Ok(())
+}
+
+/// Writes the contents of a lump to a file, or to standard output if `output` is `-`.
+/// Without `output`, the file is named after the lump, with the `.lmp` extension.
+fn extract(loader: &WadLoader, lump: &str, output: Option<&str>) -> anyhow::Result<()> {
+	let path = RelativePath::new(lump);
+	let data = loader.load(path)?;
+
+	match output {
+		Some("-") => {
+			let stdout = std::io::stdout();
+			let mut lock = stdout.lock();
+			lock.write_all(&data)?;
+			lock.flush()?;
+		}
+		_ => {
+			let output = output.map(PathBuf::from).unwrap_or_else(|| {
+				PathBuf::from(path.file_stem().unwrap_or(lump)).with_extension("lmp")
+			});
+			std::fs::write(&output, &data)
+				.context(format!("Couldn't write {}", output.display()))?;
+			log::info!("Wrote {} bytes to {}", data.len(), output.display());
+		}
+	}
+
+	Ok(())
+}
+
+/// Prints which WADs were loaded and what the game would make of them.
+fn info(loader: &WadLoader) {
+	let game_profile = GameProfile::detect(loader);
+	println!("Game: {}", game_profile.name());
+
+	println!("WADs:");
+	for wad in loader.wads() {
+		let count = loader
+			.lumps()
+			.iter()
+			.filter(|lump| lump.path == wad)
+			.count();
+		println!("  {} ({} lumps)", wad.display(), count);
+	}
+
+	let hidden = loader.lumps().iter().filter(|lump| lump.hidden).count();
+	println!(
+		"Lumps: {} in total, {} sprite lumps replaced",
+		loader.lumps().len(),
+		hidden
+	);
+
+	let mut maps: Vec<&str> = loader
+		.names()
+		.filter(|name| loader.exists(&RelativePath::new(name).with_extension("things")))
+		.collect();
+	maps.sort_unstable();
+	println!("Maps: {}", maps.join(" ").to_ascii_uppercase());
+}
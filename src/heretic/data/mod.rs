@@ -0,0 +1,18 @@
+/// Heretic's built-in entity templates, as a JSON list of `EntityTemplateDef`.
+pub const MOBJS: &str = include_str!("mobjs.json");
+
+/// The ambient sounds that Heretic has built in, in the same form as a SNDINFO lump.
+/// The original plays these as sequences of sounds at random intervals,
+/// here they are approximated as continuous sounds.
+pub const SNDINFO: &str = "
+$ambient 1 amb1 surround continuous 1.0 // Scream
+$ambient 2 amb2 surround continuous 1.0 // Squish
+$ambient 3 amb3 surround continuous 1.0 // Drops
+$ambient 4 amb4 surround continuous 1.0 // Slow footsteps
+$ambient 5 amb5 surround continuous 1.0 // Heartbeat
+$ambient 6 amb6 surround continuous 1.0 // Bells
+$ambient 7 bstsit surround continuous 1.0 // Growl
+$ambient 8 amb8 surround continuous 1.0 // Magic
+$ambient 9 amb9 surround continuous 1.0 // Laughter
+$ambient 10 amb4 surround continuous 1.0 // Fast footsteps
+";
@@ -0,0 +1,114 @@
+use crate::{
+	common::{
+		assets::{AssetStorage, Importer},
+		module::GameModule,
+		spawn::SpawnMergerHandlerSet,
+	},
+	doom::{
+		entitytemplate,
+		module::{load_shared_data, DoomModule},
+		profile::GameProfile,
+		ui,
+	},
+	heretic::data,
+};
+use legion::{systems::Builder, Resources, World, Write};
+use nalgebra::Vector2;
+use std::time::Duration;
+
+/// Heretic runs on the Doom game code, with its own things and status bar.
+/// Its sector and linedef types are close enough to Doom's to share them.
+pub struct HereticModule {
+	doom: DoomModule,
+}
+
+impl HereticModule {
+	pub fn new() -> HereticModule {
+		HereticModule {
+			doom: DoomModule::new(GameProfile::Heretic),
+		}
+	}
+}
+
+impl GameModule for HereticModule {
+	fn name(&self) -> &'static str {
+		"heretic"
+	}
+
+	fn frame_time(&self) -> Duration {
+		self.doom.frame_time()
+	}
+
+	fn importer(&self) -> Importer {
+		self.doom.importer()
+	}
+
+	fn add_asset_types(&self, asset_storage: &mut AssetStorage) {
+		self.doom.add_asset_types(asset_storage);
+	}
+
+	fn register_components(&self, handler_set: &mut SpawnMergerHandlerSet) {
+		self.doom.register_components(handler_set);
+	}
+
+	fn add_resources(&self, resources: &mut Resources) {
+		self.doom.add_resources(resources);
+	}
+
+	fn load_data(&self, resources: &mut Resources) -> anyhow::Result<()> {
+		entitytemplate::load_default_templates(resources, data::MOBJS)?;
+		load_shared_data(resources)
+	}
+
+	fn add_update_systems(&self, builder: &mut Builder, resources: &mut Resources) {
+		self.doom.add_update_systems(builder, resources);
+	}
+
+	fn add_output_systems(
+		&self,
+		builder: &mut Builder,
+		resources: &mut Resources,
+	) -> anyhow::Result<()> {
+		self.doom.add_output_systems(builder, resources)
+	}
+
+	// Heretic's status bar is taller, and has no face or weapon numbers yet
+	fn spawn_hud(&self, world: &mut World, resources: &mut Resources) {
+		let mut asset_storage = <Write<AssetStorage>>::fetch_mut(resources);
+
+		world.extend(vec![
+			(
+				ui::UiTransform {
+					position: Vector2::new(0.0, 158.0),
+					depth: 1.0,
+					alignment: [ui::UiAlignment::Near, ui::UiAlignment::Far],
+					size: Vector2::new(320.0, 42.0),
+					stretch: [true, false],
+				},
+				ui::UiImage {
+					image: asset_storage.load(GameProfile::Heretic.border_flat()),
+				},
+			),
+			(
+				ui::UiTransform {
+					position: Vector2::new(0.0, 158.0),
+					depth: 2.0,
+					alignment: [ui::UiAlignment::Middle, ui::UiAlignment::Far],
+					size: Vector2::new(320.0, 42.0),
+					stretch: [false; 2],
+				},
+				ui::UiImage {
+					image: asset_storage.load("barback.patch"),
+				},
+			),
+		]);
+	}
+
+	fn first_map(&self) -> &'static str {
+		self.doom.first_map()
+	}
+
+	fn warp_map_name(&self, warp: &[u32]) -> anyhow::Result<String> {
+		self.doom.warp_map_name(warp)
+	}
+}
@@ -1,39 +1,44 @@
-mod common;
 mod doom;
+mod heretic;
+
+// The game-independent parts live in their own crate, but are still referred to by their
+// old module name
+use ferret_engine as common;
 
 use crate::common::{
-	assets::{AssetHandle, AssetStorage},
+	assets::{AssetHandle, AssetStorage, DataSource},
 	audio::Sound,
-	frame::{frame_state_system, FrameRng, FrameRngDef, FrameState},
-	input::InputState,
+	cache::DiskCache,
+	configvars::{ConfigVariable, ConfigVariables},
+	events::event_maintenance_system,
+	frame::{frame_state_system, FrameInterpolation, FrameRng, FrameRngDef, FrameState},
+	gamestate::{GameState, GameStateMachine},
+	input::{CommandBindings, InputState, MouseGrab},
+	module::GameModule,
+	profiler,
 	quadtree::Quadtree,
 	spawn::SpawnMergerHandlerSet,
-	video::{AsBytes, DrawList, RenderContext, RenderTarget},
+	video::{ImageUploader, RenderContext, RenderTarget},
 };
-use anyhow::{bail, Context};
-use clap::{App, Arg, ArgMatches};
+use anyhow::{bail, ensure, Context};
+use clap::{App, Arg, ArgMatches, SubCommand};
 use legion::{systems::ResourceSet, Entity, Read, Resources, Schedule, World, Write};
-use nalgebra::Vector2;
 use rand::SeedableRng;
 use relative_path::RelativePath;
 use std::{
-	path::PathBuf,
+	path::{Path, PathBuf},
 	sync::Mutex,
 	time::{Duration, Instant},
 };
-use vulkano::{
-	format::Format,
-	image::{Dimensions, ImmutableImage},
-	sampler::{Filter, MipmapMode, Sampler, SamplerAddressMode},
-};
+use vulkano::instance::debug::DebugCallback;
 use winit::{
-	event::{ElementState, Event, KeyboardInput, VirtualKeyCode, WindowEvent},
+	event::{Event, WindowEvent},
 	event_loop::{ControlFlow, EventLoop},
 	platform::desktop::EventLoopExtDesktop,
 };
 
 fn main() -> anyhow::Result<()> {
-	let arg_matches = App::new(clap::crate_name!())
+	let app = App::new(clap::crate_name!())
 		.about(clap::crate_description!())
 		.version(clap::crate_version!())
 		.arg(
@@ -46,7 +51,24 @@ fn main() -> anyhow::Result<()> {
 				.help("IWAD file to use instead of the default")
 				.short("i")
 				.long("iwad")
-				.value_name("FILE"),
+				.value_name("FILE")
+				.global(true),
+		)
+		.arg(
+			Arg::with_name("file")
+				.help("PWAD files to add, same as giving them without an option")
+				.long("file")
+				.value_name("FILE")
+				.multiple(true)
+				.global(true),
+		)
+		.arg(
+			Arg::with_name("game")
+				.help("Game to run, instead of the one the IWAD is for")
+				.long("game")
+				.value_name("NAME")
+				.possible_values(&GAME_MODULES)
+				.global(true),
 		)
 		.arg(
 			Arg::with_name("map")
@@ -55,6 +77,68 @@ fn main() -> anyhow::Result<()> {
 				.long("map")
 				.value_name("NAME"),
 		)
+		.arg(
+			Arg::with_name("warp")
+				.help("Map to load at startup, by episode and map number or just map number")
+				.long("warp")
+				.value_name("NUM")
+				.min_values(1)
+				.max_values(2)
+				.conflicts_with("map"),
+		)
+		.arg(
+			Arg::with_name("skill")
+				.help("Skill level to play on")
+				.long("skill")
+				.value_name("NUM")
+				.possible_values(&["1", "2", "3", "4", "5"]),
+		)
+		.arg(
+			Arg::with_name("nomonsters")
+				.help("Don't spawn any monsters")
+				.long("nomonsters"),
+		)
+		.arg(
+			Arg::with_name("fast")
+				.help("Make monsters faster, as on Nightmare")
+				.long("fast"),
+		)
+		.arg(
+			Arg::with_name("deathmatch")
+				.help("Play a deathmatch game")
+				.long("deathmatch"),
+		)
+		.arg(
+			Arg::with_name("benchmark")
+				.help("Record frame timings once the map starts, write them to a CSV or JSON file and quit")
+				.long("benchmark")
+				.value_name("FILE"),
+		)
+		.arg(
+			Arg::with_name("benchmark-frames")
+				.help("Number of frames to record with \"--benchmark\"")
+				.long("benchmark-frames")
+				.value_name("NUM")
+				.default_value("1000")
+				.requires("benchmark"),
+		)
+		.arg(
+			Arg::with_name("dedicated")
+				.help("Run as a dedicated server, without video or sound")
+				.long("dedicated"),
+		)
+		.arg(
+			Arg::with_name("rcon-password")
+				.help("Password that clients must give to run remote commands")
+				.long("rcon-password")
+				.value_name("PASSWORD"),
+		)
+		.arg(
+			Arg::with_name("cache")
+				.help("Directory to keep composed textures and other slow-to-make assets in between runs")
+				.long("cache")
+				.value_name("DIR"),
+		)
 		.arg(
 			Arg::with_name("log-level")
 				.help("Highest log level to display")
@@ -62,309 +146,357 @@ fn main() -> anyhow::Result<()> {
 				.value_name("LEVEL")
 				.possible_values(&["ERROR", "WARN", "INFO", "DEBUG", "TRACE"]),
 		)
-		.get_matches();
+		.arg(
+			Arg::with_name("fuzz")
+				.help("Parse data from standard input as the given kind of lump, then quit")
+				.long("fuzz")
+				.value_name("KIND")
+				.possible_values(&["map", "patch", "sound"])
+				.hidden(true),
+		)
+		.subcommand(
+			SubCommand::with_name("check")
+				.about("Loads maps without starting the game, and reports problems in them")
+				.after_help(
+					"Exits with 0 if no problems were found, 1 if there were problems, \
+					 and 2 if the maps couldn't be checked at all.",
+				)
+				.arg(
+					Arg::with_name("TARGET")
+						.help("WAD file to check all maps of, or name of a map in the loaded WADs")
+						.required(true),
+				)
+				.arg(
+					Arg::with_name("PWADS")
+						.help("PWAD files to add")
+						.multiple(true),
+				),
+		)
+		.subcommand(
+			SubCommand::with_name("demo")
+				.about("Looks at recorded demos")
+				.subcommand(
+					SubCommand::with_name("stats")
+						.about("Plays a demo without video or sound, and shows what the player did")
+						.arg(
+							Arg::with_name("FILE")
+								.help("Demo file to play")
+								.required(true),
+						)
+						.arg(
+							Arg::with_name("PWADS")
+								.help("PWAD files to add")
+								.multiple(true),
+						),
+				),
+		)
+		.subcommand(
+			SubCommand::with_name("wad")
+				.about("Looks inside WAD files without starting the game")
+				.subcommand(
+					SubCommand::with_name("list")
+						.about("Lists the lumps in a WAD file")
+						.arg(
+							Arg::with_name("FILE")
+								.help("WAD file to list")
+								.required(true),
+						),
+				)
+				.subcommand(
+					SubCommand::with_name("extract")
+						.about("Writes the contents of a lump to a file")
+						.arg(
+							Arg::with_name("LUMP")
+								.help("Name of the lump to extract")
+								.required(true),
+						)
+						.arg(
+							Arg::with_name("PWADS")
+								.help("PWAD files to add")
+								.multiple(true),
+						)
+						.arg(
+							Arg::with_name("output")
+								.help("File to write to, or \"-\" for standard output")
+								.short("o")
+								.long("output")
+								.value_name("FILE"),
+						),
+				)
+				.subcommand(
+					SubCommand::with_name("info")
+						.about("Shows which WADs are loaded, the game they are for and their maps")
+						.arg(
+							Arg::with_name("PWADS")
+								.help("PWAD files to add")
+								.multiple(true),
+						),
+				),
+		);
+
+	#[cfg(feature = "scripting")]
+	let app = app.arg(
+		Arg::with_name("script")
+			.help("Gameplay script files to load")
+			.long("script")
+			.value_name("FILE")
+			.multiple(true)
+			.number_of_values(1),
+	);
+
+	let arg_matches = app.get_matches_from(classic_args(std::env::args()));
 
-	common::logger::init(&arg_matches)?;
+	common::logger::init(
+		arg_matches
+			.value_of("log-level")
+			.map(|level| level.parse().unwrap()),
+	)?;
+
+	// Fuzzing crashes on purpose, so it skips writing crash reports
+	if let Some(kind) = arg_matches.value_of("fuzz") {
+		return doom::fuzz::fuzz_stdin(kind);
+	}
+
+	common::crash::init(concat!(clap::crate_name!(), " ", clap::crate_version!()));
+
+	match arg_matches.subcommand() {
+		("check", Some(check_matches)) => {
+			let exit_code = match doom::check::run(check_matches) {
+				Ok(0) => 0,
+				Ok(_) => 1,
+				Err(err) => {
+					log::error!("Couldn't check maps: {:?}", err);
+					2
+				}
+			};
+			std::process::exit(exit_code);
+		}
+		("demo", Some(demo_matches)) => return doom::demo::run(demo_matches),
+		("wad", Some(wad_matches)) => return doom::wadtool::run(wad_matches),
+		_ => (),
+	}
 
 	// Set up resources
 	let mut resources = Resources::default();
 
-	let (command_sender, command_receiver) = common::commands::init()?;
-	let mut event_loop = EventLoop::new();
+	let mut config_variables = create_config_variables();
 
-	let (render_context, _debug_callback) =
-		RenderContext::new(&event_loop).context("Could not create RenderContext")?;
-	let render_target = RenderTarget::new(
-		render_context.surface().clone(),
-		render_context.device().clone(),
-	)
-	.context("Couldn't create RenderTarget")?;
+	if let Err(err) = config_variables.read_config(Path::new(CONFIG_FILE)) {
+		log::error!("{:?}", err);
+	}
 
-	let mut draw_list = DrawList::new(&render_context, render_target.dimensions())
-		.context("Couldn't create DrawList")?;
-	draw_list.add_step(
-		doom::render::world::DrawWorld::new(&render_context)
-			.context("Couldn't create DrawWorld")?,
-	);
-	draw_list.add_step(
-		doom::render::map::DrawMap::new(draw_list.render_pass())
-			.context("Couldn't create DrawMap")?,
-	);
-	draw_list.add_step(
-		doom::render::sprite::DrawSprites::new(&render_context, draw_list.render_pass())
-			.context("Couldn't create DrawSprites")?,
-	);
-	draw_list.add_step(
-		doom::render::psprite::DrawPlayerSprites::new(&render_context, draw_list.render_pass())
-			.context("Couldn't create DrawPlayerSprites")?,
-	);
-	draw_list.add_step(
-		doom::render::ui::DrawUi::new(&render_context, draw_list.render_pass())
-			.context("Couldn't create DrawUi")?,
-	);
+	resources.insert(config_variables);
 
-	resources.insert(
-		Sampler::new(
-			render_context.device().clone(),
-			Filter::Nearest,
-			Filter::Nearest,
-			MipmapMode::Nearest,
-			SamplerAddressMode::Repeat,
-			SamplerAddressMode::Repeat,
-			SamplerAddressMode::Repeat,
-			0.0,
-			1.0,
-			0.0,
-			0.0,
-		)
-		.context("Couldn't create texture sampler")?,
-	);
-	resources.insert(render_target);
-	resources.insert(render_context);
+	let (command_sender, command_receiver) = common::commands::init()?;
+	let mut network = common::net::Network::new(
+		command_sender.clone(),
+		arg_matches.value_of("rcon-password").map(str::to_owned),
+	)
+	.context("Couldn't initialise networking")?;
 
-	let sound_sender = common::audio::init()?;
-	resources.insert(sound_sender);
+	let mut loader = doom::wad::WadLoader::new();
+	load_wads(&mut loader, &arg_matches)?;
+	common::crash::set_wads(loader.wads().map(Path::to_owned).collect());
 
-	let bindings = doom::data::get_bindings();
-	resources.insert(bindings);
+	let game_profile = doom::profile::GameProfile::detect(&loader);
+	log::info!("Game: {}", game_profile.name());
+	resources.insert(game_profile);
 
-	resources.insert(InputState::new());
-	resources.insert(Vec::<(AssetHandle<Sound>, Entity)>::new());
-	resources.insert(doom::client::Client::default());
+	let module = game_module(arg_matches.value_of("game"), game_profile);
+	let frame_time = module.frame_time();
 
-	let frame_state = FrameState {
-		delta_time: doom::data::FRAME_TIME,
-		time: Duration::default(),
-		rng: Mutex::new(FrameRng::from_entropy()),
+	let dedicated = arg_matches.is_present("dedicated");
+	let (mut event_loop, mut output_dispatcher, _debug_callback) = if dedicated {
+		log::info!("Running as a dedicated server");
+		(None, None, None)
+	} else {
+		let event_loop = EventLoop::new();
+		let (output_dispatcher, debug_callback) =
+			init_output(&event_loop, &mut resources, &*module)?;
+		(Some(event_loop), Some(output_dispatcher), debug_callback)
 	};
-	resources.insert(frame_state);
 
-	let mut loader = doom::wad::WadLoader::new();
-	load_wads(&mut loader, &arg_matches)?;
+	if dedicated && !network.is_listening() {
+		bail!(
+			"Couldn't listen on port {}, is another server running?",
+			common::net::DEFAULT_PORT
+		);
+	}
+
+	add_game_resources(&*module, &mut resources);
 
 	// Select map
-	let map =
-		if let Some(map) = arg_matches.value_of("map") {
-			map
-		} else {
-			let wad = loader.wads().next().unwrap().file_name().unwrap();
+	let map = if let Some(map) = arg_matches.value_of("map") {
+		map.to_owned()
+	} else if let Some(warp) = arg_matches.values_of("warp") {
+		let warp = warp
+			.map(str::parse)
+			.collect::<Result<Vec<u32>, _>>()
+			.context("Invalid \"-warp\" value")?;
+		module.warp_map_name(&warp)?
+	} else if loader.exists(RelativePath::new(module.first_map())) {
+		module.first_map().to_owned()
+	} else {
+		bail!("No default map is known for this IWAD. Try specifying one with the \"-m\" option.")
+	};
 
-			if wad == "doom.wad" || wad == "doom1.wad" || wad == "doomu.wad" {
-				"E1M1"
-			} else if wad == "doom2.wad" || wad == "tnt.wad" || wad == "plutonia.wad" {
-				"MAP01"
-			} else {
-				bail!("No default map is known for this IWAD. Try specifying one with the \"-m\" option.")
-			}
-		};
-	command_sender.send(format!("map {}", map)).ok();
+	// Apply the remaining options through the console, like the player would
+	let mut commands = Vec::new();
+
+	if let Some(skill) = arg_matches.value_of("skill") {
+		commands.push(format!("skill {}", skill));
+	}
+
+	if arg_matches.is_present("nomonsters") {
+		commands.push("nomonsters 1".to_owned());
+	}
+
+	if arg_matches.is_present("fast") {
+		commands.push("fast 1".to_owned());
+	}
+
+	if arg_matches.is_present("deathmatch") {
+		commands.push("gamemode deathmatch".to_owned());
+	}
+
+	commands.push(format!("map {}", map));
+	command_sender.send(commands.join("; ")).ok();
 
 	// Asset types
-	let mut asset_storage = AssetStorage::new(doom::import, loader);
-	asset_storage.add_storage::<doom::entitytemplate::EntityTemplate>(false);
-	asset_storage.add_storage::<doom::image::Image>(true);
-	asset_storage.add_storage::<doom::image::ImageData>(false);
-	asset_storage.add_storage::<doom::image::Palette>(false);
-	asset_storage.add_storage::<doom::map::Map>(false);
-	asset_storage.add_storage::<doom::map::textures::PNames>(false);
-	asset_storage.add_storage::<doom::map::textures::Textures>(false);
-	asset_storage.add_storage::<doom::sprite::Sprite>(false);
-	asset_storage.add_storage::<doom::sound::Sound>(false);
+	let mut asset_storage = create_asset_storage(&*module, loader);
+
+	if let Some(dir) = arg_matches.value_of("cache") {
+		match DiskCache::new(Path::new(dir)) {
+			Ok(cache) => asset_storage.set_cache(cache),
+			Err(err) => log::warn!("Not using the cache: {:?}", err),
+		}
+	}
+
 	resources.insert(asset_storage);
 
-	// Component types
-	let mut handler_set = SpawnMergerHandlerSet::new();
-	handler_set.register_spawn::<FrameRngDef, FrameRng>();
-	handler_set.register_clone::<doom::camera::Camera>();
-	handler_set.register_clone::<doom::client::UseAction>();
-	handler_set.register_clone::<doom::client::User>();
-	handler_set.register_clone::<doom::components::SpawnPoint>();
-	handler_set.register_spawn::<doom::components::TransformDef, doom::components::Transform>();
-	handler_set.register_from::<doom::components::VelocityDef, doom::components::Velocity>();
-	handler_set.register_clone::<doom::door::DoorActive>();
-	handler_set.register_spawn::<doom::entitytemplate::EntityTemplateRefDef, doom::entitytemplate::EntityTemplateRef>();
-	handler_set.register_clone::<doom::floor::FloorActive>();
-	handler_set.register_spawn::<doom::light::LightFlashDef, doom::light::LightFlash>();
-	handler_set.register_clone::<doom::light::LightGlow>();
-	handler_set.register_clone::<doom::map::LinedefRef>();
-	handler_set.register_clone::<doom::map::MapDynamic>();
-	handler_set.register_clone::<doom::map::SectorRef>();
-	handler_set.register_clone::<doom::physics::BoxCollider>();
-	handler_set.register_clone::<doom::physics::TouchAction>();
-	handler_set.register_clone::<doom::plat::PlatActive>();
-	handler_set.register_clone::<doom::psprite::PlayerSpriteRender>();
-	handler_set.register_clone::<doom::sectormove::CeilingMove>();
-	handler_set.register_clone::<doom::sectormove::FloorMove>();
-	handler_set.register_clone::<doom::sound::SoundPlaying>();
-	handler_set.register_clone::<doom::sprite::SpriteRender>();
-	handler_set.register_spawn::<doom::state::StateDef, doom::state::State>();
-	handler_set.register_clone::<doom::switch::SwitchActive>();
-	handler_set.register_clone::<doom::texture::TextureScroll>();
-	resources.insert(handler_set);
+	// Component types and systems
+	let mut update_dispatcher = create_update_dispatcher(&*module, &mut resources);
 
-	// Create systems
-	#[rustfmt::skip]
-	let mut update_dispatcher = Schedule::builder()
-		.add_thread_local(doom::client::player_command_system()).flush()
-		.add_thread_local(doom::client::player_move_system()).flush()
-		.add_thread_local(doom::client::player_attack_system(&mut resources)).flush()
-		.add_thread_local(doom::client::player_use_system(&mut resources)).flush()
-		.add_thread_local(doom::physics::physics_system(&mut resources)).flush()
-		.add_thread_local(doom::camera::camera_system(&mut resources)).flush()
-		.add_thread_local(doom::door::door_use_system(&mut resources)).flush()
-		.add_thread_local(doom::door::door_switch_system(&mut resources)).flush()
-		.add_thread_local(doom::door::door_touch_system(&mut resources)).flush()
-		.add_thread_local(doom::floor::floor_switch_system(&mut resources)).flush()
-		.add_thread_local(doom::floor::floor_touch_system(&mut resources)).flush()
-		.add_thread_local(doom::plat::plat_switch_system(&mut resources)).flush()
-		.add_thread_local(doom::plat::plat_touch_system(&mut resources)).flush()
-		.add_thread_local(doom::sectormove::sector_move_system(&mut resources)).flush()
-		.add_thread_local(doom::door::door_active_system(&mut resources)).flush()
-		.add_thread_local(doom::floor::floor_active_system(&mut resources)).flush()
-		.add_thread_local(doom::plat::plat_active_system(&mut resources)).flush()
-		.add_thread_local(doom::light::light_flash_system()).flush()
-		.add_thread_local(doom::light::light_glow_system()).flush()
-		.add_thread_local(doom::switch::switch_active_system()).flush()
-		.add_thread_local(doom::texture::texture_animation_system()).flush()
-		.add_thread_local(doom::texture::texture_scroll_system()).flush()
-		.add_thread_local(doom::state::state_system(&mut resources)).flush()
-		.add_thread_local(frame_state_system(doom::data::FRAME_TIME)).flush()
-		.build();
-
-	let mut output_dispatcher = Schedule::builder()
-		.add_thread_local_fn(doom::render::render_system(draw_list))
-		.add_thread_local_fn(doom::sound::sound_system())
-		.build();
+	#[cfg(feature = "scripting")]
+	{
+		let paths: Vec<PathBuf> = arg_matches
+			.values_of("script")
+			.map_or(Vec::new(), |iter| iter.map(PathBuf::from).collect());
+		let scripting = doom::scripting::Scripting::new(&paths, &mut resources)?;
+		resources.insert(scripting);
+	}
 
 	// Create world
 	let mut world = World::default();
 
-	{
-		let mut asset_storage = <Write<AssetStorage>>::fetch_mut(&mut resources);
-
-		world.extend(vec![
-			(
-				doom::ui::UiTransform {
-					position: Vector2::new(0.0, 168.0),
-					depth: 1.0,
-					alignment: [doom::ui::UiAlignment::Near, doom::ui::UiAlignment::Far],
-					size: Vector2::new(320.0, 32.0),
-					stretch: [true, false],
-				},
-				doom::ui::UiImage {
-					image: asset_storage.load("floor7_2.flat"),
-				},
-			),
-			(
-				doom::ui::UiTransform {
-					position: Vector2::new(0.0, 168.0),
-					depth: 2.0,
-					alignment: [doom::ui::UiAlignment::Middle, doom::ui::UiAlignment::Far],
-					size: Vector2::new(320.0, 32.0),
-					stretch: [false; 2],
-				},
-				doom::ui::UiImage {
-					image: asset_storage.load("stbar.patch"),
-				},
-			),
-			(
-				doom::ui::UiTransform {
-					position: Vector2::new(104.0, 168.0),
-					depth: 3.0,
-					alignment: [doom::ui::UiAlignment::Middle, doom::ui::UiAlignment::Far],
-					size: Vector2::new(40.0, 32.0),
-					stretch: [false; 2],
-				},
-				doom::ui::UiImage {
-					image: asset_storage.load("starms.patch"),
-				},
-			),
-			(
-				doom::ui::UiTransform {
-					position: Vector2::new(143.0, 168.0),
-					depth: 10.0,
-					alignment: [doom::ui::UiAlignment::Middle, doom::ui::UiAlignment::Far],
-					size: Vector2::new(24.0, 29.0),
-					stretch: [false; 2],
-				},
-				doom::ui::UiImage {
-					image: asset_storage.load("stfst00.patch"),
-				},
-			),
-		]);
-	}
+	let mut game_state = GameStateMachine::new();
+	game_state.set(GameState::Title);
+	resources.insert(game_state);
+
+	// Benchmarks start recording when gameplay starts, and count down the frames from there
+	let benchmark_path = arg_matches.value_of("benchmark").map(PathBuf::from);
+	let benchmark_frames: usize = arg_matches
+		.value_of("benchmark-frames")
+		.unwrap()
+		.parse()
+		.context("Invalid \"--benchmark-frames\" value")?;
+	let mut benchmark_frames_left = None;
+
+	let mut window_title = doom::title::WindowTitle::new("Ferret");
 
 	let mut should_quit = false;
+	let mut auto_paused = false;
 	let mut old_time = Instant::now();
 	let mut leftover_time = Duration::default();
 
 	while !should_quit {
-		let mut delta;
-		let mut new_time;
+		profiler::begin_frame();
+
+		// Sleep until the next frame is due
+		let deadline = if output_dispatcher.is_none() {
+			// Nothing to draw, so wait for the next game frame
+			old_time + (frame_time - leftover_time)
+		} else {
+			let mut max_fps: f32 = <Read<ConfigVariables>>::fetch(&resources).get("r_maxfps");
+
+			// Frames that the monitor can't show are wasted
+			if <Read<ConfigVariables>>::fetch(&resources).get::<bool>("r_refreshcap") {
+				if let Some(refresh_rate) = resources
+					.get::<common::video::mode::MonitorInfo>()
+					.and_then(|monitor_info| monitor_info.refresh_rate)
+				{
+					let refresh_rate = refresh_rate as f32;
+					max_fps = if max_fps > 0.0 {
+						max_fps.min(refresh_rate)
+					} else {
+						refresh_rate
+					};
+				}
+			}
 
-		// Busy-loop until there is at least a millisecond of delta
-		while {
-			new_time = Instant::now();
-			delta = new_time - old_time;
-			delta.as_millis() < 1
-		} {}
+			if max_fps > 0.0 {
+				old_time + Duration::from_secs_f32(1.0 / max_fps)
+			} else {
+				old_time
+			}
+		};
+		{
+			let _profile = profiler::scope("sleep");
+			common::time::sleep_until(deadline);
+		}
 
+		let new_time = Instant::now();
+		let delta = new_time - old_time;
 		old_time = new_time;
-		//println!("{} fps", 1.0/delta.as_secs_f32());
 
 		// Process events from the system
-		event_loop.run_return(|event, _, control_flow| {
-			let (mut input_state, render_context, mut render_target) =
-				<(Write<InputState>, Read<RenderContext>, Write<RenderTarget>)>::fetch_mut(
-					&mut resources,
-				);
-			input_state.process_event(&event);
+		let mut focus_change = None;
 
-			match event {
-				Event::WindowEvent { event, .. } => match event {
-					WindowEvent::CloseRequested => {
-						command_sender.send("quit".to_owned()).ok();
-						*control_flow = ControlFlow::Exit;
-					}
-					WindowEvent::Resized(new_size) => {
-						render_target.window_resized(new_size.into());
+		if let Some(event_loop) = &mut event_loop {
+			event_loop.run_return(|event, _, control_flow| {
+				let (command_bindings, mut input_state, mut mouse_grab, mut render_target) =
+					<(
+						Read<CommandBindings>,
+						Write<InputState>,
+						Write<MouseGrab>,
+						Write<RenderTarget>,
+					)>::fetch_mut(&mut resources);
+
+				if let Some(button) = input_state.process_event(&event) {
+					if let Some(command) = command_bindings.command(button) {
+						command_sender.send(command.to_owned()).ok();
 					}
-					WindowEvent::MouseInput {
-						state: ElementState::Pressed,
-						..
-					} => {
-						let window = render_context.surface().window();
-						if let Err(err) = window.set_cursor_grab(true) {
-							log::warn!("Couldn't grab cursor: {}", err);
+				}
+
+				match event {
+					Event::WindowEvent { event, .. } => match event {
+						WindowEvent::CloseRequested => {
+							command_sender.send("quit".to_owned()).ok();
+							*control_flow = ControlFlow::Exit;
 						}
-						window.set_cursor_visible(false);
-						input_state.set_mouse_delta_enabled(true);
-					}
-					WindowEvent::Focused(false)
-					| WindowEvent::KeyboardInput {
-						input:
-							KeyboardInput {
-								state: ElementState::Pressed,
-								virtual_keycode: Some(VirtualKeyCode::Escape),
-								..
-							},
-						..
-					} => {
-						let window = render_context.surface().window();
-						if let Err(err) = window.set_cursor_grab(false) {
-							log::warn!("Couldn't release cursor: {}", err);
+						WindowEvent::Resized(new_size) => {
+							render_target.window_resized(new_size.into());
+						}
+						WindowEvent::Focused(focused) => {
+							mouse_grab.set_focused(focused);
+							focus_change = Some(focused);
 						}
-						window.set_cursor_visible(true);
-						input_state.set_mouse_delta_enabled(false);
+						_ => {}
+					},
+					Event::RedrawEventsCleared => {
+						*control_flow = ControlFlow::Exit;
 					}
 					_ => {}
-				},
-				Event::RedrawEventsCleared => {
-					*control_flow = ControlFlow::Exit;
 				}
-				_ => {}
-			}
-		});
+			});
+		}
+
+		if let Some(focused) = focus_change {
+			focus_changed(focused, &mut auto_paused, &network, &mut resources);
+		}
+
+		// Process network traffic
+		network.process();
 
 		// Execute console commands
 		while let Some(command) = command_receiver.try_iter().next() {
@@ -380,38 +512,616 @@ fn main() -> anyhow::Result<()> {
 			// Split further into subcommands
 			for args in tokens.split(|tok| tok == ";") {
 				match args[0].as_str() {
-					"map" => load_map(&format!("{}", args[1]), &mut world, &mut resources)?,
+					"connect" => match args.get(1).map(|a| common::net::parse_addr(a)) {
+						Some(Ok(addr)) => network.connect(addr),
+						Some(Err(err)) => log::error!("Invalid address: {}", err),
+						None => log::error!("Usage: connect <ip[:port]>"),
+					},
+					"disconnect" => network.disconnect(),
+					"listservers" => network.list_servers(),
+					"spectate" => doom::spectator::spectate(
+						args.get(1).map(String::as_str),
+						&world,
+						&mut resources,
+					),
+					"rcon" => {
+						if args.len() < 3 {
+							log::error!("Usage: rcon <password> <command>");
+						} else {
+							network.rcon(&args[1], &args[2..].join(" "));
+						}
+					}
+					"map" => match args.get(1) {
+						Some(map) => {
+							// Starting a map from the console begins a new game
+							doom::game::new_game(&mut resources);
+
+							<Write<GameStateMachine>>::fetch_mut(&mut resources)
+								.set(GameState::Loading { map: map.clone() });
+						}
+						None => log::error!("Usage: map <name>"),
+					},
+					"meminfo" => doom::meminfo::meminfo(&resources),
+					"pause" => {
+						let mut game_state = <Write<GameStateMachine>>::fetch_mut(&mut resources);
+
+						let new_state = match game_state.current() {
+							GameState::Gameplay => GameState::Paused,
+							GameState::Paused => GameState::Gameplay,
+							_ => continue,
+						};
+						game_state.set(new_state);
+					}
+					"profile" => match args.get(1).map(String::as_str) {
+						Some("dump") => {
+							let path = args.get(2).map_or("profile.json", String::as_str);
+
+							match profiler::dump(path.as_ref()) {
+								Ok(()) => log::info!("Wrote profile to {}", path),
+								Err(err) => log::error!("Couldn't write profile: {:?}", err),
+							}
+						}
+						_ => log::error!("Usage: profile dump [file]"),
+					},
+					"export" => match args.get(1).map(String::as_str) {
+						Some("map") => match (args.get(2), args.get(3)) {
+							(Some(name), Some(path)) => match doom::map::export::export_map(
+								name,
+								path.as_ref(),
+								&world,
+								&resources,
+							) {
+								Ok(()) => log::info!("Exported map {} to {}", name, path),
+								Err(err) => log::error!("Couldn't export map: {:?}", err),
+							},
+							_ => log::error!("Usage: export map <name> <file.obj>"),
+						},
+						Some("automap") => {
+							let width = args.get(3).map_or(Ok(2048), |arg| arg.parse::<u32>());
+							let height = args.get(4).map(|arg| arg.parse::<u32>()).transpose();
+
+							match (args.get(2), width, height) {
+								(Some(path), Ok(width), Ok(height)) => {
+									match doom::map::export::export_automap(
+										path.as_ref(),
+										width,
+										height,
+										&world,
+										&mut resources,
+									) {
+										Ok(()) => log::info!("Exported automap to {}", path),
+										Err(err) => {
+											log::error!("Couldn't export automap: {:?}", err)
+										}
+									}
+								}
+								_ => {
+									log::error!("Usage: export automap <file.png> [width] [height]")
+								}
+							}
+						}
+						_ => log::error!(
+							"Usage: export map <name> <file.obj> | export automap <file.png> [width] [height]"
+						),
+					},
+					"changemus" => match args.get(1) {
+						Some(name) => doom::music::play_music(name, &mut resources),
+						None => doom::music::stop_music(&mut resources),
+					},
+					"idmus" => match args.get(1) {
+						Some(number) => doom::music::change_music_cheat(number, &mut resources),
+						None => log::error!("Usage: idmus <two digits>"),
+					},
+					"kill" => doom::health::kill_player(&mut resources),
+					"togglegrab" => <Write<MouseGrab>>::fetch_mut(&mut resources).toggle(),
 					"quit" => should_quit = true,
-					_ => log::error!("Unknown command: {}", args[0]),
+					"reloadtemplates" => doom::entitytemplate::reload_templates(&mut resources),
+					"spawnlist" => doom::entitytemplate::list_templates(&resources),
+					"templateinfo" => match args.get(1) {
+						Some(name) => doom::entitytemplate::dump_template(name, &resources),
+						None => log::error!("Usage: templateinfo <name or thing number>"),
+					},
+					"snd_listdevices" => {
+						for name in common::audio::output_device_names() {
+							log::info!("{}", name);
+						}
+					}
+					"vid_listmonitors" => match resources.get::<RenderContext>() {
+						Some(render_context) => {
+							let window = render_context.surface().window();
+
+							for (i, monitor) in
+								common::video::mode::monitors(window).iter().enumerate()
+							{
+								let size = monitor.size();
+								log::info!(
+									"{}: {}, {}x{}",
+									i + 1,
+									monitor.name().as_deref().unwrap_or("unnamed"),
+									size.width,
+									size.height
+								);
+							}
+						}
+						None => log::error!("No video output"),
+					},
+					"vid_listmodes" => match resources.get::<RenderContext>() {
+						Some(render_context) => {
+							let window = render_context.surface().window();
+
+							for [width, height] in
+								common::video::mode::available_resolutions(window)
+							{
+								log::info!("{}x{}", width, height);
+							}
+						}
+						None => log::error!("No video output"),
+					},
+					"cvarlist" => {
+						let config_variables = <Read<ConfigVariables>>::fetch(&resources);
+
+						for variable in config_variables.iter() {
+							log::info!("{} \"{}\"", variable.name(), variable);
+						}
+					}
+					_ => {
+						let mut config_variables =
+							<Write<ConfigVariables>>::fetch_mut(&mut resources);
+
+						if config_variables.contains(&args[0]) {
+							if let Err(err) = config_variables.command(args) {
+								log::error!("{}", err);
+							}
+						} else {
+							drop(config_variables);
+
+							#[cfg(feature = "scripting")]
+							{
+								if doom::scripting::command(args, &mut world, &mut resources) {
+									continue;
+								}
+							}
+
+							log::error!("Unknown command: {}", args[0]);
+						}
+					}
 				}
 			}
 		}
 
 		if should_quit {
-			return Ok(());
+			break;
+		}
+
+		// Change game state
+		loop {
+			let transition = <Write<GameStateMachine>>::fetch_mut(&mut resources).take_transition();
+			let (old_state, new_state) = match transition {
+				Some(x) => x,
+				None => break,
+			};
+
+			if let GameState::Loading { map } = &new_state {
+				network.server_info.map = map.to_ascii_uppercase();
+			}
+
+			exit_state(&old_state, &mut resources);
+			enter_state(&new_state, &mut world, &mut resources, &*module, dedicated);
+			window_title.set_state(&new_state, &resources);
+		}
+
+		if resources.contains::<RenderContext>() {
+			let (render_context, mut input_state, mut mouse_grab) =
+				<(Read<RenderContext>, Write<InputState>, Write<MouseGrab>)>::fetch_mut(
+					&mut resources,
+				);
+			mouse_grab.apply(render_context.surface().window(), &mut input_state);
 		}
 
 		// Run game frames
-		leftover_time += delta;
+		let runs_world = <Read<GameStateMachine>>::fetch(&resources)
+			.current()
+			.runs_world();
+
+		if runs_world {
+			// Scale the passage of game time, for slow motion or fast forward
+			let timescale: f32 = <Read<ConfigVariables>>::fetch(&resources).get("sv_timescale");
+
+			// If we fell far behind, drop the excess rather than trying to catch up
+			const MAX_FRAMES: f32 = 5.0;
+			leftover_time = (leftover_time + delta.mul_f32(timescale))
+				.min(frame_time.mul_f32(MAX_FRAMES * timescale.max(1.0)));
+
+			while leftover_time >= frame_time {
+				let _profile = profiler::scope("update");
+				update_dispatcher.execute(&mut world, &mut resources);
+
+				#[cfg(feature = "scripting")]
+				doom::scripting::tick(&mut world, &mut resources);
+				leftover_time -= frame_time;
+
+				let mut input_state = <Write<InputState>>::fetch_mut(&mut resources);
+				input_state.reset();
+			}
+		} else {
+			leftover_time = Duration::default();
+		}
+
+		// Draw the world part of the way between the last two game frames
+		<Write<FrameInterpolation>>::fetch_mut(&mut resources).fraction = if runs_world {
+			leftover_time.as_secs_f32() / frame_time.as_secs_f32()
+		} else {
+			1.0
+		};
+
+		profiler::count("entities", world.len() as u64);
 
-		if leftover_time >= doom::data::FRAME_TIME {
-			update_dispatcher.execute(&mut world, &mut resources);
-			leftover_time -= doom::data::FRAME_TIME;
+		if let Some(path) = &benchmark_path {
+			let in_gameplay =
+				*<Read<GameStateMachine>>::fetch(&resources).current() == GameState::Gameplay;
+
+			match benchmark_frames_left {
+				None => {
+					if in_gameplay {
+						log::info!("Starting benchmark of {} frames", benchmark_frames);
+						profiler::start_recording();
+						benchmark_frames_left = Some(benchmark_frames);
+					}
+				}
+				Some(0) => {
+					match profiler::stop_recording(path) {
+						Ok(count) => {
+							log::info!("Wrote {} benchmark frames to {}", count, path.display())
+						}
+						Err(err) => log::error!("Couldn't write benchmark results: {:?}", err),
+					}
 
-			let mut input_state = <Write<InputState>>::fetch_mut(&mut resources);
-			input_state.reset();
+					should_quit = true;
+				}
+				Some(left) => benchmark_frames_left = Some(left - 1),
+			}
 		}
 
 		// Update video and sound
-		output_dispatcher.execute(&mut world, &mut resources);
+		window_title.update(delta, &resources);
+
+		if let Some(output_dispatcher) = &mut output_dispatcher {
+			output_dispatcher.execute(&mut world, &mut resources);
+		} else {
+			// Nobody is listening
+			<Write<Vec<(AssetHandle<Sound>, Entity)>>>::fetch_mut(&mut resources).clear();
+		}
+	}
+
+	if let Err(err) =
+		<Read<ConfigVariables>>::fetch(&resources).write_config(Path::new(CONFIG_FILE))
+	{
+		log::error!("{:?}", err);
 	}
 
 	Ok(())
 }
 
+fn enter_state(
+	state: &GameState,
+	world: &mut World,
+	resources: &mut Resources,
+	module: &dyn GameModule,
+	dedicated: bool,
+) {
+	match state {
+		GameState::Title => {
+			clear_world(world, resources);
+		}
+		GameState::Loading { map } => {
+			clear_world(world, resources);
+			common::crash::set_map(Some(map.clone()));
+
+			let new_state = match load_map(map, world, resources, module, dedicated) {
+				Ok(()) => {
+					if !dedicated {
+						module.spawn_hud(world, resources);
+					}
+
+					GameState::Gameplay
+				}
+				Err(err) => {
+					log::error!("Couldn't load map {}: {:?}", map, err);
+					clear_world(world, resources);
+					GameState::Title
+				}
+			};
+
+			<Write<GameStateMachine>>::fetch_mut(resources).set(new_state);
+		}
+		GameState::Intermission => {
+			clear_world(world, resources);
+			doom::intermission::enter_intermission(resources);
+		}
+		GameState::Gameplay => {
+			<Write<MouseGrab>>::fetch_mut(resources).set_wanted(true);
+		}
+		GameState::Paused => {
+			log::info!("Paused");
+		}
+		_ => {}
+	}
+}
+
+/// Pauses a single player game and turns the sound down while the window doesn't have focus,
+/// and undoes both when the focus comes back. A game that was paused by hand stays paused.
+fn focus_changed(
+	focused: bool,
+	auto_paused: &mut bool,
+	network: &common::net::Network,
+	resources: &mut Resources,
+) {
+	let (autopause, unfocused_volume) = {
+		let config_variables = <Read<ConfigVariables>>::fetch(resources);
+		(
+			config_variables.get::<bool>("autopause"),
+			config_variables.get::<f32>("snd_unfocusedvolume"),
+		)
+	};
+
+	if let Some(audio) = resources.get::<common::audio::Audio>() {
+		audio.set_master_volume(if focused { 1.0 } else { unfocused_volume });
+	}
+
+	let multiplayer =
+		<Read<doom::game::GameMode>>::fetch(resources).is_multiplayer() || network.has_peers();
+	let mut game_state = <Write<GameStateMachine>>::fetch_mut(resources);
+
+	if focused {
+		if *auto_paused && *game_state.current() == GameState::Paused {
+			game_state.set(GameState::Gameplay);
+		}
+
+		*auto_paused = false;
+	} else if autopause && !multiplayer && *game_state.current() == GameState::Gameplay {
+		game_state.set(GameState::Paused);
+		*auto_paused = true;
+	}
+}
+
+fn exit_state(state: &GameState, resources: &mut Resources) {
+	match state {
+		GameState::Gameplay => {
+			// Don't carry inputs over into the next state
+			<Write<InputState>>::fetch_mut(resources).reset();
+			<Write<MouseGrab>>::fetch_mut(resources).set_wanted(false);
+		}
+		GameState::Paused => {
+			log::info!("Unpaused");
+		}
+		_ => {}
+	}
+}
+
+fn clear_world(world: &mut World, resources: &mut Resources) {
+	world.clear();
+	resources.remove::<doom::map::textures::TextureArrays>();
+	common::crash::set_map(None);
+
+	let mut client = <Write<doom::client::Client>>::fetch_mut(resources);
+	client.entity = None;
+	client.view = doom::client::ClientView::Player;
+	client.previous_view_transform = None;
+
+	<Write<Vec<(AssetHandle<Sound>, Entity)>>>::fetch_mut(resources).clear();
+	<Write<doom::acs::AcsState>>::fetch_mut(resources).clear();
+	<Write<Vec<doom::map::spawn::SpawnRequest>>>::fetch_mut(resources).clear();
+	<Write<doom::decal::Decals>>::fetch_mut(resources).clear();
+	<Write<doom::dialog::DialogState>>::fetch_mut(resources).clear();
+	<Write<doom::item::ItemRespawnQueue>>::fetch_mut(resources).clear();
+	<Write<doom::message::HudMessage>>::fetch_mut(resources).clear();
+	<Write<doom::monster::MonsterRespawnQueue>>::fetch_mut(resources).clear();
+	<Write<doom::particle::Particles>>::fetch_mut(resources).clear();
+}
+
+fn init_output(
+	event_loop: &EventLoop<()>,
+	resources: &mut Resources,
+	module: &dyn GameModule,
+) -> anyhow::Result<(Schedule, Option<DebugCallback>)> {
+	let (render_context, debug_callback) =
+		RenderContext::new(event_loop).context("Could not create RenderContext")?;
+	let render_target = RenderTarget::new(
+		render_context.surface().clone(),
+		render_context.device().clone(),
+	)
+	.context("Couldn't create RenderTarget")?;
+
+	// Don't leave the mouse grabbed if the game crashes
+	let window = render_context.surface().window().clone();
+	common::crash::set_release_input(move || {
+		window.set_cursor_grab(false).ok();
+		window.set_cursor_visible(true);
+	});
+
+	resources.insert(render_target);
+	resources.insert(render_context);
+	resources.insert(common::video::mode::MonitorInfo::default());
+
+	let (audio, audio_device_control) = common::audio::init()?;
+	resources.insert(audio);
+	resources.insert(audio_device_control);
+
+	let mut builder = Schedule::builder();
+	module.add_output_systems(&mut builder, resources)?;
+	let output_dispatcher = builder.build();
+
+	Ok((output_dispatcher, debug_callback))
+}
+
+/// The file that archived config variables are kept in between runs.
+const CONFIG_FILE: &str = "ferret.cfg";
+
+/// Creates all the config variables, with their default values.
+fn create_config_variables() -> ConfigVariables {
+	let mut config_variables = ConfigVariables::new();
+	config_variables.add(
+		// 0 means unlimited, which keeps a CPU core busy, so don't make that the default
+		ConfigVariable::new("r_maxfps", 200.0f32)
+			.with_validator(|v| *v >= 0.0)
+			.archived(),
+	);
+	config_variables.add(ConfigVariable::new("sv_timescale", 1.0f32).with_validator(|v| *v > 0.0));
+	config_variables
+		.add(ConfigVariable::new("skill", 3u32).with_validator(|v| (1..=5).contains(v)));
+	config_variables.add(ConfigVariable::new("nomonsters", false));
+	config_variables.add(ConfigVariable::new("fast", false));
+	config_variables.add(ConfigVariable::new("sv_itemrespawn", false));
+	config_variables.add(ConfigVariable::new("sv_buildreject", false));
+	config_variables.add(
+		ConfigVariable::new("gamemode", String::from("single"))
+			.with_validator(|v| v.parse::<doom::game::GameMode>().is_ok()),
+	);
+	config_variables.add(ConfigVariable::new("autopause", true).archived());
+	config_variables.add(ConfigVariable::new("showfps", false).archived());
+	config_variables.add(ConfigVariable::new("showperf", false));
+	config_variables.add(ConfigVariable::new("hud_stats", false).archived());
+	config_variables.add(ConfigVariable::new("hud_timer", false).archived());
+	config_variables.add(ConfigVariable::new("lang", String::from("enu")).archived());
+	config_variables.add(ConfigVariable::new("lang_file", String::new()));
+	config_variables.add(
+		ConfigVariable::new("r_spritedistance", 0.0f32)
+			.with_validator(|v| *v >= 0.0)
+			.archived(),
+	);
+	config_variables.add(
+		ConfigVariable::new("r_renderscale", 1.0f32)
+			.with_validator(|v| (0.0..=2.0).contains(v))
+			.archived(),
+	);
+	config_variables.add(
+		ConfigVariable::new("r_filter", String::from("nearest"))
+			.with_validator(|v| ["nearest", "linear", "anisotropic"].contains(&v.as_str()))
+			.archived(),
+	);
+	config_variables.add(ConfigVariable::new("r_compress", false).archived());
+	config_variables.add(ConfigVariable::new("r_dynlights", false).archived());
+	config_variables.add(ConfigVariable::new("r_lowres", false).archived());
+	config_variables.add(ConfigVariable::new("r_particles", false).archived());
+	config_variables.add(ConfigVariable::new("r_texelalign", false).archived());
+	config_variables.add(ConfigVariable::new("r_refreshcap", false).archived());
+	config_variables.add(ConfigVariable::new("r_vsync", false).archived());
+	config_variables.add(
+		ConfigVariable::new("r_presentmode", String::from("auto"))
+			.with_validator(|v| ["auto", "fifo", "mailbox", "immediate"].contains(&v.as_str()))
+			.archived(),
+	);
+	config_variables.add(
+		ConfigVariable::new("vid_mode", String::from("windowed"))
+			.with_validator(|v| ["windowed", "borderless", "fullscreen"].contains(&v.as_str()))
+			.archived(),
+	);
+	config_variables.add(ConfigVariable::new("vid_monitor", 0u32).archived());
+	config_variables.add(ConfigVariable::new("vid_titlefps", false));
+	config_variables.add(
+		ConfigVariable::new("vid_width", 800u32)
+			.with_validator(|v| *v >= 320)
+			.archived(),
+	);
+	config_variables.add(
+		ConfigVariable::new("vid_height", 600u32)
+			.with_validator(|v| *v >= 240)
+			.archived(),
+	);
+	config_variables.add(
+		ConfigVariable::new("mus_crossfade", 1.0f32)
+			.with_validator(|v| *v >= 0.0)
+			.archived(),
+	);
+	config_variables.add(ConfigVariable::new("snd_captions", false).archived());
+	config_variables.add(ConfigVariable::new("snd_device", String::from("default")).archived());
+	config_variables.add(ConfigVariable::new("snd_doppler", false).archived());
+	config_variables.add(ConfigVariable::new("snd_lowpass", true).archived());
+	config_variables.add(ConfigVariable::new("snd_reverb", true).archived());
+	config_variables.add(
+		ConfigVariable::new("snd_unfocusedvolume", 0.0f32)
+			.with_validator(|v| (0.0..=1.0).contains(v))
+			.archived(),
+	);
+	config_variables
+}
+
+/// Inserts the resources that the game needs before anything is loaded.
+fn add_game_resources(module: &dyn GameModule, resources: &mut Resources) {
+	resources.insert(InputState::new());
+	resources.insert(CommandBindings::new());
+	resources.insert(MouseGrab::new());
+	resources.insert(Vec::<(AssetHandle<Sound>, Entity)>::new());
+	module.add_resources(resources);
+
+	let frame_state = FrameState {
+		delta_time: module.frame_time(),
+		time: Duration::default(),
+		rng: Mutex::new(FrameRng::from_entropy()),
+	};
+	resources.insert(frame_state);
+	resources.insert(FrameInterpolation::default());
+}
+
+/// Registers the component types of the game, and creates the systems that run each game frame.
+fn create_update_dispatcher(module: &dyn GameModule, resources: &mut Resources) -> Schedule {
+	let mut handler_set = SpawnMergerHandlerSet::new();
+	handler_set.register_spawn::<FrameRngDef, FrameRng>();
+	module.register_components(&mut handler_set);
+	resources.insert(handler_set);
+
+	let mut builder = Schedule::builder();
+	module.add_update_systems(&mut builder, resources);
+	builder
+		.add_thread_local(frame_state_system(module.frame_time()))
+		.flush()
+		.add_thread_local_fn(event_maintenance_system())
+		.build()
+}
+
+/// Sets up the asset storage with all asset types the game uses, loading them from `loader`.
+fn create_asset_storage(module: &dyn GameModule, loader: doom::wad::WadLoader) -> AssetStorage {
+	let mut asset_storage = AssetStorage::new(module.importer(), loader);
+	module.add_asset_types(&mut asset_storage);
+	asset_storage
+}
+
+/// The names of the games that can be chosen with `--game`.
+const GAME_MODULES: [&str; 2] = ["doom", "heretic"];
+
+/// Creates the module for the game named `name`,
+/// or for the game that the IWAD belongs to if none is given.
+fn game_module(
+	name: Option<&str>,
+	game_profile: doom::profile::GameProfile,
+) -> Box<dyn GameModule> {
+	let name = name.unwrap_or(match game_profile {
+		doom::profile::GameProfile::Heretic => "heretic",
+		_ => "doom",
+	});
+
+	match name {
+		"heretic" => Box::new(heretic::module::HereticModule::new()),
+		_ => Box::new(doom::module::DoomModule::new(game_profile)),
+	}
+}
+
 fn load_wads(loader: &mut doom::wad::WadLoader, arg_matches: &ArgMatches) -> anyhow::Result<()> {
 	let mut wads = Vec::new();
-	const IWADS: [&str; 6] = ["doom2", "plutonia", "tnt", "doomu", "doom", "doom1"];
+	const IWADS: [&str; 12] = [
+		"doom2",
+		"plutonia",
+		"tnt",
+		"doomu",
+		"doom",
+		"doom1",
+		"freedoom2",
+		"freedoom1",
+		"freedm",
+		"chex",
+		"heretic",
+		"heretic1",
+	];
 
 	let iwad = if let Some(iwad) = arg_matches.value_of("iwad") {
 		PathBuf::from(iwad)
@@ -431,21 +1141,32 @@ fn load_wads(loader: &mut doom::wad::WadLoader, arg_matches: &ArgMatches) -> any
 		wads.extend(iter.map(PathBuf::from));
 	}
 
+	if let Some(iter) = arg_matches.values_of("file") {
+		wads.extend(iter.map(PathBuf::from));
+	}
+
 	for path in wads {
-		loader
-			.add(&path)
-			.context(format!("Couldn't load {}", path.display()))?;
-
-		// Try to load the .gwa file as well if present
-		if let Some(extension) = path.extension() {
-			if extension == "wad" {
-				let path = path.with_extension("gwa");
-
-				if path.is_file() {
-					loader
-						.add(&path)
-						.context(format!("Couldn't load {}", path.display()))?;
-				}
+		add_wad(loader, &path)?;
+	}
+
+	Ok(())
+}
+
+/// Adds a single WAD, along with the .gwa file next to it if there is one.
+fn add_wad(loader: &mut doom::wad::WadLoader, path: &Path) -> anyhow::Result<()> {
+	loader
+		.add(path)
+		.context(format!("Couldn't load {}", path.display()))?;
+
+	// Try to load the .gwa file as well if present
+	if let Some(extension) = path.extension() {
+		if extension == "wad" {
+			let path = path.with_extension("gwa");
+
+			if path.is_file() {
+				loader
+					.add(&path)
+					.context(format!("Couldn't load {}", path.display()))?;
 			}
 		}
 	}
@@ -453,22 +1174,72 @@ fn load_wads(loader: &mut doom::wad::WadLoader, arg_matches: &ArgMatches) -> any
 	Ok(())
 }
 
-fn load_map(name: &str, world: &mut World, resources: &mut Resources) -> anyhow::Result<()> {
+/// Rewrites the single-dash options of the original Doom executable, like `-warp`,
+/// into the double-dash form that clap understands.
+fn classic_args(args: impl Iterator<Item = String>) -> Vec<String> {
+	const CLASSIC_OPTIONS: [&str; 7] = [
+		"-iwad",
+		"-file",
+		"-warp",
+		"-skill",
+		"-nomonsters",
+		"-fast",
+		"-deathmatch",
+	];
+
+	args.map(|arg| {
+		if CLASSIC_OPTIONS.contains(&arg.as_str()) {
+			format!("-{}", arg)
+		} else {
+			arg
+		}
+	})
+	.collect()
+}
+
+fn load_map(
+	name: &str,
+	world: &mut World,
+	resources: &mut Resources,
+	module: &dyn GameModule,
+	dedicated: bool,
+) -> anyhow::Result<()> {
 	log::info!("Starting map {}...", name);
 	let name_lower = name.to_ascii_lowercase();
+	let game_profile = *<Read<doom::profile::GameProfile>>::fetch(resources);
 	let start_time = Instant::now();
 
 	log::info!("Loading entity data...");
-	doom::data::mobjs::load(resources);
-	doom::data::sectors::load(resources);
-	doom::data::linedefs::load(resources);
+	module.load_data(resources)?;
 
 	log::info!("Loading map...");
 	let map_handle: AssetHandle<doom::map::Map> = {
 		let mut asset_storage = <Write<AssetStorage>>::fetch_mut(resources);
+		ensure!(
+			asset_storage
+				.source()
+				.exists(RelativePath::new(&name_lower)),
+			"Map {} does not exist",
+			name
+		);
 		asset_storage.load(&format!("{}.map", name_lower))
 	};
 
+	// Sight checks are quicker with a REJECT table, so make one if the map doesn't have it
+	{
+		let (asset_storage, config_variables) =
+			<(Read<AssetStorage>, Read<ConfigVariables>)>::fetch(resources);
+		let map = asset_storage.get(&map_handle).unwrap();
+
+		if map.reject.is_empty() && config_variables.get::<bool>("sv_buildreject") {
+			log::info!("Map has no REJECT table, building one in the background");
+			doom::map::reject::build_reject_in_background(
+				map.clone(),
+				asset_storage.cache().cloned(),
+			);
+		}
+	}
+
 	// Create quadtree
 	let bbox = {
 		let asset_storage = <Read<AssetStorage>>::fetch(resources);
@@ -477,64 +1248,89 @@ fn load_map(name: &str, world: &mut World, resources: &mut Resources) -> anyhow:
 	};
 	resources.insert(Quadtree::new(bbox));
 
-	log::info!("Processing assets...");
-	{
-		let (render_context, mut asset_storage) =
-			<(Read<RenderContext>, Write<AssetStorage>)>::fetch_mut(resources);
-
-		// Palette
-		let palette_handle: AssetHandle<doom::image::Palette> =
-			asset_storage.load("playpal.palette");
-
-		// Images
-		asset_storage.process::<doom::image::Image, _>(|data, asset_storage| {
-			let image_data: doom::image::ImageData = *data.downcast().ok().unwrap();
-			let palette = asset_storage.get(&palette_handle).unwrap();
-			let data: Vec<_> = image_data
-				.data
-				.into_iter()
-				.map(|pixel| {
-					if pixel.a == 0xFF {
-						palette[pixel.i as usize]
-					} else {
-						crate::doom::image::RGBAColor::default()
-					}
-				})
-				.collect();
-
-			// Create the image
-			let (image, _future) = ImmutableImage::from_iter(
-				data.as_bytes().iter().copied(),
-				Dimensions::Dim2d {
-					width: image_data.size[0] as u32,
-					height: image_data.size[1] as u32,
-				},
-				Format::R8G8B8A8Unorm,
-				render_context.queues().graphics.clone(),
-			)?;
-
-			Ok(crate::doom::image::Image {
-				image,
-				offset: Vector2::new(image_data.offset[0] as f32, image_data.offset[1] as f32),
-			})
-		});
+	// Images are only needed for rendering
+	let texture_arrays = if let Some(render_context) = resources.get::<RenderContext>() {
+		log::info!("Processing assets...");
+		let mut asset_storage = resources.get_mut::<AssetStorage>().unwrap();
+		let compress = resources
+			.get::<ConfigVariables>()
+			.unwrap()
+			.get::<bool>("r_compress");
+		let mut uploader = ImageUploader::new();
+		doom::image::process_images(&render_context, &mut asset_storage, &mut uploader);
+		let texture_arrays = doom::map::textures::TextureArrays::new(
+			&map_handle,
+			&render_context,
+			&mut asset_storage,
+			&mut uploader,
+			compress,
+		)?;
+		uploader.upload(&render_context)?;
+		Some(texture_arrays)
+	} else {
+		None
+	};
+
+	if let Some(texture_arrays) = texture_arrays {
+		resources.insert(texture_arrays);
 	}
 
 	log::info!("Spawning entities...");
+	let hexen_format = {
+		let asset_storage = <Read<AssetStorage>>::fetch(resources);
+		doom::map::load::is_hexen_format(asset_storage.source(), RelativePath::new(&name_lower))
+	};
 	let things = {
 		let asset_storage = <Write<AssetStorage>>::fetch_mut(resources);
 		doom::map::load::build_things(
 			&asset_storage
 				.source()
 				.load(&RelativePath::new(&name_lower).with_extension("things"))?,
+			hexen_format,
 		)?
 	};
 	doom::map::spawn::spawn_map_entities(world, resources, &map_handle)?;
 	doom::map::spawn::spawn_things(things, world, resources)?;
+	resources.insert(doom::stats::LevelStats::new(world));
+
+	let boss_actions = {
+		let asset_storage = <Read<AssetStorage>>::fetch(resources);
+		doom::boss::BossActions::new(&name_lower, world, &asset_storage, game_profile)
+	};
+	resources.insert(boss_actions);
+
+	// Hexen-format maps have scripts
+	let behavior = if hexen_format {
+		let mut asset_storage = <Write<AssetStorage>>::fetch_mut(resources);
+		let handle: AssetHandle<doom::acs::behavior::Behavior> =
+			asset_storage.load(&format!("{}.behavior", name_lower));
+		asset_storage.get(&handle).cloned()
+	} else {
+		None
+	};
+	<Write<doom::acs::AcsState>>::fetch_mut(resources).start_map(behavior);
+
+	let dialogs =
+		<Write<AssetStorage>>::fetch_mut(resources).load(&format!("{}.dialog", name_lower));
+	<Write<doom::dialog::DialogState>>::fetch_mut(resources).start_map(dialogs);
 
 	// Spawn player
-	let entity = doom::map::spawn::spawn_player(world, resources, 1)?;
-	<Write<doom::client::Client>>::fetch_mut(resources).entity = Some(entity);
+	if !dedicated {
+		let entity = doom::map::spawn::spawn_player(world, resources, 1)?;
+		<Write<doom::client::Client>>::fetch_mut(resources).entity = Some(entity);
+	}
+
+	let music = {
+		let asset_storage = <Read<AssetStorage>>::fetch(resources);
+		doom::mapinfo::map_info(&asset_storage, name).and_then(|entry| entry.music.clone())
+	};
+
+	if let Some(music) = music.or_else(|| doom::music::map_music(name, game_profile)) {
+		doom::music::play_music(&music, resources);
+	}
+
+	#[cfg(feature = "scripting")]
+	doom::scripting::map_loaded(name, world, resources);
 
 	log::debug!(
 		"Loading took {} s",